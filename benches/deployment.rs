@@ -0,0 +1,129 @@
+//! The hot paths of listing and deploying a large mod-list, on synthetic mod-lists of
+//! `mods` mods with `files` files each. Every tenth file of a mod is also provided by all
+//! other mods, so there are plenty of conflicts to resolve.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use tempfile::TempDir;
+
+use starmod_core::{
+    conflict::conflict_list_by_file,
+    manifest::{index::ManifestIndex, install_file::InstallFile, Manifest},
+    mods::{GatherModList, ModKind, ModList},
+};
+
+const SIZES: [(usize, usize); 3] = [(50, 100), (200, 250), (500, 500)];
+const SHARED_FILE_EVERY: usize = 10;
+
+/// `mods` enabled mods of `files` files each, for the cache in `cache_dir`.
+fn synthetic_mods(cache_dir: &Utf8Path, mods: usize, files: usize) -> Vec<Manifest> {
+    (0..mods)
+        .map(|m| {
+            let name = format!("mod_{m:04}");
+            let files = (0..files)
+                .map(|f| {
+                    let source = if f % SHARED_FILE_EVERY == 0 {
+                        format!("textures/shared/file_{f:04}.dds")
+                    } else {
+                        format!("textures/{name}/file_{f:04}.dds")
+                    };
+                    InstallFile::new(Utf8PathBuf::from(&source), &source).unwrap()
+                })
+                .collect();
+
+            let mut md = Manifest::new(
+                cache_dir,
+                Utf8Path::new(&name),
+                name.clone(),
+                name.clone(),
+                None,
+                None,
+                files,
+                Vec::new(),
+                ModKind::Data,
+            );
+            md.temp_set_enabled();
+            md
+        })
+        .collect()
+}
+
+/// A cache directory with the manifests of a synthetic mod-list written to it.
+fn synthetic_cache(mods: usize, files: usize) -> (TempDir, Utf8PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let cache_dir = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+    for md in synthetic_mods(&cache_dir, mods, files) {
+        md.write().unwrap();
+    }
+    (dir, cache_dir)
+}
+
+fn conflicts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conflict_list_by_file");
+    for (mods, files) in SIZES {
+        let mod_list = synthetic_mods(Utf8Path::new("/cache"), mods, files);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{mods}x{files}")),
+            &mod_list,
+            |b, mod_list| b.iter(|| conflict_list_by_file(black_box(mod_list)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn gather(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gather_mods");
+    for (mods, files) in SIZES {
+        let (_dir, cache_dir) = synthetic_cache(mods, files);
+        let size = format!("{mods}x{files}");
+
+        group.bench_with_input(
+            BenchmarkId::new("index", &size),
+            &cache_dir,
+            |b, cache_dir| b.iter(|| Vec::gather_mods(cache_dir).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("manifests", &size),
+            &cache_dir,
+            |b, cache_dir| {
+                b.iter_batched(
+                    || ManifestIndex::invalidate(cache_dir),
+                    |()| Vec::gather_mods(cache_dir).unwrap(),
+                    BatchSize::PerIteration,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+fn enable(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ModList::enable");
+    // Every iteration links all files into a fresh game directory.
+    group.sample_size(10);
+    for (mods, files) in SIZES {
+        let (_dir, cache_dir) = synthetic_cache(mods, files);
+        let mod_list = Vec::gather_mods(&cache_dir).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{mods}x{files}")),
+            &mod_list,
+            |b, mod_list| {
+                b.iter_batched(
+                    || (mod_list.clone(), TempDir::new().unwrap()),
+                    |(mut mod_list, game_dir)| {
+                        let path = Utf8Path::from_path(game_dir.path()).unwrap();
+                        mod_list.enable(&cache_dir, path).unwrap();
+                        // Removed outside of the measurement.
+                        game_dir
+                    },
+                    BatchSize::PerIteration,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, conflicts, gather, enable);
+criterion_main!(benches);
@@ -0,0 +1,445 @@
+# The messages of starmod, in English.
+#
+# Copy this file to '~/.local/share/starmod/locales/<locale>.ftl', like 'de.ftl' or
+# 'pt-BR.ftl', and translate the messages to see them in that locale; everything after the
+# '=' is the message. Keep the '{ $name }' arguments as they are. Messages without a
+# translation are shown in English.
+
+## Confirmations
+confirm-affected = This will { $action }:
+confirm-question = Do you want to { $action }?
+confirm-aborted = Aborted.
+password-prompt = Password (leave empty to give up):
+
+## Installers
+fomod-installer = FoMod Installer for { $name }
+fomod-install-step = Install Step: { $name }
+fomod-group = Group Name: { $name }
+fomod-recorded-choices = Using recorded choices for group '{ $group }'
+fomod-select-exactly-one = Please select one of the following:
+fomod-select-at-least-one = Please select at-least one of the following:
+fomod-select-at-most-one = Please select at-most one of the following:
+fomod-select-any = Please select any of the following:
+fomod-done = D) Done with the selection
+fomod-exit = E) Exit Installer
+fomod-prompt = Select :
+fomod-invalid-choice = Invalid choice..
+fomod-at-least-one-required = Please select at-least one option.
+loader-select-files = Which files of '{ $mod_dir }' should be installed into the game directory?
+
+## Prompts
+mod-filter-help = Filter with text, or with 'tag:<tag>', 'state:disabled', 'kind:<kind>' and '!' to negate
+select-plugin-to-clean = Please select a plugin to clean:
+
+## The mod-list
+column-index = Index
+column-tag = Tag
+column-name = Name
+column-priority = Priority
+column-status = Status
+column-version = Version
+column-nexus-id = Nexus Id
+column-mod-type = Mod Type
+column-size = Size
+column-tags = Tags
+column-notes = Notes
+unknown = <Unknown>
+untagged = <Untagged>
+update-available = Update Available
+column-endorsed = Endorsed
+endorsed = Yes
+abstained = Abstained
+
+## The legenda
+legenda-tag = Tag
+legenda-color = Color
+legenda-meaning = Meaning
+tag-enabled = Nothing to see here; move along citizen.
+tag-winner = Conflict winner
+tag-loser = Conflict loser
+tag-complete-loser = Complete conflict loser; ALL files are overwitten by other mods
+tag-conflict = Conflict winner for some files, conflict loser for other files.
+tag-disabled = Mod is disabled.
+tag-pinned = Mod is pinned; it stays disabled until it is unpinned.
+
+## Errors of the settings
+error-setting-config-not-found = No valid config file could be found; Please run '{ $cmd } update-config' first.
+error-setting-no-game-dir-found = The game directory for { $game } cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-no-download-dir-found = A download directory for cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-no-cache-dir-found = The cache directory cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-no-proton-dir-found = The proton directory cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-no-compat-dir-found = The compat directory cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-no-steam-dir-found = The steam directory cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-no-wine-prefix-found = Wine is configured without a prefix, Please run '{ $cmd } update-config' and provide one.
+error-setting-no-xedit-dir-found = The xEdit directory cannot be found, Please run '{ $cmd } update-config' and provide manually.
+error-setting-executable-not-found = The executable could not be found: { $path }.
+error-setting-no-executable-configured = No executable is configured for this command; Please run '{ $cmd } update-config' and provide one.
+error-setting-read-only = Running in read-only mode; refusing to { $action }.
+error-setting-not-confirmed = Refusing to { $action } without confirmation; use '--yes' to confirm.
+error-setting-daemon-running = A daemon is already listening on { $socket }.
+error-setting-no-nexus-api-key = No Nexus API key could be found; Please set '{ $env }' or configure one in dmodman.
+error-setting-alias-is-command = '{ $alias }' is a command of starmod; an alias cannot replace it.
+error-setting-invalid-alias-name = '{ $alias }' cannot be used as an alias; it should be a single word, not starting with '-'.
+error-setting-alias-not-found = No alias named '{ $alias }' exists.
+error-setting-no-state-dir-configured = No state directory is configured; Please run '{ $cmd } config update --state-dir <dir>' first.
+error-setting-invalid-backup-extension = '{ $extension }' cannot be used as the backup extension; it should be a file name extension, without '/'.
+
+## Errors of the game and its tools
+error-game-config-not-found = Could not find file(s) '{ $files }' in the game directories.
+error-game-process-failed = '{ $process }' failed: { $reason }.
+error-game-plugin-not-found = Could not find plugin '{ $plugin }' in the game's data directory.
+error-game-empty-rule = A rule for '{ $plugin }' needs '--after' or '--group'.
+error-game-rule-not-found = There are no rules for plugin '{ $plugin }'.
+error-game-invalid-plugin = '{ $plugin }' is not a valid plugin.
+error-game-checks-failed = { $count } pre-flight check(s) failed; fix them or run again with '--skip-checks'.
+error-game-overlay-unavailable = '{ $program }' is needed for the overlay deployment, but could not be run; is it installed?
+error-game-already-mounted = Something is already mounted on '{ $dir }'; unmount it with 'fusermount -u' first.
+error-game-no-snapshot = No snapshot of the game files was taken; run '{ $cmd } game snapshot' first.
+error-game-snapshot-exists = A snapshot of the game files was already taken; use '--force' to take it again.
+
+## Errors of mods
+error-mod-mod-not-found = The mod '{ $name }' could not be found. Is the mod installed?
+error-mod-file-not-found = Could not find the file(s) '{ $files }' in mod { $name }.
+error-mod-tag-not-found = Could not find tag '{ $tag }' in mod { $name }. Did you perhaps mispel it?
+error-mod-duplicate-tag = Could not add tag '{ $tag }' to mod { $name }. Perhaps the mod al-ready has that tag?
+error-mod-mod-already-exists = A mod named '{ $name }' already exists.
+error-mod-no-nexus-id = Mod '{ $name }' has no Nexus id; was it downloaded from Nexus?
+error-mod-manifest-too-new = The manifest of mod '{ $name }' has format { $format }, which is newer than this version of starmod supports. Please upgrade starmod.
+error-mod-manifest-not-migrated = The manifest of mod '{ $name }' has format { $format }, which this version of starmod does not know how to upgrade.
+error-mod-no-executable = The mod '{ $name }' has no executable to launch.
+error-mod-no-launcher-dir = Unable to find the applications directory; please supply one with '--output'.
+error-mod-output-exists = The file '{ $file }' already exists; refusing to overwrite it.
+error-mod-invalid-pattern = '{ $pattern }' is not a valid pattern: { $reason }.
+error-mod-lint-failed = { $count } lint error(s) were found.
+
+## Errors of downloads
+error-download-archive-not-found = the archive { $archive } cannot be found.
+error-download-staging-dir-not-found = the staging directory { $dir } cannot be found.
+error-download-extractions-failed = { $count } archive(s) could not be extracted.
+error-download-upgrades-failed = { $count } mod(s) could not be upgraded.
+error-download-no-download-link = Nexus did not offer a download link for { $file }.
+error-download-no-metadata = the archive { $archive } has no dmodman metadata, so its Nexus page is not known.
+error-download-unknown-to-nexus = Nexus does not know the archive { $archive }; was it downloaded from Nexus?
+error-download-collection-for-other-game = the collection { $collection } is meant for { $game }.
+error-download-nexus-request-failed = the Nexus API answered with { $status }: { $reason }.
+error-download-nexus-daily-limit = the daily limit of Nexus API requests has been reached; it resets at { $reset }.
+
+## Errors of installers
+error-installer-dependencies-not-met = the mod { $name } has unmet dependencies.
+error-installer-multiple-data-directories = the mod { $name } has multiple data directories.
+error-installer-installer-cancelled = the installer of mod { $name } has been cancelled.
+error-installer-no-installer-script = no installer script installs the mod { $name }.
+error-installer-script-failed = the installer script { $script } failed: { $reason }.
+error-installer-invalid-mapping = the installer script { $script } wrote an invalid mapping: { $reason }.
+
+## Internal errors
+error-internal = We encountered an internal error, please report this: { $error }.
+
+## Help of the commands
+# 'help-<command>-<subcommand>' is the help of a command, 'help-<command>--<argument>' that
+# of one of its arguments; a '.long' attribute holds the long help, shown by '--help'.
+
+help = Cmdline Modmanager for Starfield on Linux/Proton
+help--verbose = Set output to verbose
+help--generator = Generate shell completion scripts for the given shell
+help--version = Show information related to this build of starmod
+help--long_version = Show information related to this build of starmod
+help--list_commands = Show Long Help
+help--no_pager = Never run long output through '$PAGER'
+help--read_only = Never change manifests, the cache or the game directory
+help--no_color = Do not use colours in the output; also enabled by setting `NO_COLOR`
+help--yes = Answer yes to all confirmation prompts
+help-config = Config related commands; defaults to showing the current settings
+help-config-show = Show starmod's configuration values
+help-config-doctor = Check that the configured directories and the deployment work on this system
+help-config-update = Update settings
+help-config-update--list_columns = Comma separated list of columns shown by 'list mods'
+help-config-update--colour = Use colours in the output
+help-config-update--page_size = Number of entries shown in selection lists
+help-config-update--shared_cache = Share the cache directory with other users; each user keeps their own mod order
+help-config-update--new_mod_priority = Priority of newly installed mods
+help-config-update--tag_colours = Comma separated list of '<tag>=<colour>', used to colour mods in the mod-list
+help-config-update--editors = Comma separated list of '<extension>=<command>', the editor used for those files; other files use the 'editor' setting
+help-config-update--ini_merge = How 'mods merge-ini' merges keys set by several mods
+help-config-update--run_wrappers = Command to run the game and tools through, like 'gamemoderun' or 'gamescope -W 2560 -H 1440 --'; repeat for more wrappers, pass "" to remove them
+help-config-update--threads = Number of threads used for parallel work; 0 uses the number of CPUs
+help-config-update--io_limit = Number of files extracted, hashed or linked at the same time; 0 uses 'threads'. Set this low, like 1 or 2, when the game or cache is on a hard disk
+help-config-update--notifications = Show a desktop notification when long operations, like 'extract-all', finish
+help-config-update--deployment = How mods end up in the game directory; 'overlay' is experimental, and needs fuse-overlayfs
+help-config-update--case_insensitive = Deploy into the directories already in the game directory, whatever their casing; for mods which disagree about it, like 'Data/Textures' and 'Data/textures'
+help-config-update--wine = Run the game with this wine binary instead of Steam's proton, for games not installed through Steam; pass "" to go back to proton
+help-config-update--wine_prefix = The wine prefix the game is installed in, used with 'wine'
+help-config-update--auto_update_masterlist = Download LOOT's masterlist before the plugins are sorted, or LOOT is run
+help-config-update--symbols_only = Accessibility mode: no colours; the state of mods is shown by the glyphs of their tags instead, see 'legenda'
+help-config-update--locale = Language of the messages, like 'de' or 'pt-BR'; the catalog is read from 'locales/<locale>.ftl' in starmod's data directory. Pass "" to use the environment
+help-config-update--state_dir = Keep the priority, state, tags and notes of the mods in <state-dir> instead of the cache, to put it under git; see 'state'. Pass "" to keep them in the cache again
+help-config-update--cache_images = Download the images of the mods on Nexus with 'check-updates', so 'mods show --image' does not wait for them
+help-config-update--backup_extension = Extension of the backups of files in the way of links, like 'bak'; run 'purge deployment' before changing it. Pass "" to use the default
+help-list = Various lists; defaults to showing the mod-list
+help-list-mods = Show all mods
+help-list-mods--columns = Comma separated list of columns to show; the name is always shown. Defaults to the 'list-columns' setting
+help-list-mods--sort = Sort by <column>[:asc|:desc]; one of index, name, priority, status, version, nexus-id, mod-type or size
+help-list-mods--group_by = Show the mods in groups; by tag, mod-type or status
+help-list-conflicts = Show all conflicting files in the current active mod-list; files which are identical in every mod are shown in grey
+help-list-conflicts--hide_identical = Leave out files which are identical in every mod
+help-list-files = Show all files currently in the active mod-list; Files shown in red are ignored and green files are used instead
+help-list-files--tree = Show the deployed files as a directory tree
+help-list-files--depth = Collapse directories below this depth in the tree
+help-list-disabled-files = Show all disabled files
+help-list-export = Write the mod-list as a table with the name, version, Nexus link, tags and notes of the mods, like for posting it on a forum or keeping it in git
+help-list-export--format = Format of the table
+help-list-export--all = Also list the disabled mods
+help-list-export--output = Write the table to <output> instead of showing it
+help-list-tag = Show all mods containing <tag>
+help-mods = Commands related to mods; defaults to showing the mod-list
+help-mods-copy-to-custom = Copy 'file_name' from mod 'origin_mod' to mod 'custom_mod'
+help-mods-copy-to-custom--source = The source mod to copy <file_name> from
+help-mods-copy-to-custom--destination = The destination mod to copy <file_name> to
+help-mods-copy-to-custom--file = The <file_name> from <source> mod to copy
+help-mods-clone = Copy mod 'source' into a new custom mod 'name', leaving the original untouched
+help-mods-clone--source = Name of the mod to clone
+help-mods-clone--name = Name of the new custom mod
+help-mods-create-label = Create a new label with 'name'
+help-mods-create-label--name = Name of the label
+help-mods-create-custom = Create a custom mod 'name', optionally, instead of creating a directory, link to 'origin'
+help-mods-create-custom--name = Name of the new custom mod
+help-mods-create-custom--origin = Path to the underlying directory which will be symlinked into the cache directory
+help-mods-disable = Disable mod 'name'
+help-mods-disable--name = Name of the mod to disable
+help-mods-disable-all = Disable all mods
+help-mods-disable-file = Disable 'file_name' from mod 'mod_name'
+help-mods-disable-file--name = Name of the mod which hosts <file>
+help-mods-disable-file--file = File to disable
+help-mods-enable-file = Enable 'file_name' from mod 'mod_name'
+help-mods-enable-file--name = Name of the mod which hosts <file>
+help-mods-enable-file--file = File to enable
+help-mods-edit-config = Find either <config_name> or all files with <extension> in mod <name>. Then optionally copy those files to <custom_mod>. Finally run the configured editor, which was taken from '$EDITOR', or use 'xdg-open', on those files
+help-mods-edit-config--name = name of the mod which hosts the config file
+help-mods-edit-config--destination = name of the mod which should host the modified config file
+help-mods-edit-config--config_name = Config file name, should not be used together with <--extention>
+help-mods-edit-config--extension = Config file extention. Should not be used together with <--config_name>
+help-mods-enable = Enable mod 'name'
+help-mods-enable--name = Name of the mod to enable
+help-mods-enable-all = Enable all mods, except the pinned ones
+help-mods-pin = Disable mod 'name' and keep it disabled; enabling all mods and upgrading leave it alone until it is unpinned
+help-mods-pin--name = Name of the mod to pin
+help-mods-unpin = Let pinned mod 'name' be enabled again; it stays disabled until it is enabled
+help-mods-unpin--name = Name of the mod to unpin
+help-mods-endorse = Endorse mod 'name' on Nexus, to thank its author
+help-mods-endorse--name = Name of the mod to endorse
+help-mods-endorse--abstain = Abstain from endorsing the mod instead, so Nexus stops asking
+help-mods-changelog = Show the changes made on Nexus since the installed version of mod 'name'
+help-mods-changelog--name = Name of the mod to show the changes of
+help-mods-changelog--all = Show the changes of every version, including the installed one and older
+help-mods-open = Open the Nexus page of mod 'name' in the browser
+help-mods-open--name = Name of the mod to open
+help-mods-set-nexus-id = Link mod 'name' to mod 'nexus-id' on Nexus, for archives downloaded without dmodman; so its updates can be checked
+help-mods-set-nexus-id--name = Name of the mod to link
+help-mods-set-nexus-id--nexus_id = The id of the mod on Nexus, as in its url
+help-mods-set-nexus-id--file_id = The id of the installed file on Nexus, to learn its version
+help-mods-set-nexus-id--md5 = Find the mod and file on Nexus by the md5 checksum of its archive instead
+help-mods-refresh-metadata = Ask Nexus about every mod with a Nexus id, to fill in the authors and categories missing from their manifests, and to learn their latest versions
+help-mods-refresh-metadata--rename = Rename the mods to their names on Nexus too, unless another mod has that name
+help-mods-track = Track mod 'name' on Nexus, to hear about its updates
+help-mods-track--name = Name of the mod to track
+help-mods-untrack = Stop tracking mod 'name' on Nexus
+help-mods-untrack--name = Name of the mod to stop tracking
+help-mods-list = Show all mods; Alias from 'mod list'
+help-mods-where = Show where <file> from mod <name> ends up: its path in the cache, its destination, the state of the game directory and, when it lost a conflict, the mod which provides it instead
+help-mods-where--name = Name of the mod which hosts <file>
+help-mods-where--file = Source or destination of the file
+help-mods-show = Show the details of mod 'name'
+help-mods-show--name = Name of the mod to show
+help-mods-show--tree = Show the files of the mod as a directory tree
+help-mods-show--depth = Collapse directories below this depth in the tree
+help-mods-show--image = Show the image of the mod on Nexus, in the terminal when it can; the image is kept in the cache directory
+help-mods-show--docs = Show the readme or changelog of the mod
+help-mods-tag-add = Add tag <tag> to mod <name>; 'needs:<what>' and 'provides:<what>' tags declare what mods need to run, which is checked before the game starts
+help-mods-tag-add--name = Name of the mod to add <tag> to
+help-mods-tag-add--tag = Name of the tag
+help-mods-tag-remove = Remove tag <tag> from mod <name>
+help-mods-tag-remove--name = Name of the mod to add <tag> to
+help-mods-tag-remove--tag = Name of the tag
+help-mods-reinstall-all = Re-run the installers of all mods, or only those of kind <kind>; priority, tags and enabled state are preserved
+help-mods-reinstall-all--kind = Only re-install mods of this kind
+help-mods-remove = Remove mod 'name' from installation. Does not remove the mod from the downloads directory
+help-mods-remove--name = Name of the mod to remove from the mod-list..
+help-mods-rename = Rename mod 'old_mod_name' to 'new_mod_name'
+help-mods-set-priority = Set mod to new priority; mods with a higher priority win the conflicts with mods of a lower priority
+help-mods-set-priority--name = Name of the mod to set to the new priority
+help-mods-set-priority--priority = value of the new priority
+help-mods-set-priority--renumber = When another mod already has this priority, renumber the mod-list; this mod is placed before the mods it collides with
+help-mods-renumber = Spread the priorities evenly (10, 20, 30, ...), leaving room to insert mods in between; the order of the mods does not change
+help-mods-renumber--step = Distance between consecutive priorities
+help-mods-sequence = Show the order of mods with the same priority, which follows the order they were installed in; with <name>, move that mod within its priority to <sequence>
+help-mods-sequence--name = Name of the mod to move
+help-mods-sequence--sequence = Its new sequence number; mods with a lower number are deployed before it
+help-mods-sequence--renumber = Number all mods 1, 2, 3, ... in their current order, to make the order of mods installed before it was recorded explicit
+help-mods-merge-ini = Merge ini files shipped by more than one enabled mod, key by key, into a custom mod which is placed above all other mods and enabled; run again after changes
+help-mods-merge-ini--name = Name of the custom mod holding the merged files
+help-mods-merge-ini--strategy = How to merge keys set by several mods; defaults to the 'ini-merge' setting
+help-mods-create-launcher = Write a desktop entry which runs an executable of mod <name> through proton, so tools like xEdit can be started from the desktop environment
+help-mods-create-launcher--output = Directory to write the desktop entry to; defaults to the user's applications directory
+help-mods-notes = Set the notes of a mod; leave out the notes to remove them
+help-mods-merge = Merge mods <names> into a new custom mod <into>, holding the files which would win between them; useful to flatten many small patches into a single mod
+help-mods-merge--names = Names of the mods to merge; at least two
+help-mods-merge--into = Name of the new custom mod
+help-mods-merge--disable = Disable the merged mods, and enable the new mod when any of them was enabled
+help-mods-split = Move the files of mod <name> matching <path> into a new mod <into>, so optional parts of a mod can be enabled and disabled on their own
+help-mods-split--path = Glob matched, case-insensitively, against both the path of a file within the mod and its destination; like 'data/textures/*'
+help-mods-split--into = Name of the new mod
+help-mods-remap = Deploy the files of mod <name> matching <from> to <to> instead; without <to> the rule for <from> is removed and without <from> the rules of the mod are shown. Without wildcards <from> is a single file which is renamed to <to>, otherwise the directory in front of the first wildcard is replaced by <to>
+help-mods-remap--from = Glob matched, case-insensitively, against the destinations of the files; like 'Data/foo/textures/*'
+help-mods-remap--to = The new destination, like 'Data/textures'
+help-mods-exclude = Leave the files of mod <name> matching <pattern> out, without disabling them one by one; without <pattern> the exclusions of the mod are shown
+help-mods-exclude--pattern = Glob matched, case-insensitively, against both the path of a file within the mod and its destination; like '**/*.psd'
+help-mods-exclude--remove = Remove the exclusion <pattern> instead
+help-mods-lint = Check the files of mod <name>, or of all mods, for common packaging mistakes: no files, files outside 'Data' or programs in a data mod, two files with the same destination and plugins and archives not named after each other. Fails when any error is found
+help-mods-lint--json = Print the findings as json
+help-mods-pack = Re-archive the files of mod <name> into <output>, so edited or custom mods can be shared or backed up; the format follows the extension of <output>
+help-mods-pack--with_manifest = Also store starmod's manifest and state of the mod in the root of the archive; extracting the archive takes over its priority, tags and notes
+help-enable = Enable mod 'name'; alias for 'mods enable'
+help-enable--name = Name of the mod to enable
+help-disable = Disable mod 'name'; alias for 'mods disable'
+help-disable--name = Name of the mod to disable
+help-set-priority = Set mod to new priority; alias for 'mods set-priority'
+help-set-priority--name = Name of the mod to set to the new priority
+help-set-priority--priority = value of the new priority
+help-set-priority--renumber = When another mod already has this priority, renumber the mod-list; this mod is placed before the mods it collides with
+help-show = Show the details of mod 'name'; alias for 'mods show'
+help-show--name = Name of the mod to show
+help-show--tree = Show the files of the mod as a directory tree
+help-show--depth = Collapse directories below this depth in the tree
+help-show--image = Show the image of the mod on Nexus
+help-show--docs = Show the readme or changelog of the mod
+help-tags = Commands related to tags over all mods; defaults to listing the tags
+help-tags-list = List all tags, with the number of mods which have them
+help-tags-rename = Rename tag <old> to <new> in all mods
+help-tags-delete = Remove tag <tag> from all mods
+help-tags-apply = Add tag <tag> to all mods which match every filter
+help-tags-apply--filters = Only tag mods matching this filter, like 'kind:data' or 'name:armor'
+help-downloads = Commands related to download archives; defaults to showing the downloaded files
+help-downloads-list = List all archives in the download directory
+help-downloads-extract = Extract given archive
+help-downloads-extract--priority = Priority of the new mod; other mods move up to make room. Defaults to the 'new-mod-priority' setting
+help-downloads-extract--after = Place the new mod directly after this mod; other mods move up to make room
+help-downloads-extract--password = Password of a 7z or rar archive; asked for when extracting fails without one
+help-downloads-extract--preview = Show which enabled mods the new mod would overwrite and lose to, and ask whether to keep it, change its priority or remove it again
+help-downloads-extract-all = Extract all archives which are not in the cache directory
+help-downloads-extract-all--priority = Priority of the new mod; other mods move up to make room. Defaults to the 'new-mod-priority' setting
+help-downloads-extract-all--after = Place the new mod directly after this mod; other mods move up to make room
+help-downloads-extract-all--retry_failed = Only extract the archives which failed during the previous 'extract-all'
+help-downloads-extract-all--password = Password for the 7z and rar archives; archives without a password extract as usual
+help-downloads-re-install = Re-install given archive
+help-downloads-open = Open the Nexus page of the given archive in the browser; needs its dmodman metadata
+help-downloads-identify = Find the given archive on Nexus by its md5 checksum, and write its dmodman metadata; for archives downloaded with the browser
+help-downloads-identify--force = Replace the metadata the archive already has
+help-downloads-upgrade-all = Update all mods which have an archive in the archive directory with a newer version
+help-downloads-upgrade = Update mod which have an archive in the archive directory with a newer version
+help-downloads-upgrade--check = Only show which mods have a newer archive available; do not change anything
+help-downloads-upgrade--json = Print the result of '--check' as json
+help-game = Game related commands; defaults to running the game
+help-game-run = Run 'cmd'; defaults to running the game
+help-game-run--skip_checks = Don't check that the game is ready to run before running it
+help-game-run--detach = Start the program in the background and return immediately, without its output
+help-game-run-game = Run the game
+help-game-run-loader = Run the game's script extender
+help-game-run-loot = Run loot
+help-game-run-xedit = Run the game's xedit
+help-game-run-tool = Run a windows executable, like a tool installed by a mod
+help-game-run-tool--executable = Path to the executable
+help-game-edit-config = Edit game config files using the editor configured for their extension, $EDITOR or 'xdg-open'
+help-game-edit-config--config_name = Name of the config-file to edit; If not supplied, all known files will be supplied to the editor. Uses the $EDITOR as defined when the config file is created, or runs 'xdg-open'
+help-game-print-launch-command = Print the command which runs 'cmd' through proton or wine, like 'run' does
+help-game-print-launch-command-game = Run the game
+help-game-print-launch-command-loader = Run the game's script extender
+help-game-print-launch-command-loot = Run loot
+help-game-print-launch-command-xedit = Run the game's xedit
+help-game-print-launch-command-tool = Run a windows executable, like a tool installed by a mod
+help-game-print-launch-command-tool--executable = Path to the executable
+help-game-set-steam-launch = Set the launch options of the game in Steam, so starting it from Steam runs the pre-flight checks and the default run command
+help-game-set-steam-launch--print = Only print the launch options, instead of changing Steam's configuration
+help-game-snapshot = Record the hashes of the game's own files, preferably before modding it; 'verify' compares the game directory with them
+help-game-snapshot--force = Replace an existing snapshot
+help-game-verify = Show which of the game's own files were changed, removed or added since the snapshot, other than by deploying mods
+help-game-verify--full = Hash every file again, instead of trusting an unchanged size and modification time
+help-game-prefix = Show the winetricks components of the wine prefix of the game, or install them
+help-game-prefix-list = Show the installed components, and those the enabled mods need with 'needs:' tags
+help-game-prefix-install = Install winetricks verbs into the prefix, with protontricks, or winetricks when the game runs with wine
+help-game-steam-run = Used by the Steam launch options; runs Steam's command line for the game
+help-run = Alias for Game Run; defaults to running the game
+help-run--skip_checks = Don't check that the game is ready to run before running it
+help-run--detach = Start the program in the background and return immediately, without its output
+help-run-game = Run the game
+help-run-loader = Run the game's script extender
+help-run-loot = Run loot
+help-run-xedit = Run the game's xedit
+help-run-tool = Run a windows executable, like a tool installed by a mod
+help-run-tool--executable = Path to the executable
+help-export = Export the current setup to other formats
+help-export-script = Write a shell script with the 'mkdir' and 'ln -s' commands which reproduce the current deployment, without needing starmod
+help-export-script--file = The file to write the script to
+help-import = Import mods which are not in the download directory
+help-import-staging = Install every already-extracted mod directory within <dir>, like the staging directory of another mod manager or manually extracted archives
+help-import-staging--dir = Directory which holds one directory per mod
+help-import-staging--link = Symlink the files into the cache instead of copying them; the mods then depend on <dir> staying in place
+help-import-staging--priority = Priority of the new mod; other mods move up to make room. Defaults to the 'new-mod-priority' setting
+help-import-staging--after = Place the new mod directly after this mod; other mods move up to make room
+help-import-collection = Install the mods of a Nexus collection from its 'collection.json', in the order and with the installer choices of the collection, and take over its load order. Archives missing from the download directory are downloaded from Nexus
+help-import-collection--file = The 'collection.json' of the collection
+help-import-collection--optional = Install the mods the collection marks as optional as well
+help-cache = Commands related to the cache directory; defaults to auditing the destinations of the mods
+help-cache-audit-paths = Check the destinations of the files of all mods for backslashes, doubled separators, names with the wrong casing and paths which escape the game directory
+help-cache-audit-paths--fix = Rewrite the destinations into their canonical form; files which would end up outside of the game directory are disabled
+help-cache-audit-windows-paths = Check the destinations of the files of all mods for what windows does not allow, like the characters ':<>|?*' or paths over 259 characters; the game does not load such files under Proton
+help-cache-audit-windows-paths--fix = Add remap rules which rename the files to names windows allows; see 'mods remap'. Paths which are too long are only reported
+help-deploy = Link the files of all enabled mods into the game directory, only changing what differs; safe to run again, like after batching changes or from a hook
+help-deploy--dry_run = Only show what would change
+help-purge = Dangerous: commands related to the removal of starmod's files
+help-purge-config = Remove both config and cache; This removes all of starmod's generated files
+help-purge-cache = Remove cache directory, but keep the config files
+help-purge-deployment = Remove all of starmod's links from the game directory and restore the original files; The cache, manifests and config are kept
+help-which = Show which mod provides <path>, which other mods contain it, and whether a backup of the original game file exists
+help-which--path = Path relative to the game directory, like 'Data/textures/foo.dds'
+help-stats = Show an overview of the installed mods, their files and disk usage
+help-history = Show the most recent changes made to the mod-list
+help-history--count = Number of operations to show
+help-log = Show starmod's log file; defaults to the whole current log
+help-log--tail = Only show the last <tail> entries
+help-log--level = Only show entries of at least <level>
+help-log--follow = Keep showing new entries as they are written
+help-log--all = Include the rotated, older log files
+help-legenda = Show explanation of the colours and tag glyphs used by starmod
+help-diagnose = Show a report on starmod, its settings, mods, log and environment, to paste into a bug report
+help-diagnose--redact = Leave the home directory and user name out of the report
+help-diagnose--log_entries = Number of log entries to include
+help-list-commands = Show a flattened list all commands
+help-plugin = Plugin related commands
+help-plugin-show = Show the plugins in the game's 'Data' directory, with the enabled mod which provides each; warns about plugins which no enabled mod provides
+help-plugin-clean = Run xEdit's quick auto-clean on <plugin>, removing records identical to their master (ITM) and undeleting deleted references (UDR)
+help-plugin-rule = Sorting rules for LOOT; defaults to listing them
+help-plugin-rule-list = List all plugins with rules
+help-plugin-rule-add = Add a rule for <plugin>
+help-plugin-rule-add--after = Load <plugin> after this plugin; can be given multiple times
+help-plugin-rule-add--group = Put <plugin> in this LOOT group
+help-plugin-rule-remove = Remove all rules of <plugin>
+help-plugin-export = Write the load order of the game to <file>, to share it with other tools
+help-plugin-export--format = Format of <file>; defaults to 'load-order' for a 'loadorder.txt', and 'plugins' otherwise
+help-plugin-import = Replace the load order of the game with the one in <file>
+help-plugin-import--format = Format of <file>; defaults to 'load-order' for a 'loadorder.txt', and 'plugins' otherwise
+help-plugin-update-masterlist = Download LOOT's masterlist of the game into LOOT's data directory, for the latest sorting metadata of the community
+help-check-updates = Ask Nexus which installed mods have an update, for the notes of the mod-list; asks nothing, so it can run from a timer
+help-check-updates--max_age = Only ask about mods which were not checked in the last <max-age> hours
+help-check-updates--force = Ask about every mod, however recently it was checked
+help-state = Keep the history of the mod-list with git, in the 'state-dir'; defaults to showing it
+help-state-log = Show the most recent commits of the state directory
+help-state-log--count = Number of commits to show
+help-state-commit = Copy the load order into the state directory, and commit every change to git; the repository is created when there is none yet
+help-state-commit--message = The commit message
+help-alias = Shortcuts for commands, like 'up' for 'downloads upgrade-all'; defaults to listing the aliases
+help-alias-list = List all aliases with their commands
+help-alias-add = Add alias <name> for <command>, replacing an existing alias of that name
+help-alias-add--command = The command with its arguments, like 'downloads upgrade-all'
+help-alias-remove = Remove alias <name>
+help-daemon = Keep running, and take requests to enable, disable and deploy mods on a unix socket; each request and answer is a single line of json
+help-daemon--socket = Socket to listen on; defaults to 'starmod/daemon.sock' in the runtime directory
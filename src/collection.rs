@@ -0,0 +1,128 @@
+use std::{fs::File, io::BufReader};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Deserialize;
+
+use crate::{
+    decompress::SupportedArchives, dmodman::DmodMan, installers::fomod::FomodChoices,
+    utils::AddExtension,
+};
+
+/// The manifest of a Nexus collection, the 'collection.json' in the collection archive.
+///
+/// Only the parts starmod uses are read; the mods in their install order,
+/// the choices made in their installers and the load order.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub info: CollectionInfo,
+    #[serde(default)]
+    pub mods: Vec<CollectionMod>,
+    #[serde(default)]
+    pub plugins: Vec<CollectionPlugin>,
+}
+impl Collection {
+    /// Read the collection at `path`.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be read, or is not a collection.
+    pub fn read(path: &Utf8Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionInfo {
+    pub name: String,
+    pub author: Option<String>,
+    /// The game of the collection, as Nexus calls it.
+    pub domain_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionMod {
+    pub name: String,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    pub source: ModSource,
+    pub choices: Option<InstallerChoices>,
+    /// Mods of a later phase are installed after all mods of earlier phases.
+    #[serde(default)]
+    pub phase: u32,
+}
+impl CollectionMod {
+    pub fn fomod_choices(&self) -> Option<&FomodChoices> {
+        match self.choices.as_ref()? {
+            InstallerChoices::Fomod(choices) => Some(choices),
+            InstallerChoices::Other => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSource {
+    #[serde(rename = "type")]
+    pub kind: SourceKind,
+    pub mod_id: Option<u32>,
+    pub file_id: Option<u64>,
+    pub md5: Option<String>,
+    pub logical_filename: Option<String>,
+}
+impl ModSource {
+    /// The mod and file id of a mod which can be downloaded from Nexus.
+    pub fn nexus_ids(&self) -> Option<(u32, u64)> {
+        match self.kind {
+            SourceKind::Nexus => self.mod_id.zip(self.file_id),
+            SourceKind::Other => None,
+        }
+    }
+
+    /// Find the archive of this mod in `archives`, by the metadata dmodman wrote for it.
+    pub fn find_archive(
+        &self,
+        download_dir: &Utf8Path,
+        archives: &[(SupportedArchives, Utf8PathBuf)],
+    ) -> Option<Utf8PathBuf> {
+        let (mod_id, file_id) = self.nexus_ids()?;
+        archives
+            .iter()
+            .map(|(_, archive)| archive)
+            .find(|archive| {
+                DmodMan::try_from(download_dir.join(archive).add_extension("json"))
+                    .is_ok_and(|dmod| dmod.mod_id() == mod_id && dmod.file_id() == file_id)
+            })
+            .cloned()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    Nexus,
+    /// Direct downloads, bundled or manually downloaded archives; these have to be in the
+    /// download directory already.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum InstallerChoices {
+    Fomod(FomodChoices),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CollectionPlugin {
+    pub name: String,
+    #[serde(default)]
+    pub enabled: bool,
+}
@@ -1,33 +1,53 @@
+pub mod alias;
+pub mod cache;
 pub mod config;
+pub mod daemon;
+pub mod deploy;
+pub mod diagnose;
 pub mod downloads;
+pub mod export;
 pub mod game;
+pub mod history;
+pub mod import;
 pub mod list;
+pub mod logs;
 pub mod mods;
+pub mod plugins;
+pub mod preflight;
 pub mod purge;
+pub mod state;
+pub mod stats;
+pub mod tags;
 
 use anyhow::Result;
-use clap::{builder::styling, Parser};
+use camino::Utf8PathBuf;
+use clap::{builder::styling, Command, CommandFactory, Parser};
 use comfy_table::{Cell, Color};
 
-use crate::{list_commands, settings::create_table, tag::Tag, Settings};
+use crate::{
+    i18n::{localize_command, tr},
+    nexus::updates,
+    settings::{create_table, LogLevel, Settings},
+    tag::Tag,
+    ui::page,
+};
 
 use self::{
+    alias::AliasCmd,
+    cache::CacheCmd,
     config::ConfigCmd,
     downloads::DownloadCmd,
-    game::{GameCmd, RunCmd},
+    export::ExportCmd,
+    game::{GameCmd, RunCmd, RunOptions},
+    import::ImportCmd,
     list::ListCmd,
     mods::ModCmd,
+    plugins::PluginCmd,
     purge::PurgeCmd,
+    state::StateCmd,
+    tags::TagCmd,
 };
 
-#[cfg(feature = "loadorder")]
-pub mod plugins;
-#[cfg(feature = "loadorder")]
-use self::plugins::PluginCmd;
-
-//TODO: we should probably add the most used commands here too
-// like set-priority etc
-
 const STYLE: styling::Styles = styling::Styles::styled()
     .header(styling::AnsiColor::BrightYellow.on_default())
     .usage(styling::AnsiColor::BrightYellow.on_default())
@@ -37,6 +57,7 @@ const STYLE: styling::Styles = styling::Styles::styled()
 #[derive(Debug, Clone, Parser)]
 #[command()]
 #[clap(styles=STYLE)]
+#[allow(clippy::large_enum_variant)]
 pub enum Subcommands {
     /// Config related commands; defaults to showing the current settings.
     #[clap(visible_aliases = &["configs", "c"])]
@@ -56,6 +77,54 @@ pub enum Subcommands {
         #[command(subcommand)]
         cmd: Option<ModCmd>,
     },
+    /// Enable mod 'name'; alias for 'mods enable'.
+    #[clap(visible_alias = "en")]
+    Enable {
+        /// Name of the mod to enable
+        name: Option<String>,
+    },
+    /// Disable mod 'name'; alias for 'mods disable'.
+    #[clap(visible_alias = "dis")]
+    Disable {
+        /// Name of the mod to disable
+        name: Option<String>,
+    },
+    /// Set mod to new priority; alias for 'mods set-priority'.
+    #[clap(visible_aliases = &["set-prio", "sp"])]
+    SetPriority {
+        /// Name of the mod to set to the new priority
+        name: Option<String>,
+        /// value of the new priority.
+        priority: Option<isize>,
+        /// When another mod already has this priority, renumber the mod-list;
+        /// this mod is placed before the mods it collides with.
+        #[arg(short, long)]
+        renumber: bool,
+    },
+    /// Show the details of mod 'name'; alias for 'mods show'.
+    #[clap(visible_alias = "s")]
+    Show {
+        /// Name of the mod to show.
+        name: Option<String>,
+        /// Show the files of the mod as a directory tree.
+        #[arg(short, long)]
+        tree: bool,
+        /// Collapse directories below this depth in the tree.
+        #[arg(short, long, requires = "tree")]
+        depth: Option<usize>,
+        /// Show the image of the mod on Nexus.
+        #[arg(short, long)]
+        image: bool,
+        /// Show the readme or changelog of the mod.
+        #[arg(long)]
+        docs: bool,
+    },
+    /// Commands related to tags over all mods; defaults to listing the tags.
+    #[clap(visible_aliases = &["tag", "t"])]
+    Tags {
+        #[command(subcommand)]
+        cmd: Option<TagCmd>,
+    },
     /// Commands related to download archives; defaults to showing the downloaded files.
     #[clap(visible_aliases = &["download", "down", "d"])]
     Downloads {
@@ -73,25 +142,148 @@ pub enum Subcommands {
     Run {
         #[command(subcommand)]
         cmd: Option<RunCmd>,
+        #[command(flatten)]
+        options: RunOptions,
+    },
+    /// Export the current setup to other formats.
+    Export {
+        #[command(subcommand)]
+        cmd: ExportCmd,
+    },
+    /// Import mods which are not in the download directory.
+    Import {
+        #[command(subcommand)]
+        cmd: ImportCmd,
+    },
+    /// Commands related to the cache directory; defaults to auditing the destinations of the mods.
+    Cache {
+        #[command(subcommand)]
+        cmd: Option<CacheCmd>,
+    },
+    /// Link the files of all enabled mods into the game directory, only changing what differs;
+    /// safe to run again, like after batching changes or from a hook.
+    Deploy {
+        /// Only show what would change.
+        #[arg(short = 'n', long)]
+        dry_run: bool,
     },
     /// Dangerous: commands related to the removal of starmod's files.
     Purge {
         #[command(subcommand)]
         cmd: PurgeCmd,
     },
-    /// Show explanation of the colours used by starmod.
+    /// Show which mod provides <path>, which other mods contain it,
+    /// and whether a backup of the original game file exists.
+    Which {
+        /// Path relative to the game directory, like 'Data/textures/foo.dds'.
+        path: String,
+    },
+    /// Show an overview of the installed mods, their files and disk usage.
+    Stats,
+    /// Show the most recent changes made to the mod-list.
+    History {
+        /// Number of operations to show.
+        #[arg(short, long, default_value_t = 20)]
+        count: usize,
+    },
+    /// Show starmod's log file; defaults to the whole current log.
+    Log {
+        /// Only show the last <tail> entries.
+        #[arg(short, long)]
+        tail: Option<usize>,
+        /// Only show entries of at least <level>.
+        #[arg(short, long, value_enum)]
+        level: Option<LogLevel>,
+        /// Keep showing new entries as they are written.
+        #[arg(short, long)]
+        follow: bool,
+        /// Include the rotated, older log files.
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Show explanation of the colours and tag glyphs used by starmod.
     Legenda,
+    /// Show a report on starmod, its settings, mods, log and environment, to paste into a bug
+    /// report.
+    Diagnose {
+        /// Leave the home directory and user name out of the report.
+        #[arg(short, long)]
+        redact: bool,
+        /// Number of log entries to include.
+        #[arg(short, long, default_value_t = 20)]
+        log_entries: usize,
+    },
     /// Show a flattened list all commands
     ListCommands,
 
-    #[cfg(feature = "loadorder")]
     /// Plugin related commands
     Plugin {
         #[command(subcommand)]
         cmd: Option<PluginCmd>,
     },
+    /// Ask Nexus which installed mods have an update, for the notes of the mod-list;
+    /// asks nothing, so it can run from a timer.
+    CheckUpdates {
+        /// Only ask about mods which were not checked in the last <max-age> hours.
+        #[arg(short, long, default_value_t = 12)]
+        max_age: u32,
+        /// Ask about every mod, however recently it was checked.
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Keep the history of the mod-list with git, in the 'state-dir'; defaults to showing it.
+    State {
+        #[command(subcommand)]
+        cmd: Option<StateCmd>,
+    },
+    /// Shortcuts for commands, like 'up' for 'downloads upgrade-all'; defaults to listing the aliases.
+    Alias {
+        #[command(subcommand)]
+        cmd: Option<AliasCmd>,
+    },
+    /// Keep running, and take requests to enable, disable and deploy mods on a unix socket;
+    /// each request and answer is a single line of json.
+    Daemon {
+        /// Socket to listen on; defaults to 'starmod/daemon.sock' in the runtime directory.
+        #[arg(short, long)]
+        socket: Option<Utf8PathBuf>,
+    },
 }
 impl Subcommands {
+    /// Whether the command changes nothing of the mod-list, the cache or the configuration, and
+    /// so has no changes to record; writing a file the user asked for, like an export, is fine.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::Show { .. }
+            | Self::Which { .. }
+            | Self::Stats
+            | Self::History { .. }
+            | Self::Log { .. }
+            | Self::Legenda
+            | Self::Diagnose { .. }
+            | Self::ListCommands => true,
+            Self::Deploy { dry_run } => *dry_run,
+            Self::Config { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::List { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Mods { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Tags { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Downloads { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Game { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Export { cmd } => cmd.is_read_only(),
+            Self::Import { cmd } => cmd.is_read_only(),
+            Self::Cache { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Purge { cmd } => cmd.is_read_only(),
+            Self::Plugin { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::State { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Alias { cmd } => cmd.clone().unwrap_or_default().is_read_only(),
+            Self::Enable { .. }
+            | Self::Disable { .. }
+            | Self::SetPriority { .. }
+            | Self::Run { .. }
+            | Self::CheckUpdates { .. }
+            | Self::Daemon { .. } => false,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         //General TODO: Be more consistant in errors, error messages warnings etc.
 
@@ -99,21 +291,78 @@ impl Subcommands {
             Self::Config { cmd } => ConfigCmd::execute(cmd.unwrap_or_default(), settings),
             Self::List { cmd } => ListCmd::execute(cmd.unwrap_or_default(), settings),
             Self::Mods { cmd } => ModCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::Enable { name } => ModCmd::Enable { name }.execute(settings),
+            Self::Disable { name } => ModCmd::Disable { name }.execute(settings),
+            Self::SetPriority {
+                name,
+                priority,
+                renumber,
+            } => ModCmd::SetPriority {
+                name,
+                priority,
+                renumber,
+            }
+            .execute(settings),
+            Self::Show {
+                name,
+                tree,
+                depth,
+                image,
+                docs,
+            } => ModCmd::Show {
+                name,
+                tree,
+                depth,
+                image,
+                docs,
+            }
+            .execute(settings),
+            Self::Tags { cmd } => TagCmd::execute(cmd.unwrap_or_default(), settings),
             Self::Downloads { cmd } => DownloadCmd::execute(cmd.unwrap_or_default(), settings),
-            Self::Run { cmd } => RunCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::Run { cmd, options } => {
+                RunCmd::execute(cmd.unwrap_or_default(), settings, options)
+            }
             Self::Game { cmd } => GameCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::Cache { cmd } => CacheCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::Deploy { dry_run } => deploy::deploy(settings, dry_run),
             Self::Purge { cmd } => PurgeCmd::execute(cmd, settings),
+            Self::Export { cmd } => cmd.execute(settings),
+            Self::Import { cmd } => cmd.execute(settings),
+            Self::Which { path } => mods::which(settings, &path),
+            Self::Stats => stats::show_stats(settings),
+            Self::History { count } => history::show_history(settings, count),
+            Self::Log {
+                tail,
+                level,
+                follow,
+                all,
+            } => logs::show_log(settings, tail, level, follow, all),
             Self::ListCommands => {
-                list_commands();
+                list_commands(&localize_command(Self::command()));
                 Ok(())
             }
+            Self::Diagnose {
+                redact,
+                log_entries,
+            } => diagnose::show_report(
+                settings,
+                concat!("starmod ", env!("CARGO_PKG_VERSION")),
+                redact,
+                log_entries,
+            ),
             Self::Legenda => {
                 show_legenda();
                 Ok(())
             }
-
-            #[cfg(feature = "loadorder")]
             Self::Plugin { cmd } => PluginCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::CheckUpdates { max_age, force } => {
+                updates::check_updates(settings, chrono::Duration::hours(max_age.into()), force)
+            }
+            Self::State { cmd } => StateCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::Alias { cmd } => AliasCmd::execute(cmd.unwrap_or_default(), settings),
+            Self::Daemon { socket } => {
+                daemon::serve(settings, &daemon::socket_path(settings, socket)?)
+            }
         }
     }
 }
@@ -126,55 +375,65 @@ impl Default for Subcommands {
 }
 
 pub fn show_legenda() {
-    let mut table = create_table(vec!["Tag", "Color", "Meaning"]);
-
-    let tag = Tag::Enabled;
-    let (color, chr) = (Color::from(tag), char::from(tag));
-    table.add_row(vec![
-        Cell::new(chr.to_string()).fg(color),
-        Cell::new("White").fg(color),
-        Cell::new("Nothing to see here; move along citizen.").fg(color),
+    let mut table = create_table(vec![
+        tr("legenda-tag"),
+        tr("legenda-color"),
+        tr("legenda-meaning"),
     ]);
+    for tag in Tag::ALL {
+        let (color, chr) = (Color::from(tag), char::from(tag));
+        table.add_row(vec![
+            Cell::new(chr.to_string()).fg(color),
+            Cell::new(format!("{color:?}")).fg(color),
+            Cell::new(tag.meaning()).fg(color),
+        ]);
+    }
 
-    let tag = Tag::Winner;
-    let (color, chr) = (Color::from(tag), char::from(tag));
-    table.add_row(vec![
-        Cell::new(chr.to_string()).fg(color),
-        Cell::new("Green").fg(color),
-        Cell::new("Conflict winner").fg(color),
-    ]);
+    log::info!("{table}");
+}
 
-    let tag = Tag::Loser;
-    let (color, chr) = (Color::from(tag), char::from(tag));
-    table.add_row(vec![
-        Cell::new(chr.to_string()).fg(color),
-        Cell::new("Yellow").fg(color),
-        Cell::new("Conflict loser").fg(color),
-    ]);
+/// Show every (sub)command below `cmd` with its help.
+pub fn list_commands(cmd: &Command) {
+    let mut table = create_table(vec!["Command", "Help"]);
+    let mut list = vec![];
 
-    let tag = Tag::CompleteLoser;
-    let (color, chr) = (Color::from(tag), char::from(tag));
-    table.add_row(vec![
-        Cell::new(chr.to_string()).fg(color),
-        Cell::new("Red").fg(color),
-        Cell::new("Complete conflict loser; ALL files are overwitten by other mods").fg(color),
-    ]);
+    list.extend_from_slice(&gather_commands(cmd, cmd.get_name()));
 
-    let tag = Tag::Conflict;
-    let (color, chr) = (Color::from(tag), char::from(tag));
-    table.add_row(vec![
-        Cell::new(chr.to_string()).fg(color),
-        Cell::new("Magenta").fg(color),
-        Cell::new("Conflict winner for some files, conflict loser for other files.").fg(color),
-    ]);
+    list.sort();
 
-    let tag = Tag::Disabled;
-    let (color, chr) = (Color::from(tag), char::from(tag));
-    table.add_row(vec![
-        Cell::new(chr.to_string()).fg(color),
-        Cell::new("DarkGray").fg(color),
-        Cell::new("Mod is disabled.").fg(color),
-    ]);
+    for (prev_cmd, c, help) in list {
+        let mut cmdtable = create_table(vec!["", ""]);
+        cmdtable.add_row(vec![
+            Cell::new(prev_cmd).fg(Color::DarkCyan),
+            Cell::new(c).fg(Color::White),
+        ]);
 
-    log::info!("{table}");
+        table.add_row(vec![
+            Cell::new(format!("{}", cmdtable.lines().last().unwrap())),
+            Cell::new(help),
+        ]);
+    }
+
+    page(&format!("\n{table}"));
+}
+
+fn gather_commands(
+    cmd: &Command,
+    previous_cmds: &str,
+) -> Vec<(String, String, clap::builder::StyledStr)> {
+    let mut list = Vec::new();
+
+    for cmd in cmd.get_subcommands() {
+        list.push((
+            previous_cmds.to_string(),
+            cmd.get_name().to_string(),
+            cmd.get_about().unwrap_or_default().to_owned(),
+        ));
+
+        if cmd.has_subcommands() {
+            let lcmd = previous_cmds.to_string() + " " + cmd.get_name();
+            list.extend_from_slice(&gather_commands(cmd, &lcmd));
+        }
+    }
+    list
 }
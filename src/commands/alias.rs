@@ -0,0 +1,115 @@
+use std::{collections::BTreeMap, ffi::OsString};
+
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use comfy_table::Cell;
+
+use crate::{
+    commands::Subcommands,
+    errors::SettingErrors,
+    settings::{create_table, Settings},
+    ui::page,
+};
+
+/// Shortcuts for commands, like 'up' for 'downloads upgrade-all'; defaults to listing the aliases.
+#[derive(Debug, Clone, Parser, Default)]
+#[clap(
+    after_help = "An alias is replaced by its command before the arguments are read; with alias 'ta' for 'mods tag-add', 'starmod ta armor favourite' runs 'starmod mods tag-add armor favourite'."
+)]
+pub enum AliasCmd {
+    /// List all aliases with their commands.
+    #[default]
+    #[clap(visible_alias = "l")]
+    List,
+    /// Add alias <name> for <command>, replacing an existing alias of that name.
+    #[clap(visible_alias = "a")]
+    Add {
+        name: String,
+        /// The command with its arguments, like 'downloads upgrade-all'.
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Remove alias <name>.
+    #[clap(visible_aliases = &["rm", "r"])]
+    Remove { name: String },
+}
+impl AliasCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::List => true,
+            Self::Add { .. } | Self::Remove { .. } => false,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::List => {
+                list_aliases(settings);
+                Ok(())
+            }
+            Self::Add { name, command } => {
+                if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+                    return Err(SettingErrors::InvalidAliasName(name).into());
+                }
+                if Subcommands::command().find_subcommand(&name).is_some() {
+                    return Err(SettingErrors::AliasIsCommand(name).into());
+                }
+                let command = command.join(" ");
+                let settings = settings.set_alias(&name, Some(command.clone()))?;
+                log::info!("Added alias '{name}' for '{command}'.");
+                list_aliases(&settings);
+                Ok(())
+            }
+            Self::Remove { name } => {
+                let settings = settings.set_alias(&name, None)?;
+                log::info!("Removed alias '{name}'.");
+                list_aliases(&settings);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn list_aliases(settings: &Settings) {
+    let mut table = create_table(vec!["Alias", "Command"]);
+    for (name, command) in settings.aliases() {
+        table.add_row(vec![Cell::new(name), Cell::new(command)]);
+    }
+    table.add_row_if(|idx, _row| idx.eq(&0), vec![Cell::new("No aliases found.")]);
+
+    page(&table.to_string());
+}
+
+/// Replace the first command in `args` by the command of its alias.
+///
+/// `value_options` are the options which take a value, so that value is not mistaken for the
+/// command. Aliases do not expand further, so they cannot loop.
+pub fn expand(
+    args: Vec<OsString>,
+    aliases: &BTreeMap<String, String>,
+    value_options: &[String],
+) -> Vec<OsString> {
+    let mut is_value = false;
+    let position = args.iter().skip(1).position(|arg| {
+        let arg = arg.to_string_lossy();
+        if std::mem::take(&mut is_value) {
+            false
+        } else if arg.starts_with('-') {
+            is_value = value_options.iter().any(|o| *o == arg);
+            false
+        } else {
+            true
+        }
+    });
+    let Some(position) = position.map(|p| p + 1) else {
+        return args;
+    };
+    let Some(command) = args[position].to_str().and_then(|a| aliases.get(a)) else {
+        return args;
+    };
+
+    let mut expanded = args[..position].to_vec();
+    expanded.extend(command.split_whitespace().map(OsString::from));
+    expanded.extend_from_slice(&args[position + 1..]);
+    expanded
+}
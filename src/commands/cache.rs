@@ -0,0 +1,205 @@
+use anyhow::Result;
+use clap::Parser;
+use comfy_table::{Cell, Color};
+
+use crate::{
+    manifest::install_file::{normalize_destination, windows_safe_destination, WINDOWS_MAX_PATH},
+    mods::{GatherModList, ModList},
+    settings::{create_table, ensure_writable, Settings},
+    ui::{confirm, page},
+};
+
+#[derive(Debug, Clone, Parser)]
+pub enum CacheCmd {
+    /// Check the destinations of the files of all mods for backslashes, doubled separators,
+    /// names with the wrong casing and paths which escape the game directory.
+    AuditPaths {
+        /// Rewrite the destinations into their canonical form;
+        /// files which would end up outside of the game directory are disabled.
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Check the destinations of the files of all mods for what windows does not allow, like
+    /// the characters ':<>|?*' or paths over 259 characters; the game does not load such files
+    /// under Proton.
+    AuditWindowsPaths {
+        /// Add remap rules which rename the files to names windows allows; see 'mods remap'.
+        /// Paths which are too long are only reported.
+        #[arg(long)]
+        fix: bool,
+    },
+}
+impl Default for CacheCmd {
+    fn default() -> Self {
+        Self::AuditPaths { fix: false }
+    }
+}
+impl CacheCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::AuditPaths { fix } | Self::AuditWindowsPaths { fix } => !*fix,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::AuditPaths { fix } => audit_paths(settings, fix),
+            Self::AuditWindowsPaths { fix } => audit_windows_paths(settings, fix),
+        }
+    }
+}
+
+fn audit_paths(settings: &Settings, fix: bool) -> Result<()> {
+    let cache_dir = settings.cache_dir();
+    let game_dir = settings.game_dir();
+    let mut mod_list = Vec::gather_mods(cache_dir)?;
+
+    let mut table = create_table(vec!["Mod", "Destination", "Issues", "Canonical"]);
+    let mut affected = Vec::new();
+    for md in &mod_list {
+        let issues = md.destination_issues()?;
+        if issues.is_empty() {
+            continue;
+        }
+        affected.push(md.name().to_owned());
+
+        for (f, issues) in issues {
+            let issues = issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let canonical = normalize_destination(f.destination()).map_or_else(
+                || Cell::new("<disable>").fg(Color::Red),
+                |d| Cell::new(d).fg(Color::Green),
+            );
+            table.add_row(vec![
+                Cell::new(md.name()),
+                Cell::new(f.destination()).fg(Color::Yellow),
+                Cell::new(issues),
+                canonical,
+            ]);
+        }
+    }
+
+    if affected.is_empty() {
+        log::info!("All destinations are in their canonical form.");
+        return Ok(());
+    }
+    page(&format!("\n{table}"));
+
+    if !fix {
+        log::info!(
+            "{} mod(s) have destinations which are not canonical; use '--fix' to rewrite them.",
+            affected.len()
+        );
+        return Ok(());
+    }
+    ensure_writable("fix the destinations of mods")?;
+    if !confirm("rewrite the destinations of these mods", &affected)? {
+        return Ok(());
+    }
+
+    let mut changed = 0;
+    for md in mod_list
+        .iter_mut()
+        .filter(|md| affected.iter().any(|name| name == md.name()))
+    {
+        changed += md.normalize_destinations()?;
+    }
+
+    // Links at the old destinations are replaced by those at the fixed ones.
+    mod_list.re_enable(cache_dir, game_dir)?;
+
+    log::info!(
+        "Fixed {changed} destination(s) of {} mod(s).",
+        affected.len()
+    );
+    Ok(())
+}
+
+fn audit_windows_paths(settings: &Settings, fix: bool) -> Result<()> {
+    let cache_dir = settings.cache_dir();
+    let game_dir = settings.game_dir();
+    let mut mod_list = Vec::gather_mods(cache_dir)?;
+
+    let mut table = create_table(vec!["Mod", "Destination", "Issues", "Renamed"]);
+    let mut affected = Vec::new();
+    let mut too_long = 0;
+    for md in &mod_list {
+        let issues = md.windows_path_issues(game_dir)?;
+        if issues.is_empty() {
+            continue;
+        }
+
+        let mut renamable = false;
+        for (f, issues) in issues {
+            let is_renamable = issues.iter().any(|i| i.is_renamable());
+            too_long += usize::from(issues.iter().any(|i| !i.is_renamable()));
+            renamable |= is_renamable;
+            let renamed = if is_renamable {
+                Cell::new(windows_safe_destination(f.destination())).fg(Color::Green)
+            } else {
+                Cell::new("<shorten the game directory>").fg(Color::Red)
+            };
+            let issues = issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            table.add_row(vec![
+                Cell::new(md.name()),
+                Cell::new(f.destination()).fg(Color::Yellow),
+                Cell::new(issues),
+                renamed,
+            ]);
+        }
+        if renamable {
+            affected.push(md.name().to_owned());
+        }
+    }
+
+    if affected.is_empty() && too_long == 0 {
+        log::info!("All destinations can be loaded under Proton.");
+        return Ok(());
+    }
+    page(&format!("\n{table}"));
+    if too_long > 0 {
+        log::warn!(
+            "{too_long} file(s) end up over {} characters in '{game_dir}'; move the game to a shorter path.",
+            WINDOWS_MAX_PATH - 1
+        );
+    }
+    if affected.is_empty() {
+        return Ok(());
+    }
+
+    if !fix {
+        log::info!(
+            "{} mod(s) have files windows cannot name; use '--fix' to rename them.",
+            affected.len()
+        );
+        return Ok(());
+    }
+    ensure_writable("rename the files of mods")?;
+    if !confirm(
+        "add remap rules renaming the files of these mods",
+        &affected,
+    )? {
+        return Ok(());
+    }
+
+    let mut renamed = 0;
+    for md in mod_list
+        .iter_mut()
+        .filter(|md| affected.iter().any(|name| name == md.name()))
+    {
+        renamed += md.rename_for_windows()?;
+    }
+
+    // Links at the old destinations are replaced by those at the new ones.
+    mod_list.re_enable(cache_dir, game_dir)?;
+
+    log::info!("Renamed {renamed} file(s) of {} mod(s).", affected.len());
+    Ok(())
+}
@@ -2,14 +2,31 @@ use anyhow::Result;
 use camino::Utf8PathBuf;
 use clap::Parser;
 
-use crate::settings::{RunCmdKind, Settings};
+use crate::{
+    deployment::{case::case_duplicates, deployed_links, overlay, DeploymentStrategy},
+    ini::IniMergeStrategy,
+    installers::DATA_DIR_NAME,
+    mods::GatherModList,
+    requirements::unmet_components,
+    settings::{EditorMapping, RunCmdKind, Settings, TagColour},
+    steam,
+    ui::ModListColumn,
+};
+
+use super::{
+    game::{installed_components, prefix_dir},
+    preflight::{show_outcomes, Outcome},
+};
 
 #[derive(Debug, Clone, Parser, Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum ConfigCmd {
     /// Show starmod's configuration values
     #[default]
     #[clap(visible_alias = "s")]
     Show,
+    /// Check that the configured directories and the deployment work on this system.
+    Doctor,
     /// Update settings
     #[clap(visible_alias = "u")]
     Update {
@@ -33,15 +50,100 @@ pub enum ConfigCmd {
         // loot_type: Option<LootType>, FIXME
         #[arg(long)]
         loot_data_dir: Option<Utf8PathBuf>,
+        /// Comma separated list of columns shown by 'list mods'.
+        #[arg(long, value_enum, value_delimiter = ',')]
+        list_columns: Option<Vec<ModListColumn>>,
+        /// Use colours in the output.
+        #[arg(long)]
+        colour: Option<bool>,
+        /// Number of entries shown in selection lists.
+        #[arg(long)]
+        page_size: Option<usize>,
+        /// Share the cache directory with other users; each user keeps their own mod order.
+        #[arg(long)]
+        shared_cache: Option<bool>,
+        /// Priority of newly installed mods.
+        #[arg(long, allow_hyphen_values = true)]
+        new_mod_priority: Option<isize>,
+        /// Comma separated list of '<tag>=<colour>', used to colour mods in the mod-list.
+        #[arg(long, value_delimiter = ',')]
+        tag_colours: Option<Vec<TagColour>>,
+        /// Comma separated list of '<extension>=<command>', the editor used for those files;
+        /// other files use the 'editor' setting.
+        #[arg(long, value_delimiter = ',')]
+        editors: Option<Vec<EditorMapping>>,
+        /// How 'mods merge-ini' merges keys set by several mods.
+        #[arg(long, value_enum)]
+        ini_merge: Option<IniMergeStrategy>,
+        /// Command to run the game and tools through, like 'gamemoderun' or
+        /// 'gamescope -W 2560 -H 1440 --'; repeat for more wrappers, pass "" to remove them.
+        #[arg(long = "run-wrapper")]
+        run_wrappers: Option<Vec<String>>,
+        /// Number of threads used for parallel work; 0 uses the number of CPUs.
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Number of files extracted, hashed or linked at the same time; 0 uses 'threads'.
+        /// Set this low, like 1 or 2, when the game or cache is on a hard disk.
+        #[arg(long)]
+        io_limit: Option<usize>,
+        /// Show a desktop notification when long operations, like 'extract-all', finish.
+        #[arg(long)]
+        notifications: Option<bool>,
+        /// How mods end up in the game directory; 'overlay' is experimental, and needs
+        /// fuse-overlayfs.
+        #[arg(long, value_enum)]
+        deployment: Option<DeploymentStrategy>,
+        /// Deploy into the directories already in the game directory, whatever their casing;
+        /// for mods which disagree about it, like 'Data/Textures' and 'Data/textures'.
+        #[arg(long)]
+        case_insensitive: Option<bool>,
+        /// Run the game with this wine binary instead of Steam's proton, for games not
+        /// installed through Steam; pass "" to go back to proton.
+        #[arg(long)]
+        wine: Option<Utf8PathBuf>,
+        /// The wine prefix the game is installed in, used with 'wine'.
+        #[arg(long)]
+        wine_prefix: Option<Utf8PathBuf>,
+        /// Download LOOT's masterlist before the plugins are sorted, or LOOT is run.
+        #[arg(long)]
+        auto_update_masterlist: Option<bool>,
+        /// Accessibility mode: no colours; the state of mods is shown by the glyphs of
+        /// their tags instead, see 'legenda'.
+        #[arg(long)]
+        symbols_only: Option<bool>,
+        /// Language of the messages, like 'de' or 'pt-BR'; the catalog is read from
+        /// 'locales/<locale>.ftl' in starmod's data directory. Pass "" to use the environment.
+        #[arg(long)]
+        locale: Option<String>,
+        /// Keep the priority, state, tags and notes of the mods in <state-dir> instead of the
+        /// cache, to put it under git; see 'state'. Pass "" to keep them in the cache again.
+        #[arg(long)]
+        state_dir: Option<Utf8PathBuf>,
+        /// Download the images of the mods on Nexus with 'check-updates', so 'mods show --image'
+        /// does not wait for them.
+        #[arg(long)]
+        cache_images: Option<bool>,
+        /// Extension of the backups of files in the way of links, like 'bak';
+        /// run 'purge deployment' before changing it. Pass "" to use the default.
+        #[arg(long)]
+        backup_extension: Option<String>,
     },
 }
 impl ConfigCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Show | Self::Doctor => true,
+            Self::Update { .. } => false,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         match self {
             Self::Show => {
                 log::info!("{}", &settings);
                 Ok(())
             }
+            Self::Doctor => doctor(settings),
             Self::Update {
                 download_dir,
                 game_dir,
@@ -53,8 +155,33 @@ impl ConfigCmd {
                 xedit_dir,
                 // loot_type,
                 loot_data_dir,
+                list_columns,
+                colour,
+                page_size,
+                shared_cache,
+                new_mod_priority,
+                tag_colours,
+                editors,
+                ini_merge,
+                run_wrappers,
+                threads,
+                io_limit,
+                notifications,
+                deployment,
+                case_insensitive,
+                wine,
+                wine_prefix,
+                auto_update_masterlist,
+                symbols_only,
+                locale,
+                state_dir,
+                cache_images,
+                backup_extension,
             } => {
                 let loot_type = None;
+                let previous_deployment = settings.deployment();
+                let previous_case_insensitive = settings.case_insensitive();
+                let previous_backup_extension = settings.backup_extension().to_owned();
                 let settings = settings.create_config(
                     download_dir,
                     game_dir,
@@ -66,10 +193,242 @@ impl ConfigCmd {
                     xedit_dir,
                     loot_type,
                     loot_data_dir,
+                    list_columns,
+                    colour,
+                    page_size,
+                    shared_cache,
+                    new_mod_priority,
+                    tag_colours,
+                    editors,
+                    ini_merge,
+                    run_wrappers,
+                    threads,
+                    io_limit,
+                    notifications,
+                    deployment,
+                    case_insensitive,
+                    wine,
+                    wine_prefix,
+                    auto_update_masterlist,
+                    symbols_only,
+                    locale,
+                    state_dir,
+                    cache_images,
+                    backup_extension,
                 )?;
+                match deployment.filter(|d| *d != previous_deployment) {
+                    Some(DeploymentStrategy::Overlay) => log::warn!(
+                        "Mods are now mounted when the game runs; run 'purge deployment' to take their links out of the game directory."
+                    ),
+                    Some(DeploymentStrategy::Symlink) => log::warn!(
+                        "Mods are now linked into the game directory; run 'deploy' to link the enabled mods."
+                    ),
+                    None => {}
+                }
+                if case_insensitive.is_some_and(|c| c != previous_case_insensitive) {
+                    log::warn!("Run 'deploy' to move the links to their new destinations.");
+                }
+                if settings.backup_extension() != previous_backup_extension {
+                    log::warn!(
+                        "Backups already made keep the '{previous_backup_extension}' extension."
+                    );
+                }
                 log::info!("{}", &settings);
                 Ok(())
             }
         }
     }
 }
+
+fn doctor(settings: &Settings) -> Result<()> {
+    let checks = [
+        ("Directories", check_directories(settings)),
+        ("Steam", check_steam(settings)),
+        ("Proton", check_proton(settings)),
+        ("Deployment", check_deployment(settings)?),
+        ("Case sensitivity", check_case_sensitivity(settings)),
+        ("Prefix components", check_prefix_components(settings)?),
+    ];
+    let failed = show_outcomes(&checks);
+    if failed > 0 {
+        log::warn!("{failed} check(s) failed.");
+    }
+    Ok(())
+}
+
+fn check_directories(settings: &Settings) -> Outcome {
+    let unusable = [
+        ("game", settings.game_dir()),
+        ("cache", settings.cache_dir()),
+        ("download", settings.download_dir()),
+    ]
+    .into_iter()
+    .filter(|(_, dir)| {
+        dir.metadata()
+            .map_or(true, |m| !m.is_dir() || m.permissions().readonly())
+    })
+    .map(|(name, dir)| format!("{name} directory '{dir}'"))
+    .collect::<Vec<_>>();
+
+    if unusable.is_empty() {
+        Outcome::Pass("game, cache and download directories are writable".to_owned())
+    } else {
+        Outcome::Fail(format!(
+            "missing or read-only: {}; run 'config update' to change them",
+            unusable.join(", ")
+        ))
+    }
+}
+
+fn check_steam(settings: &Settings) -> Outcome {
+    match settings.steam_dir() {
+        Some(steam_dir) if !steam_dir.is_dir() => {
+            Outcome::Warn(format!("steam directory '{steam_dir}' does not exist"))
+        }
+        Some(steam_dir) if steam::is_flatpak(steam_dir) => Outcome::Pass(format!(
+            "flatpak in '{steam_dir}'; proton is run through 'flatpak run'"
+        )),
+        Some(steam_dir) => Outcome::Pass(format!("'{steam_dir}'")),
+        None => Outcome::Warn("no steam directory found".to_owned()),
+    }
+}
+
+fn check_proton(settings: &Settings) -> Outcome {
+    if let Some(wine) = settings.wine() {
+        return match settings.wine_prefix() {
+            // A bare name, like 'wine', is looked up in the PATH.
+            _ if wine.parent().is_some_and(|p| !p.as_str().is_empty()) && !wine.is_file() => {
+                Outcome::Fail(format!("wine '{wine}' does not exist"))
+            }
+            Some(prefix) if prefix.is_dir() => Outcome::Pass(format!("'{wine}' in '{prefix}'")),
+            Some(prefix) => Outcome::Fail(format!("wine prefix '{prefix}' does not exist")),
+            None => Outcome::Fail("wine is configured without a prefix".to_owned()),
+        };
+    }
+    match (settings.proton_dir(), settings.compat_dir()) {
+        (Some(proton), Some(compat)) if proton.is_dir() && compat.is_dir() => {
+            Outcome::Pass(format!("'{proton}'"))
+        }
+        (Some(proton), Some(_)) if !proton.is_dir() => {
+            Outcome::Warn(format!("proton directory '{proton}' does not exist"))
+        }
+        (Some(_), Some(compat)) => {
+            Outcome::Warn(format!("compat directory '{compat}' does not exist"))
+        }
+        _ => Outcome::Warn(
+            "no proton or compat directory configured; the game can only be run from Steam"
+                .to_owned(),
+        ),
+    }
+}
+
+/// The runtimes the enabled mods need, by their 'needs:' tags, should be installed in the prefix.
+fn check_prefix_components(settings: &Settings) -> Result<Outcome> {
+    let Ok(prefix) = prefix_dir(settings) else {
+        return Ok(Outcome::Warn(
+            "no proton or wine prefix configured".to_owned(),
+        ));
+    };
+    if !prefix.is_dir() {
+        return Ok(Outcome::Warn(format!(
+            "prefix '{prefix}' does not exist; run the game once"
+        )));
+    }
+
+    let installed = installed_components(&prefix);
+    let unmet = unmet_components(&Vec::gather_mods(settings.cache_dir())?, &installed);
+    Ok(if !unmet.is_empty() {
+        let mut verbs = unmet
+            .iter()
+            .map(|u| u.requirement.as_str())
+            .collect::<Vec<_>>();
+        verbs.sort_unstable();
+        verbs.dedup();
+        Outcome::Warn(format!(
+            "{}\ninstall with 'game prefix install {}'",
+            unmet
+                .iter()
+                .map(|u| format!("'{}' needs '{}'", u.mod_name, u.requirement))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            verbs.join(" ")
+        ))
+    } else if installed.is_empty() {
+        Outcome::Pass("no winetricks components installed, nor needed".to_owned())
+    } else {
+        Outcome::Pass(format!("installed: {}", installed.join(", ")))
+    })
+}
+
+fn check_deployment(settings: &Settings) -> Result<Outcome> {
+    Ok(match settings.deployment() {
+        DeploymentStrategy::Symlink => {
+            Outcome::Pass("mods are linked into the game directory".to_owned())
+        }
+        DeploymentStrategy::Overlay => {
+            let missing = overlay::missing_requirements();
+            let linked = deployed_links(settings.cache_dir(), settings.game_dir())?.len();
+            if !missing.is_empty() {
+                Outcome::Fail(format!(
+                    "the overlay deployment needs: {}",
+                    missing.join(", ")
+                ))
+            } else if overlay::is_mounted(settings.game_dir()) {
+                Outcome::Warn(
+                    "the game directory is still mounted; unmount it with 'fusermount -u'"
+                        .to_owned(),
+                )
+            } else if linked > 0 {
+                Outcome::Warn(format!(
+                    "{linked} file(s) are still linked into the game directory; run 'purge deployment'"
+                ))
+            } else {
+                Outcome::Pass("mods are mounted over the game directory when it runs".to_owned())
+            }
+        }
+    })
+}
+
+/// Windows ignores the casing of paths, and so do mods; on Linux that splits directories,
+/// unless the file system ignores it too, or the 'case-insensitive' setting is used.
+fn check_case_sensitivity(settings: &Settings) -> Outcome {
+    let exe_name = settings.game().exe_name();
+    if settings.game_dir().join(exe_name).exists()
+        && settings.game_dir().join(swap_case(exe_name)).exists()
+    {
+        return Outcome::Pass("the game directory is on a case-insensitive file system".to_owned());
+    }
+    if settings.case_insensitive() {
+        return Outcome::Pass(
+            "files are deployed into the directories already in the game directory".to_owned(),
+        );
+    }
+
+    let duplicates = case_duplicates(&settings.game_dir().join(DATA_DIR_NAME));
+    duplicates.first().map_or_else(
+        || Outcome::Pass(format!("no paths in '{DATA_DIR_NAME}' differ only in casing")),
+        |first| {
+            Outcome::Warn(format!(
+                "{} path(s) in '{DATA_DIR_NAME}' differ only in casing, like {}; run 'config update --case-insensitive true' and 'deploy'",
+                duplicates.len(),
+                first
+                    .iter()
+                    .map(|p| format!("'{p}'"))
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ))
+        },
+    )
+}
+
+fn swap_case(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_uppercase() {
+                c.to_ascii_lowercase()
+            } else {
+                c.to_ascii_uppercase()
+            }
+        })
+        .collect()
+}
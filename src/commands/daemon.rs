@@ -0,0 +1,165 @@
+use std::{
+    fs::remove_file,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use xdg::BaseDirectories;
+
+use crate::{
+    deployment::DeployPlan,
+    errors::{ModErrors, SettingErrors},
+    history,
+    manifest::Manifest,
+    mods::{FindInModList, GatherModList, ModList},
+    settings::{links_mods, share_with_group, Settings},
+};
+
+const SOCKET_FILE: &str = "daemon.sock";
+
+/// A request to the daemon; sent as a single line of json, like `{"cmd":"enable","name":"foo"}`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case")]
+pub enum Request {
+    /// Show the mods with their priority and whether they are enabled.
+    Status,
+    /// Enable the mod called `name`.
+    Enable { name: String },
+    /// Disable the mod called `name`.
+    Disable { name: String },
+    /// Bring the links in the game directory in line with the enabled mods.
+    Deploy,
+}
+
+/// The answer to a `Request`; sent as a single line of json, like `{"status":"ok"}`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum Response {
+    Ok,
+    Mods { mods: Vec<ModStatus> },
+    Error { message: String },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ModStatus {
+    pub name: String,
+    pub priority: isize,
+    pub enabled: bool,
+    pub kind: String,
+}
+
+/// The socket of the daemon; in `$XDG_RUNTIME_DIR` by default.
+pub fn socket_path(settings: &Settings, socket: Option<Utf8PathBuf>) -> Result<Utf8PathBuf> {
+    if let Some(socket) = socket {
+        return Ok(socket);
+    }
+    let xdg_base = BaseDirectories::with_prefix(settings.cmd_name())?;
+    Ok(Utf8PathBuf::try_from(
+        xdg_base.place_runtime_file(SOCKET_FILE)?,
+    )?)
+}
+
+/// Answer requests on `socket` until stopped, one connection at a time, so requests never
+/// change the mod-list at the same time.
+pub fn serve(settings: &Settings, socket: &Utf8Path) -> Result<()> {
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(SettingErrors::DaemonRunning(socket.to_owned()).into());
+        }
+        // Left behind by a daemon which did not stop cleanly.
+        remove_file(socket)?;
+    }
+
+    let listener = UnixListener::bind(socket)?;
+    share_with_group(socket)?;
+    log::info!("Listening on {socket}");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(settings, stream) {
+                    log::warn!("Connection closed: {e}");
+                }
+            }
+            Err(e) => log::warn!("Unable to accept a connection: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(settings: &Settings, stream: UnixStream) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                log::debug!("Request: {request:?}");
+                handle_request(settings, &request).unwrap_or_else(|e| Response::Error {
+                    message: format!("{e:#}"),
+                })
+            }
+            Err(e) => Response::Error {
+                message: format!("invalid request: {e}"),
+            },
+        };
+
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    }
+    Ok(())
+}
+
+fn handle_request(settings: &Settings, request: &Request) -> Result<Response> {
+    let cache_dir = settings.cache_dir();
+    let game_dir = settings.game_dir();
+
+    // Clients poll the status, which changes nothing worth recording.
+    if matches!(request, Request::Status) {
+        return Ok(Response::Mods {
+            mods: Vec::gather_mods(cache_dir)?
+                .iter()
+                .map(|md| ModStatus {
+                    name: md.name().to_owned(),
+                    priority: md.priority(),
+                    enabled: md.is_enabled(),
+                    kind: md.kind().to_string(),
+                })
+                .collect(),
+        });
+    }
+
+    // Changes are recorded per request; the daemon may run for days.
+    history::record(settings, || {
+        let mut mod_list = Vec::gather_mods(cache_dir)?;
+        match request {
+            Request::Enable { name } => {
+                let idx = find_mod(&mod_list, name)?;
+                mod_list.enable_mod(cache_dir, game_dir, idx)?;
+            }
+            Request::Disable { name } => {
+                let idx = find_mod(&mod_list, name)?;
+                mod_list.disable_mod(cache_dir, game_dir, idx)?;
+            }
+            // With the overlay deployment, the mods are mounted when the game runs.
+            Request::Deploy if links_mods() => {
+                DeployPlan::new(&mod_list, cache_dir, game_dir)?.apply(game_dir)?;
+            }
+            Request::Status | Request::Deploy => {}
+        }
+        Ok(())
+    })?;
+    Ok(Response::Ok)
+}
+
+/// Nobody is around to answer a prompt, so mods are only found by their exact name.
+fn find_mod(mod_list: &[Manifest], name: &str) -> Result<usize> {
+    mod_list
+        .find_mod_by_name(name)
+        .ok_or_else(|| ModErrors::ModNotFound(name.to_owned()).into())
+}
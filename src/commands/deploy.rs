@@ -0,0 +1,68 @@
+use anyhow::Result;
+use comfy_table::{Cell, Color};
+
+use crate::{
+    deployment::{DeployPlan, DeploymentStrategy, Link},
+    mods::GatherModList,
+    settings::{create_table, Settings},
+    ui::page,
+};
+
+/// Link the files of all enabled mods into the game directory, changing only the links which
+/// differ; running it again without changes to the mod-list does nothing.
+pub fn deploy(settings: &Settings, dry_run: bool) -> Result<()> {
+    if settings.deployment() == DeploymentStrategy::Overlay {
+        log::info!(
+            "Mods are mounted over the game directory when the game runs; nothing to deploy."
+        );
+        return Ok(());
+    }
+
+    let game_dir = settings.game_dir();
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let plan = DeployPlan::new(&mod_list, settings.cache_dir(), game_dir)?;
+
+    if plan.is_empty() {
+        log::info!(
+            "The game directory is up to date; {} link(s) in place.",
+            plan.unchanged()
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        let mut table = create_table(vec!["Action", "Destination", "Mod"]);
+        for (action, color, links) in [
+            ("Link", Color::Green, plan.create()),
+            ("Relink", Color::Yellow, plan.replace()),
+            ("Unlink", Color::Red, plan.remove()),
+        ] {
+            for link in links {
+                table.add_row(vec![
+                    Cell::new(action).fg(color),
+                    Cell::new(destination(link, settings)).fg(color),
+                    Cell::new(link.mod_name().unwrap_or_default()).fg(color),
+                ]);
+            }
+        }
+        page(&format!("\n{table}"));
+        return Ok(());
+    }
+
+    plan.apply(game_dir)?;
+    log::info!(
+        "Linked {}, relinked {} and unlinked {} file(s); {} link(s) were already in place.",
+        plan.create().len(),
+        plan.replace().len(),
+        plan.remove().len(),
+        plan.unchanged()
+    );
+    Ok(())
+}
+
+fn destination<'a>(link: &'a Link, settings: &Settings) -> &'a str {
+    link.destination()
+        .strip_prefix(settings.game_dir())
+        .unwrap_or_else(|_| link.destination())
+        .as_str()
+}
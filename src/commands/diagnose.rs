@@ -0,0 +1,109 @@
+use std::{collections::BTreeMap, env, fmt::Write, fs::read_to_string};
+
+use anyhow::Result;
+
+use crate::{commands::logs, mods::GatherModList, settings::Settings, steam};
+
+const OS_RELEASE: &str = "/etc/os-release";
+const KERNEL_RELEASE: &str = "/proc/sys/kernel/osrelease";
+// Environment variables which tell something about the session starmod runs in.
+const SESSION_ENVS: [&str; 5] = [
+    "XDG_CURRENT_DESKTOP",
+    "XDG_SESSION_TYPE",
+    "LANG",
+    "SteamDeck",
+    "PAGER",
+];
+
+/// Print a report on starmod and its environment, to paste into a bug report.
+///
+/// `build` describes the build of starmod; the binary passes its long version. With `redact`,
+/// the home directory and user name are left out of the report.
+pub fn show_report(
+    settings: &Settings,
+    build: &str,
+    redact: bool,
+    log_entries: usize,
+) -> Result<()> {
+    let mut report = String::new();
+
+    writeln!(report, "## Build\n```\n{}\n```", build.trim())?;
+
+    writeln!(report, "## Environment\n```")?;
+    let os = read_to_string(OS_RELEASE).ok().and_then(|contents| {
+        contents.lines().find_map(|line| {
+            line.strip_prefix("PRETTY_NAME=")
+                .map(|name| name.trim_matches('"').to_owned())
+        })
+    });
+    writeln!(report, "OS: {}", os.as_deref().unwrap_or("<unknown>"))?;
+    let kernel = read_to_string(KERNEL_RELEASE).unwrap_or_default();
+    writeln!(report, "Kernel: {}", kernel.trim())?;
+    for key in SESSION_ENVS {
+        if let Ok(value) = env::var(key) {
+            writeln!(report, "{key}: {value}")?;
+        }
+    }
+    for steam_dir in steam::steam_dirs() {
+        let kind = if steam::is_flatpak(&steam_dir) {
+            "flatpak"
+        } else {
+            "native"
+        };
+        writeln!(report, "Steam: {steam_dir} ({kind})")?;
+    }
+    writeln!(report, "```")?;
+
+    writeln!(report, "## Settings\n```\n{settings}\n```")?;
+
+    writeln!(report, "## Mods\n```")?;
+    match Vec::gather_mods(settings.cache_dir()) {
+        Ok(mod_list) => {
+            let enabled = mod_list.iter().filter(|md| md.is_enabled()).count();
+            writeln!(report, "Mods: {} ({enabled} enabled)", mod_list.len())?;
+            let mut kinds = BTreeMap::new();
+            for md in &mod_list {
+                *kinds.entry(md.kind().to_string()).or_insert(0_usize) += 1;
+            }
+            for (kind, count) in kinds {
+                writeln!(report, "{kind}: {count}")?;
+            }
+        }
+        Err(e) => writeln!(report, "Unable to read the mod-list: {e:#}")?,
+    }
+    writeln!(report, "```")?;
+
+    writeln!(report, "## Log\n```")?;
+    match logs::tail(settings.log_file(), log_entries) {
+        Ok(lines) => {
+            for line in lines {
+                writeln!(report, "{line}")?;
+            }
+        }
+        Err(e) => writeln!(report, "Unable to read the log: {e:#}")?,
+    }
+    writeln!(report, "```")?;
+
+    if redact {
+        report = redact_user(&report);
+    }
+
+    // Print directly; logging would add the whole report to the log.
+    print!("{report}");
+    Ok(())
+}
+
+/// `report`, with the home directory shown as '~' and the user name in paths as '<user>',
+/// like in the users directory of a wine prefix.
+fn redact_user(report: &str) -> String {
+    let mut report = report.to_owned();
+    if let Some(home) = dirs::home_dir() {
+        report = report.replace(&*home.to_string_lossy(), "~");
+    }
+    if let Ok(user) = env::var("USER").or_else(|_| env::var("LOGNAME")) {
+        if !user.is_empty() {
+            report = report.replace(&format!("/{user}"), "/<user>");
+        }
+    }
+    report
+}
@@ -1,32 +1,56 @@
 use std::{
-    collections::HashMap,
     fs::{self, metadata, remove_dir_all, remove_file},
-    io::{stdin, IsTerminal},
+    io::IsTerminal,
     sync::{atomic::AtomicBool, Arc, Mutex},
     thread,
     time::Duration,
 };
 
 use crate::{
+    conflict::conflict_list_by_mod,
     decompress::SupportedArchives,
     dmodman::{DmodMan, DMODMAN_EXTENSION},
-    errors::DownloadError,
-    installers::stdin::{Input, InputWithDefault},
-    manifest::Manifest,
+    errors::{DownloadError, ModErrors},
+    installers::fomod::FomodChoices,
+    manifest::{Manifest, ManifestState},
     mods::{FindInModList, GatherModList, ModKind, ModList},
-    settings::Settings,
-    ui::{ArchiveListBuilder, FindSelectBuilder},
-    utils::{rename_recursive, AddExtension},
+    nexus::Nexus,
+    settings::{
+        create_table, ensure_writable, is_read_only, share_with_group, share_with_group_recursive,
+        with_io_limit, Settings,
+    },
+    ui::{
+        ask_password, confirm, fuzzy_find, notify, ArchiveListBuilder, FindSelectBuilder,
+        SelectToIdx,
+    },
+    utils::{human_size, open_url, rename_recursive, AddExtension},
+    version::Version,
 };
 
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
-use clap::Parser;
-use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use clap::{Args, Parser};
+use comfy_table::{Cell, Color};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use read_stdin::prompt_until_ok;
+use inquire::{Confirm, CustomType};
+use serde::Serialize;
 
-use super::list::list_mods;
+use super::{list::list_mods, mods::show_mod_docs};
+
+// Not a manifest or an archive, so neither the mod-list nor the archive list picks it up.
+const FAILED_EXTRACTIONS_FILE: &str = "failed_extractions.json";
+
+/// Where newly installed mods are placed in the mod-list.
+#[derive(Debug, Clone, Args, Default)]
+pub struct Placement {
+    /// Priority of the new mod; other mods move up to make room.
+    /// Defaults to the 'new-mod-priority' setting.
+    #[arg(short, long, allow_hyphen_values = true)]
+    priority: Option<isize>,
+    /// Place the new mod directly after this mod; other mods move up to make room.
+    #[arg(short, long, conflicts_with = "priority")]
+    after: Option<String>,
+}
 
 #[derive(Debug, Clone, Parser, Default)]
 pub enum DownloadCmd {
@@ -35,43 +59,108 @@ pub enum DownloadCmd {
     #[clap(visible_aliases = &["lists", "l"])]
     List,
     /// Extract given archive
-    Extract { name: Option<String> },
+    Extract {
+        name: Option<String>,
+        #[command(flatten)]
+        placement: Placement,
+        /// Password of a 7z or rar archive; asked for when extracting fails without one.
+        #[arg(long)]
+        password: Option<String>,
+        /// Show which enabled mods the new mod would overwrite and lose to, and ask whether to
+        /// keep it, change its priority or remove it again.
+        #[arg(long)]
+        preview: bool,
+    },
     /// Extract all archives which are not in the cache directory.
-    ExtractAll,
+    ExtractAll {
+        #[command(flatten)]
+        placement: Placement,
+        /// Only extract the archives which failed during the previous 'extract-all'.
+        #[arg(long)]
+        retry_failed: bool,
+        /// Password for the 7z and rar archives; archives without a password extract as usual.
+        #[arg(long)]
+        password: Option<String>,
+    },
     /// Re-install given archive
     ReInstall { name: Option<String> },
+    /// Open the Nexus page of the given archive in the browser; needs its dmodman metadata.
+    Open { name: Option<String> },
+    /// Find the given archive on Nexus by its md5 checksum, and write its dmodman metadata;
+    /// for archives downloaded with the browser.
+    Identify {
+        name: Option<String>,
+        /// Replace the metadata the archive already has.
+        #[arg(short, long)]
+        force: bool,
+    },
     /// Update all mods which have an archive in the archive directory with a newer version.
     #[clap(visible_alias = "update-all")]
     UpgradeAll,
     /// Update mod which have an archive in the archive directory with a newer version.
     #[clap(visible_alias = "update")]
-    Upgrade { name: Option<String> },
+    Upgrade {
+        name: Option<String>,
+        /// Only show which mods have a newer archive available; do not change anything.
+        #[arg(long)]
+        check: bool,
+        /// Print the result of '--check' as json.
+        #[arg(long, requires = "check")]
+        json: bool,
+    },
 }
 impl DownloadCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::List | Self::Open { .. } => true,
+            Self::Upgrade { check, .. } => *check,
+            Self::Extract { .. }
+            | Self::ExtractAll { .. }
+            | Self::ReInstall { .. }
+            | Self::Identify { .. }
+            | Self::UpgradeAll => false,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         match self {
             Self::List => list_downloaded_files(settings.download_dir(), settings.cache_dir()),
-            Self::Extract { name } => {
-                let name = FindSelectBuilder::new(
-                    ArchiveListBuilder::new(settings.download_dir(), settings.cache_dir())
-                        .with_index()
-                        .with_status()
-                        .with_colour(),
-                )
-                .with_msg("Please select an archive to extract:")
-                .with_input(name.as_deref())
-                .build()?
-                .prompt()?;
-
-                let idx = name.split_whitespace().skip(1).next().unwrap();
+            Self::Extract {
+                name,
+                placement,
+                password,
+                preview,
+            } => extract(
+                settings,
+                name.as_deref(),
+                &placement,
+                password.as_deref(),
+                preview,
+            ),
+            Self::ExtractAll {
+                placement,
+                retry_failed,
+                password,
+            } => {
+                let (new_mods, failures) = extract_downloaded_files(
+                    settings.download_dir(),
+                    settings.cache_dir(),
+                    retry_failed,
+                    password.as_deref(),
+                )?;
+                let installed = new_mods.len();
+                place_new_mods(settings, &placement, new_mods)?;
+                list_mods(settings)?;
 
-                find_and_extract_archive(settings.download_dir(), settings.cache_dir(), idx)?;
-
-                list_mods(settings)
-            }
-            Self::ExtractAll => {
-                extract_downloaded_files(settings.download_dir(), settings.cache_dir())?;
-                list_mods(settings)
+                notify(
+                    "Extraction finished",
+                    &format!("Installed {installed} new mod(s); {failures} archive(s) failed."),
+                );
+                if failures > 0 {
+                    Err(DownloadError::ExtractionsFailed(failures).into())
+                } else {
+                    Ok(())
+                }
             }
             Self::ReInstall { name } => {
                 let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
@@ -84,56 +173,36 @@ impl DownloadCmd {
                 mod_list.disable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
                 mod_list[idx].remove()?;
 
-                let mod_type =
+                let installer =
                     ModKind::detect_mod_type(settings.cache_dir(), mod_list[idx].manifest_dir())?;
-                mod_type.create_mod(settings.cache_dir(), mod_list[idx].manifest_dir())?;
+                installer.create_mod(settings.cache_dir(), mod_list[idx].manifest_dir())?;
                 Ok(())
             }
-            Self::UpgradeAll => {
-                let dmodman_list = DmodMan::gather_list(settings.download_dir())?;
-                let dmodman_list = dmodman_list
-                    .iter()
-                    .map(|dm| ((dm.name(), dm.mod_id()), dm.clone()))
-                    .collect::<HashMap<_, _>>();
-                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
-                mod_list.retain(|md| {
-                    dmodman_list
-                        .get(&(
-                            md.bare_file_name().to_string(),
-                            md.nexus_id().unwrap_or_default(),
-                        ))
-                        .is_some_and(|dmod| md.is_an_update(dmod))
-                });
-
-                for md in mod_list {
-                    //TODO Move this to manifest::upgrade
-                    let priority = md.priority();
-                    let enabled = md.is_enabled();
-                    let name = dmodman_list
-                        .get(&(
-                            md.bare_file_name().to_string(),
-                            md.nexus_id().unwrap_or_default(),
-                        ))
-                        .map(DmodMan::file_name)
-                        .unwrap_or_default();
-                    log::info!("Updating '{name}'");
-                    md.remove()?;
-
-                    if let Some(mut manifest) = find_and_extract_archive(
-                        settings.download_dir(),
-                        settings.cache_dir(),
-                        name,
-                    )? {
-                        manifest.set_priority(priority)?;
-                        if enabled {
-                            manifest.set_enabled()?;
-                        }
-                    }
-                }
-
-                list_mods(settings)
+            Self::Open { name } => {
+                let idx = FindSelectBuilder::new(
+                    ArchiveListBuilder::new(settings.download_dir(), settings.cache_dir())
+                        .with_index()
+                        .with_colour(),
+                )
+                .with_msg("Please select an archive to open:")
+                .with_input(name.as_deref())
+                .build()?
+                .prompt()?;
+                let (_, file) = downloaded_files(settings.download_dir())?
+                    .get(idx)
+                    .cloned()
+                    .ok_or_else(|| DownloadError::ArchiveNotFound(idx.to_string()))?;
+                let dmodman =
+                    DmodMan::try_from(settings.download_dir().join(&file).add_extension("json"))
+                        .map_err(|_| DownloadError::NoMetadata(file.to_string()))?;
+                open_url(&dmodman.nexus_url())
             }
-            Self::Upgrade { name } => {
+            Self::Identify { name, force } => identify(settings, name.as_deref(), force),
+            Self::UpgradeAll => upgrade_all(settings),
+            Self::Upgrade {
+                check: true, json, ..
+            } => upgrade_check(settings, json),
+            Self::Upgrade { name, .. } => {
                 let dmodman_list = DmodMan::gather_list(settings.download_dir())?;
                 let mod_list = Vec::gather_mods(settings.cache_dir())?;
                 let idx = FindSelectBuilder::new(mod_list.default_list_builder())
@@ -148,24 +217,8 @@ impl DownloadCmd {
                 });
 
                 if let Some(dmod) = dmodman {
-                    //TODO Move this to manifest::upgrade
-                    let priority = md.priority();
-                    let enabled = md.is_enabled();
-                    let name = dmod.file_name();
-
-                    log::info!("Updating '{name}'");
-                    md.remove()?;
-
-                    if let Some(mut manifest) = find_and_extract_archive(
-                        settings.download_dir(),
-                        settings.cache_dir(),
-                        name,
-                    )? {
-                        manifest.set_priority(priority)?;
-                        if enabled {
-                            manifest.set_enabled()?;
-                        }
-                    }
+                    log::info!("Updating '{}'", dmod.file_name());
+                    upgrade_mod(settings.download_dir(), settings.cache_dir(), md, dmod)?;
                 }
                 Ok(())
             }
@@ -173,6 +226,351 @@ impl DownloadCmd {
     }
 }
 
+/// Extract and install one archive; asks for a password when a 7z or rar archive fails to
+/// extract without one.
+fn extract(
+    settings: &Settings,
+    name: Option<&str>,
+    placement: &Placement,
+    password: Option<&str>,
+    preview: bool,
+) -> Result<()> {
+    let idx = FindSelectBuilder::new(
+        ArchiveListBuilder::new(settings.download_dir(), settings.cache_dir())
+            .with_index()
+            .with_status()
+            .with_colour(),
+    )
+    .with_msg("Please select an archive to extract:")
+    .with_input(name)
+    .build()?
+    .prompt()?;
+
+    let (archive_type, file) = downloaded_files(settings.download_dir())?
+        .get(idx)
+        .cloned()
+        .ok_or_else(|| DownloadError::ArchiveNotFound(idx.to_string()))?;
+    let extract = |password: Option<&str>| {
+        extract_archive(
+            settings.download_dir(),
+            settings.cache_dir(),
+            archive_type,
+            &file,
+            password,
+        )
+    };
+    let new_mods = match extract(password) {
+        Err(e) if password.is_none() && archive_type.supports_password() => {
+            let Some(password) = ask_password(&format!(
+                "Extracting '{file}' failed ({e}); it may need a password."
+            ))?
+            else {
+                return Err(e);
+            };
+            extract(Some(&password))?
+        }
+        new_mods => new_mods?,
+    };
+    let new_mods = new_mods.into_iter().collect::<Vec<_>>();
+    let manifest_dirs = new_mods
+        .iter()
+        .map(|md| md.manifest_dir().to_owned())
+        .collect::<Vec<_>>();
+    place_new_mods(settings, placement, new_mods)?;
+
+    if preview {
+        for manifest_dir in &manifest_dirs {
+            preview_conflicts(settings, manifest_dir)?;
+        }
+    }
+    if std::io::stdin().is_terminal() {
+        for manifest_dir in &manifest_dirs {
+            offer_docs(settings, manifest_dir)?;
+        }
+    }
+
+    list_mods(settings)
+}
+
+/// Offer to show the readme or changelog of the new mod in `manifest_dir`, if it has one.
+fn offer_docs(settings: &Settings, manifest_dir: &Utf8Path) -> Result<()> {
+    // Removed again by the preview, perhaps.
+    let Ok(md) = Manifest::from_file(settings.cache_dir(), manifest_dir) else {
+        return Ok(());
+    };
+    if md.docs().is_empty() {
+        return Ok(());
+    }
+    let show = Confirm::new(&format!("'{}' comes with a readme; show it?", md.name()))
+        .with_default(false)
+        .prompt()?;
+    if show {
+        show_mod_docs(&md)?;
+    }
+    Ok(())
+}
+
+/// Show the conflicts the new mod in `manifest_dir` would have once enabled; then ask
+/// whether to keep it, to change its priority or to remove it again.
+fn preview_conflicts(settings: &Settings, manifest_dir: &Utf8Path) -> Result<()> {
+    const KEEP: &str = "Keep the mod";
+    const CHANGE_PRIORITY: &str = "Change its priority";
+    const REMOVE: &str = "Remove the mod again";
+
+    loop {
+        let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+        let Some(idx) = mod_list
+            .iter()
+            .position(|md| md.manifest_dir() == manifest_dir)
+        else {
+            return Ok(());
+        };
+        let name = mod_list[idx].name().to_owned();
+
+        // Enabled only in this copy; the mod stays disabled until the user enables it.
+        let mut enabled_list = mod_list.clone();
+        enabled_list[idx].temp_set_enabled();
+        let Some(conflicts) = conflict_list_by_mod(&enabled_list)?.remove(&name) else {
+            log::info!("'{name}' does not conflict with any enabled mod.");
+            return Ok(());
+        };
+        let mut overwrites = conflicts.winning_over().iter().cloned().collect::<Vec<_>>();
+        let mut loses_to = conflicts.losing_to().iter().cloned().collect::<Vec<_>>();
+        overwrites.sort_unstable();
+        loses_to.sort_unstable();
+
+        let mut table = create_table(vec!["Priority", "Files", "Overwrites", "Loses to"]);
+        table.add_row(vec![
+            mod_list[idx].priority().to_string(),
+            conflicts.conflict_files().len().to_string(),
+            overwrites.join("\n"),
+            loses_to.join("\n"),
+        ]);
+        log::info!("Conflicts of '{name}':\n{table}");
+
+        if !std::io::stdin().is_terminal() {
+            return Ok(());
+        }
+        let choices = vec![KEEP, CHANGE_PRIORITY, REMOVE];
+        match choices
+            [SelectToIdx::new("What should happen with the new mod?", choices.clone()).prompt()?]
+        {
+            CHANGE_PRIORITY => {
+                let priority = CustomType::<isize>::new("New priority:")
+                    .with_default(mod_list[idx].priority())
+                    .prompt()?;
+                make_room(&mut mod_list, priority, manifest_dir)?;
+                mod_list[idx].set_priority(priority)?;
+            }
+            REMOVE => {
+                mod_list[idx].remove()?;
+                log::info!("Removed mod '{name}'.");
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// How upgrading a single mod through `upgrade-all` ended.
+enum UpgradeResult {
+    Upgraded,
+    Pinned,
+    Failed(anyhow::Error),
+}
+
+/// Upgrade every mod with a newer archive, showing the progress per mod and a summary at the end;
+/// a failing mod does not stop the others. Pinned mods are left alone.
+fn upgrade_all(settings: &Settings) -> Result<()> {
+    let dmodman_list = DmodMan::gather_list(settings.download_dir())?;
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let upgrades = available_upgrades(&mod_list, &dmodman_list);
+
+    if upgrades.is_empty() {
+        log::info!("All mods are up to date.");
+        return Ok(());
+    }
+    let affected = upgrades
+        .iter()
+        .filter(|(idx, _)| !mod_list[*idx].mod_state().is_pinned())
+        .map(|(idx, dmod)| format!("{} -> {}", mod_list[*idx].name(), dmod.file_name()))
+        .collect::<Vec<_>>();
+    if !affected.is_empty()
+        && !confirm("reinstall these mods from their newer archives", &affected)?
+    {
+        return Ok(());
+    }
+
+    let sty = ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}").unwrap();
+    let multi = MultiProgress::new();
+    let progress_bars = upgrades
+        .iter()
+        .map(|(idx, _)| {
+            let p = multi.add(ProgressBar::new(1).with_style(sty.clone()));
+            p.set_message(format!("Waiting: {}", mod_list[*idx].name()));
+            p
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(upgrades.len());
+    for ((idx, dmod), pb) in upgrades.iter().zip(&progress_bars) {
+        let md = &mod_list[*idx];
+        let name = md.name();
+
+        let result = if md.mod_state().is_pinned() {
+            pb.finish_with_message(format!("Skipped: {name} ... => Pinned."));
+            UpgradeResult::Pinned
+        } else {
+            pb.set_message(format!("Upgrading: {name} -> {}", dmod.file_name()));
+            pb.enable_steady_tick(Duration::from_millis(70));
+
+            match upgrade_mod(settings.download_dir(), settings.cache_dir(), md, dmod) {
+                Ok(()) => {
+                    pb.inc(1);
+                    pb.finish_with_message(format!("Upgrading: {name} ... => Done."));
+                    UpgradeResult::Upgraded
+                }
+                Err(e) => {
+                    pb.finish_with_message(format!("Upgrading: {name} ... => Failed."));
+                    UpgradeResult::Failed(e)
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    let mut table = create_table(vec!["Name", "Old Version", "New Version", "Result"]);
+    let (mut succeeded, mut pinned, mut failures) = (0, 0, 0);
+    for ((idx, dmod), result) in upgrades.iter().zip(results) {
+        let md = &mod_list[*idx];
+        let result = match result {
+            UpgradeResult::Upgraded => {
+                succeeded += 1;
+                Cell::new("Upgraded").fg(Color::Green)
+            }
+            UpgradeResult::Pinned => {
+                pinned += 1;
+                Cell::new("Skipped, pinned").fg(Color::Yellow)
+            }
+            UpgradeResult::Failed(e) => {
+                failures += 1;
+                Cell::new(format!("Failed: {e}")).fg(Color::Red)
+            }
+        };
+        table.add_row(vec![
+            Cell::new(md.name()),
+            Cell::new(md.version().unwrap_or("<Unknown>")),
+            Cell::new(dmod.version().unwrap_or_else(|| "<Unknown>".to_owned())),
+            result,
+        ]);
+    }
+    log::info!("{table}");
+
+    notify(
+        "Upgrade finished",
+        &format!("{succeeded} mod(s) upgraded, {pinned} pinned, {failures} failed."),
+    );
+    if failures > 0 {
+        Err(DownloadError::UpgradesFailed(failures).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Replace `md` with the contents of the newer archive `dmod`, keeping its priority and state.
+pub fn upgrade_mod(
+    download_dir: &Utf8Path,
+    cache_dir: &Utf8Path,
+    md: &Manifest,
+    dmod: &DmodMan,
+) -> Result<()> {
+    // The old mod is only removed once the new one is installed, so a broken archive
+    // leaves it as it was.
+    let mut manifest = match find_and_extract_archive(download_dir, cache_dir, dmod.file_name())? {
+        Some(manifest) => manifest,
+        None => Manifest::from_file(cache_dir, &cache_name(Utf8Path::new(dmod.file_name())))?,
+    };
+    if manifest.manifest_dir() != md.manifest_dir() {
+        manifest.take_state_of(md)?;
+        md.remove()?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct UpgradeInfo {
+    name: String,
+    current_version: Option<String>,
+    new_version: Option<String>,
+    archive: String,
+    size: Option<u64>,
+}
+
+fn upgrade_check(settings: &Settings, json: bool) -> Result<()> {
+    let dmodman_list = DmodMan::gather_list(settings.download_dir())?;
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+
+    let upgrades = available_upgrades(&mod_list, &dmodman_list)
+        .into_iter()
+        .map(|(idx, dmod)| UpgradeInfo {
+            name: mod_list[idx].name().to_owned(),
+            current_version: mod_list[idx].version().map(ToOwned::to_owned),
+            new_version: dmod.version(),
+            archive: dmod.file_name().to_owned(),
+            size: metadata(settings.download_dir().join(dmod.file_name()))
+                .map(|m| m.len())
+                .ok(),
+        })
+        .collect::<Vec<_>>();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&upgrades)?);
+        return Ok(());
+    }
+
+    let mut table = create_table(vec![
+        "Name",
+        "Current Version",
+        "New Version",
+        "Archive",
+        "Size",
+    ]);
+    for u in upgrades {
+        table.add_row(vec![
+            Cell::new(u.name),
+            Cell::new(u.current_version.unwrap_or_else(|| "<Unknown>".to_owned())),
+            Cell::new(u.new_version.unwrap_or_else(|| "<Unknown>".to_owned())).fg(Color::Yellow),
+            Cell::new(u.archive),
+            Cell::new(u.size.map_or_else(|| "<Unknown>".to_owned(), human_size)),
+        ]);
+    }
+    table.add_row_if(
+        |idx, _row| idx.eq(&0),
+        vec![Cell::new("No upgrades available.")],
+    );
+
+    log::info!("{table}");
+    Ok(())
+}
+
+/// Find the newest archive in `dmodman_list` for every mod in `mod_list` which has an update.
+pub fn available_upgrades<'a>(
+    mod_list: &[Manifest],
+    dmodman_list: &'a [DmodMan],
+) -> Vec<(usize, &'a DmodMan)> {
+    mod_list
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, md)| {
+            dmodman_list
+                .iter()
+                .filter(|dmod| md.is_an_update(dmod))
+                .max_by_key(|dmod| Version::from(dmod.version().as_deref()))
+                .map(|dmod| (idx, dmod))
+        })
+        .collect()
+}
+
 pub fn list_downloaded_files(download_dir: &Utf8Path, cache_dir: &Utf8Path) -> Result<()> {
     let list = ArchiveListBuilder::new(download_dir, cache_dir)
         .with_index()
@@ -202,12 +600,152 @@ pub fn downloaded_files(download_dir: &Utf8Path) -> Result<Vec<(SupportedArchive
     Ok(supported_files)
 }
 
-pub fn extract_downloaded_files(download_dir: &Utf8Path, cache_dir: &Utf8Path) -> Result<()> {
+fn identify(settings: &Settings, name: Option<&str>, force: bool) -> Result<()> {
+    let idx = FindSelectBuilder::new(
+        ArchiveListBuilder::new(settings.download_dir(), settings.cache_dir())
+            .with_index()
+            .with_colour(),
+    )
+    .with_msg("Please select an archive to identify:")
+    .with_input(name)
+    .build()?
+    .prompt()?;
+    let (_, file) = downloaded_files(settings.download_dir())?
+        .get(idx)
+        .cloned()
+        .ok_or_else(|| DownloadError::ArchiveNotFound(idx.to_string()))?;
+    let archive = settings.download_dir().join(&file);
+
+    if archive.add_extension("json").exists() && !force {
+        log::info!("'{file}' already has dmodman metadata; use '--force' to replace it.");
+        return Ok(());
+    }
+    ensure_writable(&format!("write the metadata of '{file}'"))?;
+
+    let nexus = Nexus::new(settings)?;
+    let found = nexus.identify(&archive)?;
+    let mod_id = found.mod_info.mod_id;
+    nexus.write_metadata(&archive, mod_id, &found.file_details)?;
+    log::info!(
+        "'{file}' is '{}' of mod '{}' ({mod_id}) on Nexus.",
+        found.file_details.name,
+        found.mod_info.name.as_deref().unwrap_or("<unknown>")
+    );
+
+    // A mod extracted before is linked too, so its updates can be checked.
+    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let bare_file_name = file.with_extension("").as_str().to_lowercase();
+    if let Some(md) = mod_list
+        .iter_mut()
+        .find(|md| md.bare_file_name() == bare_file_name && md.nexus_id().is_none())
+    {
+        md.set_nexus_id(mod_id, found.file_details.version)?;
+        log::info!("Linked mod '{}' to Nexus.", md.name());
+    }
+    Ok(())
+}
+
+/// The archive in `download_dir` which mod `md` was extracted from, if it is still there.
+pub fn mod_archive(download_dir: &Utf8Path, md: &Manifest) -> Result<Option<Utf8PathBuf>> {
+    Ok(downloaded_files(download_dir)?
+        .into_iter()
+        .map(|(_, f)| f)
+        .find(|f| f.with_extension("").as_str().to_lowercase() == md.bare_file_name()))
+}
+
+/// Give newly installed mods their priority, before they are deployed for the first time.
+pub fn place_new_mods(
+    settings: &Settings,
+    placement: &Placement,
+    mut new_mods: Vec<Manifest>,
+) -> Result<()> {
+    if new_mods.is_empty() {
+        return Ok(());
+    }
+    new_mods.sort_by(|a, b| a.name().cmp(b.name()));
+
+    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let priority = if let Some(after) = &placement.after {
+        let idx = mod_list
+            .find_mod(after)
+            .ok_or_else(|| ModErrors::ModNotFound(after.clone()))?;
+        Some(mod_list[idx].priority() + 1)
+    } else {
+        placement.priority
+    };
+
+    let Some(mut priority) = priority else {
+        // Without an explicit position every new mod gets the same priority.
+        let priority = settings.new_mod_priority();
+        if priority != 0 {
+            for md in &mut new_mods {
+                md.set_priority(priority)?;
+            }
+        }
+        return Ok(());
+    };
+
+    for md in &mut new_mods {
+        make_room(&mut mod_list, priority, md.manifest_dir())?;
+        md.set_priority(priority)?;
+        if let Some(m) = mod_list
+            .iter_mut()
+            .find(|m| m.manifest_dir() == md.manifest_dir())
+        {
+            *m = md.clone();
+        }
+        priority += 1;
+    }
+    Ok(())
+}
+
+/// Move the mods at `priority` (except `new_mod`) one up, together with every mod
+/// directly above them, up to the first unused priority; their order does not change.
+fn make_room(mod_list: &mut [Manifest], priority: isize, new_mod: &Utf8Path) -> Result<()> {
+    let mut gap = priority;
+    while mod_list
+        .iter()
+        .any(|m| m.manifest_dir() != new_mod && m.priority() == gap)
+    {
+        gap += 1;
+    }
+
+    for md in mod_list
+        .iter_mut()
+        .filter(|m| m.manifest_dir() != new_mod && (priority..gap).contains(&m.priority()))
+    {
+        md.set_priority(md.priority() + 1)?;
+    }
+    Ok(())
+}
+
+/// Extract all archives which are not in the cache directory.
+///
+/// With `retry_failed`, only those which failed the previous time are extracted. An archive
+/// which fails does not stop the others; the failures are shown in a table, and are remembered
+/// for `--retry-failed`.
+///
+/// Returns the newly installed mods and the number of archives which failed.
+pub fn extract_downloaded_files(
+    download_dir: &Utf8Path,
+    cache_dir: &Utf8Path,
+    retry_failed: bool,
+    password: Option<&str>,
+) -> Result<(Vec<Manifest>, usize)> {
     use rayon::prelude::*;
 
-    let sf = downloaded_files(download_dir)?;
+    let mut sf = downloaded_files(download_dir)?;
+    if retry_failed {
+        let failed = read_failed_extractions(cache_dir);
+        sf.retain(|(_, f)| failed.contains(f));
+        if sf.is_empty() {
+            log::info!("No failed archives to retry.");
+            return Ok((Vec::new(), 0));
+        }
+    }
     let extracted_files = Vec::with_capacity(sf.len());
     let extracted_files = Arc::new(Mutex::new(extracted_files));
+    let failures = Mutex::new(Vec::new());
 
     let sty = ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}").unwrap();
     let multi = MultiProgress::new();
@@ -235,26 +773,117 @@ pub fn extract_downloaded_files(download_dir: &Utf8Path, cache_dir: &Utf8Path) -
             }
         });
 
-        sf.par_iter().enumerate().try_for_each(|(idx, (typ, f))| {
-            if extract_downloaded_file(download_dir, cache_dir, *typ, f)? {
-                extracted_files.lock().unwrap().push(f.as_path());
-                progress_bars[idx].inc(1);
-                progress_bars[idx].finish_with_message(format!("Extracting: {f} ... => Done."));
-            } else {
-                progress_bars[idx].finish_with_message(format!("Skipped: {f} ... => Done."));
-            }
-            Ok::<(), anyhow::Error>(())
-        })?;
+        with_io_limit(|| {
+            sf.par_iter().enumerate().for_each(|(idx, (typ, f))| {
+                match extract_downloaded_file(download_dir, cache_dir, *typ, f, password) {
+                    Ok(true) => {
+                        extracted_files.lock().unwrap().push(f.as_path());
+                        progress_bars[idx].inc(1);
+                        progress_bars[idx]
+                            .finish_with_message(format!("Extracting: {f} ... => Done."));
+                    }
+                    Ok(false) => {
+                        progress_bars[idx]
+                            .finish_with_message(format!("Skipped: {f} ... => Done."));
+                    }
+                    Err(e) => {
+                        failures.lock().unwrap().push((f.clone(), e));
+                        progress_bars[idx]
+                            .finish_with_message(format!("Extracting: {f} ... => Failed."));
+                    }
+                }
+            });
+        });
 
         running.store(false, std::sync::atomic::Ordering::Relaxed);
-        Ok::<(), anyhow::Error>(())
-    })?;
+    });
+
+    let mut failures = failures.into_inner().unwrap();
+    let extracted_files = std::mem::take(&mut *extracted_files.lock().unwrap());
+
+    // Installing needs no input, except for fomod installers; those are queued and prompted
+    // for one at a time once the others are installed, so their prompts do not interleave.
+    let detected = extracted_files
+        .par_iter()
+        .map(|f| (*f, ModKind::detect_mod_type(cache_dir, &cache_name(f))))
+        .collect::<Vec<_>>();
+    let mut queued = Vec::new();
+    let mut unattended = Vec::new();
+    for (f, installer) in detected {
+        match installer {
+            Ok(installer) if installer.is_interactive() => queued.push((f, installer)),
+            Ok(installer) => unattended.push((f, installer)),
+            Err(e) => failures.push((f.to_path_buf(), e)),
+        }
+    }
 
-    let extracted_files = extracted_files.lock().unwrap();
-    for name in extracted_files.iter() {
-        install_downloaded_file(cache_dir, name)?;
+    let installed = unattended
+        .par_iter()
+        .map(|(f, installer)| (*f, installer.create_mod(cache_dir, &cache_name(f))))
+        .collect::<Vec<_>>();
+    let installed = installed.into_iter().chain(
+        queued
+            .into_iter()
+            .map(|(f, installer)| (f, installer.create_mod(cache_dir, &cache_name(f)))),
+    );
+
+    let mut new_mods = Vec::with_capacity(extracted_files.len());
+    for (f, manifest) in installed {
+        match manifest {
+            Ok(manifest) => new_mods.push(manifest),
+            Err(e) => failures.push((f.to_path_buf(), e)),
+        }
+    }
+
+    write_failed_extractions(cache_dir, &failures)?;
+    show_failed_extractions(&mut failures);
+
+    Ok((new_mods, failures.len()))
+}
+
+fn show_failed_extractions(failures: &mut [(Utf8PathBuf, anyhow::Error)]) {
+    if failures.is_empty() {
+        return;
     }
 
+    failures.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut table = create_table(vec!["Archive", "Error"]);
+    for (f, e) in failures.iter() {
+        table.add_row(vec![
+            Cell::new(f).fg(Color::Red),
+            Cell::new(format!("{e:#}")),
+        ]);
+    }
+    log::warn!("{table}");
+    log::warn!("Use '--retry-failed' to only extract these archives again.");
+}
+
+/// The archives which failed to extract during the last `extract-all`.
+fn read_failed_extractions(cache_dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    fs::read_to_string(cache_dir.join(FAILED_EXTRACTIONS_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_failed_extractions(
+    cache_dir: &Utf8Path,
+    failures: &[(Utf8PathBuf, anyhow::Error)],
+) -> Result<()> {
+    if is_read_only() {
+        return Ok(());
+    }
+
+    let path = cache_dir.join(FAILED_EXTRACTIONS_FILE);
+    if failures.is_empty() {
+        if path.exists() {
+            remove_file(&path)?;
+        }
+    } else {
+        let failed = failures.iter().map(|(f, _)| f).collect::<Vec<_>>();
+        fs::write(&path, serde_json::to_string_pretty(&failed)?)?;
+        share_with_group(&path)?;
+    }
     Ok(())
 }
 
@@ -264,31 +893,42 @@ pub fn find_and_extract_archive(
     name: &str,
 ) -> Result<Option<Manifest>> {
     let sf = downloaded_files(download_dir)?;
-    if let Some(idx) = name.parse::<usize>().ok() {
-        if let Some((sa, f)) = sf.get(idx).cloned() {
-            if extract_downloaded_file(download_dir, cache_dir, sa, f.as_path())? {
-                install_downloaded_file(cache_dir, &f).map(Some)
-            } else {
-                Ok(None)
-            }
-        } else {
-            Ok(None)
-        }
-    } else if let Some((sa, f)) = find_archive_by_name(&sf, name) {
-        if extract_downloaded_file(download_dir, cache_dir, sa, f.as_path())? {
-            install_downloaded_file(cache_dir, &f).map(Some)
-        } else {
-            Ok(None)
-        }
-    } else if let Some((sa, f)) = find_archive_by_name_fuzzy(&sf, name) {
-        if extract_downloaded_file(download_dir, cache_dir, sa, f.as_path())? {
-            install_downloaded_file(cache_dir, &f).map(Some)
-        } else {
-            Ok(None)
-        }
-    } else {
+    let Some((sa, f)) = find_archive(&sf, name).map(|idx| sf[idx].clone()) else {
         log::trace!("Archive \'{name}\' not found");
-        Err(DownloadError::ArchiveNotFound(name.to_owned()).into())
+        return Err(DownloadError::ArchiveNotFound(name.to_owned()).into());
+    };
+
+    extract_archive(download_dir, cache_dir, sa, &f, None)
+}
+
+/// Extract and install archive `file`; `None` when it was extracted before.
+pub fn extract_archive(
+    download_dir: &Utf8Path,
+    cache_dir: &Utf8Path,
+    archive_type: SupportedArchives,
+    file: &Utf8Path,
+    password: Option<&str>,
+) -> Result<Option<Manifest>> {
+    if extract_downloaded_file(download_dir, cache_dir, archive_type, file, password)? {
+        install_downloaded_file(cache_dir, file).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Extract and install archive `file` of the download directory, using the recorded FOMOD
+/// `choices` where possible; an archive which was extracted before keeps its mod.
+pub fn install_archive(
+    download_dir: &Utf8Path,
+    cache_dir: &Utf8Path,
+    file: &Utf8Path,
+    choices: Option<&FomodChoices>,
+) -> Result<Manifest> {
+    let archive_type = SupportedArchives::from_path(file.as_std_path())?;
+    if extract_downloaded_file(download_dir, cache_dir, archive_type, file, None)? {
+        install_extracted_file(cache_dir, &cache_name(file), choices)
+    } else {
+        Manifest::from_file(cache_dir, &cache_name(file))
     }
 }
 
@@ -297,6 +937,7 @@ fn extract_downloaded_file(
     cache_dir: &Utf8Path,
     archive_type: SupportedArchives,
     file: &Utf8Path,
+    password: Option<&str>,
 ) -> Result<bool> {
     //destination:
     //Force utf-8 compatible strings, in lower-case, here to simplify futher code.
@@ -320,6 +961,7 @@ fn extract_downloaded_file(
         Ok(false)
     } else {
         //TODO: if either one of Dir or Manifest file is missing or corrupt, remove them,
+        ensure_writable(&format!("extract '{download_file}'"))?;
 
         if archive.exists() {
             if archive.is_dir() {
@@ -331,14 +973,21 @@ fn extract_downloaded_file(
 
         // log::info!("Extracting {}", download_file);
         log::debug!("Extracting {} to {}", download_file, archive);
-        archive_type
-            .decompress(download_file.as_std_path(), archive.as_std_path())
-            .unwrap();
+        if let Err(e) =
+            archive_type.decompress(download_file.as_std_path(), archive.as_std_path(), password)
+        {
+            // Do not leave a partially extracted archive behind.
+            if archive.is_dir() {
+                remove_dir_all(&archive)?;
+            }
+            return Err(e);
+        }
 
         // Rename all extracted files to their lower-case counterpart
         // This is especially important for fomod mods, because otherwise we would
         // not know if their name in the fomod package matches their actual names.
         rename_recursive(&archive)?;
+        share_with_group_recursive(&archive)?;
 
         // TODO: Right now we just copy the dmodman file
         // we should incorporate it into the manifest
@@ -357,77 +1006,45 @@ fn extract_downloaded_file(
 }
 
 fn install_downloaded_file(cache_dir: &Utf8Path, file: &Utf8Path) -> Result<Manifest> {
-    let file = Utf8PathBuf::from(file.as_str().to_lowercase()).with_extension("");
-    let mod_kind = ModKind::detect_mod_type(cache_dir, &file)?;
-    mod_kind.create_mod(cache_dir, &file)
-}
-
-pub fn find_archive_by_name(
-    archive_list: &[(SupportedArchives, Utf8PathBuf)],
-    name: &str,
-) -> Option<(SupportedArchives, Utf8PathBuf)> {
-    archive_list
-        .iter()
-        .find_map(|(archive_type, f)| (f == name).then(|| (*archive_type, f.clone())))
+    install_extracted_file(cache_dir, &cache_name(file), None)
 }
-pub fn find_archive_by_name_fuzzy(
-    archive_list: &[(SupportedArchives, Utf8PathBuf)],
-    fuzzy_name: &str,
-) -> Option<(SupportedArchives, Utf8PathBuf)> {
-    let matcher = SkimMatcherV2::default();
-    let mut match_vec = Vec::new();
 
-    for (st, f) in archive_list {
-        let i = matcher.fuzzy_match(f.as_str(), fuzzy_name).unwrap_or(0);
-        match_vec.push((st, f, i));
+/// Install the extracted archive `name`, with the state 'mods pack --with-manifest' packed
+/// into it, if any.
+fn install_extracted_file(
+    cache_dir: &Utf8Path,
+    name: &Utf8Path,
+    choices: Option<&FomodChoices>,
+) -> Result<Manifest> {
+    let packed_state = ManifestState::take_packed(&cache_dir.join(name))?;
+    let installer = ModKind::detect_mod_type(cache_dir, name)?;
+    let mut manifest = installer.create_mod_with_choices(cache_dir, name, choices)?;
+    if let Some(state) = packed_state {
+        manifest.take_packed_state(state)?;
     }
+    Ok(manifest)
+}
 
-    match_vec.sort_unstable_by(|(_, _, ia), (_, _, ib)| ia.cmp(ib));
-    let match_vec = match_vec
-        .iter()
-        .rev()
-        .enumerate()
-        .take_while(|(i, (_, _, mv))| *i <= 5 && *mv > 50)
-        .map(|(_, (sa, f, _))| (*(*sa), (*f).clone()))
-        .collect::<Vec<_>>();
-
-    if match_vec.len() == 1 {
-        match_vec.first().cloned()
-    } else if match_vec.len() > 1 {
-        let choice = if stdin().is_terminal() {
-            //TODO more color and stuff
-
-            log::info!(
-                "Multiple matches found; Please choose one: (Defaults to 0/'{}' on Enter)",
-                match_vec.first().unwrap().1
-            );
-            for (i, (_, f)) in match_vec.iter().enumerate() {
-                log::info!("{i}) {}", f);
-            }
-            log::info!("E) Exit");
-
-            loop {
-                let input: InputWithDefault = prompt_until_ok("Select : ");
-                match input {
-                    InputWithDefault::Input(Input::Exit) => {
-                        return None?;
-                    }
-                    InputWithDefault::Default => {
-                        break 0;
-                    }
-                    InputWithDefault::Input(Input::Digit(d)) => {
-                        if (d as usize) < match_vec.len() {
-                            break d as usize;
-                        }
-                    }
-                }
-            }
-        } else {
-            0
-        };
+/// The name of the directory in the cache an archive is extracted to.
+fn cache_name(file: &Utf8Path) -> Utf8PathBuf {
+    Utf8PathBuf::from(file.as_str().to_lowercase()).with_extension("")
+}
 
-        match_vec.get(choice).cloned()
-    } else {
-        None
-    }
+/// Find archive `name` by its index, its file name or, fuzzily, part of its file name;
+/// like mods are found.
+pub fn find_archive(
+    archive_list: &[(SupportedArchives, Utf8PathBuf)],
+    name: &str,
+) -> Option<usize> {
+    name.parse::<usize>()
+        .ok()
+        .filter(|&idx| idx < archive_list.len())
+        .or_else(|| archive_list.iter().position(|(_, f)| f == name))
+        .or_else(|| {
+            let names = archive_list
+                .iter()
+                .map(|(_, f)| f.as_str())
+                .collect::<Vec<_>>();
+            fuzzy_find(&names, name)
+        })
 }
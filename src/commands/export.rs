@@ -0,0 +1,116 @@
+use std::{
+    collections::BTreeSet, fmt::Write as _, fs::File, io::Write, os::unix::fs::PermissionsExt,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+
+use crate::{
+    mods::{deployed_files, GatherModList},
+    settings::{backup_extension, Settings},
+    utils::shell_quote,
+};
+
+/// Export the current setup to other formats
+#[derive(Debug, Clone, Parser)]
+pub enum ExportCmd {
+    /// Write a shell script with the 'mkdir' and 'ln -s' commands which
+    /// reproduce the current deployment, without needing starmod.
+    Script {
+        /// The file to write the script to.
+        file: Utf8PathBuf,
+    },
+}
+impl ExportCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Script { .. } => true,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::Script { file } => export_script(settings, &file),
+        }
+    }
+}
+
+fn export_script(settings: &Settings, file: &Utf8Path) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let files = deployed_files(&mod_list)?;
+
+    let mut script = String::new();
+    script.push_str("#!/bin/sh\n");
+    writeln!(
+        script,
+        "# Generated by {}; reproduces the deployment of {} mod(s) and {} file(s).",
+        settings.cmd_name(),
+        mod_list.iter().filter(|m| m.is_enabled()).count(),
+        files.len()
+    )?;
+    script.push_str("set -eu\n\n");
+    writeln!(
+        script,
+        "CACHE_DIR={}",
+        shell_quote(settings.cache_dir().as_str())
+    )?;
+    writeln!(
+        script,
+        "GAME_DIR={}\n",
+        shell_quote(settings.game_dir().as_str())
+    )?;
+    let extension = backup_extension();
+    write!(
+        script,
+        r#"# Files which are not ours are moved aside, like starmod does.
+link() {{
+    if [ -e "$2" ] && [ ! -L "$2" ]; then
+        backup="$2.{extension}"
+        n=1
+        while [ -e "$backup" ]; do
+            backup="$2.{extension}.$n"
+            n=$((n + 1))
+        done
+        mv "$2" "$backup"
+    fi
+    ln -sfn "$1" "$2"
+}}
+"#
+    )?;
+
+    let directories = files
+        .iter()
+        .filter_map(|(_, f)| Utf8Path::new(f.destination()).parent())
+        .filter(|d| !d.as_str().is_empty())
+        .collect::<BTreeSet<_>>();
+    script.push('\n');
+    for dir in directories {
+        writeln!(
+            script,
+            "mkdir -p \"$GAME_DIR\"/{}",
+            shell_quote(dir.as_str())
+        )?;
+    }
+
+    let mut current_mod = None;
+    for (name, f) in &files {
+        if current_mod != Some(name) {
+            writeln!(script, "\n# {name}")?;
+            current_mod = Some(name);
+        }
+        writeln!(
+            script,
+            "link \"$CACHE_DIR\"/{} \"$GAME_DIR\"/{}",
+            shell_quote(f.source().as_str()),
+            shell_quote(f.destination())
+        )?;
+    }
+
+    let mut output = File::create(file)?;
+    output.write_all(script.as_bytes())?;
+    output.set_permissions(std::fs::Permissions::from_mode(0o755))?;
+
+    log::info!("Exported the deployment of {} files to {file}", files.len());
+    Ok(())
+}
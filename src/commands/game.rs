@@ -1,13 +1,38 @@
+use std::{
+    fs::{copy, read_to_string, write},
+    process::Command,
+};
+
 use anyhow::Result;
-use camino::Utf8PathBuf;
-use clap::Parser;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Args, Parser};
+use comfy_table::{Cell, Color};
 use walkdir::WalkDir;
 
 use crate::{
+    deployment::{deployed_links, overlay::OverlayMount, DeploymentStrategy},
     errors::{GameErrors, SettingErrors},
-    settings::{LootType, Settings},
+    masterlist,
+    mods::GatherModList,
+    requirements::unmet_components,
+    settings::{backup_extension, create_table, ensure_writable, LootType, Settings},
+    snapshot::{FileChange, GameSnapshot},
+    steam,
+    ui::{notify, page},
+    utils::{edit_files, run_process, shell_quote, AddExtension},
+    vdf,
 };
 
+use super::preflight::run_checks;
+
+const STEAM_USERDATA_DIR: &str = "userdata";
+const STEAM_LOCAL_CONFIG: &str = "config/localconfig.vdf";
+const STEAM_PROCESS: &str = "steam";
+const PROTONTRICKS_PROGRAM: &str = "protontricks";
+const WINETRICKS_PROGRAM: &str = "winetricks";
+// Where winetricks records the verbs it installed into a prefix.
+const WINETRICKS_LOG: &str = "winetricks.log";
+
 #[derive(Clone, Debug, Parser)]
 pub enum GameCmd {
     /// Run 'cmd'; defaults to running the game.
@@ -15,33 +40,208 @@ pub enum GameCmd {
         /// Command to run
         #[command(subcommand)]
         cmd: Option<RunCmd>,
+        #[command(flatten)]
+        options: RunOptions,
     },
-    /// Edit game config files using $EDITOR or 'xdg-open'.
+    /// Edit game config files using the editor configured for their extension,
+    /// $EDITOR or 'xdg-open'.
     EditConfig {
         /// Name of the config-file to edit; If not supplied, all known files will be supplied to the editor.
         /// Uses the $EDITOR as defined when the config file is created, or runs 'xdg-open'
         config_name: Option<String>,
     },
+    /// Print the command which runs 'cmd' through proton or wine, like 'run' does.
+    PrintLaunchCommand {
+        #[command(subcommand)]
+        cmd: Option<RunCmd>,
+    },
+    /// Set the launch options of the game in Steam, so starting it from Steam
+    /// runs the pre-flight checks and the default run command.
+    SetSteamLaunch {
+        /// Only print the launch options, instead of changing Steam's configuration.
+        #[arg(short, long)]
+        print: bool,
+    },
+    /// Record the hashes of the game's own files, preferably before modding it; 'verify'
+    /// compares the game directory with them.
+    Snapshot {
+        /// Replace an existing snapshot.
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Show which of the game's own files were changed, removed or added since the snapshot,
+    /// other than by deploying mods.
+    Verify {
+        /// Hash every file again, instead of trusting an unchanged size and modification time.
+        #[arg(short, long)]
+        full: bool,
+    },
+    /// Show the winetricks components of the wine prefix of the game, or install them.
+    Prefix {
+        #[command(subcommand)]
+        cmd: Option<PrefixCmd>,
+    },
+    /// Used by the Steam launch options; runs Steam's command line for the game.
+    #[clap(hide = true)]
+    SteamRun {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
 }
 impl Default for GameCmd {
     fn default() -> Self {
         Self::Run {
             cmd: Some(RunCmd::default()),
+            options: RunOptions::default(),
         }
     }
 }
 impl GameCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::PrintLaunchCommand { .. } | Self::Verify { .. } => true,
+            Self::SetSteamLaunch { print } => *print,
+            Self::Prefix { cmd } => matches!(cmd, None | Some(PrefixCmd::List)),
+            Self::Run { .. }
+            | Self::EditConfig { .. }
+            | Self::Snapshot { .. }
+            | Self::SteamRun { .. } => false,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         match self {
-            Self::Run { cmd } => cmd
+            Self::Run { cmd, options } => cmd
                 .unwrap_or_else(|| settings.default_run().map(Into::into).unwrap_or_default())
-                .execute(settings),
+                .execute(settings, options),
             Self::EditConfig { config_name } => edit_game_config_files(settings, config_name),
+            Self::PrintLaunchCommand { cmd } => {
+                let cmd = cmd
+                    .unwrap_or_else(|| settings.default_run().map(Into::into).unwrap_or_default());
+                let Some(executable) = cmd.executable(settings) else {
+                    return Err(SettingErrors::NoExecutableConfigured(
+                        settings.cmd_name().to_owned(),
+                    )
+                    .into());
+                };
+                println!(
+                    "{}",
+                    WindowsEnv::new(settings)?.command_line(
+                        &executable,
+                        &[],
+                        settings.run_wrappers()
+                    )
+                );
+                Ok(())
+            }
+            Self::SetSteamLaunch { print } => set_steam_launch(settings, print),
+            Self::SteamRun { command } => steam_run(settings, command),
+            Self::Snapshot { force } => take_snapshot(settings, force),
+            Self::Verify { full } => verify_snapshot(settings, full),
+            Self::Prefix { cmd } => cmd.unwrap_or_default().execute(settings),
+        }
+    }
+}
+
+/// The runtimes windows tools need, like 'vcrun2022' or 'dotnet48', in the prefix of the game.
+#[derive(Clone, Debug, Parser, Default)]
+pub enum PrefixCmd {
+    /// Show the installed components, and those the enabled mods need with 'needs:' tags.
+    #[default]
+    #[clap(visible_alias = "l")]
+    List,
+    /// Install winetricks verbs into the prefix, with protontricks, or winetricks when the game
+    /// runs with wine.
+    #[clap(visible_alias = "i")]
+    Install {
+        #[arg(required = true)]
+        verbs: Vec<String>,
+    },
+}
+impl PrefixCmd {
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::List => {
+                let prefix = prefix_dir(settings)?;
+                let installed = installed_components(&prefix);
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+
+                let mut table = create_table(vec!["Component", "Status"]);
+                for verb in &installed {
+                    table.add_row(vec![
+                        Cell::new(verb),
+                        Cell::new("installed").fg(Color::Green),
+                    ]);
+                }
+                for unmet in unmet_components(&mod_list, &installed) {
+                    table.add_row(vec![
+                        Cell::new(&unmet.requirement),
+                        Cell::new(format!("needed by '{}'", unmet.mod_name)).fg(Color::Red),
+                    ]);
+                }
+                table.add_row_if(
+                    |idx, _| idx.eq(&0),
+                    vec![Cell::new("No components installed.")],
+                );
+                log::info!("Prefix '{prefix}':\n{table}");
+                Ok(())
+            }
+            Self::Install { verbs } => {
+                let mut command = match WindowsEnv::new(settings)? {
+                    WindowsEnv::Proton { .. } => {
+                        let mut command = Command::new(PROTONTRICKS_PROGRAM);
+                        command.arg(settings.game().steam_id().to_string());
+                        command
+                    }
+                    WindowsEnv::Wine { wine, prefix } => {
+                        let mut command = Command::new(WINETRICKS_PROGRAM);
+                        command.env("WINEPREFIX", prefix).env("WINE", wine);
+                        command
+                    }
+                };
+                command.arg("-q").args(&verbs);
+                log::info!(
+                    "Installing {} into the prefix of the game.",
+                    verbs.join(", ")
+                );
+                run_process(command, false)
+            }
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Parser, Default)]
+/// The wine prefix the game runs in.
+pub fn prefix_dir(settings: &Settings) -> Result<Utf8PathBuf> {
+    Ok(match WindowsEnv::new(settings)? {
+        WindowsEnv::Proton { compat_dir, .. } => compat_dir.join("pfx"),
+        WindowsEnv::Wine { prefix, .. } => prefix,
+    })
+}
+
+/// The winetricks verbs installed into `prefix`, with protontricks or winetricks.
+pub fn installed_components(prefix: &Utf8Path) -> Vec<String> {
+    let mut verbs = read_to_string(prefix.join(WINETRICKS_LOG))
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>();
+    verbs.sort_unstable();
+    verbs.dedup();
+    verbs
+}
+
+#[derive(Clone, Copy, Debug, Default, Args)]
+pub struct RunOptions {
+    /// Don't check that the game is ready to run before running it.
+    #[arg(long, global = true)]
+    skip_checks: bool,
+    /// Start the program in the background and return immediately, without its output.
+    #[arg(long, global = true)]
+    detach: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Parser, Default)]
 pub enum RunCmd {
     /// Run the game
     #[default]
@@ -56,94 +256,80 @@ pub enum RunCmd {
 
     #[clap(id = "xedit32")]
     XEdit32,
+    /// Run a windows executable, like a tool installed by a mod
+    Tool {
+        /// Path to the executable
+        executable: Utf8PathBuf,
+    },
 }
 impl RunCmd {
-    pub fn execute(self, settings: &Settings) -> Result<()> {
+    /// Run the command; the pre-flight checks are run first when this starts the game.
+    pub fn execute(self, settings: &Settings, mut options: RunOptions) -> Result<()> {
+        let overlay = mount_overlay(settings)?;
+        if overlay.is_some() && options.detach {
+            log::warn!(
+                "The mods are unmounted when the program exits; not running it in the background."
+            );
+            options.detach = false;
+        }
+
+        if !options.skip_checks && matches!(self, Self::Game | Self::Loader) {
+            run_checks(settings)?;
+        }
+
         match self {
-            Self::XEdit | Self::XEdit32 => Self::run_xedit(settings),
-            Self::Game | Self::Loader => self.run_executable(settings),
-            Self::Loot => match settings.loot() {
-                LootType::Windows(_) => self.run_executable(settings),
-                LootType::FlatPack => Self::run_flatpack_loot(settings),
-            },
+            Self::XEdit | Self::XEdit32 => Self::run_xedit(settings, options.detach),
+            Self::Game | Self::Loader if !options.detach => {
+                let result = self.run_executable(settings, false);
+                notify_game_exited(settings, &result);
+                result
+            }
+            Self::Game | Self::Loader | Self::Tool { .. } => {
+                self.run_executable(settings, options.detach)
+            }
+            Self::Loot => {
+                masterlist::refresh(settings);
+                match settings.loot() {
+                    LootType::Windows(_) => self.run_executable(settings, options.detach),
+                    LootType::FlatPack => Self::run_flatpack_loot(settings, options.detach),
+                }
+            }
         }
     }
-    fn run_executable(self, settings: &Settings) -> Result<()> {
-        if let Some(proton_dir) = settings.proton_dir() {
-            if let Some(compat_dir) = settings.compat_dir() {
-                if let Some(steam_dir) = settings.steam_dir() {
-                    let mut compat_dir = compat_dir.to_path_buf();
-                    if compat_dir.file_name().unwrap_or_default()
-                        != settings.game().steam_id().to_string().as_str()
-                    {
-                        compat_dir.push(settings.game().steam_id().to_string());
-                    }
-                    let mut proton_exe = proton_dir.to_path_buf();
-                    proton_exe.push("proton");
-
-                    let executable = match self {
-                        Self::Game => Some(settings.game_dir().join(settings.game().exe_name())),
-                        Self::Loader => {
-                            Some(settings.game_dir().join(settings.game().loader_name()))
-                        }
-                        Self::Loot => {
-                            if let LootType::Windows(loot_dir) = settings.loot() {
-                                Some(loot_dir.join(settings.game().loot_name()))
-                            } else {
-                                None
-                            }
-                        }
-                        Self::XEdit => settings
-                            .xedit_dir()
-                            .map(|xedit_dir| xedit_dir.join(settings.game().xedit_name())),
-                        Self::XEdit32 => settings
-                            .xedit_dir()
-                            .map(|xedit_dir| xedit_dir.join(settings.game().xedit32_name())),
-                    };
-
-                    if let Some(executable) = executable {
-                        if executable.exists() {
-                            if log::log_enabled!(log::Level::Debug) {
-                                log::debug!("Running 'STEAM_COMPAT_DATA_PATH={} STEAM_COMPAT_CLIENT_INSTALL_PATH={} {} run {}'", compat_dir, steam_dir, proton_exe, executable );
-                            } else {
-                                log::info!("Running '{}'", executable);
-                            }
-
-                            let output = std::process::Command::new(proton_exe)
-                                .arg("run")
-                                // .arg("waitforexitandrun")
-                                .arg(executable)
-                                .env("STEAM_COMPAT_DATA_PATH", compat_dir)
-                                .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_dir)
-                                .output()?;
-
-                            if !output.status.success() && !output.stdout.is_empty() {
-                                log::info!("{:?}", output.stdout);
-                                //FIXME: output.status.exit_ok()
-                            }
-                            Ok(())
-                        } else {
-                            Err(SettingErrors::ExecutableNotFound(executable).into())
-                        }
-                    } else {
-                        //TODO: this should be an error, right?
-                        println!("Proper Path not set, please update your configuration via 'starmod config update'");
-                        Ok(())
-                    }
+    /// The windows executable this runs through proton, if known.
+    fn executable(&self, settings: &Settings) -> Option<Utf8PathBuf> {
+        match self {
+            Self::Game => Some(settings.game_dir().join(settings.game().exe_name())),
+            Self::Loader => Some(settings.game_dir().join(settings.game().loader_name())),
+            Self::Loot => {
+                if let LootType::Windows(loot_dir) = settings.loot() {
+                    Some(loot_dir.join(settings.game().loot_name()))
                 } else {
-                    Err(SettingErrors::NoSteamDirFound(settings.cmd_name().to_owned()).into())
+                    None
                 }
-            } else {
-                Err(SettingErrors::NoCompatDirFound(settings.cmd_name().to_owned()).into())
             }
-        } else {
-            Err(SettingErrors::NoProtonDirFound(settings.cmd_name().to_owned()).into())
+            Self::XEdit => settings
+                .xedit_dir()
+                .map(|xedit_dir| xedit_dir.join(settings.game().xedit_name())),
+            Self::XEdit32 => settings
+                .xedit_dir()
+                .map(|xedit_dir| xedit_dir.join(settings.game().xedit32_name())),
+            Self::Tool { executable } => Some(executable.clone()),
         }
     }
-    fn run_flatpack_loot(settings: &Settings) -> Result<()> {
+    fn run_executable(self, settings: &Settings, detach: bool) -> Result<()> {
+        let Some(executable) = self.executable(settings) else {
+            return Err(
+                SettingErrors::NoExecutableConfigured(settings.cmd_name().to_owned()).into(),
+            );
+        };
+        run_with_proton(settings, &executable, &[], settings.run_wrappers(), detach)
+    }
+    fn run_flatpack_loot(settings: &Settings, detach: bool) -> Result<()> {
         log::info!("Running 'flatpak run io.github.loot.loot --game starfield --game-path {} --loot-data-path {}'", settings.game_dir(), settings.loot_data_dir());
 
-        let output = std::process::Command::new("flatpak")
+        let mut command = Command::new("flatpak");
+        command
             .arg("run")
             .arg("io.github.loot.loot")
             .arg("--game")
@@ -151,26 +337,375 @@ impl RunCmd {
             // .arg("--game-path")
             // .arg(settings.game_dir()) //FIXME
             .arg("--loot-data-path")
-            .arg(settings.loot_data_dir())
-            .output()?;
+            .arg(settings.loot_data_dir());
+        run_process(command, detach)
+    }
+    fn run_xedit(settings: &Settings, detach: bool) -> Result<()> {
+        // RunCmd::XEdit32.run_executable(settings)?;
+        RunCmd::XEdit.run_executable(settings, detach)
+    }
+}
+
+/// What is needed to run windows executables of the game.
+enum WindowsEnv {
+    /// Steam's proton, in the prefix of the game in the compat directory.
+    Proton {
+        proton_exe: Utf8PathBuf,
+        compat_dir: Utf8PathBuf,
+        steam_dir: Utf8PathBuf,
+        // With the flatpak of Steam, proton runs inside its sandbox; the cache directory has
+        // to be made available there, or the links into it are dangling.
+        flatpak_cache_dir: Option<Utf8PathBuf>,
+    },
+    /// A plain wine, in a prefix of its own, for games not installed through Steam.
+    Wine {
+        wine: Utf8PathBuf,
+        prefix: Utf8PathBuf,
+    },
+}
+impl WindowsEnv {
+    fn new(settings: &Settings) -> Result<Self> {
+        if let Some(wine) = settings.wine() {
+            let Some(prefix) = settings.wine_prefix() else {
+                return Err(
+                    SettingErrors::NoWinePrefixFound(settings.cmd_name().to_owned()).into(),
+                );
+            };
+            return Ok(Self::Wine {
+                wine: wine.to_path_buf(),
+                prefix: prefix.to_path_buf(),
+            });
+        }
+
+        let Some(proton_dir) = settings.proton_dir() else {
+            return Err(SettingErrors::NoProtonDirFound(settings.cmd_name().to_owned()).into());
+        };
+        let Some(compat_dir) = settings.compat_dir() else {
+            return Err(SettingErrors::NoCompatDirFound(settings.cmd_name().to_owned()).into());
+        };
+        let Some(steam_dir) = settings.steam_dir() else {
+            return Err(SettingErrors::NoSteamDirFound(settings.cmd_name().to_owned()).into());
+        };
 
-        if !output.status.success() && !output.stdout.is_empty() {
-            log::info!("{:?}", output.stdout);
-            //FIXME: output.status.exit_ok()
+        let mut compat_dir = compat_dir.to_path_buf();
+        if compat_dir.file_name().unwrap_or_default()
+            != settings.game().steam_id().to_string().as_str()
+        {
+            compat_dir.push(settings.game().steam_id().to_string());
         }
-        Ok(())
+        let mut proton_exe = proton_dir.to_path_buf();
+        proton_exe.push("proton");
+
+        let flatpak_cache_dir = steam::is_flatpak(steam_dir).then(|| {
+            log::debug!("Steam runs as a flatpak; running proton through 'flatpak run'.");
+            settings.cache_dir().to_owned()
+        });
+
+        Ok(Self::Proton {
+            proton_exe,
+            compat_dir,
+            steam_dir: steam_dir.to_path_buf(),
+            flatpak_cache_dir,
+        })
     }
-    fn run_xedit(settings: &Settings) -> Result<()> {
-        // RunCmd::XEdit32.run_executable(settings)?;
-        RunCmd::XEdit.run_executable(settings)
+    /// The environment proton or wine needs.
+    fn envs(&self) -> Vec<(&'static str, &Utf8Path)> {
+        match self {
+            Self::Proton {
+                compat_dir,
+                steam_dir,
+                ..
+            } => vec![
+                ("STEAM_COMPAT_DATA_PATH", compat_dir),
+                ("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_dir),
+            ],
+            Self::Wine { prefix, .. } => vec![("WINEPREFIX", prefix)],
+        }
+    }
+    /// The command as it can be pasted into a shell.
+    fn command_line(&self, executable: &Utf8Path, args: &[String], wrappers: &[String]) -> String {
+        // The flatpak gets the environment through its arguments.
+        let envs = if matches!(
+            self,
+            Self::Proton {
+                flatpak_cache_dir: Some(_),
+                ..
+            }
+        ) {
+            Vec::new()
+        } else {
+            self.envs()
+                .iter()
+                .map(|(key, value)| format!("{key}={}", shell_quote(value.as_str())))
+                .collect()
+        };
+        envs.into_iter()
+            .chain(
+                self.command(executable, args, wrappers)
+                    .iter()
+                    .map(|arg| shell_quote(arg)),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    /// Program and arguments which run `executable` through proton or wine, and `wrappers`.
+    fn command(&self, executable: &Utf8Path, args: &[String], wrappers: &[String]) -> Vec<String> {
+        let (proton_exe, flatpak_cache_dir) = match self {
+            Self::Proton {
+                proton_exe,
+                flatpak_cache_dir,
+                ..
+            } => (proton_exe, flatpak_cache_dir),
+            Self::Wine { wine, .. } => {
+                return wrapped(
+                    wrappers,
+                    [wine.to_string(), executable.to_string()]
+                        .into_iter()
+                        .chain(args.iter().cloned()),
+                );
+            }
+        };
+        let proton = [
+            "run".to_owned(),
+            // "waitforexitandrun".to_owned(),
+            executable.to_string(),
+        ]
+        .into_iter()
+        .chain(args.iter().cloned());
+
+        let Some(cache_dir) = flatpak_cache_dir else {
+            return wrapped(
+                wrappers,
+                std::iter::once(proton_exe.to_string()).chain(proton),
+            );
+        };
+        let flatpak = ["flatpak".to_owned(), "run".to_owned()]
+            .into_iter()
+            .chain(
+                self.envs()
+                    .into_iter()
+                    .map(|(key, value)| format!("--env={key}={value}")),
+            )
+            .chain([
+                format!("--filesystem={cache_dir}"),
+                format!("--command={proton_exe}"),
+                steam::FLATPAK_ID.to_owned(),
+            ]);
+        wrapped(wrappers, flatpak.chain(proton))
+    }
+}
+
+/// The words of all `wrappers`, followed by `command`.
+fn wrapped(wrappers: &[String], command: impl Iterator<Item = String>) -> Vec<String> {
+    wrappers
+        .iter()
+        .flat_map(|w| w.split_whitespace())
+        .map(ToOwned::to_owned)
+        .chain(command)
+        .collect()
+}
+
+/// Run the windows `executable` with `args` through proton in the game's compat directory, or
+/// through the configured wine, itself run through `wrappers`; see `run_process`.
+pub fn run_with_proton(
+    settings: &Settings,
+    executable: &Utf8Path,
+    args: &[String],
+    wrappers: &[String],
+    detach: bool,
+) -> Result<()> {
+    let env = WindowsEnv::new(settings)?;
+    if !executable.exists() {
+        return Err(SettingErrors::ExecutableNotFound(executable.to_path_buf()).into());
+    }
+
+    if log::log_enabled!(log::Level::Debug) {
+        log::debug!("Running '{}'", env.command_line(executable, args, wrappers));
+    } else {
+        log::info!("Running '{}'", executable);
+    }
+
+    let args = env.command(executable, args, wrappers);
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).envs(env.envs());
+    run_process(command, detach)
+}
+
+/// The launch options which make Steam start the game through `steam-run`.
+fn steam_launch_options(settings: &Settings) -> String {
+    let starmod = std::env::current_exe()
+        .ok()
+        .and_then(|p| Utf8PathBuf::try_from(p).ok())
+        .map_or_else(|| settings.cmd_name().to_owned(), |p| p.to_string());
+    format!("{} game steam-run %command%", shell_quote(&starmod))
+}
+
+fn set_steam_launch(settings: &Settings, print: bool) -> Result<()> {
+    let options = steam_launch_options(settings);
+    if print {
+        println!("{options}");
+        return Ok(());
+    }
+
+    ensure_writable("change the Steam launch options")?;
+    let Some(steam_dir) = settings.steam_dir() else {
+        return Err(SettingErrors::NoSteamDirFound(settings.cmd_name().to_owned()).into());
+    };
+    if is_steam_running() {
+        log::warn!("Steam is running and may overwrite the launch options when it exits; close Steam and run this again if they are not kept.");
+    }
+
+    let mut changed = 0;
+    for entry in steam_dir.join(STEAM_USERDATA_DIR).read_dir_utf8()? {
+        let config = entry?.path().join(STEAM_LOCAL_CONFIG);
+        let Ok(contents) = read_to_string(&config) else {
+            continue;
+        };
+        if let Some(contents) =
+            vdf::set_launch_options(&contents, settings.game().steam_id(), &options)
+        {
+            copy(&config, config.add_extension(backup_extension()))?;
+            write(&config, contents)?;
+            log::info!("Set the launch options in '{config}'.");
+            changed += 1;
+        }
+    }
+
+    if changed == 0 {
+        log::warn!(
+            "No Steam user has settings for {}; set the launch options in Steam to:",
+            settings.game().game_name()
+        );
+        println!("{options}");
     }
+    Ok(())
+}
+
+fn is_steam_running() -> bool {
+    Utf8Path::new("/proc").read_dir_utf8().is_ok_and(|procs| {
+        procs.filter_map(Result::ok).any(|p| {
+            read_to_string(p.path().join("comm")).is_ok_and(|comm| comm.trim() == STEAM_PROCESS)
+        })
+    })
+}
+
+/// Run the `command` Steam would run to start the game; when the default run command is
+/// the loader, that is started instead of the game.
+fn steam_run(settings: &Settings, command: Vec<String>) -> Result<()> {
+    let _overlay = mount_overlay(settings)?;
+    run_checks(settings)?;
+
+    if command.is_empty() {
+        return Err(SettingErrors::NoExecutableConfigured(settings.cmd_name().to_owned()).into());
+    }
+    let mut command = wrapped(settings.run_wrappers(), command.into_iter());
+
+    if settings.default_run().map(RunCmd::from) == Some(RunCmd::Loader) {
+        let exe_name = settings.game().exe_name();
+        if let Some(arg) = command.iter_mut().find(|a| {
+            Utf8Path::new(a.as_str())
+                .file_name()
+                .is_some_and(|f| f.eq_ignore_ascii_case(exe_name))
+        }) {
+            let loader = Utf8Path::new(arg.as_str()).with_file_name(settings.game().loader_name());
+            if loader.exists() {
+                *arg = loader.to_string();
+            } else {
+                log::warn!("'{loader}' does not exist; starting the game without it.");
+            }
+        }
+    }
+
+    log::info!("Running '{}'", command.join(" "));
+    let mut process = Command::new(&command[0]);
+    process.args(&command[1..]);
+    // Steam tracks the game through this process, so never detach.
+    let result = run_process(process, false);
+    notify_game_exited(settings, &result);
+    result
+}
+
+/// With the overlay deployment, mount the enabled mods over the game directory for as long as
+/// the returned mount is kept.
+fn mount_overlay(settings: &Settings) -> Result<Option<OverlayMount>> {
+    if settings.deployment() != DeploymentStrategy::Overlay {
+        return Ok(None);
+    }
+
+    let linked = deployed_links(settings.cache_dir(), settings.game_dir())?.len();
+    if linked > 0 {
+        log::warn!("{linked} file(s) are still linked into the game directory; run 'purge deployment' to remove them.");
+    }
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    OverlayMount::mount(
+        &mod_list,
+        settings.cache_dir(),
+        settings.game_dir(),
+        &settings.overlay_dir()?,
+    )
+    .map(Some)
+}
+
+fn notify_game_exited(settings: &Settings, result: &Result<()>) {
+    let game = settings.game().game_name();
+    match result {
+        Ok(()) => notify(&format!("{game} exited"), "The game exited normally."),
+        Err(e) => notify(&format!("{game} exited"), &format!("{e:#}")),
+    }
+}
+
+fn take_snapshot(settings: &Settings, force: bool) -> Result<()> {
+    if !force && GameSnapshot::exists(settings.cache_dir()) {
+        return Err(GameErrors::SnapshotExists.into());
+    }
+    let linked = deployed_links(settings.cache_dir(), settings.game_dir())?.len();
+    if linked > 0 {
+        log::info!("Skipping the {linked} file(s) deployed by {}; their backups are used where they replaced game files.", settings.cmd_name());
+    }
+
+    let snapshot = GameSnapshot::take(settings.cache_dir(), settings.game_dir())?;
+    snapshot.write(settings.cache_dir())?;
+    log::info!("Recorded the hashes of {} game file(s).", snapshot.len());
+    Ok(())
+}
+
+fn verify_snapshot(settings: &Settings, full: bool) -> Result<()> {
+    let Some(snapshot) = GameSnapshot::read(settings.cache_dir())? else {
+        return Err(GameErrors::NoSnapshot(settings.cmd_name().to_owned()).into());
+    };
+    let changes = snapshot.verify(settings.cache_dir(), settings.game_dir(), full)?;
+    if changes.is_empty() {
+        log::info!(
+            "All {} game file(s) are as they were in the snapshot.",
+            snapshot.len()
+        );
+        return Ok(());
+    }
+
+    let mut table = create_table(vec!["File", "Change"]);
+    for (path, change) in &changes {
+        let color = match change {
+            FileChange::Modified => Color::Red,
+            FileChange::Missing => Color::Yellow,
+            FileChange::Added => Color::Green,
+        };
+        table.add_row(vec![Cell::new(path).fg(color), Cell::new(change).fg(color)]);
+    }
+    page(&format!("\n{table}"));
+    log::warn!(
+        "{} of {} game file(s) changed since the snapshot.",
+        changes.len(),
+        snapshot.len()
+    );
+    Ok(())
 }
 
 fn edit_game_config_files(settings: &Settings, config_name: Option<String>) -> Result<()> {
     let mut config_files_to_edit = Vec::new();
-    let mut game_my_document_dir = settings.compat_dir().unwrap().to_path_buf();
-    game_my_document_dir.push(settings.game().steam_id().to_string());
-    game_my_document_dir.push(settings.game().my_game_dir());
+    let Some(user_dir) = settings.windows_user_dir() else {
+        return Err(SettingErrors::NoCompatDirFound(settings.cmd_name().to_owned()).into());
+    };
+    let mut game_my_document_dir = user_dir.join(settings.game().my_game_dir());
 
     if let Some(config_name) = config_name {
         game_my_document_dir.push(config_name);
@@ -209,12 +744,6 @@ fn edit_game_config_files(settings: &Settings, config_name: Option<String>) -> R
     } else {
         log::info!("Editing: {:?}", config_files_to_edit);
 
-        let mut editor_cmd = std::process::Command::new(settings.editor());
-        for f in config_files_to_edit {
-            editor_cmd.arg(f);
-        }
-        editor_cmd.spawn()?.wait()?;
-
-        Ok(())
+        edit_files(settings, &config_files_to_edit)
     }
 }
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use comfy_table::Cell;
+
+use crate::{
+    history::read_history,
+    settings::{create_table, Settings},
+    ui::page,
+};
+
+pub fn show_history(settings: &Settings, count: usize) -> Result<()> {
+    let history = read_history(settings)?;
+
+    let mut table = create_table(vec!["When", "User", "Command", "Changes"]);
+    for entry in history.iter().rev().take(count).rev() {
+        table.add_row(vec![
+            Cell::new(entry.timestamp().format("%Y-%m-%d %H:%M:%S")),
+            Cell::new(entry.user()),
+            Cell::new(entry.command()),
+            Cell::new(
+                entry
+                    .changes()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        ]);
+    }
+
+    table.add_row_if(
+        |idx, _row| idx.eq(&0),
+        vec![Cell::new("No history recorded yet.")],
+    );
+
+    page(&format!("\n{table}"));
+    Ok(())
+}
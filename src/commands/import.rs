@@ -0,0 +1,254 @@
+use std::{
+    fs::{copy, read_dir, DirBuilder},
+    os::unix::fs::symlink,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use walkdir::WalkDir;
+
+use crate::{
+    collection::{Collection, CollectionMod},
+    errors::DownloadError,
+    manifest::Manifest,
+    mods::{GatherModList, ModKind, ModList},
+    nexus::Nexus,
+    plugin_list::PluginList,
+    settings::{ensure_writable, share_with_group_recursive, Settings},
+    ui::confirm,
+};
+
+use super::{
+    downloads::{downloaded_files, install_archive, place_new_mods, Placement},
+    list::list_mods,
+    plugins::write_plugin_list,
+};
+
+// Bookkeeping files of other mod managers; these are not part of the mod itself.
+const STAGING_METADATA_FILES: &[&str] = &["meta.ini", "__folder_managed_by_vortex"];
+
+/// Import mods from outside of the download directory
+#[derive(Debug, Clone, Parser)]
+pub enum ImportCmd {
+    /// Install every already-extracted mod directory within <dir>,
+    /// like the staging directory of another mod manager or manually extracted archives.
+    Staging {
+        /// Directory which holds one directory per mod.
+        dir: Utf8PathBuf,
+        /// Symlink the files into the cache instead of copying them;
+        /// the mods then depend on <dir> staying in place.
+        #[arg(long)]
+        link: bool,
+        #[command(flatten)]
+        placement: Placement,
+    },
+    /// Install the mods of a Nexus collection from its 'collection.json', in the order and
+    /// with the installer choices of the collection, and take over its load order.
+    /// Archives missing from the download directory are downloaded from Nexus.
+    Collection {
+        /// The 'collection.json' of the collection.
+        file: Utf8PathBuf,
+        /// Install the mods the collection marks as optional as well.
+        #[arg(long)]
+        optional: bool,
+    },
+}
+impl ImportCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Staging { .. } | Self::Collection { .. } => false,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::Staging {
+                dir,
+                link,
+                placement,
+            } => {
+                let new_mods = import_staging(settings.cache_dir(), &dir, link)?;
+                log::info!("Imported {} mod(s) from '{dir}'.", new_mods.len());
+                place_new_mods(settings, &placement, new_mods)?;
+                list_mods(settings)
+            }
+            Self::Collection { file, optional } => {
+                let collection = Collection::read(&file)?;
+                import_collection(settings, &collection, optional)?;
+                list_mods(settings)
+            }
+        }
+    }
+}
+
+fn import_collection(settings: &Settings, collection: &Collection, optional: bool) -> Result<()> {
+    let name = &collection.info.name;
+    let game = settings.game().nexus_game_name();
+    if let Some(domain) = collection.info.domain_name.as_deref() {
+        if !domain.eq_ignore_ascii_case(game) {
+            return Err(
+                DownloadError::CollectionForOtherGame(name.clone(), domain.to_owned()).into(),
+            );
+        }
+    }
+    ensure_writable(&format!("import collection '{name}'"))?;
+
+    let mut mods = collection
+        .mods
+        .iter()
+        .filter(|cm| optional || !cm.optional)
+        .collect::<Vec<_>>();
+    // Stable, so the order within a phase is kept.
+    mods.sort_by_key(|cm| cm.phase);
+    log::info!("Importing collection '{name}' with {} mod(s).", mods.len());
+
+    let Some(archives) = find_collection_archives(settings, &mods)? else {
+        return Ok(());
+    };
+
+    let download_dir = settings.download_dir();
+    let cache_dir = settings.cache_dir();
+    let mut installed = Vec::with_capacity(mods.len());
+    for (cm, archive) in mods.iter().zip(&archives) {
+        log::info!("Installing '{}' from '{archive}'", cm.name);
+        let md = install_archive(download_dir, cache_dir, archive, cm.fomod_choices())?;
+        installed.push(md.manifest_dir().to_owned());
+    }
+
+    // The mods of the collection go on top of the mod-list, in the order of the collection.
+    let mut mod_list = Vec::gather_mods(cache_dir)?;
+    let mut priority = mod_list
+        .iter()
+        .filter(|md| !installed.iter().any(|dir| dir == md.manifest_dir()))
+        .map(Manifest::priority)
+        .max()
+        .map_or(0, |p| p + 1);
+    for dir in &installed {
+        if let Some(md) = mod_list.iter_mut().find(|md| md.manifest_dir() == dir) {
+            md.set_priority(priority)?;
+            md.set_enabled()?;
+            priority += 1;
+        }
+    }
+    mod_list.sort_by(Ord::cmp);
+    mod_list.re_enable(cache_dir, settings.game_dir())?;
+
+    if !collection.plugins.is_empty() {
+        let list = collection
+            .plugins
+            .iter()
+            .map(|p| (p.name.clone(), p.enabled))
+            .collect::<PluginList>();
+        write_plugin_list(settings, &list)?;
+        log::info!("Applied the load order of {} plugin(s).", list.len());
+    }
+    Ok(())
+}
+
+/// The archives of `mods` in the download directory; missing archives are downloaded from Nexus,
+/// unless the user declines to.
+fn find_collection_archives(
+    settings: &Settings,
+    mods: &[&CollectionMod],
+) -> Result<Option<Vec<Utf8PathBuf>>> {
+    let download_dir = settings.download_dir();
+    let downloaded = downloaded_files(download_dir)?;
+    let mut archives = mods
+        .iter()
+        .map(|cm| cm.source.find_archive(download_dir, &downloaded))
+        .collect::<Vec<_>>();
+
+    let mut missing = Vec::new();
+    for (cm, archive) in mods.iter().zip(&archives) {
+        if archive.is_none() {
+            if cm.source.nexus_ids().is_none() {
+                return Err(DownloadError::ArchiveNotFound(cm.name.clone()).into());
+            }
+            missing.push(cm.name.clone());
+        }
+    }
+    if missing.is_empty() {
+        return Ok(Some(archives.into_iter().flatten().collect()));
+    }
+    if !confirm("download the missing archives from Nexus", &missing)? {
+        return Ok(None);
+    }
+
+    let nexus = Nexus::new(settings)?;
+    for (cm, archive) in mods.iter().zip(&mut archives) {
+        if let (None, Some((mod_id, file_id))) = (&archive, cm.source.nexus_ids()) {
+            *archive = Some(nexus.download(download_dir, mod_id, file_id)?);
+        }
+    }
+    Ok(Some(archives.into_iter().flatten().collect()))
+}
+
+fn import_staging(
+    cache_dir: &Utf8Path,
+    staging_dir: &Utf8Path,
+    link: bool,
+) -> Result<Vec<Manifest>> {
+    if !staging_dir.is_dir() {
+        return Err(DownloadError::StagingDirNotFound(staging_dir.to_owned()).into());
+    }
+    ensure_writable(&format!("import mods from '{staging_dir}'"))?;
+
+    let mut mod_dirs = Vec::new();
+    for entry in read_dir(staging_dir)? {
+        let path = Utf8PathBuf::try_from(entry?.path())?;
+        if path.is_dir() && !path.file_name().unwrap_or_default().starts_with('.') {
+            mod_dirs.push(path);
+        }
+    }
+    mod_dirs.sort();
+
+    let mut new_mods = Vec::with_capacity(mod_dirs.len());
+    for source in mod_dirs {
+        let name = Utf8PathBuf::from(source.file_name().unwrap_or_default().to_lowercase());
+        let destination = cache_dir.join(&name);
+
+        if destination.exists() || Manifest::from_file(cache_dir, &name).is_ok_and(|m| m.is_valid())
+        {
+            log::info!("Skipping '{source}'; '{name}' is already in the cache.");
+            continue;
+        }
+
+        log::info!("Importing '{source}'");
+        mirror_dir(&source, &destination, link)?;
+        share_with_group_recursive(&destination)?;
+
+        let installer = ModKind::detect_mod_type(cache_dir, &name)?;
+        new_mods.push(installer.create_mod(cache_dir, &name)?);
+    }
+    Ok(new_mods)
+}
+
+/// Recreate `source` at `destination` with lower-case names, like an extracted archive;
+/// the files are either copied or symlinked.
+fn mirror_dir(source: &Utf8Path, destination: &Utf8Path, link: bool) -> Result<()> {
+    DirBuilder::new().recursive(true).create(destination)?;
+
+    for entry in WalkDir::new(source).min_depth(1).follow_links(true) {
+        let entry = entry?;
+        let entry_path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
+        let relative = entry_path.strip_prefix(source)?;
+
+        if STAGING_METADATA_FILES.contains(&relative.as_str()) {
+            log::debug!("Skipping '{entry_path}'");
+            continue;
+        }
+
+        let target = destination.join(relative.as_str().to_lowercase());
+        if entry.file_type().is_dir() {
+            DirBuilder::new().recursive(true).create(&target)?;
+        } else if link {
+            log::trace!("linking {entry_path} -> {target}");
+            symlink(entry_path.canonicalize_utf8()?, &target)?;
+        } else {
+            log::trace!("copying {entry_path} -> {target}");
+            copy(&entry_path, &target)?;
+        }
+    }
+    Ok(())
+}
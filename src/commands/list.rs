@@ -1,73 +1,239 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt::Write as _, fs::write};
 
 use anyhow::Result;
-use camino::Utf8Path;
-use clap::Parser;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Parser, ValueEnum};
 use comfy_table::{Cell, Color};
 
 use crate::{
-    conflict::conflict_list_by_file,
+    conflict::{conflict_list_by_file, identical_conflicts},
+    game::Game,
+    manifest::Manifest,
     mods::GatherModList,
     settings::{create_table, Settings},
-    ui::ModListBuilder,
+    ui::{page, FileTreeBuilder, ModListBuilder, ModListColumn, ModListGroup, ModListSort},
 };
 
-#[derive(Debug, Clone, Parser, Default)]
+#[derive(Debug, Clone, Parser)]
 pub enum ListCmd {
     /// Show all mods
-    #[default]
     #[clap(visible_alias = "m")]
-    Mods,
-    /// Show all conflicting files in the current active mod-list
+    Mods {
+        /// Comma separated list of columns to show; the name is always shown.
+        /// Defaults to the 'list-columns' setting.
+        #[arg(short, long, value_enum, value_delimiter = ',')]
+        columns: Vec<ModListColumn>,
+        /// Sort by <column>[:asc|:desc]; one of index, name, priority, status,
+        /// version, nexus-id, mod-type or size.
+        #[arg(short, long)]
+        sort: Option<ModListSort>,
+        /// Show the mods in groups; by tag, mod-type or status.
+        #[arg(short, long, value_enum)]
+        group_by: Option<ModListGroup>,
+    },
+    /// Show all conflicting files in the current active mod-list;
+    /// files which are identical in every mod are shown in grey.
     #[clap(visible_alias = "c")]
-    Conflicts,
+    Conflicts {
+        /// Leave out files which are identical in every mod.
+        #[arg(long)]
+        hide_identical: bool,
+    },
     /// Show all files currently in the active mod-list;
     /// Files shown in red are ignored and green files are used instead.
     #[clap(visible_alias = "f")]
-    Files,
+    Files {
+        /// Show the deployed files as a directory tree.
+        #[arg(short, long)]
+        tree: bool,
+        /// Collapse directories below this depth in the tree.
+        #[arg(short, long, requires = "tree")]
+        depth: Option<usize>,
+    },
     /// Show all disabled files
     DisabledFiles,
+    /// Write the mod-list as a table with the name, version, Nexus link, tags and notes of
+    /// the mods, like for posting it on a forum or keeping it in git.
+    #[clap(visible_alias = "e")]
+    Export {
+        /// Format of the table.
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+        /// Also list the disabled mods.
+        #[arg(short, long)]
+        all: bool,
+        /// Write the table to <output> instead of showing it.
+        #[arg(short, long)]
+        output: Option<Utf8PathBuf>,
+    },
     ///Show all mods containing <tag>
     Tag,
 }
+impl Default for ListCmd {
+    fn default() -> Self {
+        Self::Mods {
+            columns: Vec::new(),
+            sort: None,
+            group_by: None,
+        }
+    }
+}
 impl ListCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Mods { .. }
+            | Self::Conflicts { .. }
+            | Self::Files { .. }
+            | Self::DisabledFiles
+            | Self::Export { .. }
+            | Self::Tag => true,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         match self {
-            Self::Mods => list_mods(settings),
-            Self::Conflicts => list_conflicts(settings.cache_dir()),
-            Self::Files => list_files(settings.cache_dir()),
+            Self::Mods {
+                columns,
+                sort,
+                group_by,
+            } => list_mods_with(settings, &columns, sort, group_by),
+            Self::Conflicts { hide_identical } => {
+                list_conflicts(settings.cache_dir(), hide_identical)
+            }
+            Self::Files { tree: false, .. } => list_files(settings.cache_dir()),
+            Self::Files { tree: true, depth } => list_files_tree(settings.cache_dir(), depth),
             Self::DisabledFiles => list_disabled_files(settings.cache_dir()),
+            Self::Export {
+                format,
+                all,
+                output,
+            } => export_mods(settings, format, all, output.as_deref()),
             Self::Tag => todo!(),
         }
     }
 }
 
 pub fn list_mods(settings: &Settings) -> Result<()> {
+    list_mods_with(settings, &[], None, None)
+}
+
+pub fn list_mods_with(
+    settings: &Settings,
+    columns: &[ModListColumn],
+    sort: Option<ModListSort>,
+    group_by: Option<ModListGroup>,
+) -> Result<()> {
     let mod_list = Vec::gather_mods(settings.cache_dir())?;
 
+    let columns = if columns.is_empty() {
+        settings.list_columns()
+    } else {
+        columns
+    };
+
     let table = ModListBuilder::new(&mod_list)
-        .with_index()
-        .with_priority()
-        .with_status()
-        .with_version()
-        .with_nexus_id()
-        .with_mod_type()
-        .with_tags()
-        .with_notes(settings.download_dir())
+        .with_columns(columns, settings.cache_dir())
+        .with_sort(sort)
+        .with_group_by(group_by)
+        .with_tag_colours(settings.tag_colours())
         .with_colour()
         .with_headers()
         .build()?
         .join("\n");
 
-    log::info!("");
-    log::info!("{table}");
+    page(&format!("\n{table}"));
+
+    Ok(())
+}
+
+/// Formats of the exported mod-list.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// A Markdown table.
+    Md,
+    /// Comma separated values, with a header row.
+    Csv,
+}
+
+const EXPORT_HEADERS: [&str; 5] = ["Name", "Version", "Nexus", "Tags", "Notes"];
 
+fn export_mods(
+    settings: &Settings,
+    format: ExportFormat,
+    all: bool,
+    output: Option<&Utf8Path>,
+) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let mods = mod_list.iter().filter(|md| all || md.is_enabled());
+    let table = export_table(*settings.game(), mods, format)?;
+
+    if let Some(output) = output {
+        write(output, table)?;
+        log::info!("Wrote the mod-list to '{output}'.");
+    } else {
+        // Print directly, so it can be redirected as it is.
+        print!("{table}");
+    }
     Ok(())
 }
 
-pub fn list_conflicts(cache_dir: &Utf8Path) -> Result<()> {
+/// The `mods` as a table in `format`, in order of priority.
+fn export_table<'a>(
+    game: Game,
+    mods: impl Iterator<Item = &'a Manifest>,
+    format: ExportFormat,
+) -> Result<String> {
+    let rows = mods.map(|md| {
+        let nexus = md.nexus_id().map(|id| {
+            let url = game.nexus_mod_url(id);
+            match format {
+                ExportFormat::Md => format!("[{id}]({url})"),
+                ExportFormat::Csv => url,
+            }
+        });
+        [
+            md.name().to_owned(),
+            md.version().unwrap_or_default().to_owned(),
+            nexus.unwrap_or_default(),
+            md.tags().join(", "),
+            md.notes().unwrap_or_default().to_owned(),
+        ]
+    });
+
+    let mut table = String::new();
+    match format {
+        ExportFormat::Md => {
+            writeln!(table, "| {} |", EXPORT_HEADERS.join(" | "))?;
+            writeln!(table, "|{}", "---|".repeat(EXPORT_HEADERS.len()))?;
+            for row in rows {
+                let cells = row.map(|cell| cell.replace('|', "\\|").replace('\n', "<br>"));
+                writeln!(table, "| {} |", cells.join(" | "))?;
+            }
+        }
+        ExportFormat::Csv => {
+            writeln!(table, "{}", EXPORT_HEADERS.join(","))?;
+            for row in rows {
+                let cells = row.map(|cell| {
+                    if cell.contains([',', '"', '\n']) {
+                        format!("\"{}\"", cell.replace('"', "\"\""))
+                    } else {
+                        cell
+                    }
+                });
+                writeln!(table, "{}", cells.join(","))?;
+            }
+        }
+    }
+    Ok(table)
+}
+
+pub fn list_conflicts(cache_dir: &Utf8Path, hide_identical: bool) -> Result<()> {
     let mod_list = Vec::gather_mods(cache_dir)?;
-    let conflict_list_file = conflict_list_by_file(&mod_list)?;
+    let mut conflict_list_file = conflict_list_by_file(&mod_list)?;
+    let identical = identical_conflicts(cache_dir, &mod_list, &conflict_list_file)?;
+    if hide_identical {
+        conflict_list_file.retain(|f, _| !identical.contains(f));
+    }
     let mut files = Vec::new();
 
     for m in mod_list {
@@ -89,12 +255,12 @@ pub fn list_conflicts(cache_dir: &Utf8Path) -> Result<()> {
         }
     });
 
-    log::info!("Conflict overview");
-    log::info!("");
     let mut table = create_table(vec!["File", "Mod"]);
 
     for (isf, (name, _priority)) in files {
-        let color = if conflict_list_file.contains_key(&isf.destination().to_string()) {
+        let color = if identical.contains(isf.destination()) {
+            Color::DarkGrey
+        } else if conflict_list_file.contains_key(&isf.destination().to_string()) {
             if conflict_list_file
                 .get(&isf.destination().to_string())
                 .unwrap()
@@ -121,7 +287,7 @@ pub fn list_conflicts(cache_dir: &Utf8Path) -> Result<()> {
         vec![Cell::new("No conflicting files found.")],
     );
 
-    log::info!("{table}");
+    page(&format!("Conflict overview\n\n{table}"));
     Ok(())
 }
 
@@ -148,8 +314,6 @@ pub fn list_files(cache_dir: &Utf8Path) -> Result<()> {
         }
     });
 
-    log::info!("File overview");
-    log::info!("");
     let mut table = create_table(vec!["File", "Destination", "Mod"]);
 
     for (isf, (name, _priority)) in files {
@@ -178,7 +342,30 @@ pub fn list_files(cache_dir: &Utf8Path) -> Result<()> {
 
     table.add_row_if(|idx, _row| idx.eq(&0), vec![Cell::new("No files found.")]);
 
-    log::info!("{table}");
+    page(&format!("File overview\n\n{table}"));
+
+    Ok(())
+}
+
+pub fn list_files_tree(cache_dir: &Utf8Path, depth: Option<usize>) -> Result<()> {
+    let mod_list = Vec::gather_mods(cache_dir)?;
+    let conflict_list_file = conflict_list_by_file(&mod_list)?;
+
+    let mut tree = FileTreeBuilder::new().with_depth(depth);
+
+    // The mod-list is sorted by priority; later mods overwrite earlier ones.
+    for m in mod_list.iter().filter(|m| m.is_enabled()) {
+        for isf in m.files()? {
+            let color = if conflict_list_file.contains_key(isf.destination()) {
+                Color::Green
+            } else {
+                Color::White
+            };
+            tree.insert(isf.destination(), m.name(), color);
+        }
+    }
+
+    page(&format!("File overview\n\n{}", tree.build().join("\n")));
 
     Ok(())
 }
@@ -203,7 +390,7 @@ pub fn list_disabled_files(cache_dir: &Utf8Path) -> Result<()> {
         vec![Cell::new("No disabled files found.")],
     );
 
-    log::info!("{table}");
+    page(&table.to_string());
 
     Ok(())
 }
@@ -0,0 +1,207 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    thread::sleep,
+    time::Duration,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::settings::{LogLevel, Settings};
+
+// flexi_logger names the active file '<name>_rCURRENT.<ext>' when rotating.
+const CURRENT_LOG_INFIX: &str = "_rCURRENT";
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A single log record; messages (like tables) can span multiple lines.
+struct Entry {
+    level: Option<LogLevel>,
+    lines: Vec<String>,
+}
+impl Entry {
+    fn is_visible(&self, level: Option<LogLevel>) -> bool {
+        level.is_none()
+            || self
+                .level
+                .is_some_and(|entry_level| Some(entry_level) <= level)
+    }
+}
+
+pub fn show_log(
+    settings: &Settings,
+    tail: Option<usize>,
+    level: Option<LogLevel>,
+    follow: bool,
+    all: bool,
+) -> Result<()> {
+    let mut log_files = log_files(settings.log_file())?;
+    if !all {
+        log_files = log_files.split_off(log_files.len().saturating_sub(1));
+    }
+
+    let Some(current) = log_files.last().cloned() else {
+        log::info!("No log files found for {}", settings.log_file());
+        return Ok(());
+    };
+
+    let mut entries = VecDeque::new();
+    for log_file in &log_files {
+        for entry in read_entries(&mut BufReader::new(File::open(log_file)?))? {
+            if entry.is_visible(level) {
+                entries.push_back(entry);
+                if tail.is_some_and(|tail| entries.len() > tail) {
+                    entries.pop_front();
+                }
+            }
+        }
+    }
+    for entry in entries {
+        print_entry(&entry);
+    }
+
+    if follow {
+        follow_log(&current, level)?;
+    }
+
+    Ok(())
+}
+
+/// The lines of the last `count` entries of the current log file.
+pub fn tail(log_file: &Utf8Path, count: usize) -> Result<Vec<String>> {
+    let Some(current) = log_files(log_file)?.pop() else {
+        return Ok(Vec::new());
+    };
+    let entries = read_entries(&mut BufReader::new(File::open(current)?))?;
+    Ok(entries
+        .into_iter()
+        .rev()
+        .take(count)
+        .rev()
+        .flat_map(|entry| entry.lines)
+        .collect())
+}
+
+/// All log files belonging to `log_file`, oldest first; the active file is last.
+fn log_files(log_file: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let (Some(dir), Some(stem)) = (log_file.parent(), log_file.file_stem()) else {
+        return Ok(Vec::new());
+    };
+    let extension = log_file.extension().unwrap_or_default();
+
+    let mut rotated = Vec::new();
+    let mut current = Vec::new();
+    for entry in dir.read_dir_utf8()? {
+        let path = entry?.into_path();
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        if !file_name.starts_with(stem) || path.extension() != Some(extension) {
+            continue;
+        }
+
+        if file_name.contains(CURRENT_LOG_INFIX) || path == log_file {
+            current.push(path);
+        } else if file_name.starts_with(&format!("{stem}_r")) {
+            rotated.push(path);
+        }
+    }
+
+    // Rotated files carry a timestamp, so sorting them by name sorts them by age.
+    rotated.sort_unstable();
+    rotated.extend(current);
+    Ok(rotated)
+}
+
+fn read_entries(reader: &mut impl BufRead) -> Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        match parse_level(&line) {
+            Some(level) => entries.push(Entry {
+                level: Some(level),
+                lines: vec![line],
+            }),
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.lines.push(line);
+                } else {
+                    entries.push(Entry {
+                        level: None,
+                        lines: vec![line],
+                    });
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+// Lines start with '[<timestamp>] <LEVEL> ...', see `flexi_logger::detailed_format`.
+fn parse_level(line: &str) -> Option<LogLevel> {
+    let rest = line.strip_prefix('[')?;
+    let (_timestamp, rest) = rest.split_once("] ")?;
+    match rest.split_whitespace().next()? {
+        "ERROR" => Some(LogLevel::Error),
+        "WARN" => Some(LogLevel::Warn),
+        "INFO" => Some(LogLevel::Info),
+        "DEBUG" => Some(LogLevel::Debug),
+        "TRACE" => Some(LogLevel::Trace),
+        _ => None,
+    }
+}
+
+fn print_entry(entry: &Entry) {
+    // Print directly; logging would append these lines to the log we are reading.
+    for line in &entry.lines {
+        println!("{line}");
+    }
+}
+
+fn follow_log(log_file: &Utf8Path, level: Option<LogLevel>) -> Result<()> {
+    let mut position = log_file.metadata()?.len();
+    // Continuation lines of an entry can arrive in a later poll than its first line.
+    let mut last_visible = true;
+
+    loop {
+        sleep(FOLLOW_INTERVAL);
+
+        let len = log_file.metadata().map(|m| m.len()).unwrap_or_default();
+        if len < position {
+            // The log has been rotated; start at the beginning of the new file.
+            position = 0;
+        }
+        if len == position {
+            continue;
+        }
+
+        let mut file = File::open(log_file)?;
+        file.seek(SeekFrom::Start(position))?;
+        let mut reader = BufReader::new(file);
+
+        let mut pending = String::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            if !line.ends_with('\n') {
+                // Incomplete line; wait for the rest.
+                break;
+            }
+            position += line.len() as u64;
+            pending.push_str(&line);
+            line.clear();
+        }
+
+        for entry in read_entries(&mut pending.as_bytes())? {
+            let visible = if entry.level.is_some() {
+                entry.is_visible(level)
+            } else {
+                last_visible
+            };
+            if visible {
+                print_entry(&entry);
+            }
+            last_visible = visible;
+        }
+    }
+}
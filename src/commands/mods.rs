@@ -1,24 +1,49 @@
 use std::{
     cmp::Ordering,
-    fs::{copy, DirBuilder},
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs::{copy, read, read_link, remove_dir_all, rename, DirBuilder},
+    io::IsTerminal,
+    iter,
 };
 
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
 use comfy_table::{Cell, Color};
+use glob::{MatchOptions, Pattern};
 use inquire::CustomType;
+use walkdir::WalkDir;
 
 use crate::{
     conflict::conflict_list_by_file,
-    errors::ModErrors,
-    manifest::Manifest,
+    decompress::SupportedArchives,
+    deployment::backup::BackupRegistry,
+    errors::{DownloadError, ModErrors},
+    ini::{Ini, IniMergeStrategy},
+    installers::DATA_DIR_NAME,
+    lint::{lint, Severity},
+    manifest::{install_file::InstallFile, Manifest, Remap, MANIFEST_EXTENSION},
     mods::{FindInModList, GatherModList, ModKind, ModList},
-    settings::{create_table, Settings},
-    ui::{FileListBuilder, FindSelectBuilder, InquireBuilder},
+    nexus::{images::mod_image, updates::UpdateCache, EndorseStatus, Nexus},
+    settings::{
+        create_table, ensure_writable, share_with_group, share_with_group_recursive, Settings,
+    },
+    ui::{
+        confirm, page, show_image, FileListBuilder, FileTreeBuilder, FindSelectBuilder,
+        InquireBuilder, SelectToIdx,
+    },
+    utils::{edit_files, open_url, AddExtension},
+    version::Version,
 };
 
-use super::list::list_mods;
+use super::{downloads::mod_archive, list::list_mods};
+
+const DEFAULT_PRIORITY_STEP: usize = 10;
+const INI_MERGE_MOD_NAME: &str = "ini-merge";
+const INI_EXTENSION: &str = "ini";
+const EXE_EXTENSION: &str = "exe";
+const DESKTOP_EXTENSION: &str = "desktop";
 
 //TODO: create custom and tag sub-commands
 
@@ -37,6 +62,13 @@ pub enum ModCmd {
         /// The <file_name> from <source> mod to copy.
         file: Option<String>,
     },
+    /// Copy mod 'source' into a new custom mod 'name', leaving the original untouched.
+    Clone {
+        /// Name of the mod to clone.
+        source: Option<String>,
+        /// Name of the new custom mod.
+        name: Option<String>,
+    },
     /// Create a new label with 'name'
     CreateLabel {
         /// Name of the label
@@ -92,19 +124,107 @@ pub enum ModCmd {
         /// Name of the mod to enable
         name: Option<String>,
     },
-    /// Enable all mods
+    /// Enable all mods, except the pinned ones
     EnableAll,
+    /// Disable mod 'name' and keep it disabled; enabling all mods and upgrading
+    /// leave it alone until it is unpinned.
+    Pin {
+        /// Name of the mod to pin
+        name: Option<String>,
+    },
+    /// Let pinned mod 'name' be enabled again; it stays disabled until it is enabled.
+    Unpin {
+        /// Name of the mod to unpin
+        name: Option<String>,
+    },
+    /// Endorse mod 'name' on Nexus, to thank its author.
+    Endorse {
+        /// Name of the mod to endorse
+        name: Option<String>,
+        /// Abstain from endorsing the mod instead, so Nexus stops asking.
+        #[arg(short, long)]
+        abstain: bool,
+    },
+    /// Show the changes made on Nexus since the installed version of mod 'name'.
+    Changelog {
+        /// Name of the mod to show the changes of
+        name: Option<String>,
+        /// Show the changes of every version, including the installed one and older.
+        #[arg(short, long)]
+        all: bool,
+    },
+    /// Open the Nexus page of mod 'name' in the browser.
+    Open {
+        /// Name of the mod to open
+        name: Option<String>,
+    },
+    /// Link mod 'name' to mod 'nexus-id' on Nexus, for archives downloaded without dmodman;
+    /// so its updates can be checked.
+    SetNexusId {
+        /// Name of the mod to link
+        name: Option<String>,
+        /// The id of the mod on Nexus, as in its url.
+        #[arg(required_unless_present = "md5")]
+        nexus_id: Option<u32>,
+        /// The id of the installed file on Nexus, to learn its version.
+        #[arg(short, long)]
+        file_id: Option<u64>,
+        /// Find the mod and file on Nexus by the md5 checksum of its archive instead.
+        #[arg(short, long, conflicts_with_all = ["nexus_id", "file_id"])]
+        md5: bool,
+    },
+    /// Ask Nexus about every mod with a Nexus id, to fill in the authors and categories missing
+    /// from their manifests, and to learn their latest versions.
+    #[clap(visible_alias = "refresh")]
+    RefreshMetadata {
+        /// Rename the mods to their names on Nexus too, unless another mod has that name.
+        #[arg(short, long)]
+        rename: bool,
+    },
+    /// Track mod 'name' on Nexus, to hear about its updates.
+    Track {
+        /// Name of the mod to track
+        name: Option<String>,
+    },
+    /// Stop tracking mod 'name' on Nexus.
+    Untrack {
+        /// Name of the mod to stop tracking
+        name: Option<String>,
+    },
     #[default]
     #[clap(visible_aliases = &["lists","l"])]
     /// Show all mods; Alias from 'mod list'
     List,
+    /// Show where <file> from mod <name> ends up: its path in the cache,
+    /// its destination, the state of the game directory and, when it lost
+    /// a conflict, the mod which provides it instead.
+    Where {
+        /// Name of the mod which hosts <file>
+        name: Option<String>,
+        /// Source or destination of the file
+        file: Option<String>,
+    },
     #[clap(visible_alias = "s")]
     /// Show the details of mod 'name'
     Show {
         /// Name of the mod to show.
         name: Option<String>,
+        /// Show the files of the mod as a directory tree.
+        #[arg(short, long)]
+        tree: bool,
+        /// Collapse directories below this depth in the tree.
+        #[arg(short, long, requires = "tree")]
+        depth: Option<usize>,
+        /// Show the image of the mod on Nexus, in the terminal when it can; the image is kept
+        /// in the cache directory.
+        #[arg(short, long)]
+        image: bool,
+        /// Show the readme or changelog of the mod.
+        #[arg(long)]
+        docs: bool,
     },
-    /// Add tag <tag> to mod <name>
+    /// Add tag <tag> to mod <name>; 'needs:<what>' and 'provides:<what>' tags declare what
+    /// mods need to run, which is checked before the game starts.
     TagAdd {
         /// Name of the mod to add <tag> to.
         name: Option<String>,
@@ -118,6 +238,13 @@ pub enum ModCmd {
         /// Name of the tag.
         tag: Option<String>,
     },
+    /// Re-run the installers of all mods, or only those of kind <kind>;
+    /// priority, tags and enabled state are preserved.
+    ReinstallAll {
+        /// Only re-install mods of this kind.
+        #[arg(short, long, value_enum)]
+        kind: Option<ModKind>,
+    },
     /// Remove mod 'name' from installation.
     /// Does not remove the mod from the downloads directory.
     Remove {
@@ -131,17 +258,176 @@ pub enum ModCmd {
         new_mod_name: Option<String>,
     },
     /// Set mod to new priority;
-    /// Setting a priority below zero disables the mod.
+    /// mods with a higher priority win the conflicts with mods of a lower priority.
     #[clap(visible_aliases = &["set-prio", "sp"])]
     SetPriority {
         /// Name of the mod to set to the new priority
         name: Option<String>,
         /// value of the new priority.
-        /// Setting this below zero permanently disabled the mod.
         priority: Option<isize>,
+        /// When another mod already has this priority, renumber the mod-list;
+        /// this mod is placed before the mods it collides with.
+        #[arg(short, long)]
+        renumber: bool,
+    },
+    /// Spread the priorities evenly (10, 20, 30, ...), leaving room to insert mods in between;
+    /// the order of the mods does not change.
+    Renumber {
+        /// Distance between consecutive priorities.
+        #[arg(short, long, default_value_t = DEFAULT_PRIORITY_STEP)]
+        step: usize,
+    },
+    /// Show the order of mods with the same priority, which follows the order they were
+    /// installed in; with <name>, move that mod within its priority to <sequence>.
+    #[clap(visible_alias = "seq")]
+    Sequence {
+        /// Name of the mod to move
+        name: Option<String>,
+        /// Its new sequence number; mods with a lower number are deployed before it.
+        sequence: Option<u64>,
+        /// Number all mods 1, 2, 3, ... in their current order, to make the order of
+        /// mods installed before it was recorded explicit.
+        #[arg(short, long, conflicts_with = "name")]
+        renumber: bool,
+    },
+    /// Merge ini files shipped by more than one enabled mod, key by key, into a custom
+    /// mod which is placed above all other mods and enabled; run again after changes.
+    MergeIni {
+        /// Name of the custom mod holding the merged files.
+        #[arg(short, long, default_value = INI_MERGE_MOD_NAME)]
+        name: String,
+        /// How to merge keys set by several mods; defaults to the 'ini-merge' setting.
+        #[arg(short, long, value_enum)]
+        strategy: Option<IniMergeStrategy>,
+    },
+    /// Write a desktop entry which runs an executable of mod <name> through proton,
+    /// so tools like xEdit can be started from the desktop environment.
+    CreateLauncher {
+        name: Option<String>,
+        /// Directory to write the desktop entry to; defaults to the user's applications directory.
+        #[arg(short, long)]
+        output: Option<Utf8PathBuf>,
+    },
+    /// Set the notes of a mod; leave out the notes to remove them.
+    Notes {
+        name: Option<String>,
+        notes: Option<String>,
+    },
+    /// Merge mods <names> into a new custom mod <into>, holding the files which would win
+    /// between them; useful to flatten many small patches into a single mod.
+    Merge {
+        /// Names of the mods to merge; at least two.
+        #[arg(required = true, num_args = 2..)]
+        names: Vec<String>,
+        /// Name of the new custom mod.
+        #[arg(long)]
+        into: String,
+        /// Disable the merged mods, and enable the new mod when any of them was enabled.
+        #[arg(long)]
+        disable: bool,
+    },
+    /// Move the files of mod <name> matching <path> into a new mod <into>, so optional
+    /// parts of a mod can be enabled and disabled on their own.
+    Split {
+        name: Option<String>,
+        /// Glob matched, case-insensitively, against both the path of a file within the mod
+        /// and its destination; like 'data/textures/*'.
+        #[arg(short, long)]
+        path: String,
+        /// Name of the new mod.
+        #[arg(long)]
+        into: String,
+    },
+    /// Deploy the files of mod <name> matching <from> to <to> instead; without <to> the rule
+    /// for <from> is removed and without <from> the rules of the mod are shown.
+    /// Without wildcards <from> is a single file which is renamed to <to>, otherwise the
+    /// directory in front of the first wildcard is replaced by <to>.
+    Remap {
+        name: Option<String>,
+        /// Glob matched, case-insensitively, against the destinations of the files;
+        /// like 'Data/foo/textures/*'.
+        from: Option<String>,
+        /// The new destination, like 'Data/textures'.
+        to: Option<String>,
+    },
+    /// Leave the files of mod <name> matching <pattern> out, without disabling them one by one;
+    /// without <pattern> the exclusions of the mod are shown.
+    Exclude {
+        name: Option<String>,
+        /// Glob matched, case-insensitively, against both the path of a file within the mod
+        /// and its destination; like '**/*.psd'.
+        pattern: Option<String>,
+        /// Remove the exclusion <pattern> instead.
+        #[arg(short, long, requires = "pattern")]
+        remove: bool,
+    },
+    /// Check the files of mod <name>, or of all mods, for common packaging mistakes: no files,
+    /// files outside 'Data' or programs in a data mod, two files with the same destination and
+    /// plugins and archives not named after each other.
+    /// Fails when any error is found.
+    Lint {
+        name: Option<String>,
+        /// Print the findings as json.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Re-archive the files of mod <name> into <output>, so edited or custom mods can be
+    /// shared or backed up; the format follows the extension of <output>.
+    Pack {
+        name: Option<String>,
+        output: Utf8PathBuf,
+        /// Also store starmod's manifest and state of the mod in the root of the archive;
+        /// extracting the archive takes over its priority, tags and notes.
+        #[arg(long)]
+        with_manifest: bool,
     },
 }
 impl ModCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::List
+            | Self::Where { .. }
+            | Self::Show { .. }
+            | Self::Changelog { .. }
+            | Self::Open { .. }
+            | Self::Lint { .. }
+            | Self::CreateLauncher { .. }
+            | Self::Pack { .. } => true,
+            Self::CopyToCustom { .. }
+            | Self::Clone { .. }
+            | Self::CreateLabel { .. }
+            | Self::CreateCustom { .. }
+            | Self::Disable { .. }
+            | Self::DisableAll
+            | Self::DisableFile { .. }
+            | Self::EnableFile { .. }
+            | Self::EditConfig { .. }
+            | Self::Enable { .. }
+            | Self::EnableAll
+            | Self::Pin { .. }
+            | Self::Unpin { .. }
+            | Self::Endorse { .. }
+            | Self::SetNexusId { .. }
+            | Self::RefreshMetadata { .. }
+            | Self::Track { .. }
+            | Self::Untrack { .. }
+            | Self::TagAdd { .. }
+            | Self::TagRemove { .. }
+            | Self::ReinstallAll { .. }
+            | Self::Remove { .. }
+            | Self::Rename { .. }
+            | Self::SetPriority { .. }
+            | Self::Renumber { .. }
+            | Self::Sequence { .. }
+            | Self::MergeIni { .. }
+            | Self::Notes { .. }
+            | Self::Merge { .. }
+            | Self::Split { .. }
+            | Self::Remap { .. }
+            | Self::Exclude { .. } => false,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         match self {
             Self::Disable { name } => {
@@ -170,6 +456,14 @@ impl ModCmd {
             }
             Self::DisableAll => {
                 let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let affected = mod_list
+                    .iter()
+                    .filter(|m| m.is_enabled())
+                    .map(|m| m.name().to_owned())
+                    .collect::<Vec<_>>();
+                if !confirm("disable all enabled mods", &affected)? {
+                    return Ok(());
+                }
                 mod_list.disable(settings.cache_dir(), settings.game_dir())?;
                 list_mods(settings)
             }
@@ -181,17 +475,19 @@ impl ModCmd {
                     .build()?
                     .prompt()?;
 
-                let file_name =
+                let file_idx =
                     FindSelectBuilder::new(FileListBuilder::new(&mod_list[idx]).with_origin())
                         .with_msg("Please select a file to disable:")
                         .with_input(file.as_deref())
                         .build()?
                         .prompt()?;
+                let file_name = mod_list[idx].files()?[file_idx].source().to_string();
 
-                if mod_list[idx].disable_file(&file_name) {
-                    if mod_list[idx].is_enabled() {
-                        mod_list.enable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
-                    }
+                let changed = mod_list[idx].disable_file(&file_name)?;
+                if changed && mod_list[idx].is_enabled() {
+                    mod_list.re_enable(settings.cache_dir(), settings.game_dir())?;
+                }
+                if changed {
                     Ok(())
                 } else {
                     // log::trace!("File '{file_name}' not found within mod '{mod_name}'.");
@@ -206,7 +502,7 @@ impl ModCmd {
                     .build()?
                     .prompt()?;
 
-                let file_name = FindSelectBuilder::new(
+                let file_idx = FindSelectBuilder::new(
                     FileListBuilder::new(&mod_list[idx])
                         .disabled_files()
                         .with_origin(),
@@ -215,11 +511,15 @@ impl ModCmd {
                 .with_input(file.as_deref())
                 .build()?
                 .prompt()?;
+                let file_name = mod_list[idx].disabled_files()[file_idx]
+                    .source()
+                    .to_string();
 
-                if mod_list[idx].enable_file(&file_name) {
-                    if mod_list[idx].is_enabled() {
-                        mod_list.enable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
-                    }
+                let changed = mod_list[idx].enable_file(&file_name)?;
+                if changed && mod_list[idx].is_enabled() {
+                    mod_list.re_enable(settings.cache_dir(), settings.game_dir())?;
+                }
+                if changed {
                     Ok(())
                 } else {
                     // log::trace!("File '{file_name}' not found within mod '{mod_name}'.");
@@ -253,6 +553,70 @@ impl ModCmd {
                 mod_list.enable(settings.cache_dir(), settings.game_dir())?;
                 list_mods(settings)
             }
+            Self::Pin { name } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to pin:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                if mod_list[idx].is_enabled() {
+                    mod_list.disable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
+                }
+                mod_list[idx].set_pinned()?;
+                list_mods(settings)
+            }
+            Self::Unpin { name } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to unpin:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                if !mod_list[idx].unpin()? {
+                    log::info!("'{}' is not pinned.", mod_list[idx].name());
+                }
+                list_mods(settings)
+            }
+            Self::Endorse { name, abstain } => {
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let (md, nexus_id) = select_nexus_mod(
+                    &mod_list,
+                    name.as_deref(),
+                    "Please select a mod to endorse:",
+                )?;
+                ensure_writable(&format!("endorse '{}'", md.name()))?;
+
+                Nexus::new(settings)?.endorse(nexus_id, md.version(), !abstain)?;
+                let status = if abstain {
+                    log::info!("Abstained from endorsing '{}'.", md.name());
+                    EndorseStatus::Abstained
+                } else {
+                    log::info!("Endorsed '{}'; thank you!", md.name());
+                    EndorseStatus::Endorsed
+                };
+                let mut cache = UpdateCache::read(settings.cache_dir());
+                cache.set_endorsement(nexus_id, status);
+                cache.write(settings.cache_dir())
+            }
+            Self::Changelog { name, all } => show_changelog(settings, name.as_deref(), all),
+            Self::Open { name } => {
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let (_, nexus_id) =
+                    select_nexus_mod(&mod_list, name.as_deref(), "Please select a mod to open:")?;
+                open_url(&settings.game().nexus_mod_url(nexus_id))
+            }
+            Self::SetNexusId {
+                name,
+                nexus_id,
+                file_id,
+                md5,
+            } => set_nexus_id(settings, name.as_deref(), nexus_id, file_id, md5),
+            Self::RefreshMetadata { rename } => refresh_metadata(settings, rename),
+            Self::Track { name } => track_mod(settings, name.as_deref(), true),
+            Self::Untrack { name } => track_mod(settings, name.as_deref(), false),
             Self::EditConfig {
                 name,
                 destination,
@@ -266,7 +630,34 @@ impl ModCmd {
                 &extension,
             ),
             Self::List => list_mods(settings),
-            Self::Show { name } => show_mod(settings.cache_dir(), name.as_deref()),
+            Self::Show {
+                name,
+                tree,
+                depth,
+                image,
+                docs,
+            } => show_mod(settings, name.as_deref(), tree, depth, image, docs),
+            Self::Where { name, file } => {
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select the mod which hosts the file:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                let files = mod_list[idx].files()?;
+                let file_idx = if let Some(file_idx) = file.as_deref().and_then(|file| {
+                    files.iter().position(|isf| {
+                        isf.source() == file || isf.destination().eq_ignore_ascii_case(file)
+                    })
+                }) {
+                    file_idx
+                } else {
+                    select_file_idx(&mod_list[idx], "Please select a file:", file.as_deref())?
+                };
+
+                show_file_chain(settings, &mod_list, idx, &files[file_idx])
+            }
             Self::CreateCustom { origin, name } => {
                 let name = InquireBuilder::new_with_test(
                     name,
@@ -279,18 +670,37 @@ impl ModCmd {
 
                 //TODO Use file_path_select to select destination if not given
 
+                ensure_writable(&format!("create custom mod '{name}'"))?;
                 let destination = settings.cache_dir().join(&name);
                 if let Some(origin) = origin {
                     std::os::unix::fs::symlink(&origin, &destination)?;
                     log::info!("Creating custom mod {} (link from {})", &name, origin);
                 } else {
                     log::info!("Creating custom mod {}", &name);
-                    DirBuilder::new().recursive(true).create(destination)?;
+                    DirBuilder::new().recursive(true).create(&destination)?;
+                    share_with_group(&destination)?;
                 }
                 ModKind::Custom
                     .create_mod(settings.cache_dir(), &Utf8PathBuf::from(name))
                     .map(|_| ())
             }
+            Self::Clone { source, name } => {
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let (idx, name) = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to clone:")
+                    .with_input(source.as_deref())
+                    .build()?
+                    .with_test(
+                        name,
+                        CustomType::new("Please specify the name of the clone")
+                            .with_error_message("Please type a valid name")
+                            .with_help_message("Type in the name of the new custom mod."),
+                    )
+                    .prompt()?;
+
+                clone_mod(settings.cache_dir(), &mod_list[idx], &name)?;
+                list_mods(settings)
+            }
             Self::CreateLabel { name: _ } => {
                 todo!()
                 // let destination = settings.cache_dir().join(&name);
@@ -308,11 +718,34 @@ impl ModCmd {
                     .build()?
                     .prompt()?;
 
+                let affected = vec![
+                    mod_list[idx].name().to_owned(),
+                    settings
+                        .cache_dir()
+                        .join(mod_list[idx].manifest_dir())
+                        .to_string(),
+                ];
+                if !confirm("remove this mod and its files from the cache", &affected)? {
+                    return Ok(());
+                }
                 mod_list.disable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
                 mod_list[idx].remove()?;
                 log::info!("Removed mod '{}'", mod_list[idx].name());
                 list_mods(settings)
             }
+            Self::ReinstallAll { kind } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                for md in &mut mod_list {
+                    if kind.is_none() || kind == Some(md.kind()) {
+                        log::info!("Re-installing '{}'", md.name());
+                        *md = md.reinstall()?;
+                    }
+                }
+                // Links of files which are gone, or moved, are replaced.
+                mod_list.re_enable(settings.cache_dir(), settings.game_dir())?;
+
+                list_mods(settings)
+            }
             Self::Rename {
                 old_mod_name,
                 new_mod_name,
@@ -334,7 +767,11 @@ impl ModCmd {
                 mod_list[idx].set_name(new_mod_name)?;
                 list_mods(settings)
             }
-            Self::SetPriority { name, priority } => {
+            Self::SetPriority {
+                name,
+                priority,
+                renumber,
+            } => {
                 let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
                 let (idx, priority) = FindSelectBuilder::new(mod_list.default_list_builder())
                     .with_msg("Please select a mod to rename:")
@@ -348,23 +785,235 @@ impl ModCmd {
                             .with_help_message("Type in a positive or negative number."),
                     )
                     .prompt()?;
-                let old_prio = mod_list[idx].priority();
-
                 mod_list[idx].set_priority(priority)?;
-                if mod_list[idx].is_disabled() {
-                    let priority = if priority > old_prio {
-                        priority
-                    } else {
-                        old_prio
+                if renumber
+                    && mod_list
+                        .iter()
+                        .enumerate()
+                        .any(|(i, m)| i != idx && m.priority() == priority)
+                {
+                    let moved = mod_list[idx].manifest_dir().to_owned();
+                    renumber_priorities(&mut mod_list, DEFAULT_PRIORITY_STEP, Some(&moved))?;
+                }
+                if mod_list[idx].is_enabled() {
+                    // The mod may now win, or lose, conflicts with other mods.
+                    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                    mod_list.re_enable(settings.cache_dir(), settings.game_dir())?;
+                }
+
+                crate::commands::list::list_mods(settings)?;
+                Ok(())
+            }
+            Self::Renumber { step } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                renumber_priorities(&mut mod_list, step.max(1), None)?;
+                list_mods(settings)
+            }
+            Self::Sequence {
+                name,
+                sequence,
+                renumber,
+            } => {
+                if renumber {
+                    renumber_sequences(settings)?;
+                } else if name.is_some() || sequence.is_some() {
+                    set_sequence(settings, name.as_deref(), sequence)?;
+                }
+                show_sequences(settings)
+            }
+            Self::MergeIni { name, strategy } => {
+                merge_ini_files(
+                    settings.cache_dir(),
+                    settings.game_dir(),
+                    settings.cmd_name(),
+                    &name,
+                    strategy.unwrap_or_else(|| settings.ini_merge()),
+                )?;
+                list_mods(settings)
+            }
+            Self::CreateLauncher { name, output } => {
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to create a launcher for:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                create_launcher(settings, &mod_list[idx], output)
+            }
+            Self::Notes { name, notes } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to annotate:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                mod_list[idx].set_notes(notes.filter(|n| !n.trim().is_empty()))?;
+                show_mod_status(&mod_list, idx, false, None)
+            }
+            Self::Merge {
+                names,
+                into,
+                disable,
+            } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let mut indices = names
+                    .iter()
+                    .map(|name| {
+                        mod_list
+                            .find_mod(name)
+                            .ok_or_else(|| ModErrors::ModNotFound(name.clone()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                indices.sort_unstable();
+                indices.dedup();
+
+                merge_mods(settings.cache_dir(), &mod_list, &indices, &into)?;
+
+                if disable {
+                    let was_enabled = indices.iter().any(|&idx| mod_list[idx].is_enabled());
+                    for &idx in &indices {
+                        if mod_list[idx].is_enabled() {
+                            mod_list.disable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
+                        }
+                    }
+                    if was_enabled {
+                        let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                        if let Some(idx) = mod_list.find_mod_by_name(&into) {
+                            mod_list.enable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
+                        }
+                    }
+                }
+                list_mods(settings)
+            }
+            Self::Split { name, path, into } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to split:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                let pattern = Pattern::new(&path)
+                    .map_err(|e| ModErrors::InvalidPattern(path.clone(), e.to_string()))?;
+                let matches = |f: &InstallFile| {
+                    let options = MatchOptions {
+                        case_sensitive: false,
+                        ..MatchOptions::default()
                     };
+                    pattern.matches_with(f.source().as_str(), options)
+                        || pattern.matches_with(f.destination(), options)
+                };
+                if !mod_list[idx]
+                    .files()?
+                    .iter()
+                    .chain(&mod_list[idx].disabled_files())
+                    .any(matches)
+                {
+                    return Err(
+                        ModErrors::FileNotFound(mod_list[idx].name().to_owned(), path).into(),
+                    );
+                }
 
-                    (&mut mod_list[0..priority as usize])
-                        .re_enable(settings.cache_dir(), settings.game_dir())?;
+                let was_enabled = mod_list[idx].is_enabled();
+                if was_enabled {
+                    mod_list.disable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
                 }
 
-                crate::commands::list::list_mods(settings)?;
+                split_mod(settings.cache_dir(), &mut mod_list[idx], &matches, &into)?;
+
+                if was_enabled {
+                    let original = mod_list[idx].manifest_dir().to_owned();
+                    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                    for dir in [original.as_path(), Utf8Path::new(&into)] {
+                        if let Some(idx) = mod_list.iter().position(|m| m.manifest_dir() == dir) {
+                            mod_list.enable_mod(settings.cache_dir(), settings.game_dir(), idx)?;
+                        }
+                    }
+                }
+                list_mods(settings)
+            }
+            Self::Remap { name, from, to } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to remap:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                let Some(from) = from else {
+                    show_remaps(&mod_list[idx]);
+                    return Ok(());
+                };
+
+                if let Some(to) = to {
+                    mod_list[idx].add_remap(Remap::new(&from, &to)?)?;
+                } else if !mod_list[idx].remove_remap(&from)? {
+                    log::warn!(
+                        "Mod '{}' has no remap rule for '{from}'.",
+                        mod_list[idx].name()
+                    );
+                }
+                if mod_list[idx].is_enabled() {
+                    // The links at the old destinations are replaced by those at the new ones.
+                    mod_list.re_enable(settings.cache_dir(), settings.game_dir())?;
+                }
+                show_remaps(&mod_list[idx]);
+                Ok(())
+            }
+            Self::Exclude {
+                name,
+                pattern,
+                remove,
+            } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to exclude files from:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                if let Some(pattern) = pattern {
+                    let changed = if remove {
+                        mod_list[idx].remove_exclude(&pattern)?
+                    } else {
+                        mod_list[idx].add_exclude(&pattern)?
+                    };
+                    if !changed {
+                        log::warn!(
+                            "Mod '{}' {} exclusion '{pattern}'.",
+                            mod_list[idx].name(),
+                            if remove { "has no" } else { "already has" }
+                        );
+                    } else if mod_list[idx].is_enabled() {
+                        mod_list.re_enable(settings.cache_dir(), settings.game_dir())?;
+                    }
+                }
+
+                let mut table = create_table(vec!["Excluded"]);
+                for pattern in mod_list[idx].excludes() {
+                    table.add_row(vec![Cell::new(pattern)]);
+                }
+                log::info!("Exclusions of '{}':", mod_list[idx].name());
+                log::info!("{table}");
                 Ok(())
             }
+            Self::Lint { name, json } => lint_mods(settings, name.as_deref(), json),
+            Self::Pack {
+                name,
+                output,
+                with_manifest,
+            } => {
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+                    .with_msg("Please select a mod to pack:")
+                    .with_input(name.as_deref())
+                    .build()?
+                    .prompt()?;
+
+                pack_mod(settings.cache_dir(), &mod_list[idx], &output, with_manifest)
+            }
             Self::TagAdd { name, tag } => {
                 let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
                 let (idx, tag) = FindSelectBuilder::new(mod_list.default_list_builder())
@@ -430,31 +1079,11 @@ impl ModCmd {
                         )
                         .prompt()?;
 
-                let file_name = FindSelectBuilder::new(
-                    FileListBuilder::new(&mod_list[source_idx])
-                        .with_index()
-                        .with_origin(),
-                )
-                .with_msg("Please select a file to copy:")
-                .with_input(file.as_deref())
-                .build()?
-                .prompt()?;
-
-                let file_idx = file_name
-                    .clone()
-                    .split_whitespace()
-                    .skip(1)
-                    .next()
-                    .ok_or_else(|| {
-                        ModErrors::FileNotFound(
-                            mod_list[source_idx].name().to_string(),
-                            file_name.clone(),
-                        )
-                    })?
-                    .parse::<usize>()
-                    .map_err(|_| {
-                        ModErrors::FileNotFound(mod_list[source_idx].name().to_string(), file_name)
-                    })?;
+                let file_idx = select_file_idx(
+                    &mod_list[source_idx],
+                    "Please select a file to copy:",
+                    file.as_deref(),
+                )?;
 
                 let file = &mod_list[source_idx].files()?[file_idx];
                 let origin = settings
@@ -466,6 +1095,7 @@ impl ModCmd {
                     .join(mod_list[dest_idx].manifest_dir())
                     .join(file.source());
 
+                ensure_writable(&format!("copy '{}'", file.source()))?;
                 DirBuilder::new()
                     .recursive(true)
                     .create(destination.parent().unwrap())?;
@@ -476,46 +1106,899 @@ impl ModCmd {
     }
 }
 
-fn show_mod(cache_dir: &Utf8Path, name: Option<&str>) -> Result<()> {
-    let mod_list = Vec::gather_mods(cache_dir)?;
+/// Select a mod which came from Nexus; returns it with its Nexus id.
+fn select_nexus_mod<'a>(
+    mod_list: &'a [Manifest],
+    name: Option<&str>,
+    msg: &str,
+) -> Result<(&'a Manifest, u32)> {
     let idx = FindSelectBuilder::new(mod_list.default_list_builder())
-        .with_msg("Please select a mod to show:")
-        .with_input(name.as_deref())
+        .with_msg(msg)
+        .with_input(name)
         .build()?
         .prompt()?;
-
-    show_mod_status(&mod_list, idx)
-}
-
-fn show_mod_status(mod_list: &[Manifest], idx: usize) -> Result<()> {
-    let conflict_list_file = conflict_list_by_file(mod_list)?;
     let md = &mod_list[idx];
+    let nexus_id = md
+        .nexus_id()
+        .ok_or_else(|| ModErrors::NoNexusId(md.name().to_owned()))?;
+    Ok((md, nexus_id))
+}
 
-    let color = Color::White;
+fn set_nexus_id(
+    settings: &Settings,
+    name: Option<&str>,
+    nexus_id: Option<u32>,
+    file_id: Option<u64>,
+    md5: bool,
+) -> Result<()> {
+    ensure_writable("set the Nexus id of a mod")?;
+    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+        .with_msg("Please select the mod to link to Nexus:")
+        .with_input(name)
+        .build()?
+        .prompt()?;
+    let md = &mut mod_list[idx];
 
-    let mut table = create_table(vec![
-        "Name", "Priority", "Status", "Mod Type", "Version", "Nexus Id",
-    ]);
-    table.add_row(vec![
-        Cell::new(md.name().to_string()).fg(color),
-        Cell::new(md.priority().to_string()).fg(color),
-        Cell::new(md.mod_state().to_string()).fg(color),
-        Cell::new(md.kind().to_string()).fg(color),
-        Cell::new(md.version().unwrap_or("<Unknown>").to_string()).fg(color),
-        Cell::new(
-            md.nexus_id()
-                .map_or("<Unknown>".to_owned(), |nid| nid.to_string()),
-        )
-        .fg(color),
-    ]);
+    let (nexus_id, file) = if md5 {
+        let archive = mod_archive(settings.download_dir(), md)?
+            .ok_or_else(|| DownloadError::ArchiveNotFound(md.bare_file_name().to_owned()))?;
+        let found = Nexus::new(settings)?.identify(&settings.download_dir().join(archive))?;
+        (found.mod_info.mod_id, Some(found.file_details))
+    } else {
+        // Clap demands the id without `md5`.
+        let nexus_id = nexus_id.unwrap_or_default();
+        let file = file_id
+            .map(|file_id| Nexus::new(settings)?.file_info(nexus_id, file_id))
+            .transpose()?;
+        (nexus_id, file)
+    };
 
-    log::info!("{table}");
+    md.set_nexus_id(nexus_id, file.as_ref().and_then(|f| f.version.clone()))?;
+    match file {
+        Some(file) => log::info!(
+            "Linked '{}' to mod {nexus_id} on Nexus, file '{}'.",
+            md.name(),
+            file.name
+        ),
+        None => log::info!("Linked '{}' to mod {nexus_id} on Nexus.", md.name()),
+    }
+    Ok(())
+}
 
-    let mut files = md
-        .files()?
+fn refresh_metadata(settings: &Settings, rename: bool) -> Result<()> {
+    ensure_writable("refresh the metadata of the mods")?;
+    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let nexus_ids = mod_list
         .iter()
-        .map(|i| (i.clone(), (md.name(), md.priority())))
-        .collect::<Vec<_>>();
+        .filter_map(Manifest::nexus_id)
+        .collect::<BTreeSet<_>>();
+    if nexus_ids.is_empty() {
+        log::info!("No mod has a Nexus id.");
+        return Ok(());
+    }
+
+    let nexus = Nexus::new(settings)?;
+    let categories = nexus.categories().unwrap_or_else(|e| {
+        log::warn!("Unable to get the categories from Nexus: {e:#}.");
+        BTreeMap::new()
+    });
+
+    log::info!("Refreshing the metadata of {} mod(s).", nexus_ids.len());
+    let mut cache = UpdateCache::read(settings.cache_dir());
+    let mut infos = BTreeMap::new();
+    for nexus_id in nexus_ids {
+        match nexus.mod_info(nexus_id) {
+            Ok(info) => {
+                cache.insert(nexus_id, &info);
+                infos.insert(nexus_id, info);
+            }
+            Err(e) => log::warn!("Unable to get the details of mod {nexus_id}: {e:#}."),
+        }
+    }
+    cache.write(settings.cache_dir())?;
+
+    let mut names = mod_list
+        .iter()
+        .map(|md| md.name().to_owned())
+        .collect::<BTreeSet<_>>();
+    let mut updated = 0;
+    for md in &mut mod_list {
+        let Some(info) = md.nexus_id().and_then(|id| infos.get(&id)) else {
+            continue;
+        };
+        let category = info.category_id.and_then(|id| categories.get(&id)).cloned();
+        let filled = md.fill_metadata(info.author.clone(), category)?;
+
+        // Several mods can share a Nexus id, like the main and optional files of a mod.
+        let new_name = info.name.as_ref().filter(|n| rename && !names.contains(*n));
+        if let Some(name) = new_name {
+            log::info!("Renaming '{}' to '{name}'.", md.name());
+            names.remove(md.name());
+            names.insert(name.clone());
+            md.set_name(name.clone())?;
+        }
+        if filled || new_name.is_some() {
+            updated += 1;
+        }
+    }
+    log::info!("Updated the metadata of {updated} mod(s).");
+    Ok(())
+}
+
+fn show_changelog(settings: &Settings, name: Option<&str>, all: bool) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let (md, nexus_id) = select_nexus_mod(
+        &mod_list,
+        name,
+        "Please select a mod to show the changes of:",
+    )?;
+    let installed = Version::from(md.version());
+
+    let mut changelogs = Nexus::new(settings)?
+        .changelogs(nexus_id)?
+        .into_iter()
+        .filter(|(version, _)| all || Version::from(version.as_str()) > installed)
+        .collect::<Vec<_>>();
+    if changelogs.is_empty() {
+        log::info!(
+            "No changes since version {} of '{}' are known to Nexus.",
+            md.version().unwrap_or("<Unknown>"),
+            md.name()
+        );
+        return Ok(());
+    }
+    // Newest first, like a changelog.
+    changelogs.sort_by(|(a, _), (b, _)| Version::from(b.as_str()).cmp(&Version::from(a.as_str())));
+
+    let mut output = format!(
+        "Changes of '{}'; version {} is installed.\n",
+        md.name(),
+        md.version().unwrap_or("<Unknown>")
+    );
+    for (version, changes) in changelogs {
+        writeln!(output, "\n{version}")?;
+        for change in changes {
+            // Authors write in html on Nexus.
+            let change = change.replace("<br />", "\n    ").replace("<br>", "\n    ");
+            writeln!(output, "  - {}", change.trim())?;
+        }
+    }
+    page(&output);
+    Ok(())
+}
+
+/// Track mod `name` on Nexus, or stop tracking it.
+fn track_mod(settings: &Settings, name: Option<&str>, track: bool) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let msg = if track {
+        "Please select a mod to track:"
+    } else {
+        "Please select a mod to stop tracking:"
+    };
+    let (md, nexus_id) = select_nexus_mod(&mod_list, name, msg)?;
+    ensure_writable(&format!("change the tracking of '{}'", md.name()))?;
+
+    let nexus = Nexus::new(settings)?;
+    if nexus.tracked_mods()?.contains(&nexus_id) == track {
+        let state = if track { "already" } else { "not" };
+        log::info!("'{}' is {state} tracked.", md.name());
+        return Ok(());
+    }
+    nexus.track(nexus_id, track)?;
+    if track {
+        log::info!("Tracking '{}' on Nexus.", md.name());
+    } else {
+        log::info!("Stopped tracking '{}' on Nexus.", md.name());
+    }
+    Ok(())
+}
+
+/// Give all mods with a priority of zero or more evenly spaced priorities, keeping their order.
+/// Among mods with the same priority, `first` (a manifest dir) goes before the others.
+fn renumber_priorities(
+    mod_list: &mut [Manifest],
+    step: usize,
+    first: Option<&Utf8Path>,
+) -> Result<()> {
+    let step = isize::try_from(step)?;
+
+    let mut order = mod_list
+        .iter()
+        .enumerate()
+        .map(|(idx, m)| {
+            let tie_break = (m.sequence(), m.name());
+            (
+                m.priority(),
+                Some(m.manifest_dir()) != first,
+                tie_break,
+                idx,
+            )
+        })
+        .collect::<Vec<_>>();
+    order.sort_unstable();
+    let order = order
+        .into_iter()
+        .map(|(_, _, _, idx)| idx)
+        .collect::<Vec<_>>();
+
+    let mut priority = 0;
+    for idx in order {
+        priority += step;
+        if mod_list[idx].priority() != priority {
+            log::debug!(
+                "Renumbering '{}': {} -> {priority}",
+                mod_list[idx].name(),
+                mod_list[idx].priority()
+            );
+            mod_list[idx].set_priority(priority)?;
+        }
+    }
+    Ok(())
+}
+
+fn set_sequence(settings: &Settings, name: Option<&str>, sequence: Option<u64>) -> Result<()> {
+    let cache_dir = settings.cache_dir();
+    let mut mod_list = Vec::gather_mods(cache_dir)?;
+    let (idx, sequence) = FindSelectBuilder::new(mod_list.default_list_builder())
+        .with_msg("Please select a mod to move:")
+        .with_input(name)
+        .build()?
+        .with_test(
+            sequence,
+            CustomType::new("Please specify the new sequence number")
+                .with_error_message("Please type a valid number")
+                .with_help_message("Mods with a lower number are deployed first."),
+        )
+        .prompt()?;
+
+    mod_list[idx].set_sequence(sequence)?;
+    if mod_list[idx].is_enabled() {
+        // The mod may now win, or lose, conflicts with the mods of the same priority.
+        let mut mod_list = Vec::gather_mods(cache_dir)?;
+        mod_list.re_enable(cache_dir, settings.game_dir())?;
+    }
+    Ok(())
+}
+
+/// Number all mods in their current order; the order itself does not change.
+fn renumber_sequences(settings: &Settings) -> Result<()> {
+    let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+    for (sequence, md) in (1..).zip(mod_list.iter_mut()) {
+        if md.sequence() != sequence {
+            log::debug!(
+                "Renumbering '{}': {} -> {sequence}",
+                md.name(),
+                md.sequence()
+            );
+            md.set_sequence(sequence)?;
+        }
+    }
+    Ok(())
+}
+
+fn show_sequences(settings: &Settings) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+
+    let mut table = create_table(vec!["Priority", "Sequence", "Name"]);
+    for md in &mod_list {
+        let shared = mod_list
+            .iter()
+            .filter(|m| m.priority() == md.priority())
+            .count()
+            > 1;
+        let priority = Cell::new(md.priority());
+        let priority = if shared {
+            priority.fg(Color::Yellow)
+        } else {
+            priority
+        };
+        let sequence = if md.sequence() == 0 {
+            Cell::new("<Not recorded>")
+        } else {
+            Cell::new(md.sequence())
+        };
+        table.add_row(vec![priority, sequence, Cell::new(md.name())]);
+    }
+    page(&format!("\n{table}"));
+    Ok(())
+}
+
+/// Merge the ini files shipped by more than one enabled mod into custom mod `name`,
+/// which is placed above all other mods and enabled.
+pub fn merge_ini_files(
+    cache_dir: &Utf8Path,
+    game_dir: &Utf8Path,
+    cmd_name: &str,
+    name: &str,
+    strategy: IniMergeStrategy,
+) -> Result<()> {
+    let patch_dir = Utf8PathBuf::from(name.to_lowercase());
+    let mod_list = Vec::gather_mods(cache_dir)?;
+
+    // Grouped by their destination, compared without casing, like the game does.
+    // The mod-list is sorted by priority, so the sources are too.
+    let mut inis = BTreeMap::<String, (String, Vec<(&str, Utf8PathBuf)>)>::new();
+    for md in mod_list
+        .iter()
+        .filter(|m| m.is_enabled() && m.manifest_dir() != patch_dir)
+    {
+        for f in md.files()? {
+            let is_ini = Utf8Path::new(f.destination())
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(INI_EXTENSION));
+            if is_ini {
+                inis.entry(f.destination().to_lowercase())
+                    .or_insert_with(|| (f.destination().to_owned(), Vec::new()))
+                    .1
+                    .push((
+                        md.name(),
+                        cache_dir.join(md.manifest_dir()).join(f.source()),
+                    ));
+            }
+        }
+    }
+    inis.retain(|_, (_, sources)| sources.len() > 1);
+
+    // Custom mods are deployed into 'Data', with their paths relative to the mod directory.
+    let inis = inis
+        .into_values()
+        .filter_map(|(destination, sources)| {
+            let mut components = Utf8Path::new(&destination).components();
+            if components
+                .next()
+                .is_some_and(|c| c.as_str().eq_ignore_ascii_case(DATA_DIR_NAME))
+            {
+                Some((components.as_path().to_owned(), sources))
+            } else {
+                log::warn!(
+                    "Skipping '{destination}'; custom mods can only deploy into '{DATA_DIR_NAME}'."
+                );
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if inis.is_empty() {
+        log::info!("No ini files are shipped by more than one enabled mod.");
+        return Ok(());
+    }
+
+    ensure_writable(&format!("write the merged ini files to '{name}'"))?;
+    let destination_dir = cache_dir.join(&patch_dir);
+    if destination_dir.exists() {
+        remove_dir_all(&destination_dir)?;
+    }
+
+    for (relative_destination, sources) in &inis {
+        let mut merged = Ini::default();
+        for (_, source) in sources {
+            merged.merge(
+                &Ini::parse(&String::from_utf8_lossy(&std::fs::read(source)?)),
+                strategy,
+            );
+        }
+
+        let mod_names = sources.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+        let destination = destination_dir.join(relative_destination);
+        log::info!(
+            "Merging '{relative_destination}' from: {}",
+            mod_names.join(", ")
+        );
+
+        DirBuilder::new()
+            .recursive(true)
+            .create(destination.parent().unwrap_or(&destination_dir))?;
+        std::fs::write(
+            &destination,
+            format!(
+                "; Merged by {cmd_name} ({strategy}) from: {}\n{merged}",
+                mod_names.join(", ")
+            ),
+        )?;
+    }
+    share_with_group_recursive(&destination_dir)?;
+
+    let mut md = if let Ok(md) = Manifest::from_file(cache_dir, &patch_dir) {
+        md.reinstall()?
+    } else {
+        ModKind::Custom.create_mod(cache_dir, &patch_dir)?
+    };
+    let top = mod_list
+        .iter()
+        .filter(|m| m.manifest_dir() != patch_dir)
+        .map(Manifest::priority)
+        .max()
+        .unwrap_or_default();
+    if md.priority() <= top {
+        md.set_priority(top + 1)?;
+    }
+
+    let mut mod_list = Vec::gather_mods(cache_dir)?;
+    if let Some(idx) = mod_list.iter().position(|m| m.manifest_dir() == patch_dir) {
+        mod_list.enable_mod(cache_dir, game_dir, idx)?;
+    }
+    Ok(())
+}
+
+fn select_file_idx(md: &Manifest, msg: &str, input: Option<&str>) -> Result<usize> {
+    Ok(
+        FindSelectBuilder::new(FileListBuilder::new(md).with_index().with_origin())
+            .with_msg(msg)
+            .with_input(input)
+            .build()?
+            .prompt()?,
+    )
+}
+
+fn clone_mod(cache_dir: &Utf8Path, md: &Manifest, name: &str) -> Result<()> {
+    let mod_dir = Utf8PathBuf::from(name);
+    let destination_dir = cache_dir.join(&mod_dir);
+    if destination_dir.exists() || Manifest::from_file(cache_dir, &mod_dir).is_ok() {
+        return Err(ModErrors::ModAlreadyExists(name.to_owned()).into());
+    }
+    ensure_writable(&format!("clone '{}'", md.name()))?;
+
+    log::info!("Cloning mod '{}' into custom mod '{}'", md.name(), name);
+
+    let files = md.files()?.into_iter().map(|f| (md, f)).collect::<Vec<_>>();
+    copy_into_custom(cache_dir, &destination_dir, &files)?;
+
+    let mut clone = ModKind::Custom.create_mod(cache_dir, &mod_dir)?;
+    clone.set_priority(md.priority())?;
+    for tag in md.tags() {
+        clone.add_tag(tag)?;
+    }
+    Ok(())
+}
+
+/// Create custom mod `name` from the files of the mods at `indices`; when several of them
+/// provide the same file, the one of the mod with the highest priority is used.
+fn merge_mods(
+    cache_dir: &Utf8Path,
+    mod_list: &[Manifest],
+    indices: &[usize],
+    name: &str,
+) -> Result<()> {
+    let mod_dir = Utf8PathBuf::from(name);
+    let destination_dir = cache_dir.join(&mod_dir);
+    if destination_dir.exists() || Manifest::from_file(cache_dir, &mod_dir).is_ok() {
+        return Err(ModErrors::ModAlreadyExists(name.to_owned()).into());
+    }
+    ensure_writable(&format!("merge mods into '{name}'"))?;
+
+    // The mod-list is sorted by priority, so later mods overwrite the files of earlier ones.
+    let mut winners = BTreeMap::new();
+    for &idx in indices {
+        for f in mod_list[idx].files()? {
+            winners.insert(f.destination().to_owned(), (&mod_list[idx], f));
+        }
+    }
+    let files = winners.into_values().collect::<Vec<_>>();
+
+    log::info!(
+        "Merging {} file(s) of {} into custom mod '{name}'",
+        files.len(),
+        indices
+            .iter()
+            .map(|&idx| format!("'{}'", mod_list[idx].name()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    copy_into_custom(cache_dir, &destination_dir, &files)?;
+
+    let mut merged = ModKind::Custom.create_mod(cache_dir, &mod_dir)?;
+    if let Some(priority) = indices.iter().map(|&idx| mod_list[idx].priority()).max() {
+        merged.set_priority(priority)?;
+    }
+    for tag in indices.iter().flat_map(|&idx| mod_list[idx].tags()) {
+        merged.add_tag(tag)?;
+    }
+    Ok(())
+}
+
+/// Move the files of `md` for which `matches` holds into the new mod `name`;
+/// it gets the kind, priority and tags of `md`.
+fn split_mod(
+    cache_dir: &Utf8Path,
+    md: &mut Manifest,
+    matches: &dyn Fn(&InstallFile) -> bool,
+    name: &str,
+) -> Result<()> {
+    let mod_dir = Utf8PathBuf::from(name);
+    let destination_dir = cache_dir.join(&mod_dir);
+    if destination_dir.exists() || Manifest::from_file(cache_dir, &mod_dir).is_ok() {
+        return Err(ModErrors::ModAlreadyExists(name.to_owned()).into());
+    }
+    ensure_writable(&format!("split '{}'", md.name()))?;
+
+    let source_dir = cache_dir.join(md.manifest_dir());
+    let mut split = if md.kind() == ModKind::Custom {
+        // The files of a custom mod are whatever is in its directory.
+        let files = md
+            .files()?
+            .into_iter()
+            .filter(|f| matches(f))
+            .collect::<Vec<_>>();
+        move_files(&source_dir, &destination_dir, &files, &[])?;
+        ModKind::Custom.create_mod(cache_dir, &mod_dir)?
+    } else {
+        let (files, disabled_files) = md.split_off_files(matches)?;
+        let remaining = md
+            .files()?
+            .into_iter()
+            .chain(md.disabled_files())
+            .collect::<Vec<_>>();
+        move_files(
+            &source_dir,
+            &destination_dir,
+            &[files.as_slice(), disabled_files.as_slice()].concat(),
+            &remaining,
+        )?;
+
+        // Only the original installer knows the options of a fomod; the split keeps its choices.
+        let mod_kind = if md.kind() == ModKind::FoMod {
+            ModKind::Data
+        } else {
+            md.kind()
+        };
+        let split = Manifest::new(
+            cache_dir,
+            &mod_dir,
+            name.to_owned(),
+            name.to_owned(),
+            md.nexus_id(),
+            md.version().map(ToOwned::to_owned),
+            files,
+            disabled_files,
+            mod_kind,
+        );
+        split.write()?;
+        split
+    };
+
+    split.set_priority(md.priority())?;
+    for tag in md.tags() {
+        split.add_tag(tag)?;
+    }
+    log::info!("Split '{}' into '{name}'", md.name());
+    Ok(())
+}
+
+/// Move the sources of `files` from `source_dir` to `destination_dir`;
+/// sources which are still used by one of the `remaining` files are copied instead.
+fn move_files(
+    source_dir: &Utf8Path,
+    destination_dir: &Utf8Path,
+    files: &[InstallFile],
+    remaining: &[InstallFile],
+) -> Result<()> {
+    DirBuilder::new().recursive(true).create(destination_dir)?;
+    for f in files {
+        let origin = source_dir.join(f.source());
+        let destination = destination_dir.join(f.source());
+        if destination.exists() {
+            continue;
+        }
+
+        DirBuilder::new()
+            .recursive(true)
+            .create(destination.parent().unwrap_or(destination_dir))?;
+        if remaining.iter().any(|r| r.source() == f.source()) {
+            log::trace!("Copying {origin} -> {destination}");
+            copy(&origin, &destination)?;
+        } else {
+            log::trace!("Moving {origin} -> {destination}");
+            rename(&origin, &destination)?;
+        }
+    }
+    share_with_group_recursive(destination_dir)
+}
+
+/// Copy `files` into the directory of a custom mod.
+/// Custom mods are deployed into 'Data', with their paths relative to the mod directory.
+/// Therefore store the files by their destination, so they end up in the same place.
+fn copy_into_custom(
+    cache_dir: &Utf8Path,
+    destination_dir: &Utf8Path,
+    files: &[(&Manifest, InstallFile)],
+) -> Result<()> {
+    for (md, f) in files {
+        let Some(relative_destination) = f
+            .destination()
+            .strip_prefix(DATA_DIR_NAME)
+            .map(|d| d.trim_start_matches('/'))
+        else {
+            log::warn!(
+                "Skipping '{}'; custom mods can only deploy into '{DATA_DIR_NAME}'.",
+                f.destination()
+            );
+            continue;
+        };
+
+        let origin = cache_dir.join(md.manifest_dir()).join(f.source());
+        let destination = destination_dir.join(relative_destination);
+
+        log::trace!("Copying {origin} -> {destination}");
+        DirBuilder::new()
+            .recursive(true)
+            .create(destination.parent().unwrap_or(destination_dir))?;
+        copy(origin, destination)?;
+    }
+
+    if destination_dir.exists() {
+        share_with_group_recursive(destination_dir)?;
+    }
+    Ok(())
+}
+
+pub fn which(settings: &Settings, path: &str) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let path = path.replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+
+    let providers = providers_of(&mod_list, path)?;
+    let destination = providers.first().map(|(_, f)| f.destination().to_owned());
+    let winner = winner_of(&providers);
+
+    let mut table = create_table(vec!["Mod", "Priority", "Status", "Source", "Provides"]);
+    for (md, f) in &providers {
+        let (role, color) = if winner.as_deref() == Some(md.name()) {
+            ("Winner", Color::Green)
+        } else if md.is_enabled() {
+            ("Overwritten", Color::Red)
+        } else {
+            ("Disabled", Color::DarkGrey)
+        };
+        table.add_row(vec![
+            Cell::new(md.name()).fg(color),
+            Cell::new(md.priority()).fg(color),
+            Cell::new(md.mod_state()).fg(color),
+            Cell::new(f.source()).fg(color),
+            Cell::new(role).fg(color),
+        ]);
+    }
+    table.add_row_if(
+        |idx, _row| idx.eq(&0),
+        vec![Cell::new(format!("No mod provides '{path}'."))],
+    );
+
+    log::info!("");
+    log::info!("{table}");
+    log::info!("");
+
+    let game_file = settings
+        .game_dir()
+        .join(destination.as_deref().unwrap_or(path));
+    log::info!("Game file: {}", game_file_state(&game_file)?);
+
+    let backups = BackupRegistry::read(settings.cache_dir()).backups_of(&game_file);
+    if let Some((newest, older)) = backups.split_last() {
+        log::info!("Backup of the original file: {newest}");
+        for backup in older.iter().rev() {
+            log::info!("Older backup: {backup}");
+        }
+    } else {
+        log::info!("No backup of an original file exists.");
+    }
+
+    Ok(())
+}
+
+fn show_file_chain(
+    settings: &Settings,
+    mod_list: &[Manifest],
+    idx: usize,
+    file: &InstallFile,
+) -> Result<()> {
+    let md = &mod_list[idx];
+    let providers = providers_of(mod_list, file.destination())?;
+    let winner = winner_of(&providers);
+
+    let cache_file = settings
+        .cache_dir()
+        .join(md.manifest_dir())
+        .join(file.source());
+    let game_file = settings.game_dir().join(file.destination());
+
+    let mut table = create_table(Vec::<String>::new());
+    table.add_row(vec!["Mod".to_owned(), md.name().to_owned()]);
+    table.add_row(vec!["Cache".to_owned(), cache_file.to_string()]);
+    table.add_row(vec![
+        "Destination".to_owned(),
+        file.destination().to_owned(),
+    ]);
+    table.add_row(vec!["Game file".to_owned(), game_file_state(&game_file)?]);
+
+    let (deployed, color) = match read_link(&game_file) {
+        Ok(target) if target == cache_file.as_std_path() => ("Deployed", Color::Green),
+        _ if md.is_disabled() => ("Mod is disabled", Color::DarkGrey),
+        _ => ("Not deployed", Color::Red),
+    };
+    table.add_row(vec![Cell::new("Status"), Cell::new(deployed).fg(color)]);
+
+    match winner.as_deref() {
+        Some(winner) if winner != md.name() => {
+            table.add_row(vec![
+                Cell::new("Provided by"),
+                Cell::new(winner).fg(Color::Red),
+            ]);
+        }
+        _ => {}
+    }
+
+    log::info!("");
+    log::info!("{table}");
+    Ok(())
+}
+
+/// All mods which contain a file with `destination`, in mod-list order.
+fn providers_of<'a>(
+    mod_list: &'a [Manifest],
+    destination: &str,
+) -> Result<Vec<(&'a Manifest, InstallFile)>> {
+    let mut providers = Vec::new();
+    for md in mod_list {
+        if let Some(f) = md
+            .files()?
+            .into_iter()
+            .find(|f| f.destination().eq_ignore_ascii_case(destination))
+        {
+            providers.push((md, f));
+        }
+    }
+    Ok(providers)
+}
+
+fn winner_of(providers: &[(&Manifest, InstallFile)]) -> Option<String> {
+    // The mod-list is sorted by priority, so the last enabled provider wins.
+    providers
+        .iter()
+        .rev()
+        .find(|(md, _)| md.is_enabled())
+        .map(|(md, _)| md.name().to_owned())
+}
+
+fn game_file_state(game_file: &Utf8Path) -> Result<String> {
+    Ok(if game_file.is_symlink() {
+        format!("{game_file} -> {}", read_link(game_file)?.display())
+    } else if game_file.exists() {
+        format!("{game_file} (not managed by starmod)")
+    } else {
+        format!("{game_file} (does not exist)")
+    })
+}
+
+fn show_mod(
+    settings: &Settings,
+    name: Option<&str>,
+    tree: bool,
+    depth: Option<usize>,
+    image: bool,
+    docs: bool,
+) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let idx = FindSelectBuilder::new(mod_list.default_list_builder())
+        .with_msg("Please select a mod to show:")
+        .with_input(name.as_deref())
+        .build()?
+        .prompt()?;
+
+    show_mod_status(&mod_list, idx, tree, depth)?;
+
+    if image {
+        let md = &mod_list[idx];
+        let nexus_id = md
+            .nexus_id()
+            .ok_or_else(|| ModErrors::NoNexusId(md.name().to_owned()))?;
+        match mod_image(settings, nexus_id)? {
+            Some(path) => show_image(&path)?,
+            None => log::info!("Mod '{}' has no image on Nexus.", md.name()),
+        }
+    }
+    if docs {
+        show_mod_docs(&mod_list[idx])?;
+    }
+    Ok(())
+}
+
+/// Show a readme or changelog of `md`; the user chooses one when there are several.
+/// Text is paged, other documents are opened with the viewer of the user.
+pub fn show_mod_docs(md: &Manifest) -> Result<()> {
+    let docs = md.docs();
+    let doc = match docs.as_slice() {
+        [] => {
+            log::info!("Mod '{}' has no readme or changelog.", md.name());
+            return Ok(());
+        }
+        [doc] => doc,
+        _ if !std::io::stdin().is_terminal() => {
+            let docs = docs.iter().map(|doc| doc.as_str()).collect::<Vec<_>>();
+            log::info!("Documents of '{}':\n{}", md.name(), docs.join("\n"));
+            return Ok(());
+        }
+        _ => {
+            let names = docs
+                .iter()
+                .map(|doc| doc.file_name().unwrap_or_default().to_owned())
+                .collect::<Vec<_>>();
+            &docs[SelectToIdx::new("Please select a document to show:", names).prompt()?]
+        }
+    };
+
+    let extension = doc.extension().unwrap_or_default().to_lowercase();
+    if extension.is_empty() || extension == "txt" || extension == "md" {
+        page(&String::from_utf8_lossy(&read(doc)?));
+        Ok(())
+    } else {
+        open_url(doc.as_str())
+    }
+}
+
+fn show_mod_status(
+    mod_list: &[Manifest],
+    idx: usize,
+    tree: bool,
+    depth: Option<usize>,
+) -> Result<()> {
+    let conflict_list_file = conflict_list_by_file(mod_list)?;
+    let md = &mod_list[idx];
+
+    let color = Color::White;
+
+    let mut table = create_table(vec![
+        "Name", "Priority", "Status", "Mod Type", "Version", "Nexus Id",
+    ]);
+    table.add_row(vec![
+        Cell::new(md.name().to_string()).fg(color),
+        Cell::new(md.priority().to_string()).fg(color),
+        Cell::new(md.mod_state().to_string()).fg(color),
+        Cell::new(md.kind().to_string()).fg(color),
+        Cell::new(md.version().unwrap_or("<Unknown>").to_string()).fg(color),
+        Cell::new(
+            md.nexus_id()
+                .map_or("<Unknown>".to_owned(), |nid| nid.to_string()),
+        )
+        .fg(color),
+    ]);
+
+    let mut output = table.to_string();
+    if let Some(author) = md.author() {
+        write!(output, "\nAuthor: {author}")?;
+    }
+    if let Some(category) = md.category() {
+        write!(output, "\nCategory: {category}")?;
+    }
+    if let Some(notes) = md.notes() {
+        output.push_str("\n\n");
+        output.push_str(notes);
+    }
+
+    if tree {
+        let mut tree = FileTreeBuilder::new().with_depth(depth);
+        for isf in md.files()? {
+            let (label, color) = match conflict_list_file
+                .get(isf.destination())
+                .and_then(|mods| mods.last())
+            {
+                Some(winner) if winner == md.name() => (md.name(), Color::Green),
+                Some(winner) => (winner.as_str(), Color::Red),
+                None => (md.name(), Color::White),
+            };
+            tree.insert(isf.destination(), label, color);
+        }
+
+        output.push_str("\n\n");
+        output.push_str(&tree.build().join("\n"));
+        page(&output);
+        return Ok(());
+    }
+
+    let mut files = md
+        .files()?
+        .iter()
+        .map(|i| (i.clone(), (md.name(), md.priority())))
+        .collect::<Vec<_>>();
 
     files.sort_unstable_by(|(ia, (_, pa)), (ib, (_, pb))| {
         let o = ia.destination().cmp(ib.destination());
@@ -526,7 +2009,6 @@ fn show_mod_status(mod_list: &[Manifest], idx: usize) -> Result<()> {
         }
     });
 
-    log::info!("");
     let mut table = create_table(vec!["File", "Destination"]);
 
     for (isf, (name, _priority)) in files {
@@ -554,9 +2036,7 @@ fn show_mod_status(mod_list: &[Manifest], idx: usize) -> Result<()> {
 
     table.add_row_if(|idx, _row| idx.eq(&0), vec![Cell::new("No files found.")]);
 
-    log::info!("{table}");
-
-    log::info!("");
+    write!(output, "\n\n{table}\n")?;
 
     if !md.disabled_files().is_empty() {
         let mut table = create_table(vec!["Disabled File"]);
@@ -566,9 +2046,11 @@ fn show_mod_status(mod_list: &[Manifest], idx: usize) -> Result<()> {
             table.add_row(vec![Cell::new(isf.source().to_string()).fg(color)]);
         }
 
-        log::info!("{table}");
+        write!(output, "\n{table}")?;
     }
 
+    page(&output);
+
     Ok(())
 }
 
@@ -618,7 +2100,6 @@ fn edit_mod_config_files(
     };
 
     if !config_files_to_edit.is_empty() {
-        let mut editor_cmd = std::process::Command::new(settings.editor());
         // if let Some(destination_mod_name) = destination_mod_name {
         //     // Copy
         //     if let Some(idx) = mod_list.find_mod(destination_mod_name.as_deref()) {
@@ -640,17 +2121,12 @@ fn edit_mod_config_files(
         //         }
         //     }
         // } else {
-        for (source, _) in &config_files_to_edit {
-            let _ = editor_cmd.arg(source);
-        }
+        let sources = config_files_to_edit
+            .iter()
+            .map(|(source, _)| source.clone())
+            .collect::<Vec<_>>();
+        edit_files(settings, &sources)?;
         // }
-
-        log::info!("Running '{:?}'", editor_cmd);
-
-        let status = editor_cmd.spawn()?.wait()?;
-        if !status.success() {
-            log::info!("Editor failed with exit status: {}", status);
-        }
     } else {
         log::trace!("No relevant config files found.");
         return Err(ModErrors::FileNotFound(
@@ -665,3 +2141,214 @@ fn edit_mod_config_files(
 
     Ok(())
 }
+
+fn show_remaps(md: &Manifest) {
+    let mut table = create_table(vec!["From", "To"]);
+    for remap in md.remaps() {
+        table.add_row(vec![Cell::new(remap.from()), Cell::new(remap.to())]);
+    }
+    log::info!("Remap rules of '{}':", md.name());
+    log::info!("{table}");
+}
+
+fn lint_mods(settings: &Settings, name: Option<&str>, json: bool) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let mods = match name {
+        Some(name) => {
+            let idx = mod_list
+                .find_mod(name)
+                .ok_or_else(|| ModErrors::ModNotFound(name.to_owned()))?;
+            vec![&mod_list[idx]]
+        }
+        None => mod_list.iter().collect(),
+    };
+
+    let mut findings = Vec::new();
+    for md in mods {
+        findings.extend(lint(md)?);
+    }
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else if findings.is_empty() {
+        log::info!("No problems found.");
+    } else {
+        let mut table = create_table(vec!["Mod", "Severity", "Rule", "Destination", "Problem"]);
+        for f in &findings {
+            let colour = match f.severity {
+                Severity::Error => Color::Red,
+                Severity::Warning => Color::Yellow,
+            };
+            table.add_row(vec![
+                Cell::new(&f.mod_name),
+                Cell::new(f.severity).fg(colour),
+                Cell::new(f.rule),
+                Cell::new(f.destination.as_deref().unwrap_or_default()),
+                Cell::new(f.rule.description()),
+            ]);
+        }
+        page(&format!("\n{table}"));
+    }
+
+    if errors > 0 {
+        Err(ModErrors::LintFailed(errors).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Write the files of `md` to the archive `output`; `with_manifest` adds its manifest and state
+/// file, which are read back when the archive is extracted.
+pub fn pack_mod(
+    cache_dir: &Utf8Path,
+    md: &Manifest,
+    output: &Utf8Path,
+    with_manifest: bool,
+) -> Result<()> {
+    let archive_type = SupportedArchives::from_path(output.as_std_path())?;
+    if output.exists() {
+        return Err(ModErrors::OutputExists(output.to_owned()).into());
+    }
+
+    let mod_dir = cache_dir.join(md.manifest_dir());
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&mod_dir).min_depth(1).follow_links(true) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let name = entry.path().strip_prefix(&mod_dir)?.to_string_lossy();
+            files.push((name.into_owned(), entry.path().to_path_buf()));
+        }
+    }
+    files.sort();
+
+    if with_manifest {
+        let manifest_file = mod_dir.add_extension(MANIFEST_EXTENSION);
+        for file in iter::once(manifest_file).chain(md.state_file()) {
+            let name = file.file_name().unwrap_or_default().to_owned();
+            files.push((name, file.into_std_path_buf()));
+        }
+    }
+
+    log::info!(
+        "Packing {} file(s) of '{}' into '{output}'",
+        files.len(),
+        md.name()
+    );
+    archive_type.compress(&files, output.as_std_path())
+}
+
+fn create_launcher(settings: &Settings, md: &Manifest, output: Option<Utf8PathBuf>) -> Result<()> {
+    ensure_writable("create a launcher")?;
+
+    let mut executables = md
+        .files()?
+        .into_iter()
+        .filter(|f| {
+            Utf8Path::new(f.destination())
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(EXE_EXTENSION))
+        })
+        .collect::<Vec<_>>();
+    let executable = match executables.len() {
+        0 => return Err(ModErrors::NoExecutable(md.name().to_owned()).into()),
+        1 => executables.remove(0),
+        _ => {
+            let destinations = executables
+                .iter()
+                .map(|f| f.destination().to_owned())
+                .collect::<Vec<_>>();
+            let idx = InquireBuilder::new(SelectToIdx::new(
+                "Which executable should the launcher run?",
+                destinations,
+            ))
+            .prompt()?;
+            executables.remove(idx)
+        }
+    };
+
+    // Launch the linked file, so tools find the game and the other mods next to them.
+    let executable = settings.game_dir().join(executable.destination());
+    if !md.is_enabled() {
+        log::warn!(
+            "Mod '{}' is not enabled; the launcher only works after enabling it.",
+            md.name()
+        );
+    }
+
+    let Some(output) = output.or_else(|| {
+        dirs::data_dir()
+            .and_then(|d| Utf8PathBuf::try_from(d).ok())
+            .map(|d| d.join("applications"))
+    }) else {
+        return Err(ModErrors::NoLauncherDir.into());
+    };
+    DirBuilder::new().recursive(true).create(&output)?;
+
+    let starmod = std::env::current_exe()
+        .ok()
+        .and_then(|p| Utf8PathBuf::try_from(p).ok())
+        .map_or_else(|| settings.cmd_name().to_owned(), |p| p.to_string());
+    let file_stem = executable.file_stem().unwrap_or_default();
+
+    let mut entry = String::new();
+    writeln!(entry, "[Desktop Entry]")?;
+    writeln!(entry, "Type=Application")?;
+    writeln!(entry, "Name={}", md.name())?;
+    writeln!(
+        entry,
+        "Comment=Run {} through proton, using {}",
+        executable.file_name().unwrap_or_default(),
+        settings.cmd_name()
+    )?;
+    writeln!(
+        entry,
+        "Exec={} run tool {}",
+        desktop_quote(&starmod),
+        desktop_quote(executable.as_str())
+    )?;
+    if let Some(dir) = executable.parent() {
+        writeln!(entry, "Path={dir}")?;
+    }
+    writeln!(entry, "Terminal=false")?;
+    writeln!(entry, "Categories=Game;")?;
+
+    let file = output
+        .join(format!(
+            "{}-{}-{}",
+            settings.cmd_name(),
+            md.manifest_dir(),
+            file_stem.to_lowercase()
+        ))
+        .add_extension(DESKTOP_EXTENSION);
+    std::fs::write(&file, entry)?;
+
+    log::info!("Created launcher '{file}' for '{executable}'.");
+    Ok(())
+}
+
+/// Quote an argument of the 'Exec' key of a desktop entry.
+fn desktop_quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/-_.+".contains(c))
+    {
+        return arg.to_owned();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    // The whole value is unescaped once more, so every backslash is doubled;
+    // '%' starts a field code.
+    quoted.replace('\\', "\\\\").replace('%', "%%")
+}
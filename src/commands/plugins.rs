@@ -1,24 +1,106 @@
+use std::{
+    collections::HashMap,
+    fs::{
+        copy, create_dir_all, metadata, read, read_dir, read_link, read_to_string, remove_file,
+        write,
+    },
+    os::unix::fs::symlink,
+};
+
 use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
+#[cfg(feature = "loadorder")]
 use loadorder::GameSettings;
 
-use crate::settings::Settings;
+use crate::{
+    errors::{GameErrors, SettingErrors},
+    game::Game,
+    i18n::tr,
+    installers::DATA_DIR_NAME,
+    manifest::Manifest,
+    masterlist,
+    mods::GatherModList,
+    plugin_list::{PluginList, PluginListFormat},
+    settings::{create_table, ensure_writable, Settings},
+    ui::{page, InquireBuilder, SelectToIdx},
+    userlist::UserList,
+};
+
+use super::game::run_with_proton;
+
+const PLUGIN_EXTENSIONS: &[&str] = &["esm", "esp", "esl"];
+// Summary lines written by xEdit after a quick auto-clean.
+const ITM_SUMMARY: &str = "Removed Records:";
+const UDR_SUMMARY: &str = "Undeleted Records:";
+// Written by xEdit for every single record, when it has no summary.
+const ITM_RECORD: &str = "Removing:";
+const UDR_RECORD: &str = "Undeleting:";
 
 #[derive(Debug, Clone, Parser, Default)]
 pub enum PluginCmd {
+    /// Show the plugins in the game's 'Data' directory, with the enabled mod which provides each;
+    /// warns about plugins which no enabled mod provides.
     #[default]
     Show,
-    Sort,
+    /// Sort the plugins of the game; warns about plugins which no enabled mod provides.
+    #[cfg(feature = "loadorder")]
+    Sort {
+        /// Deactivate plugins which no enabled mod provides, leaving them out of the load order.
+        #[arg(short, long)]
+        exclude_unmanaged: bool,
+    },
+    /// Run xEdit's quick auto-clean on <plugin>, removing records identical to their master (ITM)
+    /// and undeleting deleted references (UDR).
+    Clean { plugin: Option<String> },
+    /// Sorting rules for LOOT; defaults to listing them.
+    Rule {
+        #[command(subcommand)]
+        cmd: Option<RuleCmd>,
+    },
+    /// Write the load order of the game to <file>, to share it with other tools.
+    Export {
+        file: Utf8PathBuf,
+        /// Format of <file>; defaults to 'load-order' for a 'loadorder.txt', and 'plugins' otherwise.
+        #[arg(short, long, value_enum)]
+        format: Option<PluginListFormat>,
+    },
+    /// Replace the load order of the game with the one in <file>.
+    Import {
+        file: Utf8PathBuf,
+        /// Format of <file>; defaults to 'load-order' for a 'loadorder.txt', and 'plugins' otherwise.
+        #[arg(short, long, value_enum)]
+        format: Option<PluginListFormat>,
+    },
+    /// Download LOOT's masterlist of the game into LOOT's data directory, for the latest
+    /// sorting metadata of the community.
+    UpdateMasterlist,
 }
 impl PluginCmd {
-    pub fn execute(self, settings: &mut Settings) -> Result<()> {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
         match self {
-            Self::Show => {
-                log::info!("{}", "not yet implemented");
-                Ok(())
-            }
-            Self::Sort => {
-                GameSettings::new(
+            Self::Show | Self::Export { .. } => true,
+            Self::Rule { cmd } => matches!(cmd, None | Some(RuleCmd::List)),
+            #[cfg(feature = "loadorder")]
+            Self::Sort { .. } => false,
+            Self::Clean { .. } | Self::Import { .. } | Self::UpdateMasterlist => false,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::Show => show_plugins(settings),
+            #[cfg(feature = "loadorder")]
+            Self::Sort { exclude_unmanaged } => {
+                masterlist::refresh(settings);
+                let mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let unmanaged =
+                    unmanaged_plugins(&mod_list, *settings.game(), settings.game_dir())?;
+                for plugin in &unmanaged {
+                    log::warn!("Plugin '{plugin}' is not provided by any enabled mod.");
+                }
+
+                let mut load_order = GameSettings::new(
                     settings.game().game_id(),
                     settings
                         .game_dir()
@@ -26,10 +108,340 @@ impl PluginCmd {
                         .into_std_path_buf()
                         .as_path(),
                 )?
-                .into_load_order()
-                .save()?;
+                .into_load_order();
+                load_order.load()?;
+                if exclude_unmanaged {
+                    for plugin in &unmanaged {
+                        load_order.deactivate(plugin)?;
+                        log::info!("Excluded '{plugin}' from the load order.");
+                    }
+                }
+                load_order.save()?;
+                Ok(())
+            }
+            Self::Clean { plugin } => clean_plugin(settings, plugin.as_deref()),
+            Self::Rule { cmd } => cmd.unwrap_or_default().execute(settings),
+            Self::Export { file, format } => {
+                let format = format.unwrap_or_else(|| PluginListFormat::from_file_name(&file));
+                let plugins_file = plugins_file(settings)?;
+                let Ok(contents) = read_to_string(&plugins_file) else {
+                    return Err(GameErrors::ConfigNotFound(plugins_file.to_string()).into());
+                };
+                let list = PluginList::parse(&contents, PluginListFormat::Plugins);
+
+                write(&file, list.display(format).to_string())?;
+                log::info!("Exported {} plugin(s) to '{file}'.", list.len());
+                Ok(())
+            }
+            Self::Import { file, format } => {
+                ensure_writable("change the load order")?;
+
+                let format = format.unwrap_or_else(|| PluginListFormat::from_file_name(&file));
+                let mut list = PluginList::parse(&read_to_string(&file)?, format);
+
+                let plugins_file = plugins_file(settings)?;
+                if format == PluginListFormat::LoadOrder {
+                    // A load order has no states; keep those of the current plugins.
+                    let current = read_to_string(&plugins_file)
+                        .map(|c| PluginList::parse(&c, PluginListFormat::Plugins))
+                        .unwrap_or_default();
+                    list = list.with_states_of(&current);
+                }
+
+                write_plugin_list(settings, &list)?;
+                log::info!("Imported {} plugin(s) from '{file}'.", list.len());
                 Ok(())
             }
+            Self::UpdateMasterlist => {
+                if masterlist::update(settings)? == 0 {
+                    log::info!("LOOT's masterlist is up to date.");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Rules in LOOT's userlist, which LOOT applies whenever it sorts the plugins.
+#[derive(Debug, Clone, Parser, Default)]
+pub enum RuleCmd {
+    /// List all plugins with rules.
+    #[default]
+    List,
+    /// Add a rule for <plugin>.
+    Add {
+        plugin: String,
+        /// Load <plugin> after this plugin; can be given multiple times.
+        #[arg(short, long)]
+        after: Vec<String>,
+        /// Put <plugin> in this LOOT group.
+        #[arg(short, long)]
+        group: Option<String>,
+    },
+    /// Remove all rules of <plugin>.
+    Remove { plugin: String },
+}
+impl RuleCmd {
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        let path = settings.loot_userlist();
+        let mut userlist = UserList::read(&path)?;
+
+        match self {
+            Self::List => {
+                let mut table = create_table(vec!["Plugin", "After", "Group"]);
+                for rule in userlist.rules() {
+                    table.add_row(vec![
+                        rule.plugin.to_owned(),
+                        rule.after.join(", "),
+                        rule.group.unwrap_or_default().to_owned(),
+                    ]);
+                }
+                table.add_row_if(|idx, _row| idx.eq(&0), vec!["No rules found."]);
+                page(&table.to_string());
+                return Ok(());
+            }
+            Self::Add {
+                plugin,
+                after,
+                group,
+            } => {
+                if after.is_empty() && group.is_none() {
+                    return Err(GameErrors::EmptyRule(plugin).into());
+                }
+                ensure_writable("change the LOOT userlist")?;
+                userlist.add_rule(&plugin, &after, group.as_deref());
+                log::info!("Added rule for '{plugin}' to '{path}'.");
+            }
+            Self::Remove { plugin } => {
+                ensure_writable("change the LOOT userlist")?;
+                if !userlist.remove_rules(&plugin) {
+                    return Err(GameErrors::RuleNotFound(plugin).into());
+                }
+                log::info!("Removed the rules of '{plugin}' from '{path}'.");
+            }
+        }
+
+        userlist.write(&path)
+    }
+}
+
+/// Replace the load order of the game with `list`.
+pub fn write_plugin_list(settings: &Settings, list: &PluginList) -> Result<()> {
+    let data_dir = settings.game_dir().join(DATA_DIR_NAME);
+    for (plugin, _) in list.plugins() {
+        if !data_dir.join(plugin).exists() {
+            log::warn!("Plugin '{plugin}' is not installed; keeping it in the load order anyway.");
         }
     }
+
+    let plugins_file = plugins_file(settings)?;
+    if let Some(dir) = plugins_file.parent() {
+        create_dir_all(dir)?;
+    }
+    write(
+        &plugins_file,
+        list.display(PluginListFormat::Plugins).to_string(),
+    )?;
+    Ok(())
+}
+
+pub fn plugins_file(settings: &Settings) -> Result<Utf8PathBuf> {
+    let Some(user_dir) = settings.windows_user_dir() else {
+        return Err(SettingErrors::NoCompatDirFound(settings.cmd_name().to_owned()).into());
+    };
+    Ok(user_dir.join(settings.game().plugins_file()))
+}
+
+/// File names of all plugins in the game's data directory, sorted by name.
+pub fn installed_plugins(data_dir: &Utf8Path) -> Result<Vec<String>> {
+    let mut plugins = Vec::new();
+    for entry in read_dir(data_dir)? {
+        let path = Utf8PathBuf::try_from(entry?.path())?;
+        if is_plugin(&path) {
+            plugins.push(path.file_name().unwrap_or_default().to_owned());
+        }
+    }
+    plugins.sort_unstable();
+    Ok(plugins)
+}
+
+pub fn is_plugin(path: &Utf8Path) -> bool {
+    path.extension()
+        .is_some_and(|e| PLUGIN_EXTENSIONS.iter().any(|p| p.eq_ignore_ascii_case(e)))
+}
+
+/// The enabled mods which provide a plugin in the 'Data' directory, by the lower-cased name of
+/// the plugin; the mod deployed last provides it.
+fn plugin_providers(mod_list: &[Manifest]) -> Result<HashMap<String, String>> {
+    let mut providers = HashMap::new();
+    for md in mod_list.iter().filter(|m| m.is_enabled()) {
+        for dest in md.dest_files()? {
+            let dest = Utf8Path::new(&dest);
+            let in_data_dir = dest
+                .parent()
+                .is_some_and(|p| p.as_str().eq_ignore_ascii_case(DATA_DIR_NAME));
+            if in_data_dir && is_plugin(dest) {
+                providers.insert(
+                    dest.file_name().unwrap_or_default().to_lowercase(),
+                    md.name().to_owned(),
+                );
+            }
+        }
+    }
+    Ok(providers)
+}
+
+/// Installed plugins in `game_dir` which are neither part of `game`, nor provided by an enabled
+/// mod of `mod_list`.
+pub fn unmanaged_plugins(
+    mod_list: &[Manifest],
+    game: Game,
+    game_dir: &Utf8Path,
+) -> Result<Vec<String>> {
+    let managed = plugin_providers(mod_list)?;
+
+    let mut unmanaged = installed_plugins(&game_dir.join(DATA_DIR_NAME))?;
+    unmanaged.retain(|p| {
+        !managed.contains_key(&p.to_lowercase())
+            && !game
+                .base_plugins()
+                .iter()
+                .any(|b| b.eq_ignore_ascii_case(p))
+    });
+    Ok(unmanaged)
+}
+
+fn show_plugins(settings: &Settings) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let providers = plugin_providers(&mod_list)?;
+    let game = *settings.game();
+
+    let mut table = create_table(vec!["Plugin", "Mod"]);
+    for plugin in installed_plugins(&settings.game_dir().join(DATA_DIR_NAME))? {
+        let provider = providers.get(&plugin.to_lowercase()).cloned();
+        let provider = provider.unwrap_or_else(|| {
+            if game
+                .base_plugins()
+                .iter()
+                .any(|b| b.eq_ignore_ascii_case(&plugin))
+            {
+                "(game)".to_owned()
+            } else {
+                String::new()
+            }
+        });
+        table.add_row(vec![plugin, provider]);
+    }
+    table.add_row_if(|idx, _row| idx.eq(&0), vec!["No plugins found."]);
+    page(&table.to_string());
+
+    for plugin in unmanaged_plugins(&mod_list, game, settings.game_dir())? {
+        log::warn!("Plugin '{plugin}' is not provided by any enabled mod.");
+    }
+    Ok(())
+}
+
+fn clean_plugin(settings: &Settings, plugin: Option<&str>) -> Result<()> {
+    ensure_writable("clean a plugin")?;
+
+    let Some(xedit_dir) = settings.xedit_dir() else {
+        return Err(SettingErrors::NoXEditDirFound(settings.cmd_name().to_owned()).into());
+    };
+    let data_dir = settings.game_dir().join(DATA_DIR_NAME);
+
+    let mut plugins = installed_plugins(&data_dir)?;
+    if plugins.is_empty() {
+        return Err(GameErrors::PluginNotFound(plugin.unwrap_or_default().to_owned()).into());
+    }
+
+    let plugin = if let Some(idx) =
+        plugin.and_then(|p| plugins.iter().position(|f| f.eq_ignore_ascii_case(p)))
+    {
+        plugins.swap_remove(idx)
+    } else {
+        let idx = InquireBuilder::new(
+            SelectToIdx::new(&tr("select-plugin-to-clean"), plugins.clone())
+                .with_starting_filter_input(plugin.unwrap_or_default()),
+        )
+        .prompt()?;
+        plugins.swap_remove(idx)
+    };
+    let plugin_path = data_dir.join(&plugin);
+    let link_target = read_link(&plugin_path)
+        .ok()
+        .and_then(|t| Utf8PathBuf::try_from(t).ok());
+
+    let xedit = xedit_dir.join(settings.game().xedit_name());
+    let xedit_log = xedit_dir.join(format!(
+        "{}_log.txt",
+        Utf8Path::new(settings.game().xedit_name())
+            .file_stem()
+            .unwrap_or_default()
+    ));
+    // xEdit appends to its log; only read what this run adds.
+    let log_start = metadata(&xedit_log).map_or(0, |m| m.len());
+
+    let args = vec![
+        "-qac".to_owned(),
+        "-autoexit".to_owned(),
+        "-autoload".to_owned(),
+        // Proton maps the unix root to drive 'Z:'.
+        format!("-D:Z:{}\\", data_dir.as_str().replace('/', "\\")),
+        plugin.clone(),
+    ];
+    // Cleaning runs unattended, so without the wrappers meant for playing.
+    run_with_proton(settings, &xedit, &args, &[], false)?;
+
+    let log = read(&xedit_log)
+        .map(|l| {
+            let start = usize::try_from(log_start).unwrap_or_default().min(l.len());
+            String::from_utf8_lossy(&l[start..]).into_owned()
+        })
+        .unwrap_or_default();
+    for line in log.lines() {
+        log::debug!("xEdit: {line}");
+    }
+
+    // xEdit saves the cleaned plugin over the link into our cache; move it back into the mod.
+    if let Some(target) = link_target {
+        if plugin_path.is_file() && !plugin_path.is_symlink() {
+            copy(&plugin_path, &target)?;
+            remove_file(&plugin_path)?;
+            symlink(&target, &plugin_path)?;
+            log::debug!("Moved the cleaned '{plugin}' back into '{target}'.");
+        }
+    }
+
+    log::info!(
+        "Cleaned '{plugin}': removed {} identical to master record(s) (ITM) and undeleted {} reference(s) (UDR).",
+        count_cleaned(&log, ITM_SUMMARY, ITM_RECORD),
+        count_cleaned(&log, UDR_SUMMARY, UDR_RECORD),
+    );
+    Ok(())
+}
+
+/// Read the number of cleaned records from the `summary` lines of the xEdit log;
+/// when there are none, count the lines of the individual `record`s instead.
+fn count_cleaned(log: &str, summary: &str, record: &str) -> usize {
+    let mut summaries = log
+        .lines()
+        .filter_map(|l| l.split_once(summary))
+        .map(|(_, count)| {
+            count
+                .trim_start()
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse::<usize>()
+                .unwrap_or_default()
+        })
+        .peekable();
+
+    if summaries.peek().is_some() {
+        summaries.sum()
+    } else {
+        log.lines()
+            .filter(|l| l.trim_start().starts_with(record))
+            .count()
+    }
 }
@@ -0,0 +1,426 @@
+use std::{
+    fs::{read_link, read_to_string, File},
+    io::{Read, Seek, SeekFrom},
+};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use comfy_table::{Cell, Color};
+
+use crate::{
+    deployment::deployment_targets,
+    errors::GameErrors,
+    ini::Ini,
+    installers::DATA_DIR_NAME,
+    manifest::{install_file::windows_path_issues, Manifest},
+    mods::{deployed_files, GatherModList},
+    plugin_header::PluginHeader,
+    plugin_list::{PluginList, PluginListFormat},
+    requirements::{requirements, unmet_requirements},
+    settings::{create_table, Settings},
+};
+
+use super::plugins::{installed_plugins, is_plugin, plugins_file};
+
+// Marks the fixed file info in the version resource of a windows executable.
+const FIXED_FILE_INFO_SIGNATURE: [u8; 4] = 0xFEEF_04BDu32.to_le_bytes();
+// The structures of a PE file leading to its version resource.
+const PE_HEADER_POINTER: u64 = 0x3C;
+const PE_SIGNATURE: &[u8] = b"PE\0\0";
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+const RESOURCE_DIRECTORY: u64 = 2;
+const RT_VERSION: u32 = 16;
+const SUBDIRECTORY: u32 = 0x8000_0000;
+const ARCHIVE_EXTENSION: &str = "ba2";
+const DLL_EXTENSION: &str = "dll";
+
+pub enum Outcome {
+    Pass(String),
+    Warn(String),
+    Fail(String),
+}
+
+/// Check that the game is ready to run; prints a summary, and fails when any check fails.
+pub fn run_checks(settings: &Settings) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+
+    let checks = [
+        ("Deployment", check_deployment(settings, &mod_list)?),
+        ("Masters", check_masters(settings)?),
+        ("Plugin count", check_plugin_count(settings)?),
+        ("Script extender", check_loader_version(settings)?),
+        (
+            "Archive invalidation",
+            check_archive_invalidation(settings, &mod_list)?,
+        ),
+        ("Requirements", check_requirements(&mod_list)),
+        ("Windows paths", check_windows_paths(settings, &mod_list)?),
+    ];
+
+    let failed = show_outcomes(&checks);
+    if failed > 0 {
+        Err(GameErrors::ChecksFailed(failed).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Show the outcome of each check in a table; returns the number of failed checks.
+pub fn show_outcomes(checks: &[(&str, Outcome)]) -> usize {
+    let mut table = create_table(vec!["Check", "Result", "Details"]);
+    let mut failed = 0;
+    for (name, outcome) in checks {
+        let (result, color, details) = match outcome {
+            Outcome::Pass(d) => ("Ok", Color::Green, d),
+            Outcome::Warn(d) => ("Warning", Color::Yellow, d),
+            Outcome::Fail(d) => {
+                failed += 1;
+                ("Failed", Color::Red, d)
+            }
+        };
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(result).fg(color),
+            Cell::new(details),
+        ]);
+    }
+    log::info!("{table}");
+    failed
+}
+
+/// Every file of the enabled mods should be linked into the game directory.
+fn check_deployment(settings: &Settings, mod_list: &[Manifest]) -> Result<Outcome> {
+    let files = deployment_targets(mod_list, settings.cache_dir(), settings.game_dir())?;
+    let broken = files
+        .iter()
+        .filter(|(_, destination, origin)| {
+            read_link(settings.game_dir().join(destination))
+                .map_or(true, |link| link != origin.as_std_path())
+        })
+        .map(|(name, _, _)| name.as_str())
+        .collect::<Vec<_>>();
+
+    Ok(if broken.is_empty() {
+        Outcome::Pass(format!("{} file(s) deployed", files.len()))
+    } else {
+        let mut mods = broken.clone();
+        mods.sort_unstable();
+        mods.dedup();
+        Outcome::Fail(format!(
+            "{} file(s) not deployed, of: {}; re-enable these mods",
+            broken.len(),
+            mods.join(", ")
+        ))
+    })
+}
+
+/// Active plugins, starting with those of the game itself, which are always loaded.
+fn active_plugins(settings: &Settings) -> Result<Vec<String>> {
+    let mut active = settings
+        .game()
+        .base_plugins()
+        .iter()
+        .map(|p| (*p).to_owned())
+        .collect::<Vec<_>>();
+
+    if let Ok(contents) = read_to_string(plugins_file(settings)?) {
+        let list = PluginList::parse(&contents, PluginListFormat::Plugins);
+        active.extend(
+            list.plugins()
+                .filter(|(_, active)| *active)
+                .map(|(p, _)| p.to_owned()),
+        );
+    }
+    Ok(active)
+}
+
+/// Headers of the active plugins which are installed.
+fn active_headers(settings: &Settings) -> Result<Vec<(String, PluginHeader)>> {
+    let data_dir = settings.game_dir().join(DATA_DIR_NAME);
+    let installed = installed_plugins(&data_dir)?;
+
+    let mut headers = Vec::new();
+    for plugin in active_plugins(settings)? {
+        if let Some(file) = installed.iter().find(|i| i.eq_ignore_ascii_case(&plugin)) {
+            headers.push((plugin, PluginHeader::read(&data_dir.join(file))?));
+        }
+    }
+    Ok(headers)
+}
+
+fn check_masters(settings: &Settings) -> Result<Outcome> {
+    let headers = active_headers(settings)?;
+
+    let mut missing = Vec::new();
+    for (plugin, header) in &headers {
+        for master in header.masters() {
+            if !headers.iter().any(|(p, _)| p.eq_ignore_ascii_case(master)) {
+                missing.push(format!("{plugin} needs {master}"));
+            }
+        }
+    }
+
+    Ok(if missing.is_empty() {
+        Outcome::Pass(format!(
+            "{} active plugin(s) have their masters",
+            headers.len()
+        ))
+    } else {
+        Outcome::Fail(missing.join("\n"))
+    })
+}
+
+fn check_plugin_count(settings: &Settings) -> Result<Outcome> {
+    let headers = active_headers(settings)?;
+    let light = headers.iter().filter(|(_, h)| h.is_light()).count();
+    let full = headers.len() - light;
+    let (max_full, max_light) = settings.game().plugin_limits();
+
+    let details = format!("{full}/{max_full} full and {light}/{max_light} light plugin(s)");
+    Ok(if full > max_full || light > max_light {
+        Outcome::Fail(details)
+    } else {
+        Outcome::Pass(details)
+    })
+}
+
+/// The script extender only works with the game version it was built for.
+fn check_loader_version(settings: &Settings) -> Result<Outcome> {
+    let game_dir = settings.game_dir();
+    if !game_dir.join(settings.game().loader_name()).exists() {
+        return Ok(Outcome::Pass("not installed".to_owned()));
+    }
+
+    let prefix = settings.game().loader_dll_prefix();
+    let mut loader_versions = Vec::new();
+    for entry in game_dir.read_dir_utf8()? {
+        let name = entry?.file_name().to_lowercase();
+        if let Some(version) = name
+            .strip_prefix(prefix)
+            .and_then(|n| n.strip_suffix(DLL_EXTENSION))
+            .and_then(|n| n.strip_suffix('.'))
+        {
+            loader_versions.push(version.replace('_', "."));
+        }
+    }
+    if loader_versions.is_empty() {
+        return Ok(Outcome::Fail(format!(
+            "'{}' is installed, but its dll is missing",
+            settings.game().loader_name()
+        )));
+    }
+
+    let Some(game_version) = exe_version(&game_dir.join(settings.game().exe_name()))? else {
+        return Ok(Outcome::Warn("unable to read the game version".to_owned()));
+    };
+    Ok(
+        if loader_versions
+            .iter()
+            .any(|v| game_version.starts_with(v.as_str()))
+        {
+            Outcome::Pass(format!("built for game version {game_version}"))
+        } else {
+            Outcome::Fail(format!(
+                "built for game version {}, but the game is version {game_version}",
+                loader_versions.join(", ")
+            ))
+        },
+    )
+}
+
+/// Read the file version from the version resource of a windows executable.
+///
+/// Only the headers, the resource directory and the version resource are read; the executable
+/// of the game is large.
+fn exe_version(exe: &Utf8Path) -> Result<Option<String>> {
+    let mut file = File::open(exe)?;
+    let Some(resource) = version_resource(&mut file) else {
+        return Ok(None);
+    };
+    let Some(start) = resource
+        .windows(FIXED_FILE_INFO_SIGNATURE.len())
+        .position(|w| w == FIXED_FILE_INFO_SIGNATURE)
+    else {
+        return Ok(None);
+    };
+
+    // The signature is followed by the structure version, and the most and least significant
+    // halves of the file version.
+    let Some(version) = resource.get(start + 8..start + 16) else {
+        return Ok(None);
+    };
+    let ms = u32::from_le_bytes(version[..4].try_into()?);
+    let ls = u32::from_le_bytes(version[4..].try_into()?);
+    Ok(Some(format!(
+        "{}.{}.{}.{}",
+        ms >> 16,
+        ms & 0xFFFF,
+        ls >> 16,
+        ls & 0xFFFF
+    )))
+}
+
+/// The version resource of the PE file `file`; `None` when it is not a PE file or has none.
+fn version_resource(file: &mut File) -> Option<Vec<u8>> {
+    let pe_header = u64::from(read_u32(file, PE_HEADER_POINTER)?);
+    if read_bytes(file, pe_header, PE_SIGNATURE.len())? != PE_SIGNATURE {
+        return None;
+    }
+    let coff_header = pe_header + PE_SIGNATURE.len() as u64;
+    let sections = read_u16(file, coff_header + 2)?;
+    let optional_header = coff_header + 20;
+    let optional_header_len = read_u16(file, coff_header + 16)?;
+    let data_directories = match read_u16(file, optional_header)? {
+        PE32_MAGIC => optional_header + 96,
+        PE32_PLUS_MAGIC => optional_header + 112,
+        _ => return None,
+    };
+    let resource_rva = read_u32(file, data_directories + RESOURCE_DIRECTORY * 8)?;
+
+    // Resources are found by their address in memory; the sections map those to the file.
+    let section_table = optional_header + u64::from(optional_header_len);
+    let to_offset = |file: &mut File, rva: u32| {
+        (0..u64::from(sections)).find_map(|idx| {
+            let section = section_table + idx * 40;
+            let size = read_u32(file, section + 8)?;
+            let address = read_u32(file, section + 12)?;
+            let offset = read_u32(file, section + 20)?;
+            (address..address.saturating_add(size))
+                .contains(&rva)
+                .then(|| u64::from(rva - address + offset))
+        })
+    };
+    let resources = to_offset(file, resource_rva)?;
+
+    // The version is the first resource of the version type, in whatever name and language.
+    let mut directory = resources;
+    for id in [Some(RT_VERSION), None, None] {
+        let named = u64::from(read_u16(file, directory + 12)?);
+        let ids = u64::from(read_u16(file, directory + 14)?);
+        let entry = match id {
+            Some(id) => (named..named + ids)
+                .map(|idx| directory + 16 + idx * 8)
+                .find(|&entry| read_u32(file, entry) == Some(id))?,
+            None if named + ids > 0 => directory + 16,
+            None => return None,
+        };
+        let offset = read_u32(file, entry + 4)?;
+        directory = resources + u64::from(offset & !SUBDIRECTORY);
+    }
+    let data_rva = read_u32(file, directory)?;
+    let len = read_u32(file, directory + 4)?;
+    let data = to_offset(file, data_rva)?;
+    read_bytes(file, data, usize::try_from(len).ok()?)
+}
+
+fn read_bytes(file: &mut File, offset: u64, len: usize) -> Option<Vec<u8>> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut bytes = Vec::new();
+    file.take(len as u64).read_to_end(&mut bytes).ok()?;
+    (bytes.len() == len).then_some(bytes)
+}
+
+fn read_u16(file: &mut File, offset: u64) -> Option<u16> {
+    let bytes = read_bytes(file, offset, 2)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u32(file: &mut File, offset: u64) -> Option<u32> {
+    let bytes = read_bytes(file, offset, 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// The 'needs:' tags of the enabled mods should be met by other enabled mods; only a warning,
+/// as the tags are written by the user.
+fn check_requirements(mod_list: &[Manifest]) -> Outcome {
+    let declared = mod_list
+        .iter()
+        .filter(|md| md.is_enabled())
+        .map(|md| requirements(md).count())
+        .sum::<usize>();
+    let unmet = unmet_requirements(mod_list);
+
+    if unmet.is_empty() {
+        Outcome::Pass(format!("{declared} requirement(s) met"))
+    } else {
+        Outcome::Warn(
+            unmet
+                .iter()
+                .map(|u| {
+                    format!(
+                        "'{}' needs '{}', which no enabled mod provides",
+                        u.mod_name, u.requirement
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+/// Proton cannot open files whose path windows does not allow; the game silently skips them.
+fn check_windows_paths(settings: &Settings, mod_list: &[Manifest]) -> Result<Outcome> {
+    let files = deployed_files(mod_list)?;
+    let mut mods = files
+        .iter()
+        .filter(|(_, f)| !windows_path_issues(settings.game_dir(), f.destination()).is_empty())
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    let invalid = mods.len();
+    mods.sort_unstable();
+    mods.dedup();
+
+    Ok(if invalid == 0 {
+        Outcome::Pass(format!("{} path(s) allowed by windows", files.len()))
+    } else {
+        Outcome::Warn(format!(
+            "{invalid} file(s) the game cannot load, of: {}; see 'cache audit-windows-paths'",
+            mods.join(", ")
+        ))
+    })
+}
+
+/// Loose files are only loaded when the game is told not to prefer its archives.
+fn check_archive_invalidation(settings: &Settings, mod_list: &[Manifest]) -> Result<Outcome> {
+    let loose_files = deployed_files(mod_list)?
+        .iter()
+        .filter(|(_, f)| {
+            let dest = Utf8Path::new(f.destination());
+            dest.starts_with(DATA_DIR_NAME)
+                && !is_plugin(dest)
+                && dest.extension() != Some(ARCHIVE_EXTENSION)
+        })
+        .count();
+    if loose_files == 0 {
+        return Ok(Outcome::Pass("no loose files deployed".to_owned()));
+    }
+
+    let ini_file = settings.windows_user_dir().map(|user_dir| {
+        user_dir
+            .join(settings.game().my_game_dir())
+            .join(settings.game().custom_ini())
+    });
+    let ini = ini_file
+        .and_then(|f| read_to_string(f).ok())
+        .map(|c| Ini::parse(&c))
+        .unwrap_or_default();
+
+    let missing = settings
+        .game()
+        .archive_invalidation()
+        .iter()
+        .filter(|(section, key, value)| ini.get(section, key) != Some(*value))
+        .map(|(section, key, value)| format!("[{section}] {key}={value}"))
+        .collect::<Vec<_>>();
+
+    Ok(if missing.is_empty() {
+        Outcome::Pass(format!("{loose_files} loose file(s) will be loaded"))
+    } else {
+        Outcome::Warn(format!(
+            "{loose_files} loose file(s) may be ignored; add to {}:\n{}",
+            settings.game().custom_ini(),
+            missing.join("\n")
+        ))
+    })
+}
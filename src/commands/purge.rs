@@ -2,8 +2,9 @@ use anyhow::Result;
 use clap::Parser;
 
 use crate::{
-    mods::{GatherModList, ModList},
+    mods::{purge_deployment, GatherModList, ModList},
     settings::Settings,
+    ui::confirm,
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -12,20 +13,55 @@ pub enum PurgeCmd {
     Config,
     /// Remove cache directory, but keep the config files
     Cache,
+    /// Remove all of starmod's links from the game directory and restore the original files;
+    /// The cache, manifests and config are kept.
+    Deployment,
 }
 impl PurgeCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Config | Self::Cache | Self::Deployment => false,
+        }
+    }
     pub fn execute(self, settings: &Settings) -> Result<()> {
         match self {
             Self::Config => {
+                let affected = vec![
+                    settings.config_file().to_string(),
+                    settings.cache_dir().to_string(),
+                ];
+                if !confirm("remove all of starmod's configuration and cache", &affected)? {
+                    return Ok(());
+                }
                 let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
                 mod_list.disable(settings.cache_dir(), settings.game_dir())?;
                 settings.purge_config()
             }
             Self::Cache => {
+                if !confirm(
+                    "remove the cache and all installed mods",
+                    &[settings.cache_dir().to_string()],
+                )? {
+                    return Ok(());
+                }
                 let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
                 mod_list.disable(settings.cache_dir(), settings.game_dir())?;
                 settings.purge_cache()
             }
+            Self::Deployment => {
+                if !confirm(
+                    "remove all deployed mod files from the game directory",
+                    &[settings.game_dir().to_string()],
+                )? {
+                    return Ok(());
+                }
+                let (removed, restored) =
+                    purge_deployment(settings.cache_dir(), settings.game_dir())?;
+                log::info!("Removed {removed} link(s) and restored {restored} backup(s).");
+                log::info!("Mods keep their enabled state; re-enabling a mod deploys it again.");
+                Ok(())
+            }
         }
     }
 }
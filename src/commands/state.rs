@@ -0,0 +1,131 @@
+use std::{
+    fs::copy,
+    process::{Command, Stdio},
+};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use clap::Parser;
+
+use crate::{
+    commands::plugins::plugins_file,
+    errors::{GameErrors, SettingErrors},
+    settings::{ensure_writable, Settings},
+};
+
+const GIT_PROGRAM: &str = "git";
+const GIT_DIR: &str = ".git";
+const DEFAULT_MESSAGE: &str = "Update the mod-list";
+
+/// Keep the history of the mod-list with git, in the 'state-dir'; defaults to showing it.
+#[derive(Debug, Clone, Parser)]
+pub enum StateCmd {
+    /// Show the most recent commits of the state directory.
+    #[clap(visible_alias = "l")]
+    Log {
+        /// Number of commits to show.
+        #[arg(short, long, default_value_t = 20)]
+        count: usize,
+    },
+    /// Copy the load order into the state directory, and commit every change to git;
+    /// the repository is created when there is none yet.
+    #[clap(visible_alias = "c")]
+    Commit {
+        /// The commit message.
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+}
+impl Default for StateCmd {
+    fn default() -> Self {
+        Self::Log { count: 20 }
+    }
+}
+impl StateCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::Log { .. } => true,
+            Self::Commit { .. } => false,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        let state_dir = settings
+            .state_dir()
+            .ok_or_else(|| SettingErrors::NoStateDirConfigured(settings.cmd_name().to_owned()))?;
+
+        match self {
+            Self::Log { count } => {
+                if !state_dir.join(GIT_DIR).exists() {
+                    log::info!("Nothing has been committed in '{state_dir}' yet.");
+                    return Ok(());
+                }
+                git(state_dir, &["log", "--oneline", &format!("-n{count}")])
+            }
+            Self::Commit { message } => commit(settings, state_dir, message.as_deref()),
+        }
+    }
+}
+
+fn commit(settings: &Settings, state_dir: &Utf8Path, message: Option<&str>) -> Result<()> {
+    ensure_writable("commit the state directory")?;
+
+    // The load order is written by the game and LOOT, so it is copied at each commit.
+    let load_order = [plugins_file(settings).ok(), Some(settings.loot_userlist())];
+    for file in load_order.into_iter().flatten().filter(|f| f.exists()) {
+        let Some(file_name) = file.file_name() else {
+            continue;
+        };
+        log::debug!("Copying '{file}' into '{state_dir}'.");
+        copy(&file, state_dir.join(file_name))?;
+    }
+
+    if !state_dir.join(GIT_DIR).exists() {
+        git(state_dir, &["init", "--quiet"])?;
+    }
+    git(state_dir, &["add", "--all"])?;
+
+    let unchanged = Command::new(GIT_PROGRAM)
+        .arg("-C")
+        .arg(state_dir)
+        .args(["diff", "--cached", "--quiet"])
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|_| GameErrors::ProcessFailed(GIT_PROGRAM.to_owned(), "not found".to_owned()))?
+        .success();
+    if unchanged {
+        log::info!("Nothing changed since the last commit.");
+        return Ok(());
+    }
+
+    git(
+        state_dir,
+        &[
+            "commit",
+            "--quiet",
+            "-m",
+            message.unwrap_or(DEFAULT_MESSAGE),
+        ],
+    )?;
+    log::info!("Committed the changes in '{state_dir}'.");
+    Ok(())
+}
+
+/// Run git with `args` in `dir`.
+fn git(dir: &Utf8Path, args: &[&str]) -> Result<()> {
+    log::debug!("Running '{GIT_PROGRAM} -C {dir} {}'", args.join(" "));
+    let status = Command::new(GIT_PROGRAM)
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .map_err(|_| GameErrors::ProcessFailed(GIT_PROGRAM.to_owned(), "not found".to_owned()))?;
+    if !status.success() {
+        return Err(GameErrors::ProcessFailed(
+            format!("{GIT_PROGRAM} {}", args.join(" ")),
+            status.to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
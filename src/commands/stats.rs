@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use camino::Utf8Path;
+use comfy_table::Cell;
+
+use crate::{
+    conflict::conflict_list_by_file,
+    history::{read_history, Change},
+    mods::GatherModList,
+    settings::{create_table, Settings},
+    utils::human_size,
+};
+
+use super::plugins::is_plugin;
+
+pub fn show_stats(settings: &Settings) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+
+    let mut kinds = BTreeMap::new();
+    for md in &mod_list {
+        *kinds.entry(md.kind().to_string()).or_insert(0_usize) += 1;
+    }
+
+    let enabled = mod_list.iter().filter(|md| md.is_enabled()).count();
+    let tagged = mod_list.iter().filter(|md| !md.tags().is_empty()).count();
+
+    let mut plugins = 0;
+    for md in mod_list.iter().filter(|md| md.is_enabled()) {
+        plugins += md
+            .dest_files()?
+            .iter()
+            .filter(|f| is_plugin(Utf8Path::new(f)))
+            .count();
+    }
+
+    let conflicts = conflict_list_by_file(&mod_list)?.len();
+
+    let mut disk_usage = 0;
+    for md in &mod_list {
+        disk_usage += md.size()?;
+    }
+
+    // Deployments are the operations which changed which mods are enabled, or their order.
+    let last_deployment = read_history(settings)?
+        .into_iter()
+        .rev()
+        .find(|entry| {
+            entry.changes().iter().any(|c| {
+                matches!(
+                    c,
+                    Change::Enabled { .. }
+                        | Change::Disabled { .. }
+                        | Change::Pinned { .. }
+                        | Change::Priority { .. }
+                )
+            })
+        })
+        .map_or_else(
+            || "<Unknown>".to_owned(),
+            |entry| entry.timestamp().format("%Y-%m-%d %H:%M:%S").to_string(),
+        );
+
+    let mut table = create_table(vec!["Statistic", "Value"]);
+    table.add_row(vec![Cell::new("Mods"), Cell::new(mod_list.len())]);
+    for (kind, count) in kinds {
+        table.add_row(vec![Cell::new(format!("  {kind}")), Cell::new(count)]);
+    }
+    table
+        .add_row(vec![Cell::new("Enabled"), Cell::new(enabled)])
+        .add_row(vec![
+            Cell::new("Disabled"),
+            Cell::new(mod_list.len() - enabled),
+        ])
+        .add_row(vec![Cell::new("Tagged"), Cell::new(tagged)])
+        .add_row(vec![
+            Cell::new("Untagged"),
+            Cell::new(mod_list.len() - tagged),
+        ])
+        .add_row(vec![Cell::new("Enabled Plugins"), Cell::new(plugins)])
+        .add_row(vec![Cell::new("Conflicting Files"), Cell::new(conflicts)])
+        .add_row(vec![
+            Cell::new("Disk Usage"),
+            Cell::new(human_size(disk_usage)),
+        ])
+        .add_row(vec![
+            Cell::new("Last Deployment"),
+            Cell::new(last_deployment),
+        ]);
+
+    log::info!("");
+    log::info!("{table}");
+    Ok(())
+}
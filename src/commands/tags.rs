@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use clap::Parser;
+use comfy_table::Cell;
+
+use crate::{
+    errors::ModErrors,
+    filter::{matches_all, ModFilter},
+    mods::GatherModList,
+    settings::{create_table, Settings},
+    ui::page,
+};
+
+/// Commands related to tags over all mods; defaults to listing the tags.
+#[derive(Debug, Clone, Parser, Default)]
+#[clap(
+    after_help = "Filters are written as '<key>:<value>', with key one of name, version, tag, kind, state, nexus or priority; prefix with '!' to invert."
+)]
+pub enum TagCmd {
+    /// List all tags, with the number of mods which have them.
+    #[default]
+    #[clap(visible_alias = "l")]
+    List,
+    /// Rename tag <old> to <new> in all mods.
+    Rename { old: String, new: String },
+    /// Remove tag <tag> from all mods.
+    Delete { tag: String },
+    /// Add tag <tag> to all mods which match every filter.
+    Apply {
+        tag: String,
+        /// Only tag mods matching this filter, like 'kind:data' or 'name:armor'.
+        #[arg(short, long = "filter", required = true)]
+        filters: Vec<ModFilter>,
+    },
+}
+impl TagCmd {
+    /// Whether the command changes nothing; see `Subcommands::is_read_only`.
+    pub const fn is_read_only(&self) -> bool {
+        match self {
+            Self::List => true,
+            Self::Rename { .. } | Self::Delete { .. } | Self::Apply { .. } => false,
+        }
+    }
+    pub fn execute(self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::List => list_tags(settings),
+            Self::Rename { old, new } => {
+                let old = old.to_lowercase();
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let mut renamed = 0;
+                for md in mod_list.iter_mut().filter(|m| m.tags().contains(&old)) {
+                    md.remove_tag(&old)?;
+                    md.add_tag(&new)?;
+                    renamed += 1;
+                }
+                if renamed == 0 {
+                    return Err(ModErrors::TagNotFound("any mod".to_owned(), old).into());
+                }
+                log::info!("Renamed tag '{old}' to '{new}' in {renamed} mod(s).");
+                list_tags(settings)
+            }
+            Self::Delete { tag } => {
+                let tag = tag.to_lowercase();
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let mut deleted = 0;
+                for md in mod_list.iter_mut().filter(|m| m.tags().contains(&tag)) {
+                    md.remove_tag(&tag)?;
+                    deleted += 1;
+                }
+                if deleted == 0 {
+                    return Err(ModErrors::TagNotFound("any mod".to_owned(), tag).into());
+                }
+                log::info!("Removed tag '{tag}' from {deleted} mod(s).");
+                list_tags(settings)
+            }
+            Self::Apply { tag, filters } => {
+                let mut mod_list = Vec::gather_mods(settings.cache_dir())?;
+                let mut tagged = 0;
+                for md in mod_list.iter_mut().filter(|m| matches_all(&filters, m)) {
+                    if md.add_tag(&tag)? {
+                        log::debug!("Added tag '{tag}' to '{}'", md.name());
+                        tagged += 1;
+                    }
+                }
+                log::info!(
+                    "Added tag '{tag}' to {tagged} mod(s) matching '{}'.",
+                    filters
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+fn list_tags(settings: &Settings) -> Result<()> {
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+
+    let mut tags = BTreeMap::<&str, (usize, usize)>::new();
+    for md in &mod_list {
+        for tag in md.tags() {
+            let (count, enabled) = tags.entry(tag.as_str()).or_default();
+            *count += 1;
+            if md.is_enabled() {
+                *enabled += 1;
+            }
+        }
+    }
+
+    let mut table = create_table(vec!["Tag", "Mods", "Enabled"]);
+    for (tag, (count, enabled)) in tags {
+        table.add_row(vec![
+            Cell::new(tag),
+            Cell::new(count.to_string()),
+            Cell::new(enabled.to_string()),
+        ]);
+    }
+    table.add_row_if(|idx, _row| idx.eq(&0), vec![Cell::new("No tags found.")]);
+
+    page(&table.to_string());
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
 use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
 
-use crate::manifest::Manifest;
+use crate::{hash::HashCache, manifest::Manifest};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Conflicts {
@@ -11,7 +12,6 @@ pub struct Conflicts {
     winning_over_mods: HashSet<String>,
 }
 impl Conflicts {
-    #[allow(unused)]
     pub fn conflict_files(&self) -> &[String] {
         &self.conflict_files
     }
@@ -23,6 +23,11 @@ impl Conflicts {
     }
 }
 
+/// The mods which provide each destination, by destination, in the order they are deployed.
+///
+/// # Errors
+///
+/// When the files of a mod cannot be listed.
 pub fn conflict_list_by_file(mods: &[Manifest]) -> Result<HashMap<String, Vec<String>>> {
     log::trace!("Building Conflict List by File");
     let mut all_files = HashMap::new();
@@ -54,6 +59,11 @@ pub fn conflict_list_by_file(mods: &[Manifest]) -> Result<HashMap<String, Vec<St
     Ok(all_files)
 }
 
+/// The files each mod wins and loses to the other mods, by mod.
+///
+/// # Errors
+///
+/// When the files of a mod cannot be listed.
 pub fn conflict_list_by_mod(mods: &[Manifest]) -> Result<HashMap<String, Conflicts>> {
     log::trace!("Building Conflict List");
 
@@ -97,3 +107,42 @@ pub fn conflict_list_by_mod(mods: &[Manifest]) -> Result<HashMap<String, Conflic
     log::trace!("Finished Building Conflict List");
     Ok(mods_conflicts)
 }
+
+/// The conflicting files of `conflict_list` which have the same contents in every mod;
+/// which mod wins makes no difference for those.
+///
+/// # Errors
+///
+/// When a conflicting file cannot be hashed.
+pub fn identical_conflicts(
+    cache_dir: &Utf8Path,
+    mods: &[Manifest],
+    conflict_list: &HashMap<String, Vec<String>>,
+) -> Result<HashSet<String>> {
+    log::trace!("Building List of Identical Conflicts");
+    let mut sources = HashMap::<&str, Vec<Utf8PathBuf>>::new();
+    for m in mods.iter().filter(|m| m.is_enabled()) {
+        for isf in m.files()? {
+            if let Some((dest, _)) = conflict_list.get_key_value(isf.destination()) {
+                sources
+                    .entry(dest.as_str())
+                    .or_default()
+                    .push(cache_dir.join(m.manifest_dir()).join(isf.source()));
+            }
+        }
+    }
+
+    let mut hash_cache = HashCache::load(cache_dir);
+    let hashes = hash_cache.hash_all(&sources.values().flatten().cloned().collect::<Vec<_>>())?;
+    hash_cache.save()?;
+
+    Ok(sources
+        .into_iter()
+        .filter(|(_, paths)| {
+            paths
+                .windows(2)
+                .all(|pair| hashes.get(&pair[0]) == hashes.get(&pair[1]))
+        })
+        .map(|(dest, _)| dest.to_owned())
+        .collect())
+}
@@ -1,8 +1,9 @@
 use std::{
     fmt::Display,
     fs::{self, remove_dir_all, DirBuilder, File, OpenOptions, Permissions},
+    io::Read,
     os::unix::{fs::DirBuilderExt, prelude::PermissionsExt},
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 use anyhow::{Context, Result};
@@ -12,7 +13,26 @@ use thiserror::Error;
 pub enum DecompressError {
     #[error("the file `{0}` is in an unsuported format")]
     Unsupported(PathBuf),
+    #[error("the archive entry `{0}` would be extracted outside of the destination")]
+    UnsafePath(PathBuf),
 }
+/// Where archive entry `entry` is extracted to within `destination_path`; entries with an absolute
+/// path, or with more '..' components than directories, would end up elsewhere and are refused.
+fn enclosed_path(destination_path: &Path, entry: &Path) -> Result<PathBuf, DecompressError> {
+    let unsafe_path = || DecompressError::UnsafePath(entry.to_path_buf());
+
+    let mut depth = 0_usize;
+    for component in entry.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => return Err(unsafe_path()),
+            Component::ParentDir => depth = depth.checked_sub(1).ok_or_else(unsafe_path)?,
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+        }
+    }
+    Ok(destination_path.join(entry))
+}
+
 fn path_result(path: &Path) -> String {
     let spath = path.to_str();
     spath.map_or_else(|| String::from("path missing!"), String::from)
@@ -27,6 +47,11 @@ pub enum SupportedArchives {
     Rar,
 }
 impl SupportedArchives {
+    /// The format of the archive at `path`, by its extension.
+    ///
+    /// # Errors
+    ///
+    /// When the extension is not one of a supported archive format.
     pub fn from_path(path: &Path) -> Result<Self> {
         let path_str = path.as_os_str().to_string_lossy();
 
@@ -44,15 +69,46 @@ impl SupportedArchives {
             Err(DecompressError::Unsupported(path.to_path_buf()))?
         }
     }
-    pub fn decompress(self, from_path: &Path, destination_path: &Path) -> Result<()> {
+    /// Extract the archive at `from_path` into `destination_path`; `password` is used by 7z and rar
+    /// archives, other formats ignore it.
+    ///
+    /// # Errors
+    ///
+    /// When the archive cannot be read, or its files cannot be written.
+    pub fn decompress(
+        self,
+        from_path: &Path,
+        destination_path: &Path,
+        password: Option<&str>,
+    ) -> Result<()> {
+        if password.is_some() && !self.supports_password() {
+            log::debug!("{self} archives have no password; ignoring it.");
+        }
         match self {
-            Self::SevenZip => decompress_7z(from_path, destination_path),
+            Self::SevenZip => decompress_7z(from_path, destination_path, password),
             Self::Zip => decompress_zip(from_path, destination_path).or_else(|e| {
                 decompress_zip_with_permission_override(from_path, destination_path).or(Err(e))
             }),
             Self::TarGz => decompress_tar_gz(from_path, destination_path),
             Self::TarXz => decompress_tar_xz(from_path, destination_path),
-            Self::Rar => decompress_rar(from_path, destination_path),
+            Self::Rar => decompress_rar(from_path, destination_path, password),
+        }
+    }
+    pub const fn supports_password(self) -> bool {
+        matches!(self, Self::SevenZip | Self::Rar)
+    }
+    /// Write `files`, given as (name within the archive, source path) pairs, to a new archive.
+    ///
+    /// # Errors
+    ///
+    /// When a file cannot be read, or the archive cannot be written.
+    pub fn compress(self, files: &[(String, PathBuf)], destination_path: &Path) -> Result<()> {
+        match self {
+            Self::SevenZip => compress_7z(files, destination_path),
+            Self::Zip => compress_zip(files, destination_path),
+            Self::TarGz => compress_tar_gz(files, destination_path),
+            Self::TarXz => compress_tar_xz(files, destination_path),
+            Self::Rar => Err(DecompressError::Unsupported(destination_path.to_path_buf()))?,
         }
     }
 }
@@ -76,6 +132,7 @@ fn decompress_tar_gz(from_path: &Path, destination_path: &Path) -> Result<()> {
     let file = File::open(from_path)
         .with_context(|| format!("Failed to open file from Path: {}", path_result(from_path),))?;
 
+    // The tar crate already skips entries which would end up outside of the destination.
     let mut archive = Archive::new(GzDecoder::new(file));
 
     archive.unpack(destination_path).with_context(|| {
@@ -105,15 +162,38 @@ fn decompress_tar_xz(from_path: &Path, destination_path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn decompress_7z(from_path: &Path, destination_path: &Path) -> Result<()> {
-    use sevenz_rust::decompress_file;
-
-    decompress_file(from_path, destination_path).with_context(|| {
-        format!(
-            "Failed to unpack into destination : {}",
-            path_result(destination_path)
+fn decompress_7z(from_path: &Path, destination_path: &Path, password: Option<&str>) -> Result<()> {
+    use sevenz_rust::{
+        decompress_file_with_extract_fn, decompress_with_extract_fn_and_password,
+        default_entry_extract_fn, Error, SevenZArchiveEntry,
+    };
+
+    let extract = |entry: &SevenZArchiveEntry, reader: &mut dyn Read, _destination: &PathBuf| {
+        let destination = enclosed_path(destination_path, Path::new(entry.name()))
+            .map_err(|e| Error::other(e.to_string()))?;
+        default_entry_extract_fn(entry, reader, &destination)
+    };
+
+    password
+        .map_or_else(
+            || decompress_file_with_extract_fn(from_path, destination_path, extract),
+            |password| {
+                File::open(from_path).map_err(Error::from).and_then(|file| {
+                    decompress_with_extract_fn_and_password(
+                        file,
+                        destination_path,
+                        password.into(),
+                        extract,
+                    )
+                })
+            },
         )
-    })?;
+        .with_context(|| {
+            format!(
+                "Failed to unpack into destination : {}",
+                path_result(destination_path)
+            )
+        })?;
 
     Ok(())
 }
@@ -135,8 +215,7 @@ fn decompress_zip_with_permission_override(
     for idx in 0..zip.len() {
         let mut file = zip.by_index(idx)?;
 
-        file.enclosed_name();
-        let destination = destination_path.join(file.enclosed_name().unwrap());
+        let destination = enclosed_path(destination_path, Path::new(file.name()))?;
         log::trace!("Extracting: {}", destination.display());
 
         // VERY crude way of checking if the destination is a file..
@@ -172,29 +251,34 @@ fn decompress_zip(from_path: &Path, destination_path: &Path) -> Result<()> {
     let file = File::open(from_path)
         .with_context(|| format!("Failed to open file from Path: {}", path_result(from_path),))?;
 
-    ZipArchive::new(file)?
-        .extract(destination_path)
-        .with_context(|| {
-            format!(
-                "Failed to unpack into destination : {}",
-                path_result(destination_path)
-            )
-        })?;
+    let mut zip = ZipArchive::new(file)?;
+    for name in zip.file_names() {
+        enclosed_path(destination_path, Path::new(name))?;
+    }
+    zip.extract(destination_path).with_context(|| {
+        format!(
+            "Failed to unpack into destination : {}",
+            path_result(destination_path)
+        )
+    })?;
 
     Ok(())
 }
 
-fn decompress_rar(from_path: &Path, destination_path: &Path) -> Result<()> {
+fn decompress_rar(from_path: &Path, destination_path: &Path, password: Option<&str>) -> Result<()> {
     use unrar::Archive;
 
-    let mut archive = Archive::new(from_path)
+    let archive = password.map_or_else(
+        || Archive::new(from_path),
+        |password| Archive::with_password(from_path, password.as_bytes()),
+    );
+    let mut archive = archive
         .open_for_processing()
         .with_context(|| format!("Failed to open archive: {}", path_result(destination_path)))?;
 
     while let Some(header) = archive.read_header()? {
         archive = if header.entry().is_file() {
-            let mut file_path = destination_path.to_path_buf();
-            file_path.push(&header.entry().filename);
+            let file_path = enclosed_path(destination_path, &header.entry().filename)?;
 
             DirBuilder::new()
                 .recursive(true)
@@ -213,3 +297,84 @@ fn decompress_rar(from_path: &Path, destination_path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+fn compress_tar_gz(files: &[(String, PathBuf)], destination_path: &Path) -> Result<()> {
+    use flate2::{write::GzEncoder, Compression};
+    use tar::Builder;
+
+    let file = File::create(destination_path).with_context(|| {
+        format!(
+            "Failed to create archive: {}",
+            path_result(destination_path)
+        )
+    })?;
+
+    let mut archive = Builder::new(GzEncoder::new(file, Compression::default()));
+    for (name, source) in files {
+        archive.append_path_with_name(source, name)?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn compress_tar_xz(files: &[(String, PathBuf)], destination_path: &Path) -> Result<()> {
+    use lzma::writer::LzmaWriter;
+    use tar::Builder;
+
+    const PRESET: u32 = 6;
+
+    let file = File::create(destination_path).with_context(|| {
+        format!(
+            "Failed to create archive: {}",
+            path_result(destination_path)
+        )
+    })?;
+
+    let mut archive = Builder::new(LzmaWriter::new_compressor(file, PRESET)?);
+    for (name, source) in files {
+        archive.append_path_with_name(source, name)?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn compress_7z(files: &[(String, PathBuf)], destination_path: &Path) -> Result<()> {
+    use sevenz_rust::{SevenZArchiveEntry, SevenZWriter};
+
+    let mut archive = SevenZWriter::create(destination_path).with_context(|| {
+        format!(
+            "Failed to create archive: {}",
+            path_result(destination_path)
+        )
+    })?;
+    for (name, source) in files {
+        archive.push_archive_entry(
+            SevenZArchiveEntry::from_path(source, name.clone()),
+            Some(File::open(source)?),
+        )?;
+    }
+    archive.finish()?;
+    Ok(())
+}
+
+fn compress_zip(files: &[(String, PathBuf)], destination_path: &Path) -> Result<()> {
+    use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    let file = File::create(destination_path).with_context(|| {
+        format!(
+            "Failed to create archive: {}",
+            path_result(destination_path)
+        )
+    })?;
+
+    let mut zip = ZipWriter::new(file);
+    for (name, source) in files {
+        let options = FileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .unix_permissions(fs::metadata(source)?.permissions().mode());
+        zip.start_file(name, options)?;
+        std::io::copy(&mut File::open(source)?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
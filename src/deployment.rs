@@ -0,0 +1,345 @@
+//! Bringing the links in the game directory in line with the enabled mods.
+//!
+//! Instead of removing and recreating every link, the links the enabled mods should have are
+//! compared with the links which are actually there; only the difference is changed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs::{read_link, remove_file},
+    os::unix::fs::symlink,
+    sync::Mutex,
+};
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::ValueEnum;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use self::{backup::BackupRegistry, case::CaseFolder, dirs::CreatedDirs};
+use crate::{
+    manifest::{install_file::normalize_destination, Manifest},
+    mods::deployed_files,
+    settings::{ensure_writable, folds_case, with_io_limit},
+};
+
+pub mod backup;
+pub mod case;
+pub mod dirs;
+pub mod overlay;
+
+/// How the files of the enabled mods end up in the game directory.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+pub enum DeploymentStrategy {
+    /// Link the files into the game directory when mods are enabled.
+    #[default]
+    Symlink,
+    /// Experimental: leave the game directory alone, and mount the enabled mods over it with
+    /// fuse-overlayfs while a game or tool runs.
+    Overlay,
+}
+impl Display for DeploymentStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_possible_value()
+            .map_or(Ok(()), |v| f.write_str(v.get_name()))
+    }
+}
+
+/// A link which should be in the game directory, or which is there while it should not be.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Link {
+    destination: Utf8PathBuf,
+    origin: Utf8PathBuf,
+    mod_name: Option<String>,
+}
+impl Link {
+    /// Absolute path of the link in the game directory.
+    pub fn destination(&self) -> &Utf8Path {
+        &self.destination
+    }
+    /// Absolute path of the file in the cache the link points to.
+    pub fn origin(&self) -> &Utf8Path {
+        &self.origin
+    }
+    /// The enabled mod providing the file; `None` for links which have to go.
+    pub fn mod_name(&self) -> Option<&str> {
+        self.mod_name.as_deref()
+    }
+}
+
+/// The changes needed to deploy the enabled mods of a mod-list.
+#[derive(Clone, Debug, Default)]
+pub struct DeployPlan {
+    create: Vec<Link>,
+    replace: Vec<Link>,
+    remove: Vec<Link>,
+    unchanged: usize,
+    cache_dir: Utf8PathBuf,
+}
+impl DeployPlan {
+    /// Compare the files of the enabled mods in `mod_list` with the links into `cache_dir`
+    /// which are in `game_dir`.
+    ///
+    /// # Errors
+    ///
+    /// When the files of a mod or the links in `game_dir` cannot be listed.
+    pub fn new(mod_list: &[Manifest], cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<Self> {
+        Self::plan(mod_list, cache_dir, game_dir, |_| true)
+    }
+    /// Like `new`, but only links into the mods of `mod_list` are taken out; the links of
+    /// other mods are left alone, unless they are in the way. Links to files which are no
+    /// longer in the cache, like those of an upgraded mod, are always removed.
+    ///
+    /// # Errors
+    ///
+    /// When the files of a mod or the links in `game_dir` cannot be listed.
+    pub fn for_mods(
+        mod_list: &[Manifest],
+        cache_dir: &Utf8Path,
+        game_dir: &Utf8Path,
+    ) -> Result<Self> {
+        let mod_dirs = mod_list
+            .iter()
+            .map(Manifest::manifest_dir)
+            .collect::<HashSet<_>>();
+        Self::plan(mod_list, cache_dir, game_dir, |target| {
+            let mod_dir = target
+                .strip_prefix(cache_dir)
+                .ok()
+                .and_then(|t| t.components().next())
+                .map(|c| Utf8Path::new(c.as_str()));
+            mod_dir.is_some_and(|dir| mod_dirs.contains(dir)) || !target.exists()
+        })
+    }
+    fn plan(
+        mod_list: &[Manifest],
+        cache_dir: &Utf8Path,
+        game_dir: &Utf8Path,
+        removable: impl Fn(&Utf8Path) -> bool,
+    ) -> Result<Self> {
+        let mut desired = HashMap::new();
+        for (mod_name, destination, origin) in deployment_targets(mod_list, cache_dir, game_dir)? {
+            // Manifests of older releases can still have files outside of the game directory.
+            if destination.is_absolute() || normalize_destination(destination.as_str()).is_none() {
+                log::warn!(
+                    "Not deploying '{destination}' of '{mod_name}'; it is outside of the game directory."
+                );
+                continue;
+            }
+            // Like enabling, the last mod of the list wins.
+            desired.insert(game_dir.join(destination), (origin, mod_name));
+        }
+        let mut current = deployed_links(cache_dir, game_dir)?;
+
+        let mut plan = Self {
+            cache_dir: cache_dir.to_owned(),
+            ..Self::default()
+        };
+        for (destination, (origin, mod_name)) in desired {
+            let link = Link {
+                destination,
+                origin,
+                mod_name: Some(mod_name),
+            };
+            match current.remove(&link.destination) {
+                Some(target) if target == link.origin => plan.unchanged += 1,
+                Some(_) => plan.replace.push(link),
+                None => plan.create.push(link),
+            }
+        }
+        plan.remove = current
+            .into_iter()
+            .filter(|(_, target)| removable(target))
+            .map(|(destination, origin)| Link {
+                destination,
+                origin,
+                mod_name: None,
+            })
+            .collect();
+
+        for links in [&mut plan.create, &mut plan.replace, &mut plan.remove] {
+            links.sort_unstable_by(|a, b| a.destination.cmp(&b.destination));
+        }
+        Ok(plan)
+    }
+    /// Links which are missing.
+    pub fn create(&self) -> &[Link] {
+        &self.create
+    }
+    /// Links which point to the file of another mod.
+    pub fn replace(&self) -> &[Link] {
+        &self.replace
+    }
+    /// Links of mods which are no longer enabled, or no longer provide the file.
+    pub fn remove(&self) -> &[Link] {
+        &self.remove
+    }
+    /// Number of links which are already as they should be.
+    pub const fn unchanged(&self) -> usize {
+        self.unchanged
+    }
+    /// Whether the game directory is already up to date.
+    pub const fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.replace.is_empty() && self.remove.is_empty()
+    }
+    /// Number of links which are created, replaced or removed.
+    pub const fn len(&self) -> usize {
+        self.create.len() + self.replace.len() + self.remove.len()
+    }
+
+    /// Change the links in `game_dir`; original game files in the way of a link are
+    /// backed up, and restored again once their link is removed, see `backup`.
+    ///
+    /// # Errors
+    ///
+    /// When a link cannot be changed, or an original game file cannot be backed up or restored; the
+    /// links changed before that stay changed.
+    ///
+    /// # Panics
+    ///
+    /// When a thread changing the links panicked.
+    pub fn apply(&self, game_dir: &Utf8Path) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        ensure_writable("link mods into the game directory")?;
+
+        let sty = ProgressStyle::with_template("{prefix:.bold.dim} {wide_msg}: {bar:40}").unwrap();
+        let progress = ProgressBar::new(self.len() as u64)
+            .with_style(sty)
+            .with_message("Deploying files...");
+
+        let backups = Mutex::new(BackupRegistry::read(&self.cache_dir));
+        let created = Mutex::new(CreatedDirs::read(&self.cache_dir, game_dir));
+        let result = self.apply_links(game_dir, &backups, &created, &progress);
+        // Also after a failure, so what was done so far can be undone again.
+        let written = backups.into_inner().unwrap().write();
+        let dirs_written = created.into_inner().unwrap().write();
+
+        progress.finish_and_clear();
+        result.and(written).and(dirs_written)
+    }
+    fn apply_links(
+        &self,
+        game_dir: &Utf8Path,
+        backups: &Mutex<BackupRegistry>,
+        created: &Mutex<CreatedDirs>,
+        progress: &ProgressBar,
+    ) -> Result<()> {
+        use rayon::prelude::*;
+
+        // Removals first, so a file which moved to another mod can be backed up and restored.
+        with_io_limit(|| {
+            self.remove.par_iter().try_for_each(|link| {
+                log::debug!("unlink {} ({})", link.destination, link.origin);
+                remove_file(&link.destination)?;
+                backups.lock().unwrap().restore(&link.destination)?;
+                progress.inc(1);
+                Ok::<(), anyhow::Error>(())
+            })
+        })?;
+        created.lock().unwrap().remove_empty(
+            game_dir,
+            self.remove.iter().map(|l| l.destination.as_path()),
+        );
+
+        let dir_cache = Mutex::new(HashSet::new());
+        with_io_limit(|| {
+            self.replace
+                .par_iter()
+                .map(|link| (link, true))
+                .chain(self.create.par_iter().map(|link| (link, false)))
+                .try_for_each(|(link, replace)| {
+                    let destination = &link.destination;
+                    if replace {
+                        log::debug!("relink {} to {}", link.origin, destination);
+                        remove_file(destination)?;
+                    } else {
+                        create_parent(destination, &dir_cache, created)?;
+                        // Anything else in the way is an original game file, or put there by
+                        // another tool; keep it aside until the link is removed again.
+                        if destination.exists() || destination.is_symlink() {
+                            backups.lock().unwrap().back_up(destination)?;
+                        }
+                        log::debug!("link {} to {}", link.origin, destination);
+                    }
+                    symlink(&link.origin, destination).with_context(|| {
+                        format!("Unable to link {} -> {}", link.origin, destination)
+                    })?;
+                    progress.inc(1);
+                    Ok::<(), anyhow::Error>(())
+                })
+        })
+    }
+}
+
+/// The files of the enabled mods in `mod_list`, as (mod, destination relative to `game_dir`,
+/// file in `cache_dir`).
+///
+/// With the 'case-insensitive' setting, destinations take the casing of what is already in
+/// the game directory, see `case::CaseFolder`.
+///
+/// # Errors
+///
+/// When the files of a mod or the game directory cannot be listed.
+pub fn deployment_targets(
+    mod_list: &[Manifest],
+    cache_dir: &Utf8Path,
+    game_dir: &Utf8Path,
+) -> Result<Vec<(String, Utf8PathBuf, Utf8PathBuf)>> {
+    let mut folder = folds_case().then(|| CaseFolder::new(game_dir));
+    Ok(deployed_files(mod_list)?
+        .into_iter()
+        .map(|(mod_name, f)| {
+            let destination = folder.as_mut().map_or_else(
+                || Utf8PathBuf::from(f.destination()),
+                |folder| Utf8PathBuf::from(folder.fold(f.destination())),
+            );
+            (mod_name, destination, cache_dir.join(f.source()))
+        })
+        .collect())
+}
+
+/// Every link in `game_dir` into `cache_dir`, with the file it points to.
+///
+/// # Errors
+///
+/// When `game_dir` cannot be read.
+pub fn deployed_links(
+    cache_dir: &Utf8Path,
+    game_dir: &Utf8Path,
+) -> Result<HashMap<Utf8PathBuf, Utf8PathBuf>> {
+    let mut links = HashMap::new();
+    for entry in WalkDir::new(game_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .same_file_system(true)
+    {
+        let entry = entry?;
+        if !entry.path_is_symlink() {
+            continue;
+        }
+        let target = Utf8PathBuf::try_from(read_link(entry.path())?)?;
+        if target.starts_with(cache_dir) {
+            links.insert(Utf8PathBuf::try_from(entry.into_path())?, target);
+        }
+    }
+    Ok(links)
+}
+
+fn create_parent(
+    destination: &Utf8Path,
+    dir_cache: &Mutex<HashSet<Utf8PathBuf>>,
+    created: &Mutex<CreatedDirs>,
+) -> Result<()> {
+    let Some(parent) = destination.parent() else {
+        return Ok(());
+    };
+    if !dir_cache.lock().unwrap().contains(parent) {
+        created.lock().unwrap().create(parent)?;
+        dir_cache.lock().unwrap().insert(parent.to_owned());
+    }
+    Ok(())
+}
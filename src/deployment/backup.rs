@@ -0,0 +1,189 @@
+//! Files in the way of a link, like original game files, are moved aside and put back once the
+//! link is removed again.
+//!
+//! A file is never moved onto an earlier backup: the next one gets a numbered suffix, like
+//! `Starfield.ini.starmod_bkp.1`. The backups are recorded in the order they were made, so the
+//! newest is restored first; backups from before the record are taken to be older, in the order
+//! of their suffix.
+
+use std::{
+    collections::BTreeMap,
+    fs::{read, rename, File},
+    io::Write,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    settings::{backup_extension, share_with_group},
+    utils::AddExtension,
+};
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+const REGISTRY_FILE: &str = "backups.registry";
+const REGISTRY_TEMP_EXTENSION: &str = "tmp";
+
+/// The backups of the files in the way of links, by the path they were moved from.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BackupRegistry {
+    #[serde(skip)]
+    path: Utf8PathBuf,
+    backups: BTreeMap<Utf8PathBuf, Vec<Utf8PathBuf>>,
+    #[serde(skip)]
+    changed: bool,
+}
+impl BackupRegistry {
+    /// The registry in `cache_dir`; empty when there is none yet.
+    pub fn read(cache_dir: &Utf8Path) -> Self {
+        let path = cache_dir.join(REGISTRY_FILE);
+        let mut registry = read(&path)
+            .ok()
+            .and_then(|contents| {
+                rmp_serde::from_slice::<Self>(&contents)
+                    .map_err(|e| log::warn!("Ignoring damaged backup registry: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        registry.path = path;
+        registry
+    }
+    /// Write the registry, when it changed; backups which are gone are forgotten.
+    ///
+    /// # Errors
+    ///
+    /// When the registry cannot be written.
+    pub fn write(&mut self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        self.backups.retain(|_, backups| {
+            backups.retain(|b| b.is_file());
+            !backups.is_empty()
+        });
+
+        let temp_path = self.path.add_extension(REGISTRY_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&rmp_serde::to_vec_named(&self)?)?;
+        rename(&temp_path, &self.path)?;
+        share_with_group(&self.path)?;
+        self.changed = false;
+        Ok(())
+    }
+    /// Move the file at `destination` aside, without touching earlier backups of it.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be moved.
+    pub fn back_up(&mut self, destination: &Utf8Path) -> Result<Utf8PathBuf> {
+        let is_free = |path: &Utf8Path| !(path.exists() || path.is_symlink());
+        let base = destination.add_extension(backup_extension());
+        let backup = if is_free(&base) {
+            base
+        } else {
+            let mut n = 1;
+            loop {
+                let backup = base.add_extension(n.to_string());
+                if is_free(&backup) {
+                    break backup;
+                }
+                n += 1;
+            }
+        };
+
+        log::info!("renaming foreign file from {destination} -> {backup}");
+        rename(destination, &backup)?;
+        self.backups
+            .entry(destination.to_owned())
+            .or_default()
+            .push(backup.clone());
+        self.changed = true;
+        Ok(backup)
+    }
+    /// The paths with a recorded backup.
+    pub fn originals(&self) -> impl Iterator<Item = &Utf8Path> {
+        self.backups.keys().map(Utf8PathBuf::as_path)
+    }
+    /// The backups of `destination`, oldest first.
+    pub fn backups_of(&self, destination: &Utf8Path) -> Vec<Utf8PathBuf> {
+        let registered = self
+            .backups
+            .get(destination)
+            .map(|backups| {
+                backups
+                    .iter()
+                    .filter(|b| b.is_file())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let mut backups = backups_on_disk(destination)
+            .into_iter()
+            .filter(|b| !registered.contains(b))
+            .collect::<Vec<_>>();
+        backups.extend(registered);
+        backups
+    }
+    /// Put the newest backup of `destination` back, if nothing is in the way;
+    /// returns whether it did.
+    ///
+    /// # Errors
+    ///
+    /// When the backup cannot be moved back.
+    pub fn restore(&mut self, destination: &Utf8Path) -> Result<bool> {
+        if destination.exists() || destination.is_symlink() {
+            return Ok(false);
+        }
+        let Some(backup) = self.backups_of(destination).pop() else {
+            return Ok(false);
+        };
+        log::debug!("Restoring Backup: {backup} -> {destination}.");
+        rename(&backup, destination)?;
+        if let Some(backups) = self.backups.get_mut(destination) {
+            backups.retain(|b| *b != backup);
+        }
+        self.changed = true;
+        Ok(true)
+    }
+}
+
+/// Whether `path` is a backup made by starmod, numbered or not.
+pub fn is_backup(path: &Utf8Path) -> bool {
+    original_of(path).is_some()
+}
+
+/// The path `backup` was moved from, if it is a backup.
+pub fn original_of(backup: &Utf8Path) -> Option<Utf8PathBuf> {
+    let unnumbered = if backup
+        .extension()
+        .is_some_and(|e| !e.is_empty() && e.chars().all(|c| c.is_ascii_digit()))
+    {
+        backup.with_extension("")
+    } else {
+        backup.to_owned()
+    };
+    (unnumbered.extension() == Some(backup_extension())).then(|| unnumbered.with_extension(""))
+}
+
+/// The backups of `destination` in its directory, oldest first by their suffix.
+///
+/// Backups are numbered without gaps, so the numbered ones are probed until one is missing;
+/// this is called for every link, so the directory is not read.
+pub fn backups_on_disk(destination: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let is_backup_file = |path: &Utf8Path| path.symlink_metadata().is_ok_and(|m| m.is_file());
+    let base = destination.add_extension(backup_extension());
+
+    let mut backups = Vec::new();
+    if is_backup_file(&base) {
+        backups.push(base.clone());
+    }
+    for n in 1.. {
+        let backup = base.add_extension(n.to_string());
+        if !is_backup_file(&backup) {
+            break;
+        }
+        backups.push(backup);
+    }
+    backups
+}
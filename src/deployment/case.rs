@@ -0,0 +1,109 @@
+//! A case-insensitive view of the game directory, like ciopfs gives, without mounting anything.
+//!
+//! Windows does not care about the casing of paths, so mods do not either; one mod puts its
+//! files in 'Data/Textures', the next in 'Data/textures'. On a case-sensitive file system those
+//! are two directories, and the game only sees one of them. Folding gives every destination
+//! the casing of what is already in the game directory, or of the first mod which used it.
+
+use std::collections::HashMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+/// Folds destinations, relative to the game directory, onto the casing already in use.
+#[derive(Debug)]
+pub struct CaseFolder {
+    root: Utf8PathBuf,
+    // The lower-case form of every path seen so far, with the casing it is known by.
+    dirs: HashMap<String, Utf8PathBuf>,
+    // The entries of each directory seen so far, by their lower-case name.
+    entries: HashMap<Utf8PathBuf, HashMap<String, String>>,
+}
+impl CaseFolder {
+    pub fn new(root: &Utf8Path) -> Self {
+        let mut dirs = HashMap::new();
+        dirs.insert(String::new(), Utf8PathBuf::new());
+        Self {
+            root: root.to_owned(),
+            dirs,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// `destination` with the casing of the entries it shares a path with; the parts which are
+    /// new keep their own casing, and are used for every later destination which matches them.
+    pub fn fold(&mut self, destination: &str) -> String {
+        let mut folded = Utf8PathBuf::new();
+        let mut lower = String::new();
+        for component in destination.split('/').filter(|c| !c.is_empty()) {
+            if !lower.is_empty() {
+                lower.push('/');
+            }
+            lower.push_str(&component.to_lowercase());
+
+            let known = self
+                .dirs
+                .get(&lower)
+                .map(|known| known.file_name().unwrap_or(component).to_owned());
+            let name = known.unwrap_or_else(|| self.entry(&folded, component));
+            folded.push(&name);
+            self.dirs
+                .entry(lower.clone())
+                .or_insert_with(|| folded.clone());
+        }
+        folded.into_string()
+    }
+
+    /// The name of `name` in `dir`, as it is on disk, or as it was first seen.
+    fn entry(&mut self, dir: &Utf8Path, name: &str) -> String {
+        let root = &self.root;
+        let entries = self.entries.entry(dir.to_owned()).or_insert_with(|| {
+            root.join(dir)
+                .read_dir_utf8()
+                .map(|entries| {
+                    entries
+                        .filter_map(Result::ok)
+                        .map(|e| (e.file_name().to_lowercase(), e.file_name().to_owned()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        entries
+            .entry(name.to_lowercase())
+            .or_insert_with(|| name.to_owned())
+            .clone()
+    }
+}
+
+/// Paths below `dir` which only differ in their casing from another path there; each group is
+/// sorted, and the groups are sorted by their first path.
+pub fn case_duplicates(dir: &Utf8Path) -> Vec<Vec<Utf8PathBuf>> {
+    let mut by_lower: HashMap<String, Vec<Utf8PathBuf>> = HashMap::new();
+    for entry in walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let Ok(path) = Utf8PathBuf::try_from(entry.into_path()) else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        by_lower
+            .entry(relative.as_str().to_lowercase())
+            .or_default()
+            .push(relative.to_owned());
+    }
+
+    let mut duplicates = by_lower
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(|mut paths| {
+            paths.sort();
+            paths
+        })
+        .collect::<Vec<_>>();
+    duplicates.sort();
+    duplicates
+}
@@ -0,0 +1,155 @@
+//! The directories starmod created in the game directory for links.
+//!
+//! Only these are removed again once they are empty; directories of the game, or made by the
+//! user, are left alone, empty or not. Releases before the record did not keep it, so without
+//! one the directories which only hold links into the cache are taken to be starmod's.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::{read, remove_dir, rename, DirBuilder, File},
+    io::Write,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{settings::share_with_group, utils::AddExtension};
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+const REGISTRY_FILE: &str = "directories.registry";
+const REGISTRY_TEMP_EXTENSION: &str = "tmp";
+
+/// The directories created for links, which are removed once they are empty.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CreatedDirs {
+    #[serde(skip)]
+    path: Utf8PathBuf,
+    dirs: BTreeSet<Utf8PathBuf>,
+    #[serde(skip)]
+    changed: bool,
+}
+impl CreatedDirs {
+    /// The directories recorded in `cache_dir`; when nothing was recorded yet, those in
+    /// `game_dir` which only hold links into `cache_dir`.
+    pub fn read(cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Self {
+        let path = cache_dir.join(REGISTRY_FILE);
+        let mut created = read(&path).map_or_else(
+            |_| Self {
+                dirs: link_dirs(cache_dir, game_dir),
+                changed: true,
+                ..Self::default()
+            },
+            |contents| {
+                rmp_serde::from_slice::<Self>(&contents)
+                    .map_err(|e| log::warn!("Ignoring damaged directory registry: {e}"))
+                    .unwrap_or_default()
+            },
+        );
+        created.path = path;
+        created
+    }
+    /// Write the directories, when they changed.
+    ///
+    /// # Errors
+    ///
+    /// When the file of the directories cannot be written.
+    pub fn write(&mut self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        let temp_path = self.path.add_extension(REGISTRY_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&rmp_serde::to_vec_named(&self)?)?;
+        rename(&temp_path, &self.path)?;
+        share_with_group(&self.path)?;
+        self.changed = false;
+        Ok(())
+    }
+    /// Whether starmod created `dir`.
+    pub fn contains(&self, dir: &Utf8Path) -> bool {
+        self.dirs.contains(dir)
+    }
+    /// Create `dir` and its missing parents, and record the ones which were missing.
+    ///
+    /// # Errors
+    ///
+    /// When a directory cannot be created.
+    pub fn create(&mut self, dir: &Utf8Path) -> Result<()> {
+        let missing = dir
+            .ancestors()
+            .take_while(|d| !d.exists())
+            .map(Utf8Path::to_owned)
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        log::trace!("creating directory {dir}");
+        DirBuilder::new().recursive(true).create(dir)?;
+        self.dirs.extend(missing);
+        self.changed = true;
+        Ok(())
+    }
+    /// Remove the created directories in `game_dir` which only existed for the `removed` links.
+    pub fn remove_empty<'a>(
+        &mut self,
+        game_dir: &Utf8Path,
+        removed: impl Iterator<Item = &'a Utf8Path>,
+    ) {
+        let mut dirs = removed
+            .filter_map(Utf8Path::parent)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        // Deepest first, so parents are empty by the time they are tried.
+        dirs.sort_unstable_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+        for dir in dirs {
+            let mut dir = Some(dir);
+            while let Some(d) =
+                dir.filter(|d| *d != game_dir && d.starts_with(game_dir) && self.dirs.contains(*d))
+            {
+                if d.exists() && remove_dir(d).is_err() {
+                    break;
+                }
+                self.dirs.remove(d);
+                self.changed = true;
+                dir = d.parent();
+            }
+        }
+    }
+}
+
+/// The directories below `game_dir` with nothing in them but links into `cache_dir`, and
+/// directories like that.
+fn link_dirs(cache_dir: &Utf8Path, game_dir: &Utf8Path) -> BTreeSet<Utf8PathBuf> {
+    // Whether everything in a directory seen so far is a link, or a directory of links.
+    let mut only_links = HashMap::<Utf8PathBuf, bool>::new();
+    for entry in WalkDir::new(game_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .same_file_system(true)
+        .contents_first(true)
+        .into_iter()
+        .flatten()
+    {
+        let Ok(path) = Utf8PathBuf::try_from(entry.into_path()) else {
+            continue;
+        };
+        let is_link_or_dir = if path.is_symlink() {
+            path.read_link_utf8()
+                .is_ok_and(|target| target.starts_with(cache_dir))
+        } else {
+            only_links.get(&path).copied().unwrap_or(false)
+        };
+        if let Some(parent) = path.parent() {
+            *only_links.entry(parent.to_owned()).or_insert(true) &= is_link_or_dir;
+        }
+    }
+    only_links
+        .into_iter()
+        .filter(|(dir, only)| *only && dir != game_dir)
+        .map(|(dir, _)| dir)
+        .collect()
+}
@@ -0,0 +1,163 @@
+//! Mounting the enabled mods over the game directory with fuse-overlayfs, instead of linking
+//! them into it; the game directory itself is never changed.
+//!
+//! The links to the files of the mods are made in a directory of their own, which is mounted
+//! on top of the game directory while the game runs. Files the game writes end up in the
+//! overlay directory, and are kept there for the next run.
+
+use std::{
+    fs::{create_dir_all, read_to_string, remove_dir_all, remove_file},
+    os::unix::fs::symlink,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{errors::GameErrors, manifest::Manifest, settings::ensure_writable};
+
+use super::deployment_targets;
+
+const OVERLAY_PROGRAM: &str = "fuse-overlayfs";
+const UNMOUNT_PROGRAMS: [&str; 2] = ["fusermount3", "fusermount"];
+const MOUNTS_FILE: &str = "/proc/self/mounts";
+const FUSE_DEVICE: &str = "/dev/fuse";
+
+const LOWER_DIR: &str = "mods";
+const UPPER_DIR: &str = "changes";
+const WORK_DIR: &str = "work";
+
+/// The enabled mods, mounted over the game directory until this is dropped.
+#[derive(Debug)]
+pub struct OverlayMount {
+    game_dir: Utf8PathBuf,
+}
+impl OverlayMount {
+    /// Mount the enabled mods of `mod_list` over `game_dir`; `overlay_dir` holds the links
+    /// to their files, and the files written to the game directory while it is mounted.
+    ///
+    /// # Errors
+    ///
+    /// When the links cannot be created, or the overlay cannot be mounted.
+    pub fn mount(
+        mod_list: &[Manifest],
+        cache_dir: &Utf8Path,
+        game_dir: &Utf8Path,
+        overlay_dir: &Utf8Path,
+    ) -> Result<Self> {
+        ensure_writable("mount the mods over the game directory")?;
+        if is_mounted(game_dir) {
+            return Err(GameErrors::AlreadyMounted(game_dir.to_owned()).into());
+        }
+
+        let lower_dir = overlay_dir.join(LOWER_DIR);
+        let upper_dir = overlay_dir.join(UPPER_DIR);
+        let work_dir = overlay_dir.join(WORK_DIR);
+
+        // The links are made again every time, so they always match the mod-list.
+        if lower_dir.exists() {
+            remove_dir_all(&lower_dir)?;
+        }
+        for dir in [&lower_dir, &upper_dir, &work_dir] {
+            create_dir_all(dir)?;
+        }
+        let files = deployment_targets(mod_list, cache_dir, game_dir)?;
+        for (_, destination, origin) in &files {
+            let link = lower_dir.join(destination);
+            if let Some(parent) = link.parent() {
+                create_dir_all(parent)?;
+            }
+            // Like linking into the game directory, the last mod of the list wins.
+            if link.is_symlink() {
+                remove_file(&link)?;
+            }
+            symlink(origin, &link).with_context(|| format!("Unable to link {origin} -> {link}"))?;
+        }
+
+        // The game directory is both the lowest layer and the mount point.
+        let options =
+            format!("lowerdir={lower_dir}:{game_dir},upperdir={upper_dir},workdir={work_dir}");
+        log::debug!("Running '{OVERLAY_PROGRAM} -o {options} {game_dir}'");
+        let status = Command::new(OVERLAY_PROGRAM)
+            .arg("-o")
+            .arg(&options)
+            .arg(game_dir)
+            .status()
+            .map_err(|_| GameErrors::OverlayUnavailable(OVERLAY_PROGRAM.to_owned()))?;
+        if !status.success() {
+            return Err(
+                GameErrors::ProcessFailed(OVERLAY_PROGRAM.to_owned(), status.to_string()).into(),
+            );
+        }
+
+        log::info!(
+            "Mounted {} file(s) of the enabled mods over '{game_dir}'.",
+            files.len()
+        );
+        Ok(Self {
+            game_dir: game_dir.to_owned(),
+        })
+    }
+    pub fn game_dir(&self) -> &Utf8Path {
+        &self.game_dir
+    }
+}
+impl Drop for OverlayMount {
+    fn drop(&mut self) {
+        for program in UNMOUNT_PROGRAMS {
+            match Command::new(program).arg("-u").arg(&self.game_dir).status() {
+                Ok(status) if status.success() => {
+                    log::info!("Unmounted the mods from '{}'.", self.game_dir);
+                    return;
+                }
+                Ok(status) => {
+                    log::error!("'{program} -u {}' failed: {status}.", self.game_dir);
+                    return;
+                }
+                // Not installed; try the next one.
+                Err(_) => {}
+            }
+        }
+        log::error!(
+            "Unable to unmount '{}'; neither {} could be run.",
+            self.game_dir,
+            UNMOUNT_PROGRAMS.join(" nor ")
+        );
+    }
+}
+
+/// What the overlay deployment needs, but is missing from this system.
+pub fn missing_requirements() -> Vec<String> {
+    let runs = |program: &str| {
+        Command::new(program)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok()
+    };
+
+    let mut missing = Vec::new();
+    if !Utf8Path::new(FUSE_DEVICE).exists() {
+        missing.push(FUSE_DEVICE.to_owned());
+    }
+    if !runs(OVERLAY_PROGRAM) {
+        missing.push(OVERLAY_PROGRAM.to_owned());
+    }
+    if !UNMOUNT_PROGRAMS.iter().any(|p| runs(p)) {
+        missing.push(UNMOUNT_PROGRAMS.join(" or "));
+    }
+    missing
+}
+
+/// Whether something, like an overlay which was not unmounted, is mounted on `dir`.
+pub fn is_mounted(dir: &Utf8Path) -> bool {
+    read_to_string(MOUNTS_FILE).is_ok_and(|mounts| {
+        mounts.lines().any(|line| {
+            // Spaces in the mount point are written as '\040'.
+            line.split(' ')
+                .nth(1)
+                .is_some_and(|mount_point| mount_point.replace("\\040", " ") == dir.as_str())
+        })
+    })
+}
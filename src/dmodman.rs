@@ -1,27 +1,53 @@
 use camino::{Utf8Path, Utf8PathBuf};
 use std::{
     fs::File,
-    io::{BufReader, Read},
+    io::{BufReader, BufWriter, Read},
 };
 use walkdir::WalkDir;
 
 use anyhow::{Error, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use xdg::BaseDirectories;
 
+use crate::nexus;
+
 pub const DMODMAN_EXTENSION: &str = "dmodman";
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct DmodMan {
     game: String,
     file_name: String,
     mod_id: u32,
-    #[allow(unused)]
     file_id: u64,
     #[allow(unused)]
     update_status: UpdateStatus,
 }
 impl DmodMan {
+    /// The metadata of an archive downloaded by starmod itself, written like dmodman writes it.
+    pub fn new(game: &str, file_name: &str, mod_id: u32, file_id: u64, uploaded: u64) -> Self {
+        Self {
+            game: game.to_owned(),
+            file_name: file_name.to_owned(),
+            mod_id,
+            file_id,
+            update_status: UpdateStatus::UpToDate(uploaded),
+        }
+    }
+    /// Write the metadata to `path`, the way dmodman does.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be written.
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        let file = File::create(path)?;
+        serde_json::to_writer(BufWriter::new(file), self)?;
+        Ok(())
+    }
+    /// The metadata of every archive in `cache_dir`.
+    ///
+    /// # Errors
+    ///
+    /// When the directory cannot be read, or a metadata file is not dmodman's.
     pub fn gather_list(cache_dir: &Utf8Path) -> Result<Vec<Self>> {
         log::trace!("Gathering Dmodman List");
         let mut dmodman_list = Vec::new();
@@ -48,6 +74,11 @@ impl DmodMan {
     pub fn file_name(&self) -> &str {
         &self.file_name
     }
+    /// The name of the mod, as it is in the name of the archive.
+    ///
+    /// # Panics
+    ///
+    /// When the name of the archive does not hold the id of the mod, as Nexus names them.
     pub fn name(&self) -> String {
         self.file_name
             .to_lowercase()
@@ -58,6 +89,17 @@ impl DmodMan {
     pub const fn mod_id(&self) -> u32 {
         self.mod_id
     }
+    pub const fn file_id(&self) -> u64 {
+        self.file_id
+    }
+    /// The page of this file on Nexus Mods, in the game it was downloaded for.
+    pub fn nexus_url(&self) -> String {
+        format!(
+            "{}?tab=files&file_id={}",
+            nexus::mod_page_url(&self.game, self.mod_id),
+            self.file_id
+        )
+    }
     #[allow(unused)]
     pub fn timestamp(&self) -> Option<String> {
         self.file_name
@@ -111,7 +153,7 @@ impl PartialEq for DmodMan {
 }
 impl Eq for DmodMan {}
 
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub enum UpdateStatus {
     UpToDate(u64),     // time of your newest file,
     HasNewFile(u64),   // time of your newest file
@@ -135,7 +177,6 @@ impl UpdateStatus {
 pub struct DModManConfig {
     download_dir: Option<String>,
     profile: Option<String>,
-    #[allow(unused)]
     api_key: Option<String>,
 }
 impl DModManConfig {
@@ -155,6 +196,14 @@ impl DModManConfig {
         }
         Some(ddir)
     }
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref()
+    }
+    /// The configuration file of dmodman.
+    ///
+    /// # Errors
+    ///
+    /// When the home directory is not known, or the path is not UTF-8.
     pub fn path() -> Result<Utf8PathBuf> {
         let xdg_base = BaseDirectories::with_prefix("dmodman")?;
         Ok(Utf8PathBuf::try_from(
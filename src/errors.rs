@@ -1,63 +1,137 @@
 use camino::Utf8PathBuf;
 use thiserror::Error;
 
+use crate::i18n::{tr, tr_args};
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum SettingErrors {
-    #[error("No valid config file could be found; Please run '{0} update-config' first.")]
+    #[error("{}", tr_args("error-setting-config-not-found", &[("cmd", .0)]))]
     ConfigNotFound(String),
-    #[error("The game directory for {0} cannot be found, Please run '{1} update-config' and provide manually.")]
+    #[error("{}", tr_args("error-setting-no-game-dir-found", &[("game", .0), ("cmd", .1)]))]
     NoGameDirFound(String, String),
-    #[error("A download directory for cannot be found, Please run '{0} update-config' and provide manually.")]
+    #[error("{}", tr_args("error-setting-no-download-dir-found", &[("cmd", .0)]))]
     NoDownloadDirFound(String),
-    #[error(
-        "The cache directory cannot be found, Please run '{0} update-config' and provide manually."
-    )]
+    #[error("{}", tr_args("error-setting-no-cache-dir-found", &[("cmd", .0)]))]
     NoCacheDirFound(String),
-    #[error(
-        "The proton directory cannot be found, Please run '{0} update-config' and provide manually."
-    )]
+    #[error("{}", tr_args("error-setting-no-proton-dir-found", &[("cmd", .0)]))]
     NoProtonDirFound(String),
-    #[error(
-        "The compat directory cannot be found, Please run '{0} update-config' and provide manually."
-    )]
+    #[error("{}", tr_args("error-setting-no-compat-dir-found", &[("cmd", .0)]))]
     NoCompatDirFound(String),
-    #[error(
-        "The steam directory cannot be found, Please run '{0} update-config' and provide manually."
-    )]
+    #[error("{}", tr_args("error-setting-no-steam-dir-found", &[("cmd", .0)]))]
     NoSteamDirFound(String),
-    #[error("The executable could not be found: {0}.")]
+    #[error("{}", tr_args("error-setting-no-wine-prefix-found", &[("cmd", .0)]))]
+    NoWinePrefixFound(String),
+    #[error("{}", tr_args("error-setting-no-xedit-dir-found", &[("cmd", .0)]))]
+    NoXEditDirFound(String),
+    #[error("{}", tr_args("error-setting-executable-not-found", &[("path", .0)]))]
     ExecutableNotFound(Utf8PathBuf),
+    #[error("{}", tr_args("error-setting-no-executable-configured", &[("cmd", .0)]))]
+    NoExecutableConfigured(String),
+    #[error("{}", tr_args("error-setting-read-only", &[("action", .0)]))]
+    ReadOnly(String),
+    #[error("{}", tr_args("error-setting-not-confirmed", &[("action", .0)]))]
+    NotConfirmed(String),
+    #[error("{}", tr_args("error-setting-daemon-running", &[("socket", .0)]))]
+    DaemonRunning(Utf8PathBuf),
+    #[error("{}", tr_args("error-setting-no-nexus-api-key", &[("env", .0)]))]
+    NoNexusApiKey(String),
+    #[error("{}", tr_args("error-setting-alias-is-command", &[("alias", .0)]))]
+    AliasIsCommand(String),
+    #[error("{}", tr_args("error-setting-invalid-alias-name", &[("alias", .0)]))]
+    InvalidAliasName(String),
+    #[error("{}", tr_args("error-setting-alias-not-found", &[("alias", .0)]))]
+    AliasNotFound(String),
+    #[error("{}", tr_args("error-setting-no-state-dir-configured", &[("cmd", .0)]))]
+    NoStateDirConfigured(String),
+    #[error("{}", tr_args("error-setting-invalid-backup-extension", &[("extension", .0)]))]
+    InvalidBackupExtension(String),
 }
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum GameErrors {
-    #[error("Could not find file(s) '{0}' in the game directories.")]
+    #[error("{}", tr_args("error-game-config-not-found", &[("files", .0)]))]
     ConfigNotFound(String),
+    #[error("{}", tr_args("error-game-process-failed", &[("process", .0), ("reason", .1)]))]
+    ProcessFailed(String, String),
+    #[error("{}", tr_args("error-game-plugin-not-found", &[("plugin", .0)]))]
+    PluginNotFound(String),
+    #[error("{}", tr_args("error-game-empty-rule", &[("plugin", .0)]))]
+    EmptyRule(String),
+    #[error("{}", tr_args("error-game-rule-not-found", &[("plugin", .0)]))]
+    RuleNotFound(String),
+    #[error("{}", tr_args("error-game-invalid-plugin", &[("plugin", .0)]))]
+    InvalidPlugin(String),
+    #[error("{}", tr_args("error-game-checks-failed", &[("count", .0)]))]
+    ChecksFailed(usize),
+    #[error("{}", tr_args("error-game-overlay-unavailable", &[("program", .0)]))]
+    OverlayUnavailable(String),
+    #[error("{}", tr_args("error-game-already-mounted", &[("dir", .0)]))]
+    AlreadyMounted(Utf8PathBuf),
+    #[error("{}", tr_args("error-game-no-snapshot", &[("cmd", .0)]))]
+    NoSnapshot(String),
+    #[error("{}", tr("error-game-snapshot-exists"))]
+    SnapshotExists,
 }
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum ModErrors {
-    #[error("The mod '{0}' could not be found. Is the mod installed?")]
+    #[error("{}", tr_args("error-mod-mod-not-found", &[("name", .0)]))]
     ModNotFound(String),
-    #[error("Could not find the file(s) '{1}' in mod {0}.")]
+    #[error("{}", tr_args("error-mod-file-not-found", &[("name", .0), ("files", .1)]))]
     FileNotFound(String, String),
-    #[error("Could not find tag '{1}' in mod {0}. Did you perhaps mispel it?")]
+    #[error("{}", tr_args("error-mod-tag-not-found", &[("name", .0), ("tag", .1)]))]
     TagNotFound(String, String),
-    #[error("Could not add tag '{1}' to mod {0}. Perhaps the mod al-ready has that tag?")]
+    #[error("{}", tr_args("error-mod-duplicate-tag", &[("name", .0), ("tag", .1)]))]
     DuplicateTag(String, String),
+    #[error("{}", tr_args("error-mod-mod-already-exists", &[("name", .0)]))]
+    ModAlreadyExists(String),
+    #[error("{}", tr_args("error-mod-no-nexus-id", &[("name", .0)]))]
+    NoNexusId(String),
+    #[error("{}", tr_args("error-mod-manifest-too-new", &[("name", .0), ("format", .1)]))]
+    ManifestTooNew(String, u32),
+    #[error("{}", tr_args("error-mod-manifest-not-migrated", &[("name", .0), ("format", .1)]))]
+    ManifestNotMigrated(String, u32),
+    #[error("{}", tr_args("error-mod-no-executable", &[("name", .0)]))]
+    NoExecutable(String),
+    #[error("{}", tr("error-mod-no-launcher-dir"))]
+    NoLauncherDir,
+    #[error("{}", tr_args("error-mod-output-exists", &[("file", .0)]))]
+    OutputExists(Utf8PathBuf),
+    #[error("{}", tr_args("error-mod-invalid-pattern", &[("pattern", .0), ("reason", .1)]))]
+    InvalidPattern(String, String),
+    #[error("{}", tr_args("error-mod-lint-failed", &[("count", .0)]))]
+    LintFailed(usize),
 }
 
 #[derive(Error, Debug)]
 pub enum DownloadError {
-    #[error("the archive {0} cannot be found.")]
+    #[error("{}", tr_args("error-download-archive-not-found", &[("archive", .0)]))]
     ArchiveNotFound(String),
+    #[error("{}", tr_args("error-download-staging-dir-not-found", &[("dir", .0)]))]
+    StagingDirNotFound(Utf8PathBuf),
+    #[error("{}", tr_args("error-download-extractions-failed", &[("count", .0)]))]
+    ExtractionsFailed(usize),
+    #[error("{}", tr_args("error-download-upgrades-failed", &[("count", .0)]))]
+    UpgradesFailed(usize),
+    #[error("{}", tr_args("error-download-no-download-link", &[("file", .0)]))]
+    NoDownloadLink(String),
+    #[error("{}", tr_args("error-download-no-metadata", &[("archive", .0)]))]
+    NoMetadata(String),
+    #[error("{}", tr_args("error-download-unknown-to-nexus", &[("archive", .0)]))]
+    UnknownToNexus(String),
+    #[error("{}", tr_args("error-download-collection-for-other-game", &[("collection", .0), ("game", .1)]))]
+    CollectionForOtherGame(String, String),
+    #[error("{}", tr_args("error-download-nexus-request-failed", &[("status", .0), ("reason", .1)]))]
+    NexusRequestFailed(u16, String),
+    #[error("{}", tr_args("error-download-nexus-daily-limit", &[("reset", .0)]))]
+    NexusDailyLimit(String),
 }
 
 #[derive(Error, Debug)]
 pub enum InternalError {
-    #[error("We encountered an internal error, please report this: {0}.")]
+    #[error("{}", tr_args("error-internal", &[("error", .0)]))]
     Error(String),
 }
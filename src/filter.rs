@@ -0,0 +1,148 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use clap::ValueEnum;
+
+use crate::{manifest::Manifest, mods::ModKind};
+
+/// A single condition on a mod, written as '<key>:<value>'; a bare value matches the name.
+/// Prefix a condition with '!' to invert it.
+///
+/// Keys: 'name' and 'version' (part of, case-insensitive), 'tag', 'kind', 'state'
+/// ('enabled', 'disabled' or 'pinned'; pinned mods are disabled too), 'nexus' and 'priority'
+/// (optionally prefixed with '<', '>' or '=').
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModFilter {
+    negate: bool,
+    condition: Condition,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Condition {
+    Name(String),
+    Version(String),
+    Tag(String),
+    Kind(ModKind),
+    Enabled(bool),
+    Pinned,
+    NexusId(u32),
+    Priority(Ordering, isize),
+}
+
+impl ModFilter {
+    pub fn matches(&self, md: &Manifest) -> bool {
+        let matches = match &self.condition {
+            Condition::Name(name) => md.name().to_lowercase().contains(name),
+            Condition::Version(version) => md
+                .version()
+                .is_some_and(|v| v.to_lowercase().contains(version)),
+            Condition::Tag(tag) => md.tags().contains(tag),
+            Condition::Kind(kind) => md.kind() == *kind,
+            Condition::Enabled(enabled) => md.is_enabled() == *enabled,
+            Condition::Pinned => md.mod_state().is_pinned(),
+            Condition::NexusId(id) => md.nexus_id() == Some(*id),
+            Condition::Priority(ordering, priority) => md.priority().cmp(priority) == *ordering,
+        };
+        matches != self.negate
+    }
+}
+
+/// True when `md` matches every filter in `filters`.
+pub fn matches_all(filters: &[ModFilter], md: &Manifest) -> bool {
+    filters.iter().all(|f| f.matches(md))
+}
+
+/// Split what was typed into a prompt into the filters ('tag:ui', '!state:enabled') and the
+/// remaining text; incomplete or invalid filters are ignored while they are being typed.
+pub fn parse_filter_input(input: &str) -> (Vec<ModFilter>, String) {
+    let mut filters = Vec::new();
+    let mut text = Vec::new();
+    for word in input.split_whitespace() {
+        if word.contains(':') {
+            if let Ok(filter) = word.parse() {
+                filters.push(filter);
+            }
+        } else {
+            text.push(word);
+        }
+    }
+    (filters, text.join(" "))
+}
+
+impl FromStr for ModFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negate, s) = s.strip_prefix('!').map_or((false, s), |s| (true, s));
+        let (key, value) = s.split_once(':').unwrap_or(("name", s));
+        let value = value.trim().to_lowercase();
+        if value.is_empty() {
+            return Err(format!("filter '{s}' has no value"));
+        }
+
+        let condition = match key.trim().to_lowercase().as_str() {
+            "name" => Condition::Name(value),
+            "version" => Condition::Version(value),
+            "tag" => Condition::Tag(value),
+            "kind" => Condition::Kind(ModKind::from_str(&value, true)?),
+            "state" => match value.as_str() {
+                "enabled" => Condition::Enabled(true),
+                "disabled" => Condition::Enabled(false),
+                "pinned" => Condition::Pinned,
+                _ => {
+                    return Err(format!(
+                        "invalid state '{value}', use 'enabled', 'disabled' or 'pinned'"
+                    ))
+                }
+            },
+            "nexus" => Condition::NexusId(
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid nexus id '{value}'"))?,
+            ),
+            "priority" => {
+                let (ordering, number) = match value.chars().next() {
+                    Some('<') => (Ordering::Less, &value[1..]),
+                    Some('>') => (Ordering::Greater, &value[1..]),
+                    Some('=') => (Ordering::Equal, &value[1..]),
+                    _ => (Ordering::Equal, value.as_str()),
+                };
+                let priority = number
+                    .parse()
+                    .map_err(|_| format!("invalid priority '{number}'"))?;
+                Condition::Priority(ordering, priority)
+            }
+            key => {
+                return Err(format!(
+                    "unknown filter '{key}'; use name, version, tag, kind, state, nexus or priority"
+                ))
+            }
+        };
+
+        Ok(Self { negate, condition })
+    }
+}
+impl Display for ModFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negate {
+            f.write_str("!")?;
+        }
+        match &self.condition {
+            Condition::Name(name) => write!(f, "name:{name}"),
+            Condition::Version(version) => write!(f, "version:{version}"),
+            Condition::Tag(tag) => write!(f, "tag:{tag}"),
+            Condition::Kind(kind) => write!(f, "kind:{}", kind.to_string().to_lowercase()),
+            Condition::Enabled(true) => f.write_str("state:enabled"),
+            Condition::Enabled(false) => f.write_str("state:disabled"),
+            Condition::Pinned => f.write_str("state:pinned"),
+            Condition::NexusId(id) => write!(f, "nexus:{id}"),
+            Condition::Priority(ordering, priority) => {
+                let op = match ordering {
+                    Ordering::Less => "<",
+                    Ordering::Equal => "",
+                    Ordering::Greater => ">",
+                };
+                write!(f, "priority:{op}{priority}")
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 
+use crate::nexus;
+
 #[cfg(feature = "loadorder")]
 use loadorder::GameId;
 
@@ -27,6 +29,10 @@ impl Game {
             Self::Starfield => "starfield",
         }
     }
+    /// The page of mod `nexus_id` on Nexus Mods.
+    pub fn nexus_mod_url(self, nexus_id: u32) -> String {
+        nexus::mod_page_url(self.nexus_game_name(), nexus_id)
+    }
     #[cfg(feature = "loadorder")]
     pub fn game_id(self) -> GameId {
         match self {
@@ -54,6 +60,12 @@ impl Game {
             Self::Starfield => "loot.exe",
         }
     }
+    /// The repository of the game's masterlist, in LOOT's GitHub organisation.
+    pub const fn loot_repository(self) -> &'static str {
+        match self {
+            Self::Starfield => "starfield",
+        }
+    }
     pub const fn xedit_name(self) -> &'static str {
         match self {
             Self::Starfield => "sf1edit.exe",
@@ -69,9 +81,63 @@ impl Game {
             Self::Starfield => &["Starfield.ini", "StarfieldPrefs.ini", "StarfieldCustom.ini"],
         }
     }
+    /// Plugins which come with the game, including its official add-ons.
+    pub const fn base_plugins(self) -> &'static [&'static str] {
+        match self {
+            Self::Starfield => &[
+                "Starfield.esm",
+                "Constellation.esm",
+                "OldMars.esm",
+                "BlueprintShips-Starfield.esm",
+                "ShatteredSpace.esm",
+                "SFBGS003.esm",
+                "SFBGS004.esm",
+                "SFBGS006.esm",
+                "SFBGS007.esm",
+                "SFBGS008.esm",
+            ],
+        }
+    }
+    /// Relative to the home directory of the windows user, see `Settings::windows_user_dir`.
+    pub const fn plugins_file(self) -> &'static str {
+        match self {
+            Self::Starfield => "AppData/Local/Starfield/Plugins.txt",
+        }
+    }
+    /// Version specific dlls of the script extender start with this, followed by the game version.
+    pub const fn loader_dll_prefix(self) -> &'static str {
+        match self {
+            Self::Starfield => "sfse_",
+        }
+    }
+    /// Maximum number of active full and light plugins.
+    pub const fn plugin_limits(self) -> (usize, usize) {
+        match self {
+            // Index 0xFD is taken by medium plugins and 0xFE by light plugins.
+            Self::Starfield => (253, 4096),
+        }
+    }
+    /// The ini file for user changes, in `my_game_dir`.
+    pub const fn custom_ini(self) -> &'static str {
+        match self {
+            Self::Starfield => "StarfieldCustom.ini",
+        }
+    }
+    /// Ini settings (section, key and value) which let the game load loose files.
+    pub const fn archive_invalidation(
+        self,
+    ) -> &'static [(&'static str, &'static str, &'static str)] {
+        match self {
+            Self::Starfield => &[
+                ("Archive", "bInvalidateOlderFiles", "1"),
+                ("Archive", "sResourceDataDirsFinal", ""),
+            ],
+        }
+    }
+    /// Relative to the home directory of the windows user, like `plugins_file`.
     pub const fn my_game_dir(self) -> &'static str {
         match self {
-            Self::Starfield => "pfx/drive_c/users/steamuser/My Documents/My Games/Starfield",
+            Self::Starfield => "Documents/My Games/Starfield",
         }
     }
     pub const fn find_game() -> Option<Utf8PathBuf> {
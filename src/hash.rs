@@ -0,0 +1,169 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::{metadata, read, rename, File},
+    io::{self, BufReader, Write},
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use md5::{Digest, Md5};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    settings::{is_read_only, share_with_group, with_io_limit},
+    utils::AddExtension,
+};
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+const HASH_CACHE_FILE: &str = "hashes.index";
+const HASH_CACHE_TEMP_EXTENSION: &str = "tmp";
+
+/// The hash of the contents of a file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ContentHash([u8; blake3::OUT_LEN]);
+impl Display for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&blake3::Hash::from(self.0).to_hex())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct HashEntry {
+    len: u64,
+    modified: SystemTime,
+    hash: ContentHash,
+}
+
+/// Hashes files, remembering the hashes in the cache directory.
+///
+/// Hashing gigabytes of textures takes a while; as long as the size and modification time
+/// of a file did not change, the hash from a previous run is used instead.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HashCache {
+    #[serde(skip)]
+    cache_file: Utf8PathBuf,
+    #[serde(skip)]
+    changed: bool,
+    entries: HashMap<Utf8PathBuf, HashEntry>,
+}
+impl HashCache {
+    /// Read the hash cache of `cache_dir`; a missing or unreadable cache starts out empty.
+    pub fn load(cache_dir: &Utf8Path) -> Self {
+        let cache_file = cache_dir.join(HASH_CACHE_FILE);
+        let entries = read(&cache_file)
+            .ok()
+            .and_then(|data| rmp_serde::from_slice::<Self>(&data).ok())
+            .map(|cache| cache.entries)
+            .unwrap_or_default();
+
+        Self {
+            cache_file,
+            changed: false,
+            entries,
+        }
+    }
+
+    /// The hashes of the contents of all `paths`, hashing the files which are not cached in parallel.
+    ///
+    /// # Errors
+    ///
+    /// When a file cannot be read.
+    pub fn hash_all(&mut self, paths: &[Utf8PathBuf]) -> Result<HashMap<Utf8PathBuf, ContentHash>> {
+        let results = with_io_limit(|| {
+            paths
+                .par_iter()
+                .map(|path| {
+                    cached_hash(self.entries.get(path), path)
+                        .map(|(hash, entry)| (path, hash, entry))
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut hashes = HashMap::with_capacity(results.len());
+        for (path, hash, entry) in results {
+            if let Some(entry) = entry {
+                self.entries.insert(path.clone(), entry);
+                self.changed = true;
+            }
+            hashes.insert(path.clone(), hash);
+        }
+        Ok(hashes)
+    }
+
+    /// Write the cache back, if any hashes were added; files which no longer exist are forgotten.
+    ///
+    /// # Errors
+    ///
+    /// When the cache cannot be written.
+    pub fn save(&mut self) -> Result<()> {
+        if !self.changed || is_read_only() {
+            return Ok(());
+        }
+        self.entries.retain(|path, _| path.exists());
+
+        let temp_path = self.cache_file.add_extension(HASH_CACHE_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&rmp_serde::to_vec_named(&self)?)?;
+        rename(&temp_path, &self.cache_file)?;
+        share_with_group(&self.cache_file)?;
+
+        log::trace!("Wrote hash cache with {} entries.", self.entries.len());
+        self.changed = false;
+        Ok(())
+    }
+}
+
+/// The hash of the contents of `path`, without looking at the cache.
+///
+/// # Errors
+///
+/// When the file cannot be read.
+pub fn hash_file(path: &Utf8Path) -> Result<ContentHash> {
+    log::trace!("Hashing {path}");
+    Ok(ContentHash(
+        *blake3::Hasher::new()
+            .update_mmap_rayon(path)?
+            .finalize()
+            .as_bytes(),
+    ))
+}
+
+/// The md5 checksum of `path`, in hexadecimal, as Nexus knows its files by.
+///
+/// # Errors
+///
+/// When the file cannot be read.
+pub fn md5_file(path: &Utf8Path) -> Result<String> {
+    log::trace!("Taking the md5 checksum of {path}");
+    let mut hasher = Md5::new();
+    io::copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The hash of `path`, taken from `entry` when the file did not change since;
+/// otherwise the file is hashed and a new entry is returned as well.
+fn cached_hash(
+    entry: Option<&HashEntry>,
+    path: &Utf8Path,
+) -> Result<(ContentHash, Option<HashEntry>)> {
+    let meta = metadata(path)?;
+    let len = meta.len();
+    let modified = meta.modified()?;
+
+    if let Some(entry) = entry.filter(|e| e.len == len && e.modified == modified) {
+        return Ok((entry.hash, None));
+    }
+
+    let hash = hash_file(path)?;
+    Ok((
+        hash,
+        Some(HashEntry {
+            len,
+            modified,
+            hash,
+        }),
+    ))
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    manifest::{mod_state::ModState, Manifest},
+    mods::GatherModList,
+    settings::Settings,
+};
+
+const HISTORY_FILE: &str = "history.jsonl";
+const USER_ENV: &str = "USER";
+
+/// A single change to the mod-list, as detected between two snapshots.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Change {
+    Installed {
+        name: String,
+    },
+    Removed {
+        name: String,
+    },
+    Renamed {
+        from: String,
+        to: String,
+    },
+    Enabled {
+        name: String,
+    },
+    Disabled {
+        name: String,
+    },
+    Pinned {
+        name: String,
+    },
+    Unpinned {
+        name: String,
+    },
+    Priority {
+        name: String,
+        from: isize,
+        to: isize,
+    },
+    Version {
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    TagAdded {
+        name: String,
+        tag: String,
+    },
+    TagRemoved {
+        name: String,
+        tag: String,
+    },
+}
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unknown = "<Unknown>".to_owned();
+        match self {
+            Self::Installed { name } => write!(f, "Installed '{name}'"),
+            Self::Removed { name } => write!(f, "Removed '{name}'"),
+            Self::Renamed { from, to } => write!(f, "Renamed '{from}' to '{to}'"),
+            Self::Enabled { name } => write!(f, "Enabled '{name}'"),
+            Self::Disabled { name } => write!(f, "Disabled '{name}'"),
+            Self::Pinned { name } => write!(f, "Pinned '{name}'"),
+            Self::Unpinned { name } => write!(f, "Unpinned '{name}'"),
+            Self::Priority { name, from, to } => {
+                write!(f, "Priority of '{name}': {from} -> {to}")
+            }
+            Self::Version { name, from, to } => write!(
+                f,
+                "Version of '{name}': {} -> {}",
+                from.as_ref().unwrap_or(&unknown),
+                to.as_ref().unwrap_or(&unknown)
+            ),
+            Self::TagAdded { name, tag } => write!(f, "Added tag '{tag}' to '{name}'"),
+            Self::TagRemoved { name, tag } => write!(f, "Removed tag '{tag}' from '{name}'"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    timestamp: DateTime<Local>,
+    user: String,
+    command: String,
+    changes: Vec<Change>,
+}
+impl HistoryEntry {
+    fn new(changes: Vec<Change>) -> Self {
+        Self {
+            timestamp: Local::now(),
+            user: env::var(USER_ENV).unwrap_or_default(),
+            command: env::args().skip(1).collect::<Vec<_>>().join(" "),
+            changes,
+        }
+    }
+    pub const fn timestamp(&self) -> &DateTime<Local> {
+        &self.timestamp
+    }
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+}
+
+/// The state of a single mod, as far as the history is concerned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ModSnapshot {
+    name: String,
+    priority: isize,
+    state: ModState,
+    version: Option<String>,
+    tags: Vec<String>,
+}
+impl From<&Manifest> for ModSnapshot {
+    fn from(md: &Manifest) -> Self {
+        Self {
+            name: md.name().to_owned(),
+            priority: md.priority(),
+            state: md.mod_state(),
+            version: md.version().map(ToOwned::to_owned),
+            tags: md.tags().to_vec(),
+        }
+    }
+}
+
+/// The mod-list, keyed by the (stable) manifest directory of every mod.
+struct Snapshot(BTreeMap<Utf8PathBuf, ModSnapshot>);
+impl Snapshot {
+    fn take(cache_dir: &Utf8Path) -> Result<Self> {
+        Ok(Self(
+            Vec::gather_mods(cache_dir)?
+                .iter()
+                .map(|md| (md.manifest_dir().to_owned(), ModSnapshot::from(md)))
+                .collect(),
+        ))
+    }
+    fn changes(&self, after: &Self) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        for (dir, old) in &self.0 {
+            let Some(new) = after.0.get(dir) else {
+                changes.push(Change::Removed {
+                    name: old.name.clone(),
+                });
+                continue;
+            };
+
+            let name = new.name.clone();
+            if old.name != new.name {
+                changes.push(Change::Renamed {
+                    from: old.name.clone(),
+                    to: name.clone(),
+                });
+            }
+            if old.version != new.version {
+                changes.push(Change::Version {
+                    name: name.clone(),
+                    from: old.version.clone(),
+                    to: new.version.clone(),
+                });
+            }
+            if old.priority != new.priority {
+                changes.push(Change::Priority {
+                    name: name.clone(),
+                    from: old.priority,
+                    to: new.priority,
+                });
+            }
+            if old.state != new.state {
+                let name = name.clone();
+                changes.push(match (old.state, new.state) {
+                    (_, ModState::Enabled) => Change::Enabled { name },
+                    (_, ModState::Pinned) => Change::Pinned { name },
+                    (ModState::Pinned, ModState::Disabled) => Change::Unpinned { name },
+                    (_, ModState::Disabled) => Change::Disabled { name },
+                });
+            }
+            for tag in new.tags.iter().filter(|t| !old.tags.contains(t)) {
+                changes.push(Change::TagAdded {
+                    name: name.clone(),
+                    tag: tag.clone(),
+                });
+            }
+            for tag in old.tags.iter().filter(|t| !new.tags.contains(t)) {
+                changes.push(Change::TagRemoved {
+                    name: name.clone(),
+                    tag: tag.clone(),
+                });
+            }
+        }
+
+        for (dir, new) in &after.0 {
+            if !self.0.contains_key(dir) {
+                changes.push(Change::Installed {
+                    name: new.name.clone(),
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+pub fn history_file(settings: &Settings) -> Utf8PathBuf {
+    settings.config_file().with_file_name(HISTORY_FILE)
+}
+
+/// Run `operation`, and record the changes it made to the mod-list in the history.
+///
+/// # Errors
+///
+/// When `operation` fails; a history which cannot be updated is only warned about.
+pub fn record<F: FnOnce() -> Result<()>>(settings: &Settings, operation: F) -> Result<()> {
+    if settings.read_only() {
+        return operation();
+    }
+
+    let before = Snapshot::take(settings.cache_dir());
+    let result = operation();
+
+    // The history should never be the reason an operation fails.
+    if let (Ok(before), Ok(after)) = (before, Snapshot::take(settings.cache_dir())) {
+        let changes = before.changes(&after);
+        if !changes.is_empty() {
+            if let Err(e) = append(&history_file(settings), &HistoryEntry::new(changes)) {
+                log::warn!("Unable to update the history: {e}");
+            }
+        }
+    }
+
+    result
+}
+
+fn append(history_file: &Utf8Path, entry: &HistoryEntry) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_file)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Read the history, oldest entry first.
+///
+/// # Errors
+///
+/// When an entry of the history cannot be read.
+pub fn read_history(settings: &Settings) -> Result<Vec<HistoryEntry>> {
+    let Ok(file) = File::open(history_file(settings)) else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => log::debug!("Skipping malformed history entry: {e}"),
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,201 @@
+//! Translations of the messages shown to the user.
+//!
+//! Messages are looked up by their id in a Fluent catalog. The English catalog is built in;
+//! others are read from `locales/<locale>.ftl` in starmod's data directories, like
+//! `~/.local/share/starmod/locales/de.ftl`. Messages a catalog does not have are shown in
+//! English.
+//!
+//! The help of the commands is in the catalogs too: message `help-<command>-<subcommand>`
+//! holds the help of a command, `help-<command>--<argument>` that of its argument, with the
+//! long help, if any, as their `.long` attribute.
+
+use std::{env, fmt::Display, fs::read_to_string, sync::OnceLock};
+
+use camino::Utf8PathBuf;
+use clap::Command;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+use xdg::BaseDirectories;
+
+const ENGLISH: &str = include_str!("../locales/en.ftl");
+const LOCALES_DIR: &str = "locales";
+const CATALOG_EXTENSION: &str = "ftl";
+// In order of precedence, like gettext.
+const LOCALE_ENVS: [&str; 4] = ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"];
+const HELP_ID: &str = "help";
+const LONG_HELP_ATTRIBUTE: &str = "long";
+
+// Set once at start-up, by `Settings::apply_locale_settings`; English until then.
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+struct Catalog {
+    locale: String,
+    bundle: FluentBundle<FluentResource>,
+}
+impl Catalog {
+    fn parse(locale: &str, contents: &str) -> Option<Self> {
+        let language = locale.parse::<LanguageIdentifier>().ok()?;
+        let resource =
+            FluentResource::try_new(contents.to_owned()).unwrap_or_else(|(resource, errors)| {
+                log::warn!(
+                    "Skipping {} broken message(s) of locale '{locale}'.",
+                    errors.len()
+                );
+                resource
+            });
+
+        let mut bundle = FluentBundle::new_concurrent(vec![language]);
+        // The isolation marks around arguments are not shown well by terminals.
+        bundle.set_use_isolating(false);
+        if let Err(errors) = bundle.add_resource(resource) {
+            log::warn!("Messages of locale '{locale}' are defined twice: {errors:?}");
+        }
+        Some(Self {
+            locale: locale.to_owned(),
+            bundle,
+        })
+    }
+    /// Message `id`, or its `attribute`, with `args` filled in.
+    fn format(&self, id: &str, attribute: Option<&str>, args: &FluentArgs<'_>) -> Option<String> {
+        let message = self.bundle.get_message(id)?;
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute)?.value(),
+            None => message.value()?,
+        };
+        let mut errors = Vec::new();
+        let text = self
+            .bundle
+            .format_pattern(pattern, Some(args), &mut errors)
+            .into_owned();
+        if !errors.is_empty() {
+            log::debug!("Message '{id}' of locale '{}': {errors:?}", self.locale);
+        }
+        Some(text)
+    }
+}
+
+fn english() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| Catalog::parse("en", ENGLISH).expect("'en' is a valid locale"))
+}
+
+/// Use the catalog of `locale`, or of the locale in the environment; the catalog of
+/// 'de_DE.UTF-8' is 'de-DE.ftl', or else 'de.ftl'.
+pub fn select_locale(app_name: &str, locale: Option<&str>) {
+    let locale = locale.map(ToOwned::to_owned).or_else(|| {
+        LOCALE_ENVS.iter().find_map(|key| {
+            env::var(key)
+                .ok()
+                // LANGUAGE is a list of locales; only the first is used.
+                .and_then(|v| v.split(':').next().map(ToOwned::to_owned))
+                .filter(|v| !v.is_empty())
+        })
+    });
+    let Some(locale) = locale else {
+        return;
+    };
+
+    // 'de_DE.UTF-8@euro' is 'de-DE'.
+    let locale = locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    let language = locale.split('-').next().unwrap_or_default();
+    if matches!(language, "" | "C" | "POSIX" | "en") {
+        return;
+    }
+
+    let Ok(xdg_base) = BaseDirectories::with_prefix(app_name) else {
+        return;
+    };
+    let catalog = [locale.as_str(), language].into_iter().find_map(|name| {
+        let file = format!("{LOCALES_DIR}/{name}.{CATALOG_EXTENSION}");
+        let path = Utf8PathBuf::try_from(xdg_base.find_data_file(file)?).ok()?;
+        log::debug!("Reading the messages of '{name}' from '{path}'.");
+        read_to_string(path)
+            .ok()
+            .and_then(|contents| Catalog::parse(name, &contents))
+    });
+    match catalog {
+        Some(catalog) => {
+            // Only the first call picks the catalog.
+            let _ = CATALOG.set(catalog);
+        }
+        None => log::debug!("No messages for locale '{locale}'; using English."),
+    }
+}
+
+/// The locale of the messages; 'en' unless a catalog for the locale was found.
+pub fn locale() -> &'static str {
+    &CATALOG.get().unwrap_or_else(english).locale
+}
+
+/// Message `id`, or its `attribute`, in the selected locale, or else in English.
+fn lookup(id: &str, attribute: Option<&str>, args: &FluentArgs<'_>) -> Option<String> {
+    CATALOG
+        .get()
+        .and_then(|c| c.format(id, attribute, args))
+        .or_else(|| english().format(id, attribute, args))
+}
+
+/// The message `id`, in the selected locale.
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+/// The message `id`, in the selected locale, with each `{ $name }` replaced by the value of
+/// `name` in `args`.
+pub fn tr_args(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.to_string());
+    }
+    lookup(id, None, &fluent_args).unwrap_or_else(|| {
+        log::debug!("No message with id '{id}'.");
+        id.to_owned()
+    })
+}
+
+/// `cmd`, with the help of it, its arguments and its subcommands in the selected locale;
+/// help without a message is left as it is.
+pub fn localize_command(cmd: Command) -> Command {
+    localize(cmd, HELP_ID)
+}
+
+fn localize(mut cmd: Command, id: &str) -> Command {
+    let no_args = FluentArgs::new();
+    if let Some(about) = lookup(id, None, &no_args) {
+        cmd = cmd.about(about);
+    }
+    if let Some(long_about) = lookup(id, Some(LONG_HELP_ATTRIBUTE), &no_args) {
+        cmd = cmd.long_about(long_about);
+    }
+
+    let args = cmd
+        .get_arguments()
+        .map(|arg| arg.get_id().clone())
+        .collect::<Vec<_>>();
+    for arg in args {
+        let arg_id = format!("{id}--{arg}");
+        cmd = cmd.mut_arg(arg, |mut arg| {
+            if let Some(help) = lookup(&arg_id, None, &no_args) {
+                arg = arg.help(help);
+            }
+            if let Some(long_help) = lookup(&arg_id, Some(LONG_HELP_ATTRIBUTE), &no_args) {
+                arg = arg.long_help(long_help);
+            }
+            arg
+        });
+    }
+
+    let subcommands = cmd
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_owned())
+        .collect::<Vec<_>>();
+    for name in subcommands {
+        let sub_id = format!("{id}-{name}");
+        cmd = cmd.mut_subcommand(name, |sub| localize(sub, &sub_id));
+    }
+    cmd
+}
@@ -0,0 +1,112 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How to merge a key which is set by more than one ini file.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+pub enum IniMergeStrategy {
+    /// Use the value of the mod with the highest priority; like deploying, but per key.
+    #[default]
+    HighestPriority,
+    /// Keep the value of the mod with the lowest priority.
+    LowestPriority,
+}
+impl Display for IniMergeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_possible_value()
+            .map_or(Ok(()), |v| f.write_str(v.get_name()))
+    }
+}
+
+/// The sections and keys of an ini file, in the order they were first seen.
+/// Comments are dropped; section and key names are compared case-insensitively.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ini {
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+impl Ini {
+    pub fn parse(contents: &str) -> Self {
+        let mut ini = Self::default();
+        let mut section = String::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                name.trim().clone_into(&mut section);
+                ini.section_mut(&section);
+            } else if let Some((key, value)) = line.split_once('=') {
+                ini.set(&section, key.trim(), value.trim(), true);
+            } else {
+                log::debug!("Ignoring ini line without a key: '{line}'");
+            }
+        }
+
+        ini
+    }
+    /// Add the keys of `other`, a file of a mod with a higher priority than those merged so far.
+    pub fn merge(&mut self, other: &Self, strategy: IniMergeStrategy) {
+        let overwrite = strategy == IniMergeStrategy::HighestPriority;
+        for (section, keys) in &other.sections {
+            self.section_mut(section);
+            for (key, value) in keys {
+                self.set(section, key, value, overwrite);
+            }
+        }
+    }
+    /// The value of `key` in `section`, both compared case-insensitively.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .filter(|(s, _)| s.eq_ignore_ascii_case(section))
+            .flat_map(|(_, keys)| keys)
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+    fn section_mut(&mut self, section: &str) -> &mut Vec<(String, String)> {
+        let idx = if let Some(idx) = self
+            .sections
+            .iter()
+            .position(|(s, _)| s.eq_ignore_ascii_case(section))
+        {
+            idx
+        } else {
+            self.sections.push((section.to_owned(), Vec::new()));
+            self.sections.len() - 1
+        };
+        &mut self.sections[idx].1
+    }
+    fn set(&mut self, section: &str, key: &str, value: &str, overwrite: bool) {
+        let keys = self.section_mut(section);
+        if let Some((_, v)) = keys.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+            if overwrite {
+                value.clone_into(v);
+            }
+        } else {
+            keys.push((key.to_owned(), value.to_owned()));
+        }
+    }
+}
+impl Display for Ini {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+        for (section, keys) in &self.sections {
+            // Keys before the first section header belong to the unnamed section.
+            if !section.is_empty() {
+                if !first {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[{section}]")?;
+            }
+            for (key, value) in keys {
+                writeln!(f, "{key}={value}")?;
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
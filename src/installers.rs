@@ -1,25 +1,39 @@
 use thiserror::Error;
 
+use crate::i18n::tr_args;
+
 pub mod custom;
 pub mod data;
 pub mod fomod;
 pub mod label;
 pub mod loader;
+pub mod plugin;
+pub mod script;
 
 // These are existing directories in the Starfield game dir
 // Ensure we use the same casing to avoid multiple similar directories.
 pub const DATA_DIR_NAME: &str = "Data";
 pub const TEXTURES_DIR_NAME: &str = "Textures";
+// Relative to 'Data'; in lower-case, like the destinations of data mods.
+pub const SFSE_PLUGINS_DIR_NAME: &str = "sfse/plugins";
+// Files which belong in 'Data' itself.
+pub const DATA_EXTENSIONS: &[&str] = &["esm", "esp", "esl", "ba2"];
 
 #[derive(Error, Debug)]
 pub enum InstallerError {
     #[allow(unused)]
-    #[error("the mod {0} has unmet dependencies.")]
+    #[error("{}", tr_args("error-installer-dependencies-not-met", &[("name", .0)]))]
     DependenciesNotMet(String),
-    #[error("the mod {0} has multiple data directories.")]
+    #[error("{}", tr_args("error-installer-multiple-data-directories", &[("name", .0)]))]
     MultipleDataDirectories(String),
-    #[error("the installer of mod {0} has been cancelled.")]
+    #[error("{}", tr_args("error-installer-installer-cancelled", &[("name", .0)]))]
     InstallerCancelled(String),
+    #[error("{}", tr_args("error-installer-no-installer-script", &[("name", .0)]))]
+    NoInstallerScript(String),
+    #[error("{}", tr_args("error-installer-script-failed", &[("script", .0), ("reason", .1)]))]
+    ScriptFailed(String, String),
+    #[error("{}", tr_args("error-installer-invalid-mapping", &[("script", .0), ("reason", .1)]))]
+    InvalidMapping(String, String),
 }
 
 pub mod stdin {
@@ -9,6 +9,15 @@ use crate::{
     mods::ModKind,
 };
 
+/// Install the files of the archive in `name` as they are, with lower-cased destinations.
+///
+/// # Errors
+///
+/// When the archive directory cannot be read, or the manifest cannot be written.
+///
+/// # Panics
+///
+/// When the name of a file cannot be read, which the walk of the archive never yields.
 pub fn create_custom_manifest(
     mod_kind: ModKind,
     cache_dir: &Utf8Path,
@@ -37,7 +46,7 @@ pub fn create_custom_manifest(
 
             let destination = source.to_string().to_lowercase();
 
-            files.push(InstallFile::new(source, &destination));
+            files.extend(InstallFile::new(source, &destination));
         }
     }
 
@@ -1,3 +1,5 @@
+use std::ffi::OsStr;
+
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
 
@@ -10,8 +12,48 @@ use crate::{
     utils::AddExtension,
 };
 
-use super::InstallerError;
+use super::{InstallerError, DATA_EXTENSIONS};
+
+/// True when the archive has a 'Data' directory or files which belong in it, as deep as
+/// `create_data_manifest` looks for them; without those, it installs the archive as it is.
+///
+/// # Errors
+///
+/// When the archive directory cannot be read.
+pub fn has_data_layout(archive_dir: &Utf8Path) -> Result<bool> {
+    for entry in WalkDir::new(archive_dir)
+        .min_depth(1)
+        .max_depth(5)
+        .follow_links(false)
+        .same_file_system(true)
+    {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type().is_dir() && path.file_name() == Some(OsStr::new("data")) {
+            return Ok(true);
+        }
+        if entry.file_type().is_file()
+            && path
+                .extension()
+                .is_some_and(|e| DATA_EXTENSIONS.iter().any(|x| e == OsStr::new(x)))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
 
+/// Install the 'Data' directory of the archive `name`, or the directory its plugins are in.
+///
+/// # Errors
+///
+/// When the archive has more than one of those, its directory cannot be read, or the manifest
+/// cannot be written.
+///
+/// # Panics
+///
+/// When a file found in the archive has no name or parent directory, which the walk of the archive
+/// never yields.
 pub fn create_data_manifest(
     mod_kind: ModKind,
     cache_dir: &Utf8Path,
@@ -86,11 +128,11 @@ pub fn create_data_manifest(
 
             // Avoid '*.esp' files for they should not be used with Starfield.
             // TODO: FIXME: NOTE: disable this somehow for other games....
-            if entry_path.is_file() && entry_path.extension().unwrap() == "esp" {
+            if entry_path.is_file() && entry_path.extension().unwrap_or_default() == "esp" {
                 Err(InstallerError::MultipleDataDirectories(name.to_string()))?;
             }
 
-            if entry_path.is_file() && entry_path.extension().unwrap() == "esm" {
+            if entry_path.is_file() && entry_path.extension().unwrap_or_default() == "esm" {
                 if data_path.is_none() {
                     log::debug!("Setting Esm dir to {}.", entry_path.display());
                     data_path = Some(
@@ -122,7 +164,7 @@ pub fn create_data_manifest(
             let entry = entry?;
             let entry_path = entry.path();
 
-            if entry_path.is_file() && entry_path.extension().unwrap() == "esl" {
+            if entry_path.is_file() && entry_path.extension().unwrap_or_default() == "esl" {
                 if data_path.is_none() {
                     log::debug!("Setting Esl dir to {}.", entry_path.display());
                     data_path = Some(
@@ -175,7 +217,7 @@ pub fn create_data_manifest(
                 .map(std::borrow::ToOwned::to_owned)
                 .unwrap_or(destination);
 
-            files.push(InstallFile::new(source, &destination));
+            files.extend(InstallFile::new(source, &destination));
         }
     }
 
@@ -7,11 +7,13 @@ use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
 use fomod::{Config, Dependency, DependencyOperator, FlagDependency, Info};
 use read_stdin::prompt_until_ok;
+use serde::Deserialize;
 use std::{collections::HashSet, fs::File, io::Read};
 use walkdir::WalkDir;
 
 use crate::{
     dmodman::{DmodMan, DMODMAN_EXTENSION},
+    i18n::{tr, tr_args},
     installers::{
         stdin::{Input, InputWithDone},
         InstallerError,
@@ -21,10 +23,67 @@ use crate::{
     utils::AddExtension,
 };
 
+/// Choices made in a FOMOD installer before, in the format Nexus collections record them in.
+///
+/// Groups without recorded choices, or with choices which no longer match the installer,
+/// are asked for as usual.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct FomodChoices {
+    #[serde(default)]
+    options: Vec<StepChoices>,
+}
+#[derive(Clone, Debug, Deserialize)]
+struct StepChoices {
+    name: String,
+    #[serde(default)]
+    groups: Vec<GroupChoices>,
+}
+#[derive(Clone, Debug, Deserialize)]
+struct GroupChoices {
+    name: String,
+    #[serde(default)]
+    choices: Vec<PluginChoice>,
+}
+#[derive(Clone, Debug, Deserialize)]
+struct PluginChoice {
+    name: String,
+}
+impl FomodChoices {
+    /// The indices into `plugins` recorded for group `group` of step `step`.
+    fn recorded(&self, step: &str, group: &str, plugins: &[fomod::Plugin]) -> Option<Vec<usize>> {
+        let group = self
+            .options
+            .iter()
+            .filter(|s| s.name == step)
+            .flat_map(|s| s.groups.iter())
+            .find(|g| g.name == group)?;
+
+        group
+            .choices
+            .iter()
+            .map(|c| plugins.iter().position(|p| p.name == c.name))
+            .collect()
+    }
+}
+
+/// Asks the user which of the plugins of a group to install.
+type SelectFn = dyn Fn(&str, &[fomod::Plugin]) -> Result<Vec<usize>>;
+
+/// Install the archive `name` with its FOMOD installer, asking the user for the choices it offers,
+/// or taking them from `choices`.
+///
+/// # Errors
+///
+/// When the installer cannot be read, the user cancels it, or the manifest cannot be written.
+///
+/// # Panics
+///
+/// When a file which conflicts is not one of the files, which the list of conflicts never holds.
 pub fn create_fomod_manifest(
     mod_kind: ModKind,
     cache_dir: &Utf8Path,
     mod_dir: &Utf8Path,
+    choices: Option<&FomodChoices>,
 ) -> Result<Manifest> {
     let mut files = Vec::new();
     let mut archive_dir = Utf8PathBuf::from(cache_dir);
@@ -78,48 +137,52 @@ pub fn create_fomod_manifest(
     println!();
     println!();
 
-    println!("FoMod Installer for {name}");
+    println!("{}", tr_args("fomod-installer", &[("name", &name)]));
 
     let mut condition_flags = HashSet::new();
 
     for is in config.install_steps.vec_sorted() {
-        println!("Install Step: {}", is.name);
+        println!("{}", tr_args("fomod-install-step", &[("name", &is.name)]));
         for g in is.optional_file_groups.vec_sorted() {
             println!();
-            println!("Group Name: {}", g.name);
+            println!("{}", tr_args("fomod-group", &[("name", &g.name)]));
+
+            let choose = |plugins: &[fomod::Plugin], select: &SelectFn| -> Result<Vec<usize>> {
+                if let Some(recorded) = choices.and_then(|c| c.recorded(&is.name, &g.name, plugins))
+                {
+                    log::info!(
+                        "{}",
+                        tr_args("fomod-recorded-choices", &[("group", &g.name)])
+                    );
+                    return Ok(recorded);
+                }
+                select(&name, plugins)
+            };
 
-            match g.plugins {
+            let (chosen, plugins) = match g.plugins {
                 fomod::GroupType::SelectAtLeastOne(plugins) => {
                     let plugins = plugins.vec_sorted();
-                    let choices: Vec<usize> = select_at_least_one(&name, &plugins)?;
-                    files.extend(fetch_plugin_files(&choices, &plugins, &archive_dir)?);
-                    condition_flags.extend(fetch_plugin_flags(&choices, &plugins));
+                    (choose(&plugins, &select_at_least_one)?, plugins)
                 }
                 fomod::GroupType::SelectAtMostOne(plugins) => {
                     let plugins = plugins.vec_sorted();
-                    let choices: Vec<usize> = select_at_most_one(&name, &plugins)?;
-                    files.extend(fetch_plugin_files(&choices, &plugins, &archive_dir)?);
-                    condition_flags.extend(fetch_plugin_flags(&choices, &plugins));
+                    (choose(&plugins, &select_at_most_one)?, plugins)
                 }
                 fomod::GroupType::SelectExactlyOne(plugins) => {
                     let plugins = plugins.vec_sorted();
-                    let choices: Vec<usize> = select_exactly_one(&name, &plugins)?;
-                    files.extend(fetch_plugin_files(&choices, &plugins, &archive_dir)?);
-                    condition_flags.extend(fetch_plugin_flags(&choices, &plugins));
+                    (choose(&plugins, &select_exactly_one)?, plugins)
                 }
                 fomod::GroupType::SelectAll(plugins) => {
                     let plugins = plugins.vec_sorted();
-                    let choices: Vec<usize> = select_all(&name, &plugins);
-                    files.extend(fetch_plugin_files(&choices, &plugins, &archive_dir)?);
-                    condition_flags.extend(fetch_plugin_flags(&choices, &plugins));
+                    (choose(&plugins, &|n, p| Ok(select_all(n, p)))?, plugins)
                 }
                 fomod::GroupType::SelectAny(plugins) => {
                     let plugins = plugins.vec_sorted();
-                    let choices: Vec<usize> = select_any(&name, &plugins)?;
-                    files.extend(fetch_plugin_files(&choices, &plugins, &archive_dir)?);
-                    condition_flags.extend(fetch_plugin_flags(&choices, &plugins));
+                    (choose(&plugins, &select_any)?, plugins)
                 }
             };
+            files.extend(fetch_plugin_files(&chosen, &plugins, &archive_dir)?);
+            condition_flags.extend(fetch_plugin_flags(&chosen, &plugins));
         }
     }
 
@@ -190,7 +253,7 @@ impl FomodInstallVecExt for Vec<fomod::FileTypeEnum> {
                     let destination = f.destination.clone().unwrap_or_else(String::new);
                     let source = Utf8PathBuf::from(f.source.clone().to_lowercase());
 
-                    files.push(InstallFile::new(source, &destination));
+                    files.extend(InstallFile::new(source, &destination));
                 }
                 fomod::FileTypeEnum::Folder(f) => {
                     let mut f = f.clone();
@@ -227,7 +290,7 @@ impl FomodInstallVecExt for Vec<fomod::FileTypeEnum> {
                                 source.strip_prefix(&f.source).unwrap()
                             );
 
-                            files.push(InstallFile::new(source, &destination));
+                            files.extend(InstallFile::new(source, &destination));
                         }
                     }
                 }
@@ -268,6 +331,10 @@ fn fetch_plugin_files(
     Ok(files)
 }
 
+fn select_prompt() -> String {
+    format!("{} ", tr("fomod-prompt"))
+}
+
 fn select_all(
     _mod_name: &str,
     plugins: &[fomod::Plugin],
@@ -287,15 +354,15 @@ fn select_all(
 
 fn select_exactly_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<usize>> {
     println!();
-    println!("Please select one of the following: ");
+    println!("{}", tr("fomod-select-exactly-one"));
     for (i, p) in plugins.iter().enumerate() {
         println!("{}) {}: {}", i, p.name, p.description);
     }
-    println!("E) Exit Installer");
+    println!("{}", tr("fomod-exit"));
     println!();
 
     let choice: u8 = loop {
-        let input: Input = prompt_until_ok("Select : ");
+        let input: Input = prompt_until_ok(&select_prompt());
         match input {
             Input::Exit => {
                 return Err(InstallerError::InstallerCancelled(mod_name.to_string()).into())
@@ -313,18 +380,18 @@ fn select_exactly_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<u
 
 fn select_at_least_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<usize>> {
     println!();
-    println!("Please select at-least one of the following: ");
+    println!("{}", tr("fomod-select-at-least-one"));
     for (i, p) in plugins.iter().enumerate() {
         println!("{}) {}: {}", i, p.name, p.description);
     }
-    println!("D) Done with the selection");
-    println!("E) Exit Installer");
+    println!("{}", tr("fomod-done"));
+    println!("{}", tr("fomod-exit"));
     println!();
 
     let mut selected = false;
     let mut choices = Vec::with_capacity(4);
     loop {
-        let input: InputWithDone = prompt_until_ok("Select : ");
+        let input: InputWithDone = prompt_until_ok(&select_prompt());
         match input {
             InputWithDone::Input(i) => match i {
                 Input::Digit(d) => {
@@ -332,7 +399,7 @@ fn select_at_least_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<
                         choices.push(usize::from(d));
                         selected = true;
                     } else {
-                        println!("Invalid choice..");
+                        println!("{}", tr("fomod-invalid-choice"));
                     }
                 }
                 Input::Exit => {
@@ -343,7 +410,7 @@ fn select_at_least_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<
                 if selected {
                     break;
                 }
-                println!("Please select at-least one option.");
+                println!("{}", tr("fomod-at-least-one-required"));
             }
         }
     }
@@ -353,23 +420,23 @@ fn select_at_least_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<
 
 fn select_at_most_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<usize>> {
     println!();
-    println!("Please select at-most one of the following: ");
+    println!("{}", tr("fomod-select-at-most-one"));
     for (i, p) in plugins.iter().enumerate() {
         println!("{}) {}: {}", i, p.name, p.description);
     }
-    println!("D) Done with the selection");
-    println!("E) Exit Installer");
+    println!("{}", tr("fomod-done"));
+    println!("{}", tr("fomod-exit"));
     println!();
 
     let choice: Option<u8> = loop {
-        let input: InputWithDone = prompt_until_ok("Select : ");
+        let input: InputWithDone = prompt_until_ok(&select_prompt());
         match input {
             InputWithDone::Input(i) => match i {
                 Input::Digit(d) => {
                     if (d as usize) < plugins.len() {
                         break Some(d);
                     }
-                    println!("Invalid choice..");
+                    println!("{}", tr("fomod-invalid-choice"));
                 }
                 Input::Exit => {
                     return Err(InstallerError::InstallerCancelled(mod_name.to_string()).into())
@@ -386,17 +453,17 @@ fn select_at_most_one(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<u
 
 fn select_any(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<usize>> {
     println!();
-    println!("Please select any of the following: ");
+    println!("{}", tr("fomod-select-any"));
     for (i, p) in plugins.iter().enumerate() {
         println!("{}) {}: {}", i, p.name, p.description);
     }
-    println!("D) Done with the selection");
-    println!("E) Exit Installer");
+    println!("{}", tr("fomod-done"));
+    println!("{}", tr("fomod-exit"));
     println!();
 
     let mut choices = Vec::with_capacity(4);
     loop {
-        let input: InputWithDone = prompt_until_ok("Select : ");
+        let input: InputWithDone = prompt_until_ok(&select_prompt());
         match input {
             InputWithDone::Input(i) => match i {
                 Input::Digit(d) => {
@@ -404,7 +471,7 @@ fn select_any(mod_name: &str, plugins: &[fomod::Plugin]) -> Result<Vec<usize>> {
                     if d < plugins.len() {
                         choices.push(d);
                     } else {
-                        println!("Invalid choice..");
+                        println!("{}", tr("fomod-invalid-choice"));
                     }
                 }
                 Input::Exit => {
@@ -1,23 +1,36 @@
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
 
+use inquire::MultiSelect;
 use walkdir::WalkDir;
 
 use crate::{
     dmodman::{DmodMan, DMODMAN_EXTENSION},
+    i18n::tr_args,
     manifest::{install_file::InstallFile, Manifest},
     mods::ModKind,
+    settings::default_page_size,
     utils::AddExtension,
 };
 
+// Files which are deployed into the game's root directory.
+const ROOT_EXTENSIONS: &[&str] = &["exe", "dll"];
+
+/// Install the script extender in `mod_dir`, with its executable and dlls in the game's root
+/// directory.
+///
+/// # Errors
+///
+/// When the archive directory cannot be read, or the manifest cannot be written.
+///
+/// # Panics
+///
+/// When the name of a file cannot be read, which the walk of the archive never yields.
 pub fn create_loader_manifest(
     mod_kind: ModKind,
     cache_dir: &Utf8Path,
     mod_dir: &Utf8Path,
 ) -> Result<Manifest> {
-    let mut files = Vec::new();
-    let disabled_files = Vec::new();
-
     let archive_dir = cache_dir.join(mod_dir);
 
     let dmodman = archive_dir.add_extension(DMODMAN_EXTENSION);
@@ -29,25 +42,52 @@ pub fn create_loader_manifest(
         .same_file_system(true)
         .contents_first(false);
 
+    let mut candidates = Vec::new();
     for entry in walker {
         let entry = entry?;
         let entry_path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
 
-        if entry_path.is_file() {
-            if let Some(ext) = entry_path.extension() {
-                match ext {
-                    "dll" | "exe" => {
-                        let file = entry_path.strip_prefix(&archive_dir)?.to_path_buf();
-
-                        files.push(InstallFile::new_raw(
-                            file.clone(),
-                            file.file_name().unwrap().to_string(),
-                        ));
-                    }
-                    _ => (),
-                }
-            }
+        if entry_path.is_file()
+            && matches!(entry_path.extension(), Some(ext) if ROOT_EXTENSIONS.contains(&ext))
+        {
+            candidates.push(entry_path.strip_prefix(&archive_dir)?.to_path_buf());
+        }
+    }
+    // Files closest to the root of the archive are the most likely to be the loader itself.
+    candidates.sort_by(|a, b| {
+        a.components()
+            .count()
+            .cmp(&b.components().count())
+            .then_with(|| a.cmp(b))
+    });
+
+    let selected = select_root_files(mod_dir, &candidates)?;
+
+    let mut files = Vec::new();
+    let mut disabled_files = Vec::new();
+    for (idx, file) in candidates.into_iter().enumerate() {
+        let isf = InstallFile::new_raw(file.clone(), file.file_name().unwrap().to_string());
+        if selected.contains(&idx) {
+            files.push(isf);
+        } else {
+            disabled_files.push(isf);
+        }
+    }
+
+    let has_extension = |ext| {
+        files
+            .iter()
+            .any(|isf: &InstallFile| isf.source().extension() == Some(ext))
+    };
+    match (has_extension("exe"), has_extension("dll")) {
+        (true, true) => (),
+        (true, false) => {
+            log::info!("Loader '{mod_dir}' has no dll; only installing its executable.");
+        }
+        (false, true) => {
+            log::info!("Loader '{mod_dir}' has no executable; only installing its dll(s).");
         }
+        (false, false) => log::warn!("Loader '{mod_dir}' has no executable or dll to install."),
     }
 
     let mut version = None;
@@ -71,3 +111,39 @@ pub fn create_loader_manifest(
         mod_kind,
     ))
 }
+
+/// Decide which of the `candidates` to install.
+/// When it is unclear, because the archive has multiple executables or files with the same name,
+/// the user is asked; by default the first file of each name is installed.
+fn select_root_files(mod_dir: &Utf8Path, candidates: &[Utf8PathBuf]) -> Result<Vec<usize>> {
+    let mut defaults = Vec::new();
+    for (idx, file) in candidates.iter().enumerate() {
+        if !defaults
+            .iter()
+            .any(|&d: &usize| candidates[d].file_name() == file.file_name())
+        {
+            defaults.push(idx);
+        }
+    }
+
+    let executables = candidates
+        .iter()
+        .filter(|f| f.extension() == Some("exe"))
+        .count();
+    if executables <= 1 && defaults.len() == candidates.len() {
+        return Ok(defaults);
+    }
+
+    let message = tr_args("loader-select-files", &[("mod_dir", &mod_dir)]);
+    let selected = MultiSelect::new(&message, candidates.to_vec())
+        .with_default(&defaults)
+        .with_page_size(default_page_size())
+        .prompt()?;
+
+    Ok(candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| selected.contains(f))
+        .map(|(idx, _)| idx)
+        .collect())
+}
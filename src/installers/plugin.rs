@@ -0,0 +1,133 @@
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::{
+    dmodman::{DmodMan, DMODMAN_EXTENSION},
+    manifest::{install_file::InstallFile, Manifest},
+    mods::ModKind,
+    utils::AddExtension,
+};
+
+use super::{DATA_EXTENSIONS, SFSE_PLUGINS_DIR_NAME};
+
+const PLUGIN_EXTENSION: &str = "dll";
+// Files next to a plugin dll which configure it.
+const PLUGIN_CONFIG_EXTENSIONS: &[&str] = &["ini", "toml", "json", "yaml"];
+
+/// True when the archive ships SFSE plugin dlls, but not in their 'Data/SFSE/Plugins' directory
+/// and without game plugins or archives; those are installed by the data installer instead.
+///
+/// # Errors
+///
+/// When the archive directory cannot be read.
+pub fn is_sfse_plugin_mod(archive_dir: &Utf8Path) -> Result<bool> {
+    let mut loose_plugin = false;
+
+    for entry in WalkDir::new(archive_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .same_file_system(true)
+    {
+        let entry = entry?;
+        let entry_path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
+        let extension = entry_path.extension().unwrap_or_default();
+
+        if DATA_EXTENSIONS.contains(&extension) {
+            return Ok(false);
+        }
+        if extension == PLUGIN_EXTENSION
+            && !entry_path
+                .strip_prefix(archive_dir)?
+                .as_str()
+                .contains(SFSE_PLUGINS_DIR_NAME)
+        {
+            loose_plugin = true;
+        }
+    }
+
+    Ok(loose_plugin)
+}
+
+/// Install the SFSE plugin dlls in `name` into 'Data/SFSE/Plugins', with the files next to them.
+///
+/// # Errors
+///
+/// When the archive directory cannot be read, or the manifest cannot be written.
+///
+/// # Panics
+///
+/// When the name of a file cannot be read, which the walk of the archive never yields.
+pub fn create_plugin_manifest(
+    mod_kind: ModKind,
+    cache_dir: &Utf8Path,
+    mod_dir: &Utf8Path,
+) -> Result<Manifest> {
+    let mut files = Vec::new();
+    let mut disabled_files = Vec::new();
+
+    let archive_dir = cache_dir.join(mod_dir);
+
+    let dmodman = archive_dir.add_extension(DMODMAN_EXTENSION);
+
+    let walker = WalkDir::new(&archive_dir)
+        .min_depth(1)
+        .max_depth(usize::MAX)
+        .follow_links(false)
+        .same_file_system(true)
+        .contents_first(false);
+
+    let mut entries = Vec::new();
+    for entry in walker {
+        let entry = entry?;
+        let entry_path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
+        if entry_path.is_file() {
+            entries.push(entry_path.strip_prefix(&archive_dir)?.to_path_buf());
+        }
+    }
+
+    // Plugins, and the configuration files next to them, all go into 'Data/SFSE/Plugins'.
+    let plugin_dirs = entries
+        .iter()
+        .filter(|f| f.extension() == Some(PLUGIN_EXTENSION))
+        .map(|f| f.parent().map(Utf8Path::to_path_buf).unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    for file in entries {
+        let extension = file.extension().unwrap_or_default();
+        let in_plugin_dir = plugin_dirs
+            .iter()
+            .any(|d| file.parent().is_some_and(|p| p == d));
+
+        if extension == PLUGIN_EXTENSION
+            || (in_plugin_dir && PLUGIN_CONFIG_EXTENSIONS.contains(&extension))
+        {
+            let destination = format!("{SFSE_PLUGINS_DIR_NAME}/{}", file.file_name().unwrap());
+            files.extend(InstallFile::new(file, &destination));
+        } else {
+            disabled_files.extend(InstallFile::new(file.clone(), file.as_str()));
+        }
+    }
+
+    let mut version = None;
+    let mut nexus_id = None;
+    let mut name = mod_dir.to_string();
+    if let Ok(dmodman) = DmodMan::try_from(dmodman.as_path()) {
+        nexus_id = Some(dmodman.mod_id());
+        version = dmodman.version();
+        name = dmodman.name();
+    }
+
+    Ok(Manifest::new(
+        cache_dir,
+        mod_dir,
+        name.clone(),
+        name,
+        nexus_id,
+        version,
+        files,
+        disabled_files,
+        mod_kind,
+    ))
+}
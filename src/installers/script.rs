@@ -0,0 +1,183 @@
+//! Installers for the mods starmod cannot install itself, as scripts of the user.
+//!
+//! For an extracted archive starmod does not recognise, every executable in the installer
+//! scripts directory is asked, in order of name, whether it installs it: `<script> detect <dir>`
+//! exits with 0 when it does. The first one which does is run as
+//! `<script> install <dir> <mapping>`, and writes a json object to `<mapping>` with the
+//! destination of every file it installs by its path in `<dir>`, like
+//! `{"plugins/foo.esm": "Data/foo.esm"}`. Files it leaves out are disabled.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::{read, remove_file},
+    os::unix::fs::PermissionsExt,
+    process::{Command, Stdio},
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use walkdir::WalkDir;
+
+use crate::{
+    dmodman::{DmodMan, DMODMAN_EXTENSION},
+    manifest::{
+        install_file::{clean_components, normalize_destination, InstallFile},
+        Manifest,
+    },
+    mods::ModKind,
+    settings::installer_scripts_dir,
+    utils::AddExtension,
+};
+
+use super::InstallerError;
+
+const DETECT_ARG: &str = "detect";
+const INSTALL_ARG: &str = "install";
+const MAPPING_EXTENSION: &str = "mapping.json";
+
+/// The executables in `dir`, in order of name.
+pub fn installer_scripts(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let mut scripts = dir
+        .read_dir_utf8()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(camino::Utf8DirEntry::into_path)
+        .filter(|path| {
+            path.metadata()
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        })
+        .collect::<Vec<_>>();
+    scripts.sort_unstable();
+    scripts
+}
+
+/// Whether `script` installs the extracted archive in `archive_dir`.
+pub fn handles(script: &Utf8Path, archive_dir: &Utf8Path) -> bool {
+    Command::new(script)
+        .arg(DETECT_ARG)
+        .arg(archive_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// The first of the user's installer scripts which installs the archive `name`.
+pub fn find_installer_script(cache_dir: &Utf8Path, name: &Utf8Path) -> Option<Utf8PathBuf> {
+    let archive_dir = cache_dir.join(name);
+    installer_scripts(installer_scripts_dir()?)
+        .into_iter()
+        .find(|script| handles(script, &archive_dir))
+}
+
+/// Install the archive `name` with the first installer script which handles it.
+///
+/// # Errors
+///
+/// When no script handles it, or `run_installer_script` fails.
+pub fn create_script_manifest(
+    mod_kind: ModKind,
+    cache_dir: &Utf8Path,
+    name: &Utf8Path,
+) -> Result<Manifest> {
+    let script = find_installer_script(cache_dir, name)
+        .ok_or_else(|| InstallerError::NoInstallerScript(name.to_string()))?;
+    run_installer_script(&script, mod_kind, cache_dir, name)
+}
+
+/// Install the archive `name` with `script`, and turn the files it maps into a manifest.
+///
+/// # Errors
+///
+/// When the script fails, or maps a file which is not in the archive or a destination outside of
+/// the game directory.
+pub fn run_installer_script(
+    script: &Utf8Path,
+    mod_kind: ModKind,
+    cache_dir: &Utf8Path,
+    name: &Utf8Path,
+) -> Result<Manifest> {
+    let archive_dir = cache_dir.join(name);
+    let mapping_file = archive_dir.add_extension(MAPPING_EXTENSION);
+
+    log::info!("Installing '{name}' with installer script '{script}'.");
+    let status = Command::new(script)
+        .arg(INSTALL_ARG)
+        .arg(&archive_dir)
+        .arg(&mapping_file)
+        .status()?;
+    let contents = read(&mapping_file);
+    let _ = remove_file(&mapping_file);
+    if !status.success() {
+        return Err(InstallerError::ScriptFailed(script.to_string(), status.to_string()).into());
+    }
+    let mapping: BTreeMap<String, String> = serde_json::from_slice(&contents?)
+        .map_err(|e| InstallerError::InvalidMapping(script.to_string(), e.to_string()))?;
+
+    let mut files = Vec::with_capacity(mapping.len());
+    for (source, destination) in mapping {
+        let invalid = |reason: &str| {
+            InstallerError::InvalidMapping(script.to_string(), format!("'{source}' {reason}"))
+        };
+        // Symlinks are not followed, they can point anywhere.
+        let source = Some(source.as_str())
+            .filter(|s| !s.starts_with(['/', '\\']) && !s.split(['/', '\\']).any(|c| c == ".."))
+            .and_then(clean_components)
+            .map(|components| Utf8PathBuf::from(components.join("/")))
+            .filter(|s| {
+                archive_dir
+                    .join(s)
+                    .symlink_metadata()
+                    .is_ok_and(|m| m.is_file())
+            })
+            .ok_or_else(|| invalid("is not a file of the mod"))?;
+        let destination = normalize_destination(&destination)
+            .filter(|d| !d.is_empty())
+            .ok_or_else(|| invalid("is installed outside of the game directory"))?;
+        files.push(InstallFile::new_raw(source, destination));
+    }
+
+    // The files the script left out can be enabled later, like any disabled file.
+    let installed = files
+        .iter()
+        .map(|f| f.source().to_owned())
+        .collect::<HashSet<_>>();
+    let mut disabled_files = Vec::new();
+    for entry in WalkDir::new(&archive_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .same_file_system(true)
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let source = Utf8PathBuf::try_from(entry.into_path())?
+            .strip_prefix(&archive_dir)?
+            .to_owned();
+        if !installed.contains(&source) {
+            let destination = source.to_string();
+            disabled_files.extend(InstallFile::new(source, &destination));
+        }
+    }
+
+    let dmodman = DmodMan::try_from(archive_dir.add_extension(DMODMAN_EXTENSION).as_path()).ok();
+    let nexus_id = dmodman.as_ref().map(DmodMan::mod_id);
+    let version = dmodman.as_ref().and_then(DmodMan::version);
+    let mod_name = dmodman
+        .as_ref()
+        .map_or_else(|| name.to_string(), DmodMan::name);
+
+    Ok(Manifest::new(
+        cache_dir,
+        name,
+        mod_name.clone(),
+        mod_name,
+        nexus_id,
+        version,
+        files,
+        disabled_files,
+        mod_kind,
+    ))
+}
@@ -0,0 +1,86 @@
+#![deny(
+    nonstandard_style,
+    rust_2018_idioms,
+    future_incompatible,
+    unused_extern_crates,
+    unused_import_braces,
+    // unused_results,
+    // unused_qualifications,
+    //warnings,
+    //unused,
+    unsafe_code,
+// missing_docs,
+)]
+#![warn(
+    trivial_casts,
+    trivial_numeric_casts,
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::wildcard_dependencies
+)]
+// Most getters and builders of the library would need the attribute; not worth the noise.
+#![allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+
+//! The mod-management core of starmod, a mod manager for Starfield on Linux/Proton.
+//!
+//! The `starmod` binary is a thin command line interface on top of this library; other tools
+//! can use it to read and change the same mod-list.
+//!
+//! - [`settings`]: the configuration, with the download, cache and game directories.
+//! - [`manifest`]: a mod installed in the cache directory, with its files and state.
+//! - [`mods`]: the mod-list; gathering, enabling and disabling mods.
+//! - [`conflict`]: which mods provide the same files, and which of them wins.
+//! - [`deployment`]: linking the files of the enabled mods into the game directory.
+//! - [`installers`]: turning an extracted archive into a mod.
+//! - [`nexus`]: the Nexus Mods API, to download archives.
+//!
+//! The `commands` and `ui` modules are the command line interface itself; they are only public
+//! for the binary, and are not part of the library's API.
+
+pub mod collection;
+#[doc(hidden)]
+#[allow(
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use
+)]
+pub mod commands;
+pub mod conflict;
+pub mod decompress;
+pub mod deployment;
+pub mod dmodman;
+pub mod errors;
+pub mod filter;
+pub mod game;
+pub mod hash;
+pub mod history;
+pub mod i18n;
+pub mod ini;
+pub mod installers;
+pub mod lint;
+pub mod manifest;
+pub mod masterlist;
+pub mod modlist;
+pub mod mods;
+pub mod nexus;
+pub mod plugin_header;
+pub mod plugin_list;
+pub mod requirements;
+pub mod settings;
+pub mod snapshot;
+pub mod steam;
+pub mod tag;
+#[doc(hidden)]
+#[allow(
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use
+)]
+pub mod ui;
+pub mod userlist;
+pub mod utils;
+pub mod vdf;
+pub mod version;
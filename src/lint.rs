@@ -0,0 +1,203 @@
+//! Rules for the files of a mod, which catch mis-packaged mods before they are enabled.
+
+use std::{collections::HashSet, fmt::Display};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use serde::Serialize;
+
+use crate::{
+    commands::plugins::is_plugin, installers::DATA_DIR_NAME, manifest::Manifest, mods::ModKind,
+};
+
+const ARCHIVE_EXTENSION: &str = "ba2";
+// Files which run programs; data mods have no business shipping them.
+const EXECUTABLE_EXTENSIONS: [&str; 7] = ["exe", "com", "bat", "cmd", "ps1", "scr", "msi"];
+// The game loads the archives of plugin 'foo.esm' when they are named like 'foo - Main.ba2'.
+const ARCHIVE_SEPARATOR: &str = " - ";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// The mod has no files to deploy.
+    EmptyMod,
+    /// A data mod deploys a file outside of the data directory.
+    OutsideData,
+    /// Two files of the mod go to the same destination; only one of them is deployed.
+    DuplicateDestination,
+    /// A data mod ships a program.
+    Executable,
+    /// A plugin of a mod with archives, without an archive named after it.
+    PluginWithoutArchive,
+    /// An archive which is not named after a plugin of the mod; the game does not load it.
+    ArchiveWithoutPlugin,
+}
+impl LintRule {
+    pub const fn severity(self) -> Severity {
+        match self {
+            Self::EmptyMod | Self::DuplicateDestination => Severity::Error,
+            Self::OutsideData
+            | Self::Executable
+            | Self::PluginWithoutArchive
+            | Self::ArchiveWithoutPlugin => Severity::Warning,
+        }
+    }
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::EmptyMod => "the mod has no files to deploy",
+            Self::OutsideData => "a data mod with a file outside of the data directory",
+            Self::DuplicateDestination => "another file of the mod goes here; only one is deployed",
+            Self::Executable => "a program in a data mod",
+            Self::PluginWithoutArchive => "no archive of the mod is named after this plugin",
+            Self::ArchiveWithoutPlugin => {
+                "not named after a plugin of the mod, so the game does not load it"
+            }
+        }
+    }
+}
+impl Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::EmptyMod => "empty-mod",
+            Self::OutsideData => "outside-data",
+            Self::DuplicateDestination => "duplicate-destination",
+            Self::Executable => "executable",
+            Self::PluginWithoutArchive => "plugin-without-archive",
+            Self::ArchiveWithoutPlugin => "archive-without-plugin",
+        })
+    }
+}
+
+/// A rule broken by a mod, with the destination it is about.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    #[serde(rename = "mod")]
+    pub mod_name: String,
+    pub rule: LintRule,
+    pub severity: Severity,
+    pub destination: Option<String>,
+}
+impl Finding {
+    fn new(md: &Manifest, rule: LintRule, destination: Option<&str>) -> Self {
+        Self {
+            mod_name: md.name().to_owned(),
+            rule,
+            severity: rule.severity(),
+            destination: destination.map(ToOwned::to_owned),
+        }
+    }
+}
+
+/// Check the files of `md`, as they are deployed, against every rule.
+///
+/// # Errors
+///
+/// When the files of `md` cannot be listed.
+pub fn lint(md: &Manifest) -> Result<Vec<Finding>> {
+    let destinations = md
+        .files()?
+        .into_iter()
+        .map(|f| f.destination().to_owned())
+        .collect::<Vec<_>>();
+    if destinations.is_empty() {
+        return Ok(vec![Finding::new(md, LintRule::EmptyMod, None)]);
+    }
+
+    let mut findings = Vec::new();
+    let mut seen = HashSet::new();
+    for destination in &destinations {
+        let path = Utf8Path::new(destination);
+        let in_data = path
+            .components()
+            .next()
+            .is_some_and(|c| c.as_str() == DATA_DIR_NAME);
+
+        if !seen.insert(destination.to_lowercase()) {
+            findings.push(Finding::new(
+                md,
+                LintRule::DuplicateDestination,
+                Some(destination),
+            ));
+        }
+        if md.kind() == ModKind::Data && !in_data {
+            findings.push(Finding::new(md, LintRule::OutsideData, Some(destination)));
+        }
+        if md.kind() == ModKind::Data
+            && path.extension().is_some_and(|e| {
+                EXECUTABLE_EXTENSIONS
+                    .iter()
+                    .any(|x| x.eq_ignore_ascii_case(e))
+            })
+        {
+            findings.push(Finding::new(md, LintRule::Executable, Some(destination)));
+        }
+    }
+    findings.extend(lint_archives(md, &destinations));
+    Ok(findings)
+}
+
+/// The plugins and archives in the data directory should be named after each other.
+fn lint_archives(md: &Manifest, destinations: &[String]) -> Vec<Finding> {
+    let in_data_root = |d: &&String| {
+        Utf8Path::new(d.as_str())
+            .parent()
+            .is_some_and(|p| p.as_str() == DATA_DIR_NAME)
+    };
+    let archives = destinations
+        .iter()
+        .filter(in_data_root)
+        .filter(|d| {
+            Utf8Path::new(d.as_str())
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(ARCHIVE_EXTENSION))
+        })
+        .collect::<Vec<_>>();
+    if archives.is_empty() {
+        return Vec::new();
+    }
+    let plugins = destinations
+        .iter()
+        .filter(in_data_root)
+        .filter(|d| is_plugin(Utf8Path::new(d.as_str())))
+        .collect::<Vec<_>>();
+
+    let stem = |d: &str| {
+        Utf8Path::new(d)
+            .file_stem()
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+    let archive_of = |archive: &str, plugin: &str| {
+        stem(archive)
+            .strip_prefix(&stem(plugin))
+            .is_some_and(|rest| rest.starts_with(ARCHIVE_SEPARATOR))
+    };
+
+    let mut findings = plugins
+        .iter()
+        .filter(|p| !archives.iter().any(|a| archive_of(a, p)))
+        .map(|p| Finding::new(md, LintRule::PluginWithoutArchive, Some(p)))
+        .collect::<Vec<_>>();
+    findings.extend(
+        archives
+            .iter()
+            .filter(|a| !plugins.iter().any(|p| archive_of(a, p)))
+            .map(|a| Finding::new(md, LintRule::ArchiveWithoutPlugin, Some(a))),
+    );
+    findings
+}
@@ -20,33 +20,24 @@
     clippy::wildcard_dependencies
 )]
 
+use std::ffi::OsString;
+
 use anyhow::Result;
-use clap::{Command, CommandFactory, Parser, Subcommand};
+use clap::{Command, CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_complete::{generate, Generator, Shell};
-use comfy_table::{Cell, Color};
 use flexi_logger::{detailed_format, Cleanup, Criterion, FileSpec, Logger, Naming, WriteMode};
-use game::Game;
 use shadow_rs::shadow;
 
-mod commands;
-mod decompress;
-use commands::Subcommands;
-mod conflict;
-mod dmodman;
-mod errors;
-mod game;
-mod installers;
-mod manifest;
-mod modlist;
-mod mods;
-mod settings;
-mod tag;
-mod ui;
-mod utils;
-
-use settings::{LogLevel, Settings};
-
-use crate::{errors::SettingErrors, settings::create_table};
+use starmod_core::{
+    commands::{alias, diagnose, list_commands, Subcommands},
+    errors::SettingErrors,
+    game::Game,
+    history,
+    i18n::localize_command,
+    settings::{LogLevel, Settings},
+    ui,
+};
+
 shadow!(build);
 
 /// Simple Starfield Modding Application
@@ -79,6 +70,7 @@ impl AppLet {
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None, disable_version_flag(true))]
+#[allow(clippy::struct_excessive_bools)]
 pub struct AppLetArgs {
     /// Set output to verbose
     #[arg(short, long, value_enum, default_value_t = LogLevel::Info)]
@@ -102,6 +94,22 @@ pub struct AppLetArgs {
     /// Show Long Help
     #[arg(long)]
     list_commands: bool,
+
+    /// Never run long output through '$PAGER'
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Never change manifests, the cache or the game directory
+    #[arg(long, global = true)]
+    read_only: bool,
+
+    /// Do not use colours in the output; also enabled by setting `NO_COLOR`
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Answer yes to all confirmation prompts
+    #[arg(short = 'y', long, global = true)]
+    yes: bool,
 }
 
 fn log_stdout(
@@ -112,11 +120,59 @@ fn log_stdout(
     write!(w, "{}", record.args())
 }
 
+/// The settings of the game starmod is run for, read before its arguments are parsed.
+fn early_settings(args: &[OsString]) -> Option<Settings> {
+    // The applet, and so the game, follows from the name starmod is run as.
+    let applet = StarMod::try_parse_from(args.iter().take(1)).ok()?;
+    let (game, _) = applet.applet.unwrap();
+    Settings::read_config(game, LogLevel::default()).ok()
+}
+
+/// The command line of starmod, with its help in the language of the user.
+fn command() -> Command {
+    StarMod::command().mut_subcommand("starmod", localize_command)
+}
+
+/// The arguments of starmod, with the alias of the user replaced by its command.
+fn expand_aliases(args: Vec<OsString>, settings: &Settings) -> Vec<OsString> {
+    if settings.aliases().is_empty() {
+        return args;
+    }
+
+    let value_options = AppLetArgs::command()
+        .get_arguments()
+        .filter(|a| a.get_action().takes_values())
+        .flat_map(|a| {
+            a.get_short()
+                .map(|s| format!("-{s}"))
+                .into_iter()
+                .chain(a.get_long().map(|l| format!("--{l}")))
+        })
+        .collect::<Vec<_>>();
+    alias::expand(args, settings.aliases(), &value_options)
+}
+
+/// Run starmod with the arguments it was started with.
+///
+/// # Errors
+///
+/// When the command fails; the error is shown to the user.
 pub fn main() -> Result<()> {
-    let applet = StarMod::parse();
+    let args = std::env::args_os().collect::<Vec<_>>();
+    let args = match early_settings(&args) {
+        // The help is shown while parsing, so its language is chosen before.
+        Some(settings) => {
+            settings.apply_locale_settings();
+            expand_aliases(args, &settings)
+        }
+        None => args,
+    };
+    let applet =
+        StarMod::from_arg_matches(&command().get_matches_from(args)).unwrap_or_else(|e| e.exit());
     let (game, args) = applet.applet.unwrap();
 
-    let settings = Settings::read_config(game, args.verbose)?;
+    let mut settings = Settings::read_config(game, args.verbose)?;
+    settings.set_read_only(args.read_only);
 
     let _logger = Logger::try_with_env_or_str("trace")?
         .log_to_file(FileSpec::try_from(settings.log_file())?)
@@ -133,6 +189,21 @@ pub fn main() -> Result<()> {
         .write_mode(WriteMode::Direct)
         .start()?;
 
+    settings.apply_display_settings(args.no_color);
+    settings.apply_cache_settings()?;
+    settings.apply_concurrency_settings()?;
+    settings.apply_deployment_settings();
+    settings.apply_installer_settings();
+    if args.no_pager {
+        ui::disable_pager();
+    }
+    if args.yes {
+        ui::assume_yes();
+    }
+    if settings.notifications() {
+        ui::enable_notifications();
+    }
+
     if args.long_version {
         println!("version:{}", build::CLAP_LONG_VERSION);
         return Ok(());
@@ -148,11 +219,11 @@ pub fn main() -> Result<()> {
         return Ok(());
     }
     if args.list_commands {
-        list_commands();
+        list_commands(&localize_command(AppLetArgs::command()));
         return Ok(());
     }
     if let Some(generator) = args.generator {
-        let mut cmd = AppLetArgs::command();
+        let mut cmd = localize_command(AppLetArgs::command());
         log::info!("Generating completion file for {generator}...");
         print_completions(generator, &mut cmd);
         return Ok(());
@@ -160,9 +231,24 @@ pub fn main() -> Result<()> {
 
     log::trace!("cmd: {:?}", args.cmd);
 
+    // A report is most needed when starmod does not work, so it needs no valid settings.
+    if let Some(Subcommands::Diagnose {
+        redact,
+        log_entries,
+    }) = args.cmd
+    {
+        return diagnose::show_report(&settings, build::CLAP_LONG_VERSION, redact, log_entries);
+    }
+
     // Only allow create-config to be run when no valid settings are found
     if settings.valid_config() {
-        args.cmd.unwrap_or_default().execute(&settings)?;
+        // The daemon records the history of every request itself.
+        let cmd = args.cmd.unwrap_or_default();
+        if cmd.is_read_only() || matches!(cmd, Subcommands::Daemon { .. }) {
+            cmd.execute(&settings)?;
+        } else {
+            history::record(&settings, || cmd.execute(&settings))?;
+        }
     } else if let Some(cmd @ Subcommands::Config { .. }) = args.cmd {
         cmd.execute(&settings)?;
     } else {
@@ -175,52 +261,3 @@ pub fn main() -> Result<()> {
 fn print_completions<G: Generator>(gen: G, cmd: &mut Command) {
     generate(gen, cmd, cmd.get_name().to_string(), &mut std::io::stdout());
 }
-
-fn list_commands() {
-    let mut table = create_table(vec!["Command", "Help"]);
-    let mut list = vec![];
-
-    list.extend_from_slice(&gather_commands(
-        &AppLetArgs::command(),
-        AppLetArgs::command().get_name(),
-    ));
-
-    list.sort();
-
-    for (prev_cmd, c, help) in list {
-        let mut cmdtable = create_table(vec!["", ""]);
-        cmdtable.add_row(vec![
-            Cell::new(prev_cmd).fg(Color::DarkCyan),
-            Cell::new(c).fg(Color::White),
-        ]);
-
-        table.add_row(vec![
-            Cell::new(format!("{}", cmdtable.lines().last().unwrap())),
-            Cell::new(help),
-        ]);
-    }
-
-    log::info!("");
-    log::info!("{table}");
-}
-
-fn gather_commands(
-    cmd: &clap::Command,
-    previous_cmds: &str,
-) -> Vec<(String, String, clap::builder::StyledStr)> {
-    let mut list = Vec::new();
-
-    for cmd in cmd.get_subcommands() {
-        list.push((
-            previous_cmds.to_string(),
-            cmd.get_name().to_string(),
-            cmd.get_about().unwrap_or_default().to_owned(),
-        ));
-
-        if cmd.has_subcommands() {
-            let lcmd = previous_cmds.to_string() + " " + cmd.get_name();
-            list.extend_from_slice(&gather_commands(cmd, &lcmd));
-        }
-    }
-    list
-}
@@ -1,33 +1,59 @@
 use camino::{Utf8Path, Utf8PathBuf};
 use std::{
     cmp::Ordering,
-    collections::HashMap,
-    fs::{remove_dir_all, remove_file, File},
+    collections::{HashMap, HashSet},
+    fs::{copy, remove_dir_all, remove_file, rename, File},
     io::{BufReader, Read, Write},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{Error, Result};
+use glob::Pattern;
 use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
 
 use crate::{
     dmodman::{DmodMan, DMODMAN_EXTENSION},
+    errors::ModErrors,
     mods::ModKind,
+    settings::{ensure_writable, is_read_only, mod_state_dir, share_with_group},
     utils::AddExtension,
+    version::Version,
 };
 
 mod custom;
 mod data;
 mod loader;
+mod remap;
+mod state;
 
+pub mod index;
 pub mod install_file;
 pub mod mod_state;
 
-use install_file::InstallFile;
+use install_file::{
+    destination_issues, normalize_destination, numbered_destination, windows_path_issues,
+    windows_safe_destination, DestinationIssue, InstallFile, WindowsPathIssue,
+};
 use mod_state::ModState;
+pub use remap::Remap;
+pub use state::ManifestState;
+use state::STATE_FORMAT_VERSION;
 
 use self::{data::DataManifest, loader::LoaderManifest};
 
 pub const MANIFEST_EXTENSION: &str = "ron";
+/// Version of the manifest format written by this release of starmod.
+/// Manifests without a version predate versioning and are version 0.
+pub const MANIFEST_FORMAT_VERSION: u32 = 3;
+/// First format which keeps the priority, state and tags in a separate state file.
+const STATE_FILE_FORMAT_VERSION: u32 = 2;
+// Last known good copy of a manifest, used to recover from a corrupted manifest.
+const MANIFEST_BACKUP_EXTENSION: &str = "bak";
+const MANIFEST_TEMP_EXTENSION: &str = "tmp";
+// Files named like these are documentation of the mod, like a readme or changelog.
+const DOC_NAMES: [&str; 4] = ["readme", "read me", "changelog", "changes"];
+const DOC_EXTENSIONS: [&str; 6] = ["txt", "md", "pdf", "htm", "html", "rtf"];
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 enum ManifestInternal {
@@ -43,8 +69,10 @@ impl ManifestInternal {
         manifest_dir: &Utf8Path,
     ) -> Self {
         match mod_kind {
-            ModKind::FoMod | ModKind::Data => Self::Data(DataManifest::new(files, disabled_files)),
-            ModKind::Loader => Self::Loader(LoaderManifest::new(&files)),
+            ModKind::FoMod | ModKind::Data | ModKind::Plugin | ModKind::Scripted => {
+                Self::Data(DataManifest::new(files, disabled_files))
+            }
+            ModKind::Loader => Self::Loader(LoaderManifest::new(files, disabled_files)),
             ModKind::Custom => Self::Custom(custom::CustomManifest::new(manifest_dir)),
         }
     }
@@ -55,14 +83,6 @@ impl ManifestInternal {
             Self::Custom(c) => c.files(cache_dir),
         }
     }
-    pub fn dest_files(&self, cache_dir: &Utf8Path) -> Result<Vec<String>> {
-        let files = self.files(cache_dir)?;
-        let mut dest_files = Vec::with_capacity(files.len());
-        for f in &files {
-            dest_files.push(f.destination().to_string());
-        }
-        Ok(dest_files)
-    }
     pub fn origin_files(
         &self,
         cache_dir: &Utf8Path,
@@ -80,28 +100,42 @@ impl ManifestInternal {
     pub fn disabled_files(&self) -> Vec<InstallFile> {
         match self {
             Self::Data(d) => d.disabled_files(),
+            Self::Loader(l) => l.disabled_files(),
+            Self::Custom(c) => c.disabled_files(),
+        }
+    }
+    pub fn split_off(
+        &mut self,
+        matches: &dyn Fn(&InstallFile) -> bool,
+    ) -> (Vec<InstallFile>, Vec<InstallFile>) {
+        match self {
+            Self::Data(d) => d.split_off(matches),
+            Self::Loader(l) => l.split_off(matches),
 
-            //TODO: does it make sense disabling files in these?
-            Self::Loader(_l) => vec![],
-            Self::Custom(_c) => vec![],
+            // Custom mods have no list of files; their directory is their file list.
+            Self::Custom(_c) => (vec![], vec![]),
+        }
+    }
+    /// The files and disabled files, to change them in place; custom mods have none.
+    pub fn files_mut(&mut self) -> Vec<&mut InstallFile> {
+        match self {
+            Self::Data(d) => d.files_mut().collect(),
+            Self::Loader(l) => l.files_mut().collect(),
+            Self::Custom(_c) => Vec::new(),
         }
     }
-    pub fn disable_file(&mut self, name: &str) -> bool {
+    pub fn disable_file(&mut self, cache_dir: &Utf8Path, name: &str) -> bool {
         match self {
             Self::Data(d) => d.disable_file(name),
-
-            //TODO: does it make sense disabling files in these?
-            Self::Loader(_l) => false,
-            Self::Custom(_c) => false,
+            Self::Loader(l) => l.disable_file(name),
+            Self::Custom(c) => c.disable_file(cache_dir, name),
         }
     }
     pub fn enable_file(&mut self, name: &str) -> bool {
         match self {
             Self::Data(d) => d.enable_file(name),
-
-            //TODO: does it make sense disabling files in these?
-            Self::Loader(_l) => false,
-            Self::Custom(_c) => false,
+            Self::Loader(l) => l.enable_file(name),
+            Self::Custom(c) => c.enable_file(name),
         }
     }
 }
@@ -110,6 +144,8 @@ impl ManifestInternal {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Manifest {
+    #[serde(default)]
+    format_version: u32,
     internal: ManifestInternal,
     #[serde(skip_serializing, default)]
     cache_dir: Utf8PathBuf,
@@ -121,12 +157,29 @@ pub struct Manifest {
     #[serde(default)]
     nexus_id: Option<u32>,
     #[serde(default)]
-    mod_state: ModState,
+    author: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    /// The readme and changelog files of the mod, relative to its directory.
+    #[serde(default)]
+    docs: Vec<Utf8PathBuf>,
     mod_kind: ModKind,
     #[serde(default)]
-    priority: isize,
+    remaps: Vec<Remap>,
     #[serde(default)]
+    excludes: Vec<String>,
+    // The following are kept in the state file, see `ManifestState`;
+    // they are only read from manifests older than `STATE_FILE_FORMAT_VERSION`.
+    #[serde(default, skip_serializing)]
+    mod_state: ModState,
+    #[serde(default, skip_serializing)]
+    priority: isize,
+    #[serde(default, skip_serializing)]
     tags: Vec<String>,
+    #[serde(skip)]
+    notes: Option<String>,
+    #[serde(skip)]
+    sequence: u64,
 }
 impl Manifest {
     pub fn new(
@@ -141,55 +194,266 @@ impl Manifest {
         mod_kind: ModKind,
     ) -> Self {
         Self {
+            format_version: MANIFEST_FORMAT_VERSION,
             cache_dir: cache_dir.to_path_buf(),
             manifest_dir: manifest_dir.to_path_buf(),
             bare_file_name,
             name,
             nexus_id,
             version,
+            author: None,
+            category: None,
+            docs: find_docs(&cache_dir.join(manifest_dir)),
             mod_state: ModState::Disabled,
             priority: 0,
             mod_kind,
             internal: ManifestInternal::new(mod_kind, files, disabled_files, manifest_dir),
+            remaps: Vec::new(),
+            excludes: Vec::new(),
             tags: Vec::new(), //TODO: shall we add modkind as a tag?
+            notes: None,
+            sequence: new_sequence(),
         }
     }
+    /// Deploy the mod after mods of lower priority.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
     pub fn set_priority(&mut self, priority: isize) -> Result<()> {
         self.priority = priority;
-        if self.priority < 0 {
-            self.set_disabled()?;
-        }
-        self.write()
+        self.write_state()
     }
+    /// The manifest of the archive `archive` in `cache_dir`, with its state.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be read, or is not a manifest.
     pub fn from_file(cache_dir: &Utf8Path, archive: &Utf8Path) -> Result<Self> {
         let manifest_file = Utf8PathBuf::from(cache_dir)
             .join(archive)
             .add_extension(MANIFEST_EXTENSION);
 
-        Self::try_from(manifest_file.as_path())
+        let mut manifest = Self::try_from(manifest_file.as_path())?;
+        manifest.apply_state();
+        Ok(manifest)
+    }
+    fn read(file_path: &Utf8Path) -> Result<Self> {
+        let file = File::open(file_path)?;
+        let mut buf_reader = BufReader::new(file);
+        let mut contents = String::new();
+        buf_reader.read_to_string(&mut contents)?;
+
+        Ok(ron::from_str(&contents)?)
+    }
+    /// Upgrade a manifest written by an older release of starmod to the current format.
+    /// Returns true when the manifest was changed.
+    fn migrate(&mut self) -> Result<bool> {
+        if self.format_version > MANIFEST_FORMAT_VERSION {
+            return Err(ModErrors::ManifestTooNew(self.name.clone(), self.format_version).into());
+        }
+
+        let original_version = self.format_version;
+        while self.format_version < MANIFEST_FORMAT_VERSION {
+            match self.format_version {
+                0 => {
+                    // Unversioned manifests could contain duplicate tags, and could be
+                    // enabled with a negative priority, which now means 'disabled'.
+                    let mut tags = Vec::with_capacity(self.tags.len());
+                    for tag in self.tags.drain(..) {
+                        if !tags.contains(&tag) {
+                            tags.push(tag);
+                        }
+                    }
+                    self.tags = tags;
+                    if self.priority < 0 {
+                        self.mod_state = ModState::Disabled;
+                    }
+                }
+                1 => {
+                    // The priority, state and tags move to the state file; see `try_from`.
+                    // A negative priority kept a mod disabled, which is what pinning is for.
+                    if self.priority < 0 {
+                        self.mod_state = ModState::Pinned;
+                        self.priority = 0;
+                    }
+                }
+                2 => {
+                    // Loaders kept a single dll and exe, instead of a list of files.
+                    if let ManifestInternal::Loader(l) = &mut self.internal {
+                        l.migrate();
+                    }
+                }
+                version => {
+                    return Err(ModErrors::ManifestNotMigrated(self.name.clone(), version).into());
+                }
+            }
+            self.format_version += 1;
+        }
+
+        Ok(original_version != self.format_version)
     }
 
+    /// Write both the manifest and the state file.
+    ///
+    /// # Errors
+    ///
+    /// When either cannot be written, or in read-only mode.
     pub fn write(&self) -> Result<()> {
+        self.write_manifest()?;
+        self.write_state()
+    }
+    fn write_manifest(&self) -> Result<()> {
+        ensure_writable(&format!("update the manifest of '{}'", self.name))?;
+
         let path = Utf8PathBuf::from(self.cache_dir.as_path())
             .join(self.manifest_dir.as_path())
             .add_extension(MANIFEST_EXTENSION);
 
-        if !path.exists() {
-            log::trace!("Creating Manifest at '{}'", path);
-        }
-        let mut file = File::create(&path)?;
-
         let serialized =
             ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap();
-        log::trace!("Updating manifest file '{}'.", path);
+
+        // Write to a temporary file first and move it over the manifest afterwards,
+        // so that a crash halfway through never leaves a truncated manifest behind.
+        let temp_path = path.add_extension(MANIFEST_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
         file.write_all(serialized.as_bytes())?;
+        file.sync_all()?;
+
+        if path.exists() {
+            log::trace!("Updating manifest file '{}'.", path);
+            let backup_path = path.add_extension(MANIFEST_BACKUP_EXTENSION);
+            copy(&path, &backup_path)?;
+            share_with_group(&backup_path)?;
+        } else {
+            log::trace!("Creating Manifest at '{}'", path);
+        }
+        rename(&temp_path, &path)?;
+        share_with_group(&path)?;
+        index::ManifestIndex::invalidate(&self.cache_dir);
+        Ok(())
+    }
+    /// Store the priority, state, tags and notes; in a shared cache only for the current user,
+    /// and in the 'state-dir' when one is set.
+    fn write_state(&self) -> Result<()> {
+        ensure_writable(&format!("update the state of '{}'", self.name))?;
+        if let Some(state_dir) = mod_state_dir() {
+            self.state().write(state_dir, &self.manifest_dir)?;
+        } else {
+            let path = self.state().write(&self.cache_dir, &self.manifest_dir)?;
+            share_with_group(&path)?;
+        }
         Ok(())
     }
+    fn state(&self) -> ManifestState {
+        ManifestState {
+            format_version: STATE_FORMAT_VERSION,
+            mod_state: self.mod_state,
+            priority: self.priority,
+            sequence: self.sequence,
+            tags: self.tags.clone(),
+            notes: self.notes.clone(),
+        }
+    }
+    /// Read the priority, state, tags and notes from the state file. In a shared cache
+    /// those of the current user are used; users without their own use the shared ones.
+    /// Likewise, a new 'state-dir' starts with the state kept in the cache.
+    pub fn apply_state(&mut self) {
+        let state = mod_state_dir()
+            .and_then(|dir| ManifestState::read(dir, &self.manifest_dir))
+            .or_else(|| ManifestState::read(&self.cache_dir, &self.manifest_dir));
+        if let Some(state) = state {
+            self.set_state(state);
+        }
+    }
+    fn set_state(&mut self, state: ManifestState) {
+        self.mod_state = state.mod_state;
+        self.priority = state.priority;
+        self.sequence = state.sequence;
+        self.tags = state.tags;
+        self.notes = state.notes;
+    }
+    /// Take over the priority, state, tags and notes of `other`, which this mod replaces.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
+    pub fn take_state_of(&mut self, other: &Self) -> Result<()> {
+        self.set_state(other.state());
+        self.write_state()
+    }
+    /// Take over the priority, tags and notes packed into the archive of this mod, see
+    /// `ManifestState::take_packed`; the mod is not deployed yet, so an enabled one is disabled.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
+    pub fn take_packed_state(&mut self, mut state: ManifestState) -> Result<()> {
+        if state.mod_state == ModState::Enabled {
+            state.mod_state = ModState::Disabled;
+        }
+        self.set_state(state);
+        self.write_state()
+    }
+    /// The file the state of this mod is read from, see `apply_state`; `None` when its state
+    /// was never written.
+    pub fn state_file(&self) -> Option<Utf8PathBuf> {
+        mod_state_dir()
+            .map(|dir| ManifestState::path(dir, &self.manifest_dir))
+            .filter(|path| path.exists())
+            .or_else(|| {
+                Some(ManifestState::path(&self.cache_dir, &self.manifest_dir))
+                    .filter(|path| path.exists())
+            })
+    }
+    /// Re-run the installer on the extracted archive, keeping the name, priority, tags and state.
+    ///
+    /// # Errors
+    ///
+    /// When the installer fails, or the manifest cannot be written.
+    pub fn reinstall(&self) -> Result<Self> {
+        let installer = if self.mod_kind == ModKind::Custom {
+            ModKind::Custom.into()
+        } else {
+            ModKind::detect_mod_type(&self.cache_dir, &self.manifest_dir)?
+        };
+
+        let mut md = installer.create_mod(&self.cache_dir, &self.manifest_dir)?;
+        md.name.clone_from(&self.name);
+        md.priority = self.priority;
+        md.sequence = self.sequence;
+        md.mod_state = self.mod_state;
+        md.tags.clone_from(&self.tags);
+        md.notes.clone_from(&self.notes);
+        md.remaps.clone_from(&self.remaps);
+        md.excludes.clone_from(&self.excludes);
+        md.write()?;
+        Ok(md)
+    }
+    /// Remove the mod, with its manifest and files, from the cache.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when the files cannot be removed.
     pub fn remove(&self) -> Result<()> {
+        ensure_writable(&format!("remove '{}'", self.name))?;
         let path = self.cache_dir.join(&self.manifest_dir);
         remove_dir_all(&path)?;
         let manifest_file = path.add_extension(MANIFEST_EXTENSION);
         remove_file(&manifest_file)?;
+        let backup_file = manifest_file.add_extension(MANIFEST_BACKUP_EXTENSION);
+        if backup_file.exists() {
+            remove_file(backup_file)?;
+        }
+        for state_dir in [Some(self.cache_dir.as_path()), mod_state_dir()]
+            .into_iter()
+            .flatten()
+        {
+            let state_file = ManifestState::path(state_dir, &self.manifest_dir);
+            if state_file.exists() {
+                remove_file(state_file)?;
+            }
+        }
         let dmodman_file = manifest_file.with_extension(DMODMAN_EXTENSION);
         remove_file(dmodman_file)?;
         Ok(())
@@ -207,29 +471,70 @@ impl Manifest {
     pub fn name(&self) -> &str {
         &self.name
     }
+    /// Rename the mod.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
     pub fn set_name(&mut self, name: String) -> Result<()> {
         self.name = name;
         self.write()
     }
+    /// Enable the mod; returns false for a pinned mod.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
     pub fn set_enabled(&mut self) -> Result<bool> {
         let r = self.temp_set_enabled();
         if r {
-            self.write()?;
+            self.write_state()?;
         }
 
         Ok(r)
     }
+    /// Enable the mod, without writing its state; returns false for a pinned mod.
     pub fn temp_set_enabled(&mut self) -> bool {
-        if self.priority >= 0 {
+        if self.mod_state.is_pinned() {
+            false
+        } else {
             self.mod_state = ModState::Enabled;
             true
-        } else {
-            false
         }
     }
+    /// Disable the mod; a pinned mod stays pinned.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
     pub fn set_disabled(&mut self) -> Result<()> {
-        self.mod_state = ModState::Disabled;
-        self.write()
+        if !self.mod_state.is_pinned() {
+            self.mod_state = ModState::Disabled;
+        }
+        self.write_state()
+    }
+    /// Keep the mod disabled, until it is unpinned; it has to be taken out of the game
+    /// directory first, like any mod which is disabled.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
+    pub fn set_pinned(&mut self) -> Result<()> {
+        self.mod_state = ModState::Pinned;
+        self.write_state()
+    }
+    /// Let a pinned mod be enabled again; it stays disabled until then.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
+    pub fn unpin(&mut self) -> Result<bool> {
+        if self.mod_state.is_pinned() {
+            self.mod_state = ModState::Disabled;
+            self.write_state().map(|()| true)
+        } else {
+            Ok(false)
+        }
     }
     pub const fn nexus_id(&self) -> Option<u32> {
         self.nexus_id
@@ -237,12 +542,100 @@ impl Manifest {
     pub fn version(&self) -> Option<&str> {
         self.version.as_deref()
     }
+    /// Link the mod to mod `nexus_id` on Nexus, and to `version` of it when that is known.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn set_nexus_id(&mut self, nexus_id: u32, version: Option<String>) -> Result<()> {
+        self.nexus_id = Some(nexus_id);
+        if version.is_some() {
+            self.version = version;
+        }
+        self.write_manifest()
+    }
+    /// The readme and changelog files of the mod; those of mods installed before they were
+    /// recorded are looked up.
+    pub fn docs(&self) -> Vec<Utf8PathBuf> {
+        let mod_dir = self.cache_dir.join(&self.manifest_dir);
+        let docs = if self.docs.is_empty() {
+            find_docs(&mod_dir)
+        } else {
+            self.docs.clone()
+        };
+        docs.into_iter().map(|doc| mod_dir.join(doc)).collect()
+    }
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+    /// The category of the mod on Nexus.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+    /// Fill in the author and category of the mod, where they are missing;
+    /// returns whether anything changed.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn fill_metadata(
+        &mut self,
+        author: Option<String>,
+        category: Option<String>,
+    ) -> Result<bool> {
+        let fill_author = self.author.is_none() && author.is_some();
+        if fill_author {
+            self.author = author;
+        }
+        let fill_category = self.category.is_none() && category.is_some();
+        if fill_category {
+            self.category = category;
+        }
+        let changed = fill_author || fill_category;
+        if changed {
+            self.write_manifest()?;
+        }
+        Ok(changed)
+    }
     pub const fn mod_state(&self) -> ModState {
         self.mod_state
     }
+    /// The files of this mod without the excluded ones,
+    /// with their destinations changed by the remap rules.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed.
     pub fn files(&self) -> Result<Vec<InstallFile>> {
-        self.internal.files(&self.cache_dir)
+        let mut files = self.internal.files(&self.cache_dir)?;
+        if !self.excludes.is_empty() {
+            let excludes = self
+                .excludes
+                .iter()
+                .filter_map(|e| Pattern::new(e).ok())
+                .collect::<Vec<_>>();
+            files.retain(|f| {
+                !excludes.iter().any(|e| {
+                    e.matches_with(f.source().as_str(), remap::MATCH_OPTIONS)
+                        || e.matches_with(f.destination(), remap::MATCH_OPTIONS)
+                })
+            });
+        }
+        if !self.remaps.is_empty() {
+            for f in &mut files {
+                if let Some(destination) = self.remaps.iter().find_map(|r| r.apply(f.destination()))
+                {
+                    *f = InstallFile::new_raw(f.source().to_owned(), destination);
+                }
+            }
+        }
+        Ok(files)
     }
+    /// The files of this mod which it deploys, as it wins their conflict in `conflict_list`.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed.
     pub fn enlist_files(
         &self,
         conflict_list: &HashMap<String, Vec<String>>,
@@ -269,9 +662,149 @@ impl Manifest {
 
         Ok(enlisted_files)
     }
+    /// The files and disabled files whose destination is not in its canonical form, with what is
+    /// wrong with it; remaps are not applied, these are the destinations the installer chose.
+    /// Custom mods take their destinations from their directory, so these have none.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed.
+    pub fn destination_issues(&self) -> Result<Vec<(InstallFile, Vec<DestinationIssue>)>> {
+        if self.mod_kind == ModKind::Custom {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .internal
+            .files(&self.cache_dir)?
+            .into_iter()
+            .chain(self.internal.disabled_files())
+            .filter_map(|f| {
+                let issues = destination_issues(f.destination());
+                (!issues.is_empty()).then_some((f, issues))
+            })
+            .collect())
+    }
+    /// Rewrite the destinations of the files into their canonical form; files which would end up
+    /// outside of the game directory are disabled instead. Returns the number of changed files.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn normalize_destinations(&mut self) -> Result<usize> {
+        let mut changed = 0;
+        let mut escaping = Vec::new();
+        for f in self.internal.files_mut() {
+            match normalize_destination(f.destination()) {
+                Some(destination) if destination != f.destination() => {
+                    f.set_destination(destination);
+                    changed += 1;
+                }
+                Some(_) => {}
+                None => escaping.push(f.source().to_string()),
+            }
+        }
+        for source in &escaping {
+            if self.internal.disable_file(&self.cache_dir, source) {
+                changed += 1;
+            }
+        }
+
+        if changed > 0 {
+            self.write()?;
+        }
+        Ok(changed)
+    }
+    /// The files of this mod which would not load under Proton from `game_dir`, with why;
+    /// unlike `destination_issues`, these are the destinations the files are deployed to.
+    ///
+    /// # Errors
+    ///
+    /// When the files or the game directory cannot be listed.
+    pub fn windows_path_issues(
+        &self,
+        game_dir: &Utf8Path,
+    ) -> Result<Vec<(InstallFile, Vec<WindowsPathIssue>)>> {
+        Ok(self
+            .files()?
+            .into_iter()
+            .filter_map(|f| {
+                let issues = windows_path_issues(game_dir, f.destination());
+                (!issues.is_empty()).then_some((f, issues))
+            })
+            .collect())
+    }
+    /// Add remap rules which rename the destinations windows cannot have, see
+    /// `windows_safe_destination`; they go in front of the other rules. A renamed file which
+    /// would end up on the destination of another file is numbered. Returns the number of
+    /// renamed files.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed, or the manifest cannot be written.
+    pub fn rename_for_windows(&mut self) -> Result<usize> {
+        let files = self
+            .internal
+            .files(&self.cache_dir)?
+            .into_iter()
+            .map(|f| {
+                let destination = self
+                    .remaps
+                    .iter()
+                    .find_map(|r| r.apply(f.destination()))
+                    .unwrap_or_else(|| f.destination().to_owned());
+                (f, destination)
+            })
+            .collect::<Vec<_>>();
+        // Windows does not tell names apart by their casing.
+        let mut taken = files
+            .iter()
+            .filter(|(_, d)| windows_safe_destination(d) == *d)
+            .map(|(_, d)| d.to_lowercase())
+            .collect::<HashSet<_>>();
+
+        let mut renames = Vec::new();
+        for (f, destination) in &files {
+            let safe = windows_safe_destination(destination);
+            if safe == *destination {
+                continue;
+            }
+            let mut unique = safe.clone();
+            for number in 1.. {
+                if taken.insert(unique.to_lowercase()) {
+                    break;
+                }
+                unique = numbered_destination(&safe, number);
+            }
+            renames.push(Remap::rename(f.destination(), &unique)?);
+        }
+        if renames.is_empty() {
+            return Ok(0);
+        }
+
+        let count = renames.len();
+        self.remaps
+            .retain(|r| !renames.iter().any(|rename| rename.from() == r.from()));
+        self.remaps.splice(0..0, renames);
+        self.write_manifest()?;
+        Ok(count)
+    }
+    /// The destinations of the files of this mod.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed.
     pub fn dest_files(&self) -> Result<Vec<String>> {
-        self.internal.dest_files(&self.cache_dir)
+        Ok(self
+            .files()?
+            .iter()
+            .map(|f| f.destination().to_owned())
+            .collect())
     }
+    /// The files of this mod in the cache, both enabled and disabled.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed.
     pub fn origin_files(&self) -> Result<Vec<Utf8PathBuf>> {
         self.internal
             .origin_files(&self.cache_dir, &self.manifest_dir)
@@ -279,15 +812,81 @@ impl Manifest {
     pub fn disabled_files(&self) -> Vec<InstallFile> {
         self.internal.disabled_files()
     }
-    pub fn disable_file(&mut self, name: &str) -> bool {
-        self.internal.disable_file(name)
+    /// Total size in bytes of the files of this mod in the cache.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed, or their size cannot be read.
+    pub fn size(&self) -> Result<u64> {
+        Ok(self
+            .origin_files()?
+            .iter()
+            .filter_map(|f| std::fs::metadata(self.cache_dir.join(f)).ok())
+            .map(|m| m.len())
+            .sum())
     }
-    pub fn enable_file(&mut self, name: &str) -> bool {
-        self.internal.enable_file(name)
+    /// Remove the files (both enabled and disabled) for which `matches` holds from the manifest,
+    /// and return them. The files themselves stay in the cache directory.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn split_off_files(
+        &mut self,
+        matches: &dyn Fn(&InstallFile) -> bool,
+    ) -> Result<(Vec<InstallFile>, Vec<InstallFile>)> {
+        let split = self.internal.split_off(matches);
+        self.write_manifest()?;
+        Ok(split)
+    }
+    /// Stop deploying file `name`; returns false when the mod has no such file.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn disable_file(&mut self, name: &str) -> Result<bool> {
+        let changed = self.internal.disable_file(&self.cache_dir, name);
+        if changed {
+            self.write_manifest()?;
+        }
+        Ok(changed)
+    }
+    /// Deploy the disabled file `name` again; returns false when the mod has no such file.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn enable_file(&mut self, name: &str) -> Result<bool> {
+        let changed = self.internal.enable_file(name);
+        if changed {
+            self.write_manifest()?;
+        }
+        Ok(changed)
     }
     pub const fn priority(&self) -> isize {
         self.priority
     }
+    /// When the mod was installed, or the number given to it by `mod sequence`;
+    /// mods of equal priority are deployed in this order. Mods installed before it
+    /// was recorded have 0.
+    pub const fn sequence(&self) -> u64 {
+        self.sequence
+    }
+    /// Give the mod `sequence`, see `sequence`.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
+    pub fn set_sequence(&mut self, sequence: u64) -> Result<()> {
+        self.sequence = sequence;
+        self.write_state()
+    }
+    /// The configuration files of this mod; those with `extension`, or else those of the usual
+    /// formats.
+    ///
+    /// # Errors
+    ///
+    /// When the files cannot be listed.
     pub fn find_config_files(&self, extension: Option<&str>) -> Result<Vec<Utf8PathBuf>> {
         let mut config_files = Vec::new();
 
@@ -319,20 +918,105 @@ impl Manifest {
     pub fn is_an_update(&self, dmodman: &DmodMan) -> bool {
         dmodman.name() == self.bare_file_name
             && dmodman.mod_id() == self.nexus_id.unwrap_or_default()
-            && dmodman.version().unwrap_or_default() > self.version.clone().unwrap_or_default()
+            && Version::from(dmodman.version().as_deref()) > Version::from(self.version())
+    }
+    pub fn excludes(&self) -> &[String] {
+        &self.excludes
+    }
+    /// Exclude the files matching the glob `pattern`; returns false when it already was.
+    ///
+    /// # Errors
+    ///
+    /// When `pattern` is not a valid glob, or the manifest cannot be written.
+    pub fn add_exclude(&mut self, pattern: &str) -> Result<bool> {
+        let pattern = pattern.replace('\\', "/");
+        Pattern::new(&pattern)
+            .map_err(|e| ModErrors::InvalidPattern(pattern.clone(), e.to_string()))?;
+        if self.excludes.contains(&pattern) {
+            return Ok(false);
+        }
+        self.excludes.push(pattern);
+        self.write_manifest()?;
+        Ok(true)
+    }
+    /// Remove the exclusion `pattern`; returns false when there is none.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn remove_exclude(&mut self, pattern: &str) -> Result<bool> {
+        let pattern = pattern.replace('\\', "/");
+        let len = self.excludes.len();
+        self.excludes.retain(|e| *e != pattern);
+        if self.excludes.len() == len {
+            return Ok(false);
+        }
+        self.write_manifest()?;
+        Ok(true)
+    }
+    pub fn remaps(&self) -> &[Remap] {
+        &self.remaps
+    }
+    /// Add `remap`, replacing an existing rule for the same files.
+    ///
+    /// # Errors
+    ///
+    /// When the manifest cannot be written.
+    pub fn add_remap(&mut self, remap: Remap) -> Result<()> {
+        self.remaps.retain(|r| r.from() != remap.from());
+        self.remaps.push(remap);
+        self.write_manifest()
+    }
+    /// Remove the rule for `from`; returns false when there is none.
+    ///
+    /// # Errors
+    ///
+    /// When `from` is not a valid glob, or the manifest cannot be written.
+    pub fn remove_remap(&mut self, from: &str) -> Result<bool> {
+        let from = Remap::new(from, "")?;
+        let len = self.remaps.len();
+        self.remaps
+            .retain(|r| !r.from().eq_ignore_ascii_case(from.from()));
+        if self.remaps.len() == len {
+            return Ok(false);
+        }
+        self.write_manifest()?;
+        Ok(true)
+    }
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+    /// Replace the notes of the mod; `None` removes them.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
+    pub fn set_notes(&mut self, notes: Option<String>) -> Result<()> {
+        self.notes = notes;
+        self.write_state()
     }
     pub fn tags(&self) -> &[String] {
         &self.tags
     }
+    /// Tag the mod with `tag`, in lower-case; returns false when it already has it.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
     pub fn add_tag(&mut self, tag: &str) -> Result<bool> {
         let tag = tag.to_lowercase();
         if self.tags.contains(&tag) {
             Ok(false)
         } else {
             self.tags.push(tag);
-            self.write().map(|()| true)
+            self.write_state().map(|()| true)
         }
     }
+    /// Remove `tag` from the mod.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written.
     pub fn remove_tag(&mut self, tag: &str) -> Result<bool> {
         let tag = tag.to_lowercase();
 
@@ -344,30 +1028,86 @@ impl Manifest {
             .map(|(idx, _)| idx)
         {
             self.tags.swap_remove(idx);
-            self.write().map(|()| true)
+            self.write_state().map(|()| true)
         } else {
             Ok(true)
         }
     }
 }
+/// The sequence number of a mod installed now; the time in microseconds, so it grows with
+/// every installation without having to look at the other mods.
+fn new_sequence() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_micros()).unwrap_or(u64::MAX))
+}
+
+/// The readme and changelog files in `mod_dir`, relative to it; they are not deployed, but
+/// still tell how to use the mod.
+fn find_docs(mod_dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let mut docs = WalkDir::new(mod_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| Utf8PathBuf::try_from(entry.into_path()).ok())
+        .filter(|path| {
+            let stem = path.file_stem().unwrap_or_default().to_lowercase();
+            let extension = path.extension().unwrap_or_default().to_lowercase();
+            DOC_NAMES.iter().any(|name| stem.contains(name))
+                && (extension.is_empty() || DOC_EXTENSIONS.contains(&extension.as_str()))
+        })
+        .filter_map(|path| path.strip_prefix(mod_dir).ok().map(Utf8Path::to_path_buf))
+        .collect::<Vec<_>>();
+    docs.sort_unstable();
+    docs
+}
+
 impl<'a> TryFrom<&'a Utf8Path> for Manifest {
     type Error = Error;
 
     fn try_from(file_path: &Utf8Path) -> std::result::Result<Self, Self::Error> {
         log::trace!("Opening manifest: {}", file_path);
 
-        let file = File::open(file_path)?;
-        let mut buf_reader = BufReader::new(file);
-        let mut contents = String::new();
-        buf_reader.read_to_string(&mut contents)?;
-
-        let mut manifest: Self = ron::from_str(&contents)?;
+        let mut manifest = match Self::read(file_path) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                let backup_path = file_path.add_extension(MANIFEST_BACKUP_EXTENSION);
+                let Ok(manifest) = Self::read(&backup_path) else {
+                    return Err(err);
+                };
+                log::warn!(
+                    "Manifest '{file_path}' is damaged ({err}); restored it from '{backup_path}'."
+                );
+                if !is_read_only() {
+                    copy(&backup_path, file_path)?;
+                }
+                manifest
+            }
+        };
         manifest.cache_dir = file_path.parent().unwrap().to_path_buf();
 
+        let original_version = manifest.format_version;
+        if manifest.migrate()? && !is_read_only() {
+            log::debug!(
+                "Upgrading manifest '{file_path}' from format {original_version} to {MANIFEST_FORMAT_VERSION}."
+            );
+            if original_version < STATE_FILE_FORMAT_VERSION {
+                // Written to the cache, so it is shared by all users of a shared cache.
+                let path = manifest
+                    .state()
+                    .write(&manifest.cache_dir, &manifest.manifest_dir)?;
+                share_with_group(&path)?;
+            }
+            manifest.write_manifest()?;
+        }
+
         log::trace!("Finished opening manifest: {}", manifest.name());
         Ok(manifest)
     }
 }
+
 impl PartialOrd for Manifest {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -375,13 +1115,12 @@ impl PartialOrd for Manifest {
 }
 impl Ord for Manifest {
     fn cmp(&self, other: &Self) -> Ordering {
-        //Order around priority or, if equal, around alfabethic order
-        let o = self.priority().cmp(&other.priority());
-        if o == Ordering::Equal {
-            self.name().cmp(other.name())
-        } else {
-            o
-        }
+        // Order around priority, then the order of installation and,
+        // for mods installed before that was recorded, around alfabethic order.
+        self.priority()
+            .cmp(&other.priority())
+            .then_with(|| self.sequence.cmp(&other.sequence))
+            .then_with(|| self.name().cmp(other.name()))
     }
 }
 impl PartialEq for Manifest {
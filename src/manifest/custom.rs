@@ -8,11 +8,16 @@ use super::install_file::InstallFile;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CustomManifest {
     manifest_dir: Utf8PathBuf,
+    // The files of a custom mod are whatever is in its directory,
+    // so only the disabled ones are kept, by their path within the mod.
+    #[serde(default)]
+    disabled_files: Vec<Utf8PathBuf>,
 }
 impl CustomManifest {
     pub fn new(manifest_dir: &Utf8Path) -> Self {
         Self {
             manifest_dir: manifest_dir.to_path_buf(),
+            disabled_files: Vec::new(),
         }
     }
     pub fn files(&self, cache_dir: &Utf8Path) -> Result<Vec<InstallFile>> {
@@ -30,12 +35,42 @@ impl CustomManifest {
             let entry = entry?;
             let entry_path = Utf8PathBuf::try_from(entry.path().strip_prefix(&dir)?.to_path_buf())?;
 
-            if entry_path.is_file() {
+            if entry.file_type().is_file() && !self.disabled_files.contains(&entry_path) {
                 files.push(entry_path.into());
             }
-            // dbg!(entry_path);
         }
 
         Ok(files)
     }
+    pub fn disabled_files(&self) -> Vec<InstallFile> {
+        self.disabled_files
+            .iter()
+            .map(|f| InstallFile::from(f.as_path()))
+            .collect()
+    }
+    pub fn disable_file(&mut self, cache_dir: &Utf8Path, name: &str) -> bool {
+        let Ok(files) = self.files(cache_dir) else {
+            return false;
+        };
+        if let Some(isf) = files.iter().find(|isf| {
+            isf.source().as_str() == name || isf.source().file_name().unwrap_or_default() == name
+        }) {
+            self.disabled_files.push(isf.source().to_path_buf());
+            true
+        } else {
+            false
+        }
+    }
+    pub fn enable_file(&mut self, name: &str) -> bool {
+        if let Some(idx) = self
+            .disabled_files
+            .iter()
+            .position(|f| f.as_str() == name || f.file_name().unwrap_or_default() == name)
+        {
+            self.disabled_files.remove(idx);
+            true
+        } else {
+            false
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
 
-use super::install_file::InstallFile;
+use super::install_file::{split_off_matching, InstallFile};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct DataManifest {
@@ -21,6 +21,20 @@ impl DataManifest {
     pub fn disabled_files(&self) -> Vec<InstallFile> {
         self.disabled_files.clone()
     }
+    /// The files and disabled files, to change them in place.
+    pub fn files_mut(&mut self) -> impl Iterator<Item = &mut InstallFile> {
+        self.files.iter_mut().chain(self.disabled_files.iter_mut())
+    }
+    /// Remove the files and disabled files for which `matches` holds, and return them.
+    pub fn split_off(
+        &mut self,
+        matches: &dyn Fn(&InstallFile) -> bool,
+    ) -> (Vec<InstallFile>, Vec<InstallFile>) {
+        (
+            split_off_matching(&mut self.files, matches),
+            split_off_matching(&mut self.disabled_files, matches),
+        )
+    }
     pub fn disable_file(&mut self, name: &str) -> bool {
         if let Some((idx, _isf)) = self.files.iter().enumerate().find(|(_, isf)| {
             if isf.source().to_string().eq(name) {
@@ -0,0 +1,174 @@
+use std::{
+    fs::{read, read_dir, remove_file, rename, File},
+    io::Write,
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    state::{ManifestState, STATE_EXTENSION},
+    Manifest, MANIFEST_EXTENSION, MANIFEST_FORMAT_VERSION,
+};
+use crate::{
+    settings::{is_read_only, mod_state_dir, share_with_group},
+    utils::AddExtension,
+};
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+const INDEX_FILE: &str = "manifests.index";
+const INDEX_TEMP_EXTENSION: &str = "tmp";
+
+/// Identifies the version of a manifest or state file on disk, without reading it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+struct ManifestStamp {
+    file_name: String,
+    modified: SystemTime,
+    len: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct IndexEntry {
+    stamp: ManifestStamp,
+    manifest: Manifest,
+    state: ManifestState,
+}
+
+/// A single `MessagePack` file holding every manifest in the cache directory.
+///
+/// Reading hundreds of RON files is slow; as long as none of the manifest and state files
+/// changed since the index was written, the mod-list is read from the index instead.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ManifestIndex {
+    manifest_format: u32,
+    entries: Vec<IndexEntry>,
+    state_stamps: Vec<ManifestStamp>,
+}
+impl ManifestIndex {
+    /// Return all manifests, with their state, from the index, if it is up-to-date with the
+    /// cache and state directories.
+    pub fn load(cache_dir: &Utf8Path) -> Option<Vec<Manifest>> {
+        let index: Self = rmp_serde::from_slice(&read(cache_dir.join(INDEX_FILE)).ok()?).ok()?;
+        if index.manifest_format != MANIFEST_FORMAT_VERSION {
+            return None;
+        }
+
+        let mut stamps = manifest_stamps(cache_dir).ok()?;
+        let mut indexed = index
+            .entries
+            .iter()
+            .map(|e| e.stamp.clone())
+            .collect::<Vec<_>>();
+        stamps.sort_unstable();
+        indexed.sort_unstable();
+        if stamps != indexed || state_stamps(cache_dir).ok()? != index.state_stamps {
+            log::trace!("Manifest index is out of date.");
+            return None;
+        }
+
+        Some(
+            index
+                .entries
+                .into_iter()
+                .map(|e| {
+                    let mut manifest = e.manifest;
+                    manifest.cache_dir = cache_dir.to_path_buf();
+                    manifest.set_state(e.state);
+                    manifest
+                })
+                .collect(),
+        )
+    }
+
+    /// Rebuild the index from `manifests`, which should be all manifests in the cache directory
+    /// with their state applied.
+    ///
+    /// # Errors
+    ///
+    /// When the index cannot be written.
+    pub fn write(cache_dir: &Utf8Path, manifests: &[Manifest]) -> Result<()> {
+        if is_read_only() {
+            return Ok(());
+        }
+
+        let mut entries = Vec::with_capacity(manifests.len());
+        for stamp in manifest_stamps(cache_dir)? {
+            let Some(manifest) = manifests.iter().find(|m| {
+                m.manifest_dir
+                    .as_path()
+                    .add_extension(MANIFEST_EXTENSION)
+                    .as_str()
+                    == stamp.file_name
+            }) else {
+                // A manifest was added while we were reading; don't index a partial list.
+                return Ok(());
+            };
+            entries.push(IndexEntry {
+                stamp,
+                manifest: manifest.clone(),
+                state: manifest.state(),
+            });
+        }
+
+        let index = Self {
+            manifest_format: MANIFEST_FORMAT_VERSION,
+            entries,
+            state_stamps: state_stamps(cache_dir)?,
+        };
+
+        let path = cache_dir.join(INDEX_FILE);
+        let temp_path = path.add_extension(INDEX_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&rmp_serde::to_vec_named(&index)?)?;
+        rename(&temp_path, &path)?;
+        share_with_group(&path)?;
+
+        log::trace!("Wrote manifest index with {} entries.", index.entries.len());
+        Ok(())
+    }
+
+    /// Remove the index; it will be rebuilt the next time the mod-list is read.
+    pub fn invalidate(cache_dir: &Utf8Path) {
+        let _ = remove_file(cache_dir.join(INDEX_FILE));
+    }
+}
+
+fn manifest_stamps(cache_dir: &Utf8Path) -> Result<Vec<ManifestStamp>> {
+    file_stamps(cache_dir, MANIFEST_EXTENSION)
+}
+
+/// The state files in the cache directory and in the state directory, in order of path.
+fn state_stamps(cache_dir: &Utf8Path) -> Result<Vec<ManifestStamp>> {
+    let mut stamps = Vec::new();
+    for dir in std::iter::once(cache_dir).chain(mod_state_dir()) {
+        // The state directory is only created once a state is written to it.
+        if !dir.is_dir() {
+            continue;
+        }
+        stamps.extend(file_stamps(dir, STATE_EXTENSION)?.into_iter().map(|mut s| {
+            s.file_name = dir.join(&s.file_name).into_string();
+            s
+        }));
+    }
+    stamps.sort_unstable();
+    Ok(stamps)
+}
+
+fn file_stamps(dir: &Utf8Path, extension: &str) -> Result<Vec<ManifestStamp>> {
+    let mut stamps = Vec::new();
+    for entry in read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().unwrap_or_default() != extension {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        stamps.push(ManifestStamp {
+            file_name: entry.file_name().to_string_lossy().into_owned(),
+            modified: metadata.modified()?,
+            len: metadata.len(),
+        });
+    }
+    Ok(stamps)
+}
@@ -1,34 +1,51 @@
 use camino::{Utf8Path, Utf8PathBuf};
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
 use crate::installers::{DATA_DIR_NAME, TEXTURES_DIR_NAME};
 
+// Characters which cannot be in a file name on Windows; the game does not see such files.
+const WINDOWS_INVALID_CHARS: [char; 7] = [':', '<', '>', '|', '?', '*', '"'];
+// Names which are devices on Windows, with or without an extension.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+// Longest path most windows programs can open, including the drive and the terminating nul.
+pub const WINDOWS_MAX_PATH: usize = 260;
+const WINDOWS_MAX_NAME: usize = 255;
+// Proton and wine show the root of the file system as this drive.
+const WINE_ROOT_DRIVE: &str = "Z:";
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct InstallFile {
     source: Utf8PathBuf,
     destination: String,
 }
 impl InstallFile {
-    pub fn new(source: Utf8PathBuf, destination: &str) -> Self {
-        let destination = format!(
-            "{}/{}",
-            DATA_DIR_NAME,
-            destination
-                .strip_prefix("data")
-                .unwrap_or(destination)
-                .to_lowercase()
-        )
-        .replace("//", "/")
-        .replace("/textures/", &format!("/{TEXTURES_DIR_NAME}/"));
+    /// `source`, deployed at `destination` in the data directory; `None` when `destination`
+    /// escapes the game directory.
+    pub fn new(source: Utf8PathBuf, destination: &str) -> Option<Self> {
+        let Some(normalized) = normalize_destination(&format!("{DATA_DIR_NAME}/{destination}"))
+        else {
+            log::warn!("Skipping '{source}'; '{destination}' is outside of the game directory.");
+            return None;
+        };
+        // Archives often have their own 'data' directory.
+        let destination = normalized
+            .strip_prefix(&format!("{DATA_DIR_NAME}/data/"))
+            .map_or_else(
+                || normalized.clone(),
+                |rest| format!("{DATA_DIR_NAME}/{rest}"),
+            );
 
         log::trace!("New InstallFile: {} -> {}", source, destination);
 
-        Self {
+        Some(Self {
             source,
             destination,
-        }
+        })
     }
     pub fn new_raw(source: Utf8PathBuf, destination: String) -> Self {
         log::trace!("New InstallFile: {} -> {}", source, destination);
@@ -44,6 +61,247 @@ impl InstallFile {
     pub fn destination(&self) -> &str {
         &self.destination
     }
+    pub fn set_destination(&mut self, destination: String) {
+        self.destination = destination;
+    }
+}
+
+/// What is wrong with the destination of a file, as found by `destination_issues`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DestinationIssue {
+    Backslash,
+    DoubledSeparator,
+    Casing,
+    ParentDir,
+    EscapesGameDir,
+}
+impl Display for DestinationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Backslash => "backslashes",
+            Self::DoubledSeparator => "doubled or stray separators",
+            Self::Casing => "wrong casing",
+            Self::ParentDir => "'..' components",
+            Self::EscapesGameDir => "outside of the game directory",
+        })
+    }
+}
+
+/// Why a file would not load under Proton, as found by `windows_path_issues`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WindowsPathIssue {
+    InvalidCharacters,
+    ReservedName,
+    TrailingDotOrSpace,
+    NameTooLong,
+    /// The length of the full path, as the game sees it.
+    PathTooLong(usize),
+}
+impl WindowsPathIssue {
+    /// Whether `windows_safe_destination` renames it away; long paths need a shorter game
+    /// directory instead.
+    pub const fn is_renamable(self) -> bool {
+        !matches!(self, Self::PathTooLong(_))
+    }
+}
+impl Display for WindowsPathIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCharacters => f.write_str("characters invalid on windows"),
+            Self::ReservedName => f.write_str("a device name on windows"),
+            Self::TrailingDotOrSpace => f.write_str("names ending in a dot or space"),
+            Self::NameTooLong => write!(f, "names over {WINDOWS_MAX_NAME} characters"),
+            Self::PathTooLong(len) => {
+                write!(
+                    f,
+                    "{len} characters long, windows allows {}",
+                    WINDOWS_MAX_PATH - 1
+                )
+            }
+        }
+    }
+}
+
+/// The components of `path`, split on either separator, without empty and '.' components
+/// and with '..' applied; `None` when it escapes the directory it is relative to.
+pub fn clean_components(path: &str) -> Option<Vec<String>> {
+    let mut components = Vec::new();
+    for c in path.split(['/', '\\']) {
+        match c {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            c => components.push(c.to_owned()),
+        }
+    }
+    Some(components)
+}
+
+/// `destination`, relative to the game directory, in its canonical form;
+/// `None` when it escapes the game directory.
+///
+/// Canonical destinations are separated by '/', and in lower-case below the data directory,
+/// except for directories named 'Textures', which are spelled like `TEXTURES_DIR_NAME`. Files in
+/// the root of the game directory, like those of a script extender, keep their casing.
+pub fn normalize_destination(destination: &str) -> Option<String> {
+    let mut components = clean_components(destination)?;
+    if components
+        .first()
+        .is_some_and(|c| c.eq_ignore_ascii_case(DATA_DIR_NAME))
+    {
+        DATA_DIR_NAME.clone_into(&mut components[0]);
+        let last = components.len() - 1;
+        for (idx, c) in components.iter_mut().enumerate().skip(1) {
+            *c = if idx < last && c.eq_ignore_ascii_case(TEXTURES_DIR_NAME) {
+                TEXTURES_DIR_NAME.to_owned()
+            } else {
+                c.to_lowercase()
+            };
+        }
+    }
+    Some(components.join("/"))
+}
+
+/// Everything `normalize_destination` would change about `destination`.
+pub fn destination_issues(destination: &str) -> Vec<DestinationIssue> {
+    let mut issues = Vec::new();
+    if destination.contains('\\') {
+        issues.push(DestinationIssue::Backslash);
+    }
+    let separated = destination.replace('\\', "/");
+    if separated.starts_with('/')
+        || separated.ends_with('/')
+        || separated.split('/').any(|c| c.is_empty() || c == ".")
+    {
+        issues.push(DestinationIssue::DoubledSeparator);
+    }
+    if separated.split('/').any(|c| c == "..") {
+        issues.push(DestinationIssue::ParentDir);
+    }
+
+    match clean_components(destination) {
+        Some(components) => {
+            if normalize_destination(destination).is_some_and(|n| n != components.join("/")) {
+                issues.push(DestinationIssue::Casing);
+            }
+        }
+        None => issues.push(DestinationIssue::EscapesGameDir),
+    }
+    issues
+}
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(stem))
+}
+
+/// Everything which keeps the file at `destination` in `game_dir` from loading under Proton,
+/// where the game sees it as `Z:\<game_dir>\<destination>`.
+pub fn windows_path_issues(game_dir: &Utf8Path, destination: &str) -> Vec<WindowsPathIssue> {
+    let mut issues = Vec::new();
+    let names = destination.split('/').collect::<Vec<_>>();
+    if names
+        .iter()
+        .any(|n| n.contains(WINDOWS_INVALID_CHARS) || n.contains(char::is_control))
+    {
+        issues.push(WindowsPathIssue::InvalidCharacters);
+    }
+    if names.iter().any(|n| is_reserved_name(n)) {
+        issues.push(WindowsPathIssue::ReservedName);
+    }
+    if names.iter().any(|n| n.ends_with(['.', ' '])) {
+        issues.push(WindowsPathIssue::TrailingDotOrSpace);
+    }
+    if names.iter().any(|n| utf16_len(n) > WINDOWS_MAX_NAME) {
+        issues.push(WindowsPathIssue::NameTooLong);
+    }
+    let len = WINE_ROOT_DRIVE.len()
+        + utf16_len(game_dir.as_str().trim_end_matches('/'))
+        + 1
+        + utf16_len(destination);
+    if len >= WINDOWS_MAX_PATH {
+        issues.push(WindowsPathIssue::PathTooLong(len));
+    }
+    issues
+}
+
+/// `destination` with the names windows cannot have renamed.
+///
+/// Invalid characters become '_', trailing dots and spaces are dropped, device names get a '_'
+/// and long names are cut short, keeping their extension.
+pub fn windows_safe_destination(destination: &str) -> String {
+    destination
+        .split('/')
+        .map(|name| {
+            let mut name = name
+                .chars()
+                .map(|c| {
+                    if WINDOWS_INVALID_CHARS.contains(&c) || c.is_control() {
+                        '_'
+                    } else {
+                        c
+                    }
+                })
+                .collect::<String>();
+            let trimmed = name.trim_end_matches(['.', ' ']);
+            name = if trimmed.is_empty() {
+                "_".to_owned()
+            } else {
+                trimmed.to_owned()
+            };
+            if is_reserved_name(&name) {
+                name = match name.split_once('.') {
+                    Some((stem, extension)) => format!("{stem}_.{extension}"),
+                    None => format!("{name}_"),
+                };
+            }
+            if utf16_len(&name) > WINDOWS_MAX_NAME {
+                let extension = Utf8Path::new(&name)
+                    .extension()
+                    .map(|e| format!(".{e}"))
+                    .unwrap_or_default();
+                let keep = WINDOWS_MAX_NAME.saturating_sub(utf16_len(&extension));
+                let mut len = 0;
+                name = name
+                    .chars()
+                    .take_while(|c| {
+                        len += c.len_utf16();
+                        len <= keep
+                    })
+                    .collect::<String>()
+                    + &extension;
+            }
+            name
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `destination` with `_<number>` added to its name, to tell it apart from another file.
+pub fn numbered_destination(destination: &str, number: usize) -> String {
+    let path = Utf8Path::new(destination);
+    let name = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => format!("{stem}_{number}.{extension}"),
+        _ => format!("{}_{number}", path.file_name().unwrap_or_default()),
+    };
+    path.with_file_name(name).into_string()
+}
+
+// Windows counts the length of names and paths in UTF-16 code units.
+fn utf16_len(s: &str) -> usize {
+    s.encode_utf16().count()
+}
+
+/// Remove the files for which `matches` holds from `files`, and return them.
+pub fn split_off_matching(
+    files: &mut Vec<InstallFile>,
+    matches: &dyn Fn(&InstallFile) -> bool,
+) -> Vec<InstallFile> {
+    let (taken, kept) = std::mem::take(files).into_iter().partition(|f| matches(f));
+    *files = kept;
+    taken
 }
 impl From<Utf8PathBuf> for InstallFile {
     fn from(pb: Utf8PathBuf) -> Self {
@@ -1,30 +1,79 @@
 use camino::Utf8Path;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use super::install_file::InstallFile;
+use super::install_file::{split_off_matching, InstallFile};
 
+/// Files of a script extender (or similar tool) which are deployed into the game's root directory.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LoaderManifest {
-    dll: InstallFile,
-    exe: InstallFile,
+    #[serde(default)]
+    files: Vec<InstallFile>,
+    #[serde(default)]
+    disabled_files: Vec<InstallFile>,
+    // Before manifest format 3 a loader had exactly one dll and one exe; see `migrate`.
+    #[serde(default, skip_serializing, deserialize_with = "some_file")]
+    dll: Option<InstallFile>,
+    #[serde(default, skip_serializing, deserialize_with = "some_file")]
+    exe: Option<InstallFile>,
+}
+// Older manifests wrote the dll and exe as plain files, not as options.
+fn some_file<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<InstallFile>, D::Error> {
+    InstallFile::deserialize(deserializer).map(Some)
 }
 impl LoaderManifest {
-    pub fn new(files: &[InstallFile]) -> Self {
-        //TODO fix unwraps
-        let exe = files
-            .iter()
-            .find(|isf| isf.source().extension().unwrap_or_default().eq("exe"))
-            .unwrap()
-            .clone();
-        let dll = files
-            .iter()
-            .find(|isf| isf.source().extension().unwrap_or_default().eq("dll"))
-            .unwrap()
-            .clone();
-
-        Self { dll, exe }
+    pub fn new(files: Vec<InstallFile>, disabled_files: Vec<InstallFile>) -> Self {
+        Self {
+            files,
+            disabled_files,
+            dll: None,
+            exe: None,
+        }
+    }
+    /// Move the dll and exe of an older manifest into the list of files.
+    pub fn migrate(&mut self) {
+        self.files.extend(self.dll.take());
+        self.files.extend(self.exe.take());
     }
     pub fn files(&self, _cache_dir: &Utf8Path) -> Vec<InstallFile> {
-        vec![self.dll.clone(), self.exe.clone()]
+        self.files.clone()
+    }
+    pub fn disabled_files(&self) -> Vec<InstallFile> {
+        self.disabled_files.clone()
+    }
+    /// The files and disabled files, to change them in place.
+    pub fn files_mut(&mut self) -> impl Iterator<Item = &mut InstallFile> {
+        self.files.iter_mut().chain(self.disabled_files.iter_mut())
     }
+    /// Remove the files and disabled files for which `matches` holds, and return them.
+    pub fn split_off(
+        &mut self,
+        matches: &dyn Fn(&InstallFile) -> bool,
+    ) -> (Vec<InstallFile>, Vec<InstallFile>) {
+        (
+            split_off_matching(&mut self.files, matches),
+            split_off_matching(&mut self.disabled_files, matches),
+        )
+    }
+    pub fn disable_file(&mut self, name: &str) -> bool {
+        if let Some(idx) = find_file(&self.files, name) {
+            self.disabled_files.push(self.files.remove(idx));
+            true
+        } else {
+            false
+        }
+    }
+    pub fn enable_file(&mut self, name: &str) -> bool {
+        if let Some(idx) = find_file(&self.disabled_files, name) {
+            self.files.push(self.disabled_files.remove(idx));
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn find_file(files: &[InstallFile], name: &str) -> Option<usize> {
+    files.iter().position(|isf| {
+        isf.source().as_str() == name || isf.source().file_name().unwrap_or_default() == name
+    })
 }
@@ -7,14 +7,20 @@ pub enum ModState {
     Enabled,
     #[default]
     Disabled,
+    /// Disabled, and kept that way; enabling all mods and upgrades leave it alone
+    /// until it is unpinned.
+    Pinned,
 }
 impl ModState {
     pub const fn is_enabled(self) -> bool {
         match self {
             Self::Enabled => true,
-            Self::Disabled => false,
+            Self::Disabled | Self::Pinned => false,
         }
     }
+    pub const fn is_pinned(self) -> bool {
+        matches!(self, Self::Pinned)
+    }
 }
 impl From<bool> for ModState {
     fn from(v: bool) -> Self {
@@ -35,6 +41,7 @@ impl Display for ModState {
         match self {
             Self::Enabled => f.write_str("Enabled"),
             Self::Disabled => f.write_str("Disabled"),
+            Self::Pinned => f.write_str("Pinned"),
         }
     }
 }
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ModErrors;
+
+pub(super) const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+/// Moves the destination of the files matching `from` to `to`; used to fix mis-packaged mods.
+///
+/// Without wildcards `from` is a single file, which is renamed to `to`; wildcard characters in
+/// its name are escaped like '[?]'. Otherwise the directory in front of the first wildcard is
+/// replaced by `to`, keeping the rest of the path; 'Data/foo/textures/*' -> 'Data/textures'
+/// moves 'Data/foo/textures/a/b.dds' to 'Data/textures/a/b.dds'.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Remap {
+    from: String,
+    to: String,
+}
+impl Remap {
+    /// Rename the single file `from` to `to`, whatever characters its name has.
+    ///
+    /// # Errors
+    ///
+    /// Never, as `from` is escaped; see `new`.
+    pub fn rename(from: &str, to: &str) -> Result<Self> {
+        Self::new(&Pattern::escape(from), to)
+    }
+    /// Rename the files matching the glob `from` to `to`, where '{}' is the part of the name the
+    /// '*' matched.
+    ///
+    /// # Errors
+    ///
+    /// When `from` is not a valid glob.
+    pub fn new(from: &str, to: &str) -> Result<Self> {
+        let from = normalize(from);
+        Pattern::new(&from).map_err(|e| ModErrors::InvalidPattern(from.clone(), e.to_string()))?;
+
+        Ok(Self {
+            from,
+            to: normalize(to),
+        })
+    }
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+    /// The new destination of `destination`, if this rule applies to it.
+    pub fn apply(&self, destination: &str) -> Option<String> {
+        let pattern = Pattern::new(&self.from).ok()?;
+        if !pattern.matches_with(destination, MATCH_OPTIONS) {
+            return None;
+        }
+
+        let Some(wildcard) = first_wildcard(&self.from) else {
+            return Some(self.to.clone());
+        };
+        let prefix_len = self.from[..wildcard].rfind('/').map_or(0, |idx| idx + 1);
+        let rest = destination.get(prefix_len..)?;
+        if self.to.is_empty() {
+            Some(rest.to_owned())
+        } else {
+            Some(format!("{}/{rest}", self.to))
+        }
+    }
+}
+
+/// Byte offset of the first wildcard in `pattern`; escaped characters, like '[*]', are none.
+fn first_wildcard(pattern: &str) -> Option<usize> {
+    let bytes = pattern.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'[' if bytes.get(idx + 2) == Some(&b']')
+                && matches!(bytes.get(idx + 1), Some(b'*' | b'?' | b'[' | b']')) =>
+            {
+                idx += 3;
+            }
+            b'*' | b'?' | b'[' => return Some(idx),
+            _ => idx += 1,
+        }
+    }
+    None
+}
+
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_owned()
+}
@@ -0,0 +1,99 @@
+use std::fs::{read_to_string, remove_file, rename, File};
+use std::io::Write;
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use super::{mod_state::ModState, MANIFEST_EXTENSION};
+use crate::utils::AddExtension;
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+pub const STATE_EXTENSION: &str = "state";
+const STATE_TEMP_EXTENSION: &str = "tmp";
+/// Version of the state file format; state files without a version predate pinned mods.
+pub const STATE_FORMAT_VERSION: u32 = 1;
+
+/// Everything the user can change about an installed mod.
+///
+/// It is stored next to the manifest, which only describes what was installed,
+/// so that reinstalling or upgrading a mod can replace the manifest while keeping this.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ManifestState {
+    #[serde(default)]
+    pub format_version: u32,
+    #[serde(default)]
+    pub mod_state: ModState,
+    #[serde(default)]
+    pub priority: isize,
+    /// Orders mods of equal priority; see `Manifest::sequence`.
+    #[serde(default)]
+    pub sequence: u64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+impl ManifestState {
+    pub fn path(state_dir: &Utf8Path, manifest_dir: &Utf8Path) -> Utf8PathBuf {
+        state_dir.join(manifest_dir).add_extension(STATE_EXTENSION)
+    }
+    pub fn read(state_dir: &Utf8Path, manifest_dir: &Utf8Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(Self::path(state_dir, manifest_dir)).ok()?;
+        let mut state: Self = ron::from_str(&contents)
+            .map_err(|e| log::warn!("Ignoring damaged mod state for '{manifest_dir}': {e}"))
+            .ok()?;
+        state.migrate();
+        Some(state)
+    }
+    /// Read a state file written by an older release of starmod; it is written in the
+    /// current format the next time the state changes.
+    const fn migrate(&mut self) {
+        if self.format_version < 1 && self.priority < 0 {
+            // A negative priority kept a mod disabled, which is what pinning is for.
+            self.mod_state = ModState::Pinned;
+            self.priority = 0;
+        }
+        self.format_version = STATE_FORMAT_VERSION;
+    }
+    /// Write the state of the mod in `manifest_dir` into `state_dir`; returns the path of the file.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be written.
+    pub fn write(&self, state_dir: &Utf8Path, manifest_dir: &Utf8Path) -> Result<Utf8PathBuf> {
+        let path = Self::path(state_dir, manifest_dir);
+        let temp_path = path.add_extension(STATE_TEMP_EXTENSION);
+
+        let mut file = File::create(&temp_path)?;
+        file.write_all(
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?.as_bytes(),
+        )?;
+        rename(&temp_path, &path)?;
+        Ok(path)
+    }
+    /// The state stored in the root of the extracted archive in `archive_dir` by
+    /// 'mods pack --with-manifest'. It is taken out of the archive together with the packed
+    /// manifest, so neither is installed as a file of the mod.
+    ///
+    /// # Errors
+    ///
+    /// When the archive directory cannot be read, the packed state is broken, or the packed
+    /// files cannot be removed.
+    pub fn take_packed(archive_dir: &Utf8Path) -> Result<Option<Self>> {
+        for entry in archive_dir.read_dir_utf8()? {
+            let path = entry?.into_path();
+            let manifest = path.with_extension(MANIFEST_EXTENSION);
+            if path.extension() != Some(STATE_EXTENSION) || !path.is_file() || !manifest.is_file() {
+                continue;
+            }
+
+            let mut state: Self = ron::from_str(&read_to_string(&path)?)?;
+            state.migrate();
+            remove_file(&path)?;
+            remove_file(&manifest)?;
+            return Ok(Some(state));
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,84 @@
+//! LOOT's masterlist of the game, the sorting metadata kept up to date by the LOOT community,
+//! and the prelude it shares with the masterlists of the other games.
+
+use std::fs::{create_dir_all, read_to_string, rename, write};
+
+use anyhow::{Context, Result};
+use ureq::AgentBuilder;
+
+use crate::{
+    settings::{ensure_writable, Settings},
+    utils::AddExtension,
+};
+
+// The branch of the masterlists for the current versions of LOOT.
+const MASTERLIST_BRANCH: &str = "v0.21";
+const MASTERLIST_FILE: &str = "masterlist.yaml";
+const PRELUDE_REPOSITORY: &str = "prelude";
+const PRELUDE_FILE: &str = "prelude.yaml";
+const DOWNLOAD_EXTENSION: &str = "part";
+
+fn raw_url(repository: &str, file: &str) -> String {
+    format!("https://raw.githubusercontent.com/loot/{repository}/{MASTERLIST_BRANCH}/{file}")
+}
+
+/// Download the masterlist of the game and the prelude into LOOT's data directory; returns
+/// the number of files which changed.
+///
+/// # Errors
+///
+/// When LOOT's data directory is not known, or a file cannot be downloaded or written.
+pub fn update(settings: &Settings) -> Result<usize> {
+    ensure_writable("update LOOT's masterlist")?;
+
+    let agent = AgentBuilder::new()
+        .user_agent(&format!("starmod/{}", env!("CARGO_PKG_VERSION")))
+        .build();
+    let mut changed = 0;
+    for (url, path) in [
+        (
+            raw_url(settings.game().loot_repository(), MASTERLIST_FILE),
+            settings.loot_masterlist(),
+        ),
+        (
+            raw_url(PRELUDE_REPOSITORY, PRELUDE_FILE),
+            settings.loot_prelude(),
+        ),
+    ] {
+        log::debug!("Downloading '{url}' to '{path}'.");
+        let contents = agent
+            .get(&url)
+            .call()
+            .with_context(|| format!("Unable to download '{url}'"))?
+            .into_string()?;
+        // Never replace a working masterlist with an error page.
+        serde_yaml::from_str::<serde_yaml::Value>(&contents)
+            .with_context(|| format!("'{url}' is not a valid masterlist"))?;
+
+        if read_to_string(&path).is_ok_and(|current| current == contents) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let partial = path.add_extension(DOWNLOAD_EXTENSION);
+        write(&partial, contents)?;
+        rename(&partial, &path)?;
+        log::info!("Updated '{path}'.");
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+/// Update the masterlist before sorting, when the 'auto-update-masterlist' setting is on;
+/// when that fails, like without a network, the masterlist at hand is used.
+pub fn refresh(settings: &Settings) {
+    if !settings.auto_update_masterlist() {
+        return;
+    }
+    match update(settings) {
+        Ok(0) => log::debug!("LOOT's masterlist is up to date."),
+        Ok(_) => {}
+        Err(e) => log::warn!("Unable to update LOOT's masterlist: {e:#}."),
+    }
+}
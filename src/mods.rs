@@ -1,46 +1,64 @@
 use std::{
-    collections::HashSet,
+    collections::BTreeSet,
     fmt::Display,
-    fs::{self, read_link, remove_dir, remove_file, rename, DirBuilder},
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    fs::{self, read_link, remove_file},
 };
 
-use anyhow::{Context, Error, Result};
+use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
-use indicatif::{ProgressBar, ProgressStyle};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
 use crate::{
     conflict::conflict_list_by_file,
+    deployment::{
+        backup::{is_backup, original_of, BackupRegistry},
+        dirs::CreatedDirs,
+        DeployPlan,
+    },
     errors::InternalError,
     installers::{
         custom::create_custom_manifest,
-        data::create_data_manifest,
-        fomod::{create_fomod_manifest, FOMOD_INFO_FILE, FOMOD_MODCONFIG_FILE},
+        data::{create_data_manifest, has_data_layout},
+        fomod::{create_fomod_manifest, FomodChoices, FOMOD_INFO_FILE, FOMOD_MODCONFIG_FILE},
         loader::create_loader_manifest,
+        plugin::{create_plugin_manifest, is_sfse_plugin_mod},
+        script::{create_script_manifest, find_installer_script, run_installer_script},
     },
-    manifest::{Manifest, MANIFEST_EXTENSION},
-    ui::ModListBuilder,
-    utils::AddExtension,
+    manifest::{index::ManifestIndex, install_file::InstallFile, Manifest, MANIFEST_EXTENSION},
+    settings::{ensure_writable, links_mods},
+    ui::{fuzzy_find, ModListBuilder},
 };
 
-const BACKUP_EXTENTION: &str = "starmod_bkp";
+pub const BACKUP_EXTENTION: &str = "starmod_bkp";
 
-#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, ValueEnum)]
 pub enum ModKind {
     // Goes into Data
     Data,
     //Installer
+    #[value(name = "fomod")]
     FoMod,
     //Goes into the root dir
     Loader,
+    // SFSE plugin dlls, go into Data/SFSE/Plugins
+    Plugin,
     // Custom Mods, should always scan their files
     Custom,
+    // Installed by one of the user's installer scripts
+    Scripted,
 }
 impl ModKind {
-    pub fn detect_mod_type(cache_dir: &Utf8Path, name: &Utf8Path) -> Result<Self> {
+    /// The kind of the extracted archive `name`, with the installer which installs it.
+    ///
+    /// The user's installer scripts are only asked about archives starmod does not recognise:
+    /// no FOMOD, loader or SFSE plugin, and no 'Data' directory or files which belong in it.
+    ///
+    /// # Errors
+    ///
+    /// When the archive directory cannot be read.
+    pub fn detect_mod_type(cache_dir: &Utf8Path, name: &Utf8Path) -> Result<Installer> {
         let archive_dir = Utf8PathBuf::from(cache_dir).join(name);
 
         let walker = WalkDir::new(&archive_dir)
@@ -70,7 +88,7 @@ impl ModKind {
 
             if info && config {
                 log::trace!("Mod Type: FoMod");
-                return Ok(Self::FoMod);
+                return Ok(Self::FoMod.into());
             }
         }
 
@@ -88,44 +106,147 @@ impl ModKind {
             if let Some(ext) = entry_path.extension() {
                 if ext == "exe" {
                     log::trace!("Mod Type: Loader");
-                    return Ok(Self::Loader);
+                    return Ok(Self::Loader.into());
                 }
             }
         }
 
+        if is_sfse_plugin_mod(&archive_dir)? {
+            log::trace!("Mod Type: SFSE Plugin");
+            return Ok(Self::Plugin.into());
+        }
+
+        let script = if has_data_layout(&archive_dir)? {
+            None
+        } else {
+            find_installer_script(cache_dir, name)
+        };
+        if let Some(script) = script {
+            log::trace!("Mod Type: Scripted");
+            return Ok(Installer {
+                kind: Self::Scripted,
+                script: Some(script),
+            });
+        }
+
         log::trace!("Mod Type: Data Mod");
-        Ok(Self::Data)
+        Ok(Self::Data.into())
+    }
+    /// Installing a mod of this kind can ask the user for input; loaders ask which of their
+    /// root files to deploy when that is ambiguous.
+    pub const fn is_interactive(self) -> bool {
+        matches!(self, Self::FoMod | Self::Loader | Self::Scripted)
     }
+    /// Install the extracted archive `name` with the installer of this kind.
+    ///
+    /// # Errors
+    ///
+    /// When the installer fails, or the manifest cannot be written.
     pub fn create_mod(self, cache_dir: &Utf8Path, name: &Utf8Path) -> Result<Manifest> {
+        self.create_mod_with_choices(cache_dir, name, None)
+    }
+    /// Like `create_mod`, but a FOMOD installer uses the recorded `choices` where it can.
+    ///
+    /// # Errors
+    ///
+    /// When the installer fails, or the manifest cannot be written.
+    pub fn create_mod_with_choices(
+        self,
+        cache_dir: &Utf8Path,
+        name: &Utf8Path,
+        choices: Option<&FomodChoices>,
+    ) -> Result<Manifest> {
         let md = match self {
-            Self::FoMod => create_fomod_manifest(self, cache_dir, name)?,
+            Self::FoMod => create_fomod_manifest(self, cache_dir, name, choices)?,
             Self::Loader => create_loader_manifest(self, cache_dir, name)?,
+            Self::Plugin => create_plugin_manifest(self, cache_dir, name)?,
             Self::Custom => create_custom_manifest(self, cache_dir, name)?,
             Self::Data => create_data_manifest(self, cache_dir, name)?,
+            Self::Scripted => create_script_manifest(self, cache_dir, name)?,
         };
 
         md.write()?;
         Ok(md)
     }
 }
+/// The kind of an extracted archive, with the installer script which installs it when it is
+/// `Scripted`, so the scripts are not asked again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Installer {
+    kind: ModKind,
+    script: Option<Utf8PathBuf>,
+}
+impl Installer {
+    pub const fn kind(&self) -> ModKind {
+        self.kind
+    }
+    pub const fn is_interactive(&self) -> bool {
+        self.kind.is_interactive()
+    }
+    /// Install the extracted archive `name`; with the script, when there is one.
+    ///
+    /// # Errors
+    ///
+    /// When the installer or script fails, or the manifest cannot be written.
+    pub fn create_mod(&self, cache_dir: &Utf8Path, name: &Utf8Path) -> Result<Manifest> {
+        self.create_mod_with_choices(cache_dir, name, None)
+    }
+    /// Like `ModKind::create_mod_with_choices`, with the installer script found before.
+    ///
+    /// # Errors
+    ///
+    /// When the installer or script fails, or the manifest cannot be written.
+    pub fn create_mod_with_choices(
+        &self,
+        cache_dir: &Utf8Path,
+        name: &Utf8Path,
+        choices: Option<&FomodChoices>,
+    ) -> Result<Manifest> {
+        let Some(script) = &self.script else {
+            return self.kind.create_mod_with_choices(cache_dir, name, choices);
+        };
+        let md = run_installer_script(script, self.kind, cache_dir, name)?;
+        md.write()?;
+        Ok(md)
+    }
+}
+impl From<ModKind> for Installer {
+    fn from(kind: ModKind) -> Self {
+        Self { kind, script: None }
+    }
+}
 impl Display for ModKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Data { .. } => f.write_str("Data"),
             Self::FoMod => f.write_str("FoMod"),
             Self::Loader => f.write_str("Loader"),
+            Self::Plugin => f.write_str("Plugin"),
             Self::Custom => f.write_str("Custom"),
+            Self::Scripted => f.write_str("Scripted"),
         }
     }
 }
 
 pub trait GatherModList {
+    /// The manifests in `cache_dir`, with their state, in the order they are deployed.
+    ///
+    /// # Errors
+    ///
+    /// When a manifest cannot be read.
     fn gather_mods(cache_dir: &Utf8Path) -> Result<Vec<Manifest>>;
 }
 
 impl GatherModList for Vec<Manifest> {
     fn gather_mods(cache_dir: &Utf8Path) -> Result<Vec<Manifest>> {
         log::trace!("Gathering Mods");
+
+        if let Some(mut mod_list) = ManifestIndex::load(cache_dir) {
+            mod_list.sort_by(Ord::cmp);
+            log::trace!("Finished Gathering Mods from the index");
+            return Ok(mod_list);
+        }
+
         let paths = fs::read_dir(cache_dir)?;
 
         let mut mod_list = Self::new();
@@ -145,6 +266,11 @@ impl GatherModList for Vec<Manifest> {
             }
         }
 
+        mod_list.iter_mut().for_each(Manifest::apply_state);
+        if let Err(e) = ManifestIndex::write(cache_dir, &mod_list) {
+            log::debug!("Unable to write the manifest index: {e}");
+        }
+
         mod_list.sort_by(Ord::cmp);
 
         log::trace!("Finished Gathering Mods");
@@ -152,13 +278,151 @@ impl GatherModList for Vec<Manifest> {
     }
 }
 
+/// The files deployed for the enabled mods in `mod_list`, together with the name of
+/// the providing mod; sources are relative to the cache dir, destinations to the game dir.
+///
+/// # Errors
+///
+/// When the files of a mod cannot be listed.
+pub fn deployed_files(mod_list: &[Manifest]) -> Result<Vec<(String, InstallFile)>> {
+    let conflict_list = conflict_list_by_file(mod_list)?;
+
+    let mut file_list = Vec::new();
+    for m in mod_list.iter().filter(|m| m.is_enabled()) {
+        file_list.extend(
+            m.enlist_files(&conflict_list)?
+                .into_iter()
+                .map(|f| (m.name().to_owned(), f)),
+        );
+    }
+    Ok(file_list)
+}
+
+/// Remove every symlink into `cache_dir` from `game_dir` and restore the backups of the
+/// original game files, without touching the manifests.
+/// Returns the number of removed links and restored backups.
+///
+/// # Errors
+///
+/// In read-only mode, or when a link cannot be removed or a backup restored.
+pub fn purge_deployment(cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<(usize, usize)> {
+    ensure_writable("remove mods from the game directory")?;
+
+    let mut links = Vec::new();
+    let mut backups = Vec::new();
+    for entry in WalkDir::new(game_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .same_file_system(true)
+    {
+        let entry = entry?;
+        let entry_path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
+
+        if entry.path_is_symlink() {
+            if read_link(&entry_path)?.starts_with(cache_dir) {
+                links.push(entry_path);
+            }
+        } else if entry_path.is_file() && is_backup(&entry_path) {
+            backups.push(entry_path);
+        }
+    }
+
+    // Read before the links are gone, as it may be seeded from them.
+    let mut created = CreatedDirs::read(cache_dir, game_dir);
+    for link in &links {
+        log::debug!("Removing link {link}");
+        remove_file(link)?;
+    }
+
+    created.remove_empty(game_dir, links.iter().map(Utf8PathBuf::as_path));
+    created.write()?;
+
+    // Only the newest backup goes back; older ones were in the way of an earlier link, and
+    // are kept for the user to look at.
+    let mut registry = BackupRegistry::read(cache_dir);
+    let originals = backups
+        .iter()
+        .filter_map(|backup| original_of(backup))
+        .chain(registry.originals().map(Utf8Path::to_owned))
+        .collect::<BTreeSet<_>>();
+    let mut restored = 0;
+    for original in originals {
+        let is_restored = registry.restore(&original)?;
+        restored += usize::from(is_restored);
+        let left = registry.backups_of(&original);
+        if left.is_empty() {
+            continue;
+        }
+        if is_restored {
+            log::warn!(
+                "Keeping {} older backup(s) of '{original}': {}",
+                left.len(),
+                left.iter()
+                    .map(|b| b.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        } else {
+            log::warn!("Not restoring the backup of '{original}': it already exists.");
+        }
+    }
+    registry.write()?;
+
+    Ok((links.len(), restored))
+}
+
 pub trait ModList {
+    /// Enable every mod, and deploy them.
+    ///
+    /// # Errors
+    ///
+    /// When a state file cannot be written, or the mods cannot be deployed.
     fn enable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()>;
+    /// Disable every mod, and take them out of the game directory.
+    ///
+    /// # Errors
+    ///
+    /// When a state file cannot be written, or the links cannot be removed.
     fn disable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()>;
+    /// Deploy the enabled mods again.
+    ///
+    /// # Errors
+    ///
+    /// When the mods cannot be deployed.
     fn re_enable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()>;
+    /// Enable mod `idx`, and deploy it.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written, or the mod cannot be deployed.
     fn enable_mod(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path, idx: usize) -> Result<()>;
+    /// Disable mod `idx`, and take it out of the game directory.
+    ///
+    /// # Errors
+    ///
+    /// When the state file cannot be written, or its links cannot be removed.
     fn disable_mod(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path, idx: usize) -> Result<()>;
 }
+/// SFSE plugins only load through the script extender; warn when it is not enabled alongside them.
+fn check_plugin_loader(mod_list: &[Manifest]) {
+    let plugins = mod_list
+        .iter()
+        .filter(|m| m.is_enabled() && m.kind() == ModKind::Plugin)
+        .map(Manifest::name)
+        .collect::<Vec<_>>();
+
+    if !plugins.is_empty()
+        && !mod_list
+            .iter()
+            .any(|m| m.is_enabled() && m.kind() == ModKind::Loader)
+    {
+        log::warn!(
+            "SFSE plugin mod(s) {} are enabled, but no loader mod is; they will not be loaded.",
+            plugins.join(", ")
+        );
+    }
+}
+
 impl ModList for Vec<Manifest> {
     fn enable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()> {
         self.as_mut_slice().enable(cache_dir, game_dir)
@@ -176,239 +440,69 @@ impl ModList for Vec<Manifest> {
         self.as_mut_slice().disable_mod(cache_dir, game_dir, idx)
     }
 }
+/// Bring the links of the mods in `mod_list` in line with their state; only the links which
+/// differ are changed.
+fn deploy(mod_list: &[Manifest], cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()> {
+    if !links_mods() {
+        log::debug!("Mods are mounted over the game directory when it runs; nothing to link.");
+        return Ok(());
+    }
+    let plan = DeployPlan::for_mods(mod_list, cache_dir, game_dir)?;
+    log::debug!(
+        "Deploying: {} new, {} changed, {} removed and {} unchanged link(s)",
+        plan.create().len(),
+        plan.replace().len(),
+        plan.remove().len(),
+        plan.unchanged()
+    );
+    plan.apply(game_dir)
+}
+
 impl ModList for &mut [Manifest] {
     fn enable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()> {
         use rayon::prelude::*;
 
-        log::debug!("Temp enabling all files in list");
-        for m in self.iter_mut() {
-            if m.priority() >= 0 {
-                m.temp_set_enabled();
-            }
-        }
-
-        let conflict_list = conflict_list_by_file(self)?;
-        let mut file_list = Vec::with_capacity(conflict_list.len());
-        let dir_cache = Arc::new(Mutex::new(HashSet::new()));
+        ensure_writable("link mods into the game directory")?;
 
-        log::debug!("Collecting File List");
+        log::debug!("Temp enabling all files in list");
         for m in self.iter_mut() {
-            if m.is_enabled() {
-                file_list.extend(m.enlist_files(&conflict_list)?);
-            }
+            // Pinned mods stay disabled.
+            m.temp_set_enabled();
         }
 
-        let sty = ProgressStyle::with_template("{prefix:.bold.dim} {wide_msg}: {bar:40}").unwrap();
-        let progress = ProgressBar::new(file_list.len() as u64 + self.len() as u64)
-            .with_style(sty)
-            .with_message("Linking files...");
-
-        log::debug!("Installing Files");
-        file_list.par_iter().try_for_each(|f| {
-            // for f in file_list {
-            let origin = cache_dir.join(f.source());
-            let destination = game_dir.join(Utf8PathBuf::from(f.destination()));
-            log::trace!("starting with file: {} -> {}", origin, destination);
-
-            let destination_base = destination
-                .parent()
-                .ok_or(InternalError::Error(
-                    "ModList::enable destination has no parent".to_string(),
-                ))?
-                .to_path_buf();
-            if !dir_cache.lock().unwrap().contains(&destination_base) {
-                log::trace!("creating directory {destination_base}");
-
-                //create intermediate directories
-                DirBuilder::new()
-                    .recursive(true)
-                    .create(&destination_base)?;
-                dir_cache.lock().unwrap().insert(destination_base);
-            }
-
-            if destination.exists() {
-                log::trace!("Destination already exists.");
-
-                // Remove existing symlinks which point back to our archive dir
-                // This ensures that the last mod wins, but we should do conflict
-                // detection and resolution before this, so we can inform the user.
-                if destination.is_symlink() {
-                    let target = Utf8PathBuf::try_from(read_link(&destination)?)?;
-
-                    if target.starts_with(cache_dir) {
-                        remove_file(&destination)?;
-                        log::debug!("overrule {} ({} > {})", destination, origin, target);
-                    }
-                }
-
-                // Check if there is a backup file made by us
-                // if so, restore it.
-                if destination.is_file() {
-                    let bkp_destination = destination.add_extension(BACKUP_EXTENTION);
-                    log::info!(
-                        "renaming foreign file from {} -> {}",
-                        destination,
-                        bkp_destination
-                    );
-                    rename(&destination, bkp_destination)?;
-                }
-            }
-
-            log::debug!("link {} to {}", origin, destination);
-            std::os::unix::fs::symlink(&origin, &destination)
-                .with_context(|| format!("Unable to link {} -> {}", origin, destination))?;
-
-            progress.inc(1);
-            Ok::<(), anyhow::Error>(())
-        })?;
+        deploy(self, cache_dir, game_dir)?;
 
         log::debug!("Set Mods to Enabled");
-        self.par_iter_mut().try_for_each(|m| {
-            m.set_enabled()?;
-            progress.inc(1);
-            Ok::<(), anyhow::Error>(())
-        })?;
+        self.par_iter_mut()
+            .try_for_each(|m| m.set_enabled().map(|_| ()))?;
 
-        progress.finish_and_clear();
+        check_plugin_loader(self);
 
         Ok(())
     }
     fn disable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()> {
         use rayon::prelude::*;
 
-        let conflict_list = conflict_list_by_file(self)?;
-        let mut file_list = Vec::with_capacity(conflict_list.len());
-
-        log::debug!("Collecting File List");
-        for m in self.iter() {
-            file_list.extend(m.enlist_files(&conflict_list)?);
-        }
-
-        let sty = ProgressStyle::with_template("{prefix:.bold.dim} {wide_msg}: {bar:40}").unwrap();
-        let progress = ProgressBar::new(file_list.len() as u64 + self.len() as u64).with_style(sty);
-
-        log::debug!("Start Removing files");
-        file_list.par_iter().try_for_each(|f| {
-            let origin = cache_dir.join(f.source());
-            let destination = game_dir.join(Utf8PathBuf::from(f.destination()));
-
-            log::trace!("disabling file: {} -> {}", destination, origin);
-
-            if destination.is_file()
-                && destination.is_symlink()
-                && read_link(&destination)?.strip_prefix(&cache_dir).is_ok()
-            {
-                log::debug!("removing {} -> {}", destination, origin);
-                remove_file(&destination).ok();
-            } else {
-                let destination = Utf8PathBuf::try_from(destination)?;
-                log::debug!(
-                    "passing-over {} -> {}, (reason: is-file: {}, is-symlink: {}, points-to: {})",
-                    destination,
-                    origin,
-                    destination.is_file(),
-                    destination.is_symlink(),
-                    read_link(&destination)
-                        .unwrap_or(PathBuf::from("<Invalid>"))
-                        .display(),
-                );
-            }
-            progress.inc(1);
-            Ok::<(), anyhow::Error>(())
-        })?;
+        ensure_writable("remove mods from the game directory")?;
 
         log::debug!("Set Mods to Disabled.");
-        self.par_iter_mut().try_for_each(|m| {
-            m.set_disabled()?;
-            progress.inc(1);
-            Ok::<(), anyhow::Error>(())
-        })?;
-        progress.finish_and_clear();
-
-        log::debug!("Clean-up Game Dir");
-        let walker = WalkDir::new(game_dir)
-            .min_depth(1)
-            .max_depth(usize::MAX)
-            .follow_links(false)
-            .same_file_system(true)
-            .contents_first(true);
+        self.par_iter_mut().try_for_each(Manifest::set_disabled)?;
 
-        for entry in walker {
-            let entry = entry?;
-            let entry_path = entry.path();
-
-            // Restore backupped files
-            if entry_path.is_file()
-                && entry_path
-                    .extension()
-                    .unwrap_or_default()
-                    .to_str()
-                    .unwrap_or_default()
-                    == BACKUP_EXTENTION
-            {
-                let new = entry_path.with_extension("");
-                if !new.exists() {
-                    log::debug!(
-                        "Restoring Backup: {} -> {}.",
-                        &entry_path.display(),
-                        new.display()
-                    );
-                    rename(entry_path, new)?;
-                }
-            }
-
-            // Remove empty directories
-            if entry_path.is_dir() {
-                log::debug!("Trying to remove dir {}.", entry_path.display());
-                let _ = remove_dir(entry_path);
-            }
-        }
-
-        Ok(())
+        deploy(self, cache_dir, game_dir)
     }
     fn re_enable(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<()> {
-        let mut mod_cache = HashSet::with_capacity(self.len());
-        self.iter()
-            .enumerate()
-            .filter(|(_, m)| m.is_enabled())
-            .map(|(idx, _m)| idx)
-            .for_each(|idx| {
-                mod_cache.insert(idx);
-            });
-
-        self.disable(cache_dir, game_dir)?;
-
-        let mut mod_cache = self
-            .iter()
-            .enumerate()
-            .filter(|(idx, _m)| mod_cache.contains(idx))
-            .map(|(_idx, m)| m.clone())
-            .collect::<Vec<_>>();
-        mod_cache.enable(cache_dir, game_dir)?;
-
-        Ok(())
+        deploy(self, cache_dir, game_dir)
     }
     fn enable_mod(&mut self, cache_dir: &Utf8Path, game_dir: &Utf8Path, idx: usize) -> Result<()> {
-        if let Some(md) = self.get(idx) {
-            if md.is_enabled() {
-                self.disable_mod(cache_dir, game_dir, idx)?;
-            }
-        } else {
-            Err::<(), Error>(
-                InternalError::Error(format!(
-                    "ModList::enable_mod(0): No mod found with index: {idx}"
-                ))
-                .into(),
-            )?;
-        }
         if let Some(md) = self.get_mut(idx) {
             log::debug!("Enabling {}", md.name());
-            md.set_enabled()?;
-            self[0..=idx].as_mut().re_enable(cache_dir, game_dir)?;
-            Ok(())
+            if !md.set_enabled()? {
+                log::warn!("'{}' is pinned; unpin it to enable it.", md.name());
+            }
+            deploy(self, cache_dir, game_dir)
         } else {
             Err(InternalError::Error(format!(
-                "ModList::enable_mod(1): No mod found with index: {idx}"
+                "ModList::enable_mod: No mod found with index: {idx}"
             ))
             .into())
         }
@@ -418,8 +512,7 @@ impl ModList for &mut [Manifest] {
             log::debug!("Disabling {}", md.name());
 
             md.set_disabled()?;
-            self[0..=idx].as_mut().re_enable(cache_dir, game_dir)?;
-            Ok(())
+            deploy(self, cache_dir, game_dir)
         } else {
             Err(InternalError::Error(format!(
                 "ModList::disable_mod: No mod found with index: {idx}"
@@ -445,6 +538,7 @@ impl FindInModList for Vec<Manifest> {
     fn default_list_builder(&self) -> ModListBuilder<'_> {
         ModListBuilder::new(self)
             .with_index()
+            .with_glyph()
             .with_priority()
             .with_status()
             .with_version()
@@ -457,11 +551,23 @@ impl FindInModList for Vec<Manifest> {
 impl FindInModList for &[Manifest] {
     fn find_mod(&self, mod_name: &str) -> Option<usize> {
         // check if this is an index,
-        // if not, search by full name,
-
-        mod_name
-            .parse::<usize>()
-            .map_or_else(|_| self.find_mod_by_name(mod_name), Some)
+        // if not, search by full name, ignoring case,
+        // if not, search fuzzily, letting the user choose between multiple matches.
+
+        mod_name.parse::<usize>().map_or_else(
+            |_| {
+                self.find_mod_by_name(mod_name)
+                    .or_else(|| {
+                        self.iter()
+                            .position(|m| m.name().eq_ignore_ascii_case(mod_name))
+                    })
+                    .or_else(|| {
+                        let names = self.iter().map(Manifest::name).collect::<Vec<_>>();
+                        fuzzy_find(&names, mod_name)
+                    })
+            },
+            Some,
+        )
     }
 
     fn find_mod_by_name(&self, name: &str) -> Option<usize> {
@@ -472,6 +578,7 @@ impl FindInModList for &[Manifest] {
     fn default_list_builder(&self) -> ModListBuilder<'_> {
         ModListBuilder::new(self)
             .with_index()
+            .with_glyph()
             .with_priority()
             .with_status()
             .with_version()
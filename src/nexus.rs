@@ -0,0 +1,524 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{metadata, rename, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    str::FromStr,
+    thread::sleep,
+    time::Duration,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use ureq::{Agent, AgentBuilder, Response};
+
+use crate::{
+    dmodman::{DModManConfig, DmodMan},
+    errors::{DownloadError, SettingErrors},
+    game::Game,
+    hash::md5_file,
+    settings::{ensure_writable, share_with_group, Settings},
+    utils::AddExtension,
+};
+
+pub mod images;
+pub mod updates;
+
+const API_URL: &str = "https://api.nexusmods.com/v1";
+const TRACKED_MODS_PATH: &str = "user/tracked_mods.json";
+const API_KEY_ENV: &str = "NEXUS_API_KEY";
+const PARTIAL_DOWNLOAD_EXTENSION: &str = "part";
+
+const MAX_ATTEMPTS: u32 = 6;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_mins(2);
+// Waiting longer than this for the hourly limit to reset is not worth it; try again later.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_mins(15);
+const RATE_LIMIT_WARNING: u32 = 10;
+
+const HOURLY_REMAINING_HEADER: &str = "X-RL-Hourly-Remaining";
+const HOURLY_RESET_HEADER: &str = "X-RL-Hourly-Reset";
+const DAILY_REMAINING_HEADER: &str = "X-RL-Daily-Remaining";
+const DAILY_RESET_HEADER: &str = "X-RL-Daily-Reset";
+const RESET_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+/// The page of mod `mod_id` of the game with Nexus domain `game`.
+pub fn mod_page_url(game: &str, mod_id: u32) -> String {
+    format!("https://www.nexusmods.com/{game}/mods/{mod_id}")
+}
+
+/// A file of a mod on Nexus Mods.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FileInfo {
+    pub file_id: u64,
+    pub name: String,
+    pub version: Option<String>,
+    pub file_name: String,
+    pub uploaded_timestamp: u64,
+    pub size_in_bytes: Option<u64>,
+}
+
+/// A mod on Nexus Mods.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ModInfo {
+    pub mod_id: u32,
+    pub name: Option<String>,
+    /// The latest version, as given by the author.
+    pub version: Option<String>,
+    /// Whether the user endorsed the mod.
+    pub endorsement: Option<Endorsement>,
+    /// The primary image of the mod.
+    pub picture_url: Option<String>,
+    pub author: Option<String>,
+    pub category_id: Option<u32>,
+}
+
+/// A file on Nexus Mods with a given md5 checksum, and the mod it belongs to.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Md5Match {
+    #[serde(rename = "mod")]
+    pub mod_info: ModInfo,
+    pub file_details: FileInfo,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct GameInfo {
+    categories: Vec<Category>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Category {
+    category_id: u32,
+    name: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Endorsement {
+    pub endorse_status: EndorseStatus,
+}
+
+/// Whether the user endorsed a mod, or decided not to.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum EndorseStatus {
+    Endorsed,
+    Abstained,
+    #[default]
+    #[serde(other)]
+    Undecided,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TrackedMod {
+    mod_id: u32,
+    domain_name: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct DownloadLink {
+    #[serde(rename = "URI")]
+    uri: String,
+    short_name: String,
+}
+
+/// A client for the Nexus Mods API.
+///
+/// The API key is read from `$NEXUS_API_KEY`, or else from the configuration of dmodman.
+/// Nexus only hands out download links without a 'nxm' link to premium members.
+///
+/// Requests are tried again after network failures and rate limiting; downloads continue
+/// where they stopped, so large archives survive a flaky connection.
+pub struct Nexus {
+    agent: Agent,
+    api_key: String,
+    game: Game,
+}
+impl Nexus {
+    /// A client of the Nexus API, with the API key of the environment or of dmodman.
+    ///
+    /// # Errors
+    ///
+    /// When neither has an API key.
+    pub fn new(settings: &Settings) -> Result<Self> {
+        let api_key = env::var(API_KEY_ENV)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .or_else(|| DModManConfig::read().and_then(|dc| dc.api_key().map(str::to_owned)))
+            .ok_or_else(|| SettingErrors::NoNexusApiKey(API_KEY_ENV.to_owned()))?;
+
+        let agent = AgentBuilder::new()
+            .user_agent(&format!("starmod/{}", env!("CARGO_PKG_VERSION")))
+            .build();
+
+        Ok(Self {
+            agent,
+            api_key,
+            game: *settings.game(),
+        })
+    }
+
+    /// The details of mod `mod_id`.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached, or does not know the mod.
+    pub fn mod_info(&self, mod_id: u32) -> Result<ModInfo> {
+        self.get(&format!("mods/{mod_id}.json"))
+    }
+
+    /// Endorse mod `mod_id`, or abstain from endorsing it; Nexus wants the installed version.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached, or refuses it.
+    pub fn endorse(&self, mod_id: u32, version: Option<&str>, endorse: bool) -> Result<()> {
+        let action = if endorse { "endorse" } else { "abstain" };
+        self.send::<serde_json::Value>(
+            "POST",
+            &self.game_url(&format!("mods/{mod_id}/{action}.json")),
+            &[("version", version.unwrap_or_default())],
+        )?;
+        Ok(())
+    }
+
+    /// The mod and file of this game which `archive` was downloaded as, found by its md5
+    /// checksum.
+    ///
+    /// # Errors
+    ///
+    /// When the archive cannot be read, Nexus cannot be reached, or it does not know the archive.
+    pub fn identify(&self, archive: &Utf8Path) -> Result<Md5Match> {
+        let md5 = md5_file(archive)?;
+        log::debug!("Looking up '{archive}' on Nexus by its checksum {md5}.");
+        let matches: Vec<Md5Match> = match self.get(&format!("mods/md5_search/{md5}.json")) {
+            Ok(matches) => matches,
+            // Nexus answers 'not found' for a checksum it does not know.
+            Err(e)
+                if matches!(
+                    e.downcast_ref::<DownloadError>(),
+                    Some(DownloadError::NexusRequestFailed(404, _))
+                ) =>
+            {
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+        matches
+            .into_iter()
+            .next()
+            .ok_or_else(|| DownloadError::UnknownToNexus(archive.to_string()).into())
+    }
+
+    /// The names of the mod categories of this game, by id.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached.
+    pub fn categories(&self) -> Result<BTreeMap<u32, String>> {
+        let game: GameInfo = self.send(
+            "GET",
+            &format!("{API_URL}/games/{}.json", self.game.nexus_game_name()),
+            &[],
+        )?;
+        Ok(game
+            .categories
+            .into_iter()
+            .map(|c| (c.category_id, c.name))
+            .collect())
+    }
+
+    /// The changes of every version of mod `mod_id`, by version, as written by its author.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached, or does not know the mod.
+    pub fn changelogs(&self, mod_id: u32) -> Result<BTreeMap<String, Vec<String>>> {
+        self.get(&format!("mods/{mod_id}/changelogs.json"))
+    }
+
+    /// The ids of the mods of this game which the user tracks on Nexus.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached.
+    pub fn tracked_mods(&self) -> Result<Vec<u32>> {
+        let tracked: Vec<TrackedMod> =
+            self.send("GET", &format!("{API_URL}/{TRACKED_MODS_PATH}"), &[])?;
+        Ok(tracked
+            .into_iter()
+            .filter(|t| t.domain_name == self.game.nexus_game_name())
+            .map(|t| t.mod_id)
+            .collect())
+    }
+
+    /// Track mod `mod_id`, or stop tracking it; Nexus notifies about changes to tracked mods.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached, or refuses it.
+    pub fn track(&self, mod_id: u32, track: bool) -> Result<()> {
+        let method = if track { "POST" } else { "DELETE" };
+        let url = format!(
+            "{API_URL}/{TRACKED_MODS_PATH}?domain_name={}",
+            self.game.nexus_game_name()
+        );
+        self.send::<serde_json::Value>(method, &url, &[("mod_id", &mod_id.to_string())])?;
+        Ok(())
+    }
+
+    /// Download `url`, which is not part of the API like the image of a mod, into `path`.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when the download fails or `path` cannot be written.
+    pub fn fetch(&self, url: &str, path: &Utf8Path) -> Result<()> {
+        ensure_writable(&format!("download '{url}'"))?;
+        let response = retry(&format!("Downloading '{url}'"), || {
+            self.agent.get(url).call().map_err(Failure::from)
+        })?;
+        // Only complete files are moved into place; a file which exists counts as downloaded.
+        let partial = path.add_extension(PARTIAL_DOWNLOAD_EXTENSION);
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        io::copy(&mut response.into_reader(), &mut writer)?;
+        writer.flush()?;
+        rename(&partial, path)?;
+        share_with_group(path)?;
+        Ok(())
+    }
+
+    /// The details of file `file_id` of mod `mod_id`.
+    ///
+    /// # Errors
+    ///
+    /// When Nexus cannot be reached, or does not know the file.
+    pub fn file_info(&self, mod_id: u32, file_id: u64) -> Result<FileInfo> {
+        self.get(&format!("mods/{mod_id}/files/{file_id}.json"))
+    }
+
+    /// Download file `file_id` of mod `mod_id` into `download_dir`, together with the
+    /// metadata dmodman would write for it; returns the name of the archive.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, when Nexus does not let the user download the file, or the archive cannot
+    /// be written.
+    pub fn download(
+        &self,
+        download_dir: &Utf8Path,
+        mod_id: u32,
+        file_id: u64,
+    ) -> Result<Utf8PathBuf> {
+        let info = self.file_info(mod_id, file_id)?;
+        ensure_writable(&format!("download '{}'", info.file_name))?;
+
+        let links: Vec<DownloadLink> =
+            self.get(&format!("mods/{mod_id}/files/{file_id}/download_link.json"))?;
+        let link = links
+            .first()
+            .ok_or_else(|| DownloadError::NoDownloadLink(info.file_name.clone()))?;
+        log::debug!("Downloading '{}' from {}", info.file_name, link.short_name);
+
+        let archive = download_dir.join(&info.file_name);
+        let partial = archive.add_extension(PARTIAL_DOWNLOAD_EXTENSION);
+
+        let progress = ProgressBar::new(info.size_in_bytes.unwrap_or_default()).with_style(
+            ProgressStyle::with_template("{wide_msg} {bytes}/{total_bytes} {bytes_per_sec}")?,
+        );
+        progress.set_message(info.file_name.clone());
+        let result = retry(&format!("Downloading '{}'", info.file_name), || {
+            download_to(&self.agent, &link.uri, &partial, &progress)
+        });
+        progress.finish_and_clear();
+        // A partial download is kept, so the next attempt continues where this one stopped.
+        result?;
+
+        rename(&partial, &archive)?;
+        share_with_group(&archive)?;
+
+        self.write_metadata(&archive, mod_id, &info)?;
+
+        log::info!("Downloaded '{}'", info.file_name);
+        Ok(Utf8PathBuf::from(info.file_name))
+    }
+
+    /// Write the dmodman metadata of `archive`, which is file `info` of mod `mod_id`.
+    ///
+    /// # Errors
+    ///
+    /// When the metadata cannot be written.
+    pub fn write_metadata(&self, archive: &Utf8Path, mod_id: u32, info: &FileInfo) -> Result<()> {
+        let dmodman_file = archive.add_extension("json");
+        DmodMan::new(
+            self.game.nexus_game_name(),
+            &info.file_name,
+            mod_id,
+            info.file_id,
+            info.uploaded_timestamp,
+        )
+        .write(&dmodman_file)?;
+        share_with_group(&dmodman_file)?;
+        Ok(())
+    }
+
+    fn game_url(&self, path: &str) -> String {
+        format!("{API_URL}/games/{}/{path}", self.game.nexus_game_name())
+    }
+
+    fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        self.send("GET", &self.game_url(path), &[])
+    }
+
+    /// Send a `method` request to `url`, with `form` as its body unless it is empty.
+    fn send<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> Result<T> {
+        log::trace!("{method} {url}");
+
+        let response = retry(&format!("Requesting '{url}'"), || {
+            let request = self.agent.request(method, url).set("apikey", &self.api_key);
+            if form.is_empty() {
+                request.call()
+            } else {
+                request.send_form(form)
+            }
+            .map_err(Failure::from)
+        })?;
+        warn_about_rate_limit(&response);
+        Ok(response.into_json()?)
+    }
+}
+
+/// Why an attempt failed; transient failures are tried again, after the given wait if any.
+enum Failure {
+    Transient(anyhow::Error, Option<Duration>),
+    Fatal(anyhow::Error),
+}
+impl From<ureq::Error> for Failure {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(429, response) => {
+                if header_value::<u32>(&response, DAILY_REMAINING_HEADER) == Some(0) {
+                    let reset = response.header(DAILY_RESET_HEADER).unwrap_or("tomorrow");
+                    return Self::Fatal(DownloadError::NexusDailyLimit(reset.to_owned()).into());
+                }
+                let wait = rate_limit_wait(&response);
+                let error = DownloadError::NexusRequestFailed(429, "rate limited".to_owned());
+                match wait {
+                    Some(wait) if wait > MAX_RATE_LIMIT_WAIT => Self::Fatal(error.into()),
+                    wait => Self::Transient(error.into(), wait),
+                }
+            }
+            ureq::Error::Status(status, response) => {
+                let error =
+                    DownloadError::NexusRequestFailed(status, response.status_text().to_owned());
+                if status >= 500 {
+                    Self::Transient(error.into(), None)
+                } else {
+                    Self::Fatal(error.into())
+                }
+            }
+            ureq::Error::Transport(t) => Self::Transient(t.into(), None),
+        }
+    }
+}
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        Self::Transient(e.into(), None)
+    }
+}
+
+/// Run `attempt` until it succeeds, it fails for good or it failed `MAX_ATTEMPTS` times;
+/// waiting twice as long after every failure.
+fn retry<T>(what: &str, mut attempt: impl FnMut() -> Result<T, Failure>) -> Result<T> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempts = 1;
+    loop {
+        match attempt() {
+            Ok(t) => return Ok(t),
+            Err(Failure::Transient(e, wait)) if attempts < MAX_ATTEMPTS => {
+                let wait = wait.unwrap_or(backoff);
+                log::warn!(
+                    "{what} failed: {e}; trying again in {}s ({attempts}/{MAX_ATTEMPTS}).",
+                    wait.as_secs()
+                );
+                sleep(wait);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                attempts += 1;
+            }
+            Err(Failure::Transient(e, _) | Failure::Fatal(e)) => return Err(e),
+        }
+    }
+}
+
+/// Download `uri` into `partial`, continuing after what an earlier attempt left in it.
+fn download_to(
+    agent: &Agent,
+    uri: &str,
+    partial: &Utf8Path,
+    progress: &ProgressBar,
+) -> Result<(), Failure> {
+    let offset = metadata(partial).map(|m| m.len()).unwrap_or_default();
+    let mut request = agent.get(uri);
+    if offset > 0 {
+        request = request.set("Range", &format!("bytes={offset}-"));
+    }
+
+    let response = match request.call() {
+        // Everything was downloaded already, but not yet renamed.
+        Err(ureq::Error::Status(416, _)) if offset > 0 => return Ok(()),
+        response => response?,
+    };
+
+    // Servers which do not support ranges send the whole file again.
+    let resumed = response.status() == 206;
+    let start = if resumed { offset } else { 0 };
+    if let Some(len) = header_value::<u64>(&response, "Content-Length") {
+        progress.set_length(start + len);
+    }
+    progress.set_position(start);
+    if resumed {
+        log::debug!("Resuming download at {offset} bytes.");
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(partial)?;
+    let mut writer = BufWriter::new(file);
+    io::copy(&mut progress.wrap_read(response.into_reader()), &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// How long to wait before Nexus accepts requests again; from 'Retry-After', or the hourly reset.
+fn rate_limit_wait(response: &Response) -> Option<Duration> {
+    if let Some(seconds) = header_value::<u64>(response, "Retry-After") {
+        return Some(Duration::from_secs(seconds));
+    }
+    let reset =
+        DateTime::parse_from_str(response.header(HOURLY_RESET_HEADER)?, RESET_FORMAT).ok()?;
+    (reset.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+fn warn_about_rate_limit(response: &Response) {
+    let hourly = header_value::<u32>(response, HOURLY_REMAINING_HEADER);
+    let daily = header_value::<u32>(response, DAILY_REMAINING_HEADER);
+    if let (Some(hourly), Some(daily)) = (hourly, daily) {
+        log::trace!("Nexus requests remaining: {hourly} this hour, {daily} today.");
+        let remaining = hourly.min(daily);
+        if remaining < RATE_LIMIT_WARNING {
+            log::warn!("Only {remaining} Nexus API request(s) remaining.");
+        }
+    }
+}
+
+fn header_value<T: FromStr>(response: &Response, header: &str) -> Option<T> {
+    response.header(header).and_then(|v| v.trim().parse().ok())
+}
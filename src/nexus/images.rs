@@ -0,0 +1,68 @@
+use std::fs::create_dir_all;
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use super::Nexus;
+use crate::settings::{share_with_group, Settings};
+
+// Not a manifest, nor the name of an extracted archive.
+const IMAGES_DIR: &str = "nexus_images";
+const DEFAULT_IMAGE_EXTENSION: &str = "jpg";
+
+/// The image of mod `nexus_id` in the cache directory, if it was downloaded.
+pub fn cached_image(cache_dir: &Utf8Path, nexus_id: u32) -> Option<Utf8PathBuf> {
+    let stem = nexus_id.to_string();
+    cache_dir
+        .join(IMAGES_DIR)
+        .read_dir_utf8()
+        .ok()?
+        .flatten()
+        .map(camino::Utf8DirEntry::into_path)
+        .find(|path| path.file_stem() == Some(stem.as_str()))
+}
+
+/// Download the image at `url` of mod `nexus_id` into the cache directory.
+///
+/// # Errors
+///
+/// When the image cannot be downloaded or written.
+pub fn cache_image(
+    nexus: &Nexus,
+    cache_dir: &Utf8Path,
+    nexus_id: u32,
+    url: &str,
+) -> Result<Utf8PathBuf> {
+    let extension = url
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_lowercase())
+        .filter(|e| !e.is_empty() && e.len() <= 4 && e.chars().all(char::is_alphanumeric))
+        .unwrap_or_else(|| DEFAULT_IMAGE_EXTENSION.to_owned());
+
+    let dir = cache_dir.join(IMAGES_DIR);
+    if !dir.exists() {
+        create_dir_all(&dir)?;
+        share_with_group(&dir)?;
+    }
+    let path = dir.join(format!("{nexus_id}.{extension}"));
+    log::debug!("Downloading the image of mod {nexus_id} to '{path}'.");
+    nexus.fetch(url, &path)?;
+    Ok(path)
+}
+
+/// The image of mod `nexus_id`; downloaded from Nexus when it is not in the cache yet.
+/// Mods without an image have none.
+///
+/// # Errors
+///
+/// When Nexus cannot be reached, or the image cannot be downloaded.
+pub fn mod_image(settings: &Settings, nexus_id: u32) -> Result<Option<Utf8PathBuf>> {
+    if let Some(path) = cached_image(settings.cache_dir(), nexus_id) {
+        return Ok(Some(path));
+    }
+    let nexus = Nexus::new(settings)?;
+    let Some(url) = nexus.mod_info(nexus_id)?.picture_url else {
+        return Ok(None);
+    };
+    cache_image(&nexus, settings.cache_dir(), nexus_id, &url).map(Some)
+}
@@ -0,0 +1,200 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{read, rename, File},
+    io::Write,
+};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    images::{cache_image, cached_image},
+    EndorseStatus, ModInfo, Nexus,
+};
+use crate::{
+    manifest::Manifest,
+    mods::GatherModList,
+    settings::{ensure_writable, share_with_group, Settings},
+    ui::notify,
+    utils::AddExtension,
+    version::Version,
+};
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+const UPDATES_FILE: &str = "nexus_updates.cache";
+const UPDATES_TEMP_EXTENSION: &str = "tmp";
+
+/// What Nexus said about a mod, and when.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CheckedMod {
+    version: Option<String>,
+    checked: DateTime<Utc>,
+    #[serde(default)]
+    endorsement: EndorseStatus,
+}
+
+/// The latest versions of the installed mods on Nexus, and whether the user endorsed them,
+/// as found by `check_updates`.
+///
+/// It is kept in the cache directory, so the mod-list can show which mods have an update
+/// without asking Nexus, or reading the metadata of every download.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct UpdateCache {
+    mods: BTreeMap<u32, CheckedMod>,
+}
+impl UpdateCache {
+    /// The cache in `cache_dir`; empty when there is none yet.
+    pub fn read(cache_dir: &Utf8Path) -> Self {
+        read(cache_dir.join(UPDATES_FILE))
+            .ok()
+            .and_then(|contents| {
+                rmp_serde::from_slice(&contents)
+                    .map_err(|e| log::warn!("Ignoring damaged update cache: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+    /// Write the update cache into `cache_dir`.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be written.
+    pub fn write(&self, cache_dir: &Utf8Path) -> Result<()> {
+        let path = cache_dir.join(UPDATES_FILE);
+        let temp_path = path.add_extension(UPDATES_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&rmp_serde::to_vec_named(self)?)?;
+        rename(&temp_path, &path)?;
+        share_with_group(&path)?;
+        Ok(())
+    }
+    /// Whether mod `nexus_id` was not checked in the last `max_age`.
+    fn is_stale(&self, nexus_id: u32, max_age: Duration) -> bool {
+        self.mods
+            .get(&nexus_id)
+            .is_none_or(|m| Utc::now() - m.checked > max_age)
+    }
+    /// Remember what Nexus said about mod `nexus_id`.
+    pub fn insert(&mut self, nexus_id: u32, info: &ModInfo) {
+        self.mods.insert(
+            nexus_id,
+            CheckedMod {
+                version: info.version.clone(),
+                checked: Utc::now(),
+                endorsement: info
+                    .endorsement
+                    .as_ref()
+                    .map(|e| e.endorse_status)
+                    .unwrap_or_default(),
+            },
+        );
+    }
+    /// Whether the user endorsed mod `nexus_id`, as far as known.
+    pub fn endorsement(&self, nexus_id: u32) -> EndorseStatus {
+        self.mods
+            .get(&nexus_id)
+            .map(|m| m.endorsement)
+            .unwrap_or_default()
+    }
+    /// Remember that the user endorsed mod `nexus_id`; when it was never checked, it still is
+    /// the next time.
+    pub fn set_endorsement(&mut self, nexus_id: u32, endorsement: EndorseStatus) {
+        self.mods
+            .entry(nexus_id)
+            .or_insert_with(|| CheckedMod {
+                version: None,
+                checked: DateTime::<Utc>::MIN_UTC,
+                endorsement,
+            })
+            .endorsement = endorsement;
+    }
+    /// The latest version of `md` on Nexus, when it is newer than the installed one.
+    pub fn update_of(&self, md: &Manifest) -> Option<&str> {
+        let latest = self.mods.get(&md.nexus_id()?)?.version.as_deref()?;
+        (Version::from(latest) > Version::from(md.version())).then_some(latest)
+    }
+}
+
+/// Ask Nexus for the latest version of every installed mod which was not checked in the
+/// last `max_age`, or of all of them with `force`.
+///
+/// It asks nothing of the user, so it can run from a timer.
+///
+/// # Errors
+///
+/// When there is no API key, or the update cache cannot be read or written; mods Nexus has no
+/// answer for are skipped.
+pub fn check_updates(settings: &Settings, max_age: Duration, force: bool) -> Result<()> {
+    ensure_writable("update the update cache")?;
+    let mod_list = Vec::gather_mods(settings.cache_dir())?;
+    let mut cache = UpdateCache::read(settings.cache_dir());
+
+    let nexus_ids = mod_list
+        .iter()
+        .filter_map(Manifest::nexus_id)
+        .filter(|id| force || cache.is_stale(*id, max_age))
+        .collect::<BTreeSet<_>>();
+
+    if !nexus_ids.is_empty() {
+        let nexus = Nexus::new(settings)?;
+        log::info!("Checking {} mod(s) for updates.", nexus_ids.len());
+        let mut failures = 0;
+        for nexus_id in nexus_ids {
+            match nexus.mod_info(nexus_id) {
+                Ok(info) => {
+                    if settings.cache_images() {
+                        prefetch_image(&nexus, settings.cache_dir(), nexus_id, &info);
+                    }
+                    cache.insert(nexus_id, &info);
+                }
+                Err(e) => {
+                    log::warn!("Unable to check mod {nexus_id} for updates: {e:#}.");
+                    failures += 1;
+                }
+            }
+        }
+        // Keep what was found, even when Nexus stopped answering halfway.
+        cache.write(settings.cache_dir())?;
+        if failures > 0 {
+            log::warn!("{failures} mod(s) could not be checked; they are tried again next time.");
+        }
+    }
+
+    let updates = mod_list
+        .iter()
+        .filter_map(|md| {
+            cache.update_of(md).map(|latest| {
+                format!(
+                    "{} ({} -> {latest})",
+                    md.name(),
+                    md.version().unwrap_or("?")
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    if updates.is_empty() {
+        log::info!("All mods are up to date.");
+    } else {
+        log::info!("Updates available:\n{}", updates.join("\n"));
+        notify(
+            "Mod updates available",
+            &format!("{} mod(s) have an update on Nexus.", updates.len()),
+        );
+    }
+    Ok(())
+}
+
+/// Download the image of mod `nexus_id`, when it has one which is not in the cache yet;
+/// a failure only costs the image.
+fn prefetch_image(nexus: &Nexus, cache_dir: &Utf8Path, nexus_id: u32, info: &ModInfo) {
+    let Some(url) = info.picture_url.as_deref() else {
+        return;
+    };
+    if cached_image(cache_dir, nexus_id).is_none() {
+        if let Err(e) = cache_image(nexus, cache_dir, nexus_id, url) {
+            log::warn!("Unable to download the image of mod {nexus_id}: {e:#}.");
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::{fs::File, io::Read};
+
+use anyhow::Result;
+use camino::Utf8Path;
+
+use crate::errors::GameErrors;
+
+const HEADER_RECORD: &[u8; 4] = b"TES4";
+const MASTER_SUBRECORD: &[u8; 4] = b"MAST";
+const RECORD_HEADER_LEN: usize = 24;
+const SUBRECORD_HEADER_LEN: usize = 6;
+const LIGHT_FLAG: u32 = 0x100;
+const LIGHT_EXTENSION: &str = "esl";
+
+/// The parts of the header record of a plugin which matter for loading it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginHeader {
+    masters: Vec<String>,
+    light: bool,
+}
+impl PluginHeader {
+    /// Read the header of the plugin at `path`.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be read, or is not a plugin.
+    pub fn read(path: &Utf8Path) -> Result<Self> {
+        let invalid = || GameErrors::InvalidPlugin(path.to_string());
+
+        let mut file = File::open(path)?;
+        let mut record_header = [0; RECORD_HEADER_LEN];
+        file.read_exact(&mut record_header)?;
+        if &record_header[..4] != HEADER_RECORD {
+            return Err(invalid().into());
+        }
+        let data_len = u32::from_le_bytes(record_header[4..8].try_into()?);
+        let flags = u32::from_le_bytes(record_header[8..12].try_into()?);
+
+        let mut data = Vec::new();
+        file.take(u64::from(data_len)).read_to_end(&mut data)?;
+
+        let mut masters = Vec::new();
+        let mut rest = data.as_slice();
+        while rest.len() >= SUBRECORD_HEADER_LEN {
+            let (header, tail) = rest.split_at(SUBRECORD_HEADER_LEN);
+            let len = usize::from(u16::from_le_bytes(header[4..6].try_into()?));
+            if tail.len() < len {
+                return Err(invalid().into());
+            }
+            let (value, tail) = tail.split_at(len);
+            if &header[..4] == MASTER_SUBRECORD {
+                let name = value.split(|b| *b == 0).next().unwrap_or_default();
+                masters.push(String::from_utf8_lossy(name).into_owned());
+            }
+            rest = tail;
+        }
+
+        let light = flags & LIGHT_FLAG != 0
+            || path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case(LIGHT_EXTENSION));
+
+        Ok(Self { masters, light })
+    }
+    pub fn masters(&self) -> &[String] {
+        &self.masters
+    }
+    /// Light plugins do not count towards the limit of full plugins.
+    pub const fn is_light(&self) -> bool {
+        self.light
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::Display;
+
+use camino::Utf8Path;
+use clap::ValueEnum;
+
+const ACTIVE_MARKER: char = '*';
+const COMMENT_MARKER: char = '#';
+const LOAD_ORDER_FILE_NAME: &str = "loadorder.txt";
+
+/// File formats of a load order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PluginListFormat {
+    /// 'plugins.txt': every plugin on its own line, active plugins prefixed with '*'.
+    Plugins,
+    /// 'loadorder.txt': every plugin on its own line, without their state.
+    LoadOrder,
+}
+impl PluginListFormat {
+    /// Guess the format from the name of `file`; 'loadorder.txt' is the only one without states.
+    pub fn from_file_name(file: &Utf8Path) -> Self {
+        if file
+            .file_name()
+            .is_some_and(|f| f.eq_ignore_ascii_case(LOAD_ORDER_FILE_NAME))
+        {
+            Self::LoadOrder
+        } else {
+            Self::Plugins
+        }
+    }
+}
+
+/// The plugins of the game in load order, and whether they are active.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginList {
+    plugins: Vec<(String, bool)>,
+}
+impl PluginList {
+    /// Read a list in `format`; plugins in a 'loadorder.txt' are all active.
+    pub fn parse(contents: &str, format: PluginListFormat) -> Self {
+        let plugins = contents
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with(COMMENT_MARKER))
+            .map(|l| match format {
+                PluginListFormat::Plugins => l
+                    .strip_prefix(ACTIVE_MARKER)
+                    .map_or_else(|| (l.to_owned(), false), |p| (p.trim().to_owned(), true)),
+                PluginListFormat::LoadOrder => (l.to_owned(), true),
+            })
+            .collect();
+
+        Self { plugins }
+    }
+    pub fn plugins(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.plugins.iter().map(|(p, a)| (p.as_str(), *a))
+    }
+    pub fn is_active(&self, plugin: &str) -> Option<bool> {
+        self.plugins
+            .iter()
+            .find(|(p, _)| p.eq_ignore_ascii_case(plugin))
+            .map(|(_, a)| *a)
+    }
+    /// Take the active state of every plugin from `other`; plugins it does not know are inactive.
+    pub fn with_states_of(mut self, other: &Self) -> Self {
+        for (plugin, active) in &mut self.plugins {
+            *active = other.is_active(plugin).unwrap_or(false);
+        }
+        self
+    }
+    pub const fn len(&self) -> usize {
+        self.plugins.len()
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+    pub const fn display(&self, format: PluginListFormat) -> PluginListDisplay<'_> {
+        PluginListDisplay { list: self, format }
+    }
+}
+
+impl FromIterator<(String, bool)> for PluginList {
+    fn from_iter<I: IntoIterator<Item = (String, bool)>>(iter: I) -> Self {
+        Self {
+            plugins: iter.into_iter().collect(),
+        }
+    }
+}
+
+pub struct PluginListDisplay<'a> {
+    list: &'a PluginList,
+    format: PluginListFormat,
+}
+impl Display for PluginListDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (plugin, active) in self.list.plugins() {
+            if active && self.format == PluginListFormat::Plugins {
+                write!(f, "{ACTIVE_MARKER}")?;
+            }
+            // The game writes windows line-endings; keep them for tools which expect them.
+            write!(f, "{plugin}\r\n")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+//! What mods need to run, declared with tags.
+//!
+//! A mod tagged 'needs:<what>' needs an enabled mod which provides it. A mod provides its own
+//! name, and everything it is tagged 'provides:<what>' with; loader mods provide the script
+//! extender. Requirements like 'vcrun2022' or 'dotnet48' are components of the wine prefix
+//! instead, installed with winetricks; see 'game prefix'.
+
+use crate::{manifest::Manifest, mods::ModKind};
+
+pub const NEEDS_PREFIX: &str = "needs:";
+pub const PROVIDES_PREFIX: &str = "provides:";
+// Names under which a mod can need the script extender.
+const LOADER_REQUIREMENTS: [&str; 2] = ["loader", "sfse"];
+// Winetricks verbs of the runtimes windows tools need; a bare 'vcrun' is met by any version.
+const PREFIX_COMPONENTS: [&str; 4] = ["vcrun", "dotnet", "d3dcompiler", "xact"];
+
+/// A requirement of an enabled mod which no enabled mod provides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unmet {
+    pub mod_name: String,
+    pub requirement: String,
+}
+
+/// What `md` needs, as declared by its 'needs:' tags.
+pub fn requirements(md: &Manifest) -> impl Iterator<Item = &str> {
+    md.tags()
+        .iter()
+        .filter_map(|tag| tag.strip_prefix(NEEDS_PREFIX))
+        .filter(|requirement| !requirement.is_empty())
+}
+
+/// Whether `requirement` is a component of the wine prefix, rather than a mod.
+pub fn is_prefix_component(requirement: &str) -> bool {
+    let requirement = requirement.to_lowercase();
+    PREFIX_COMPONENTS
+        .iter()
+        .any(|component| requirement.starts_with(component))
+}
+
+/// Whether `md` provides `requirement`.
+fn provides(md: &Manifest, requirement: &str) -> bool {
+    md.name().eq_ignore_ascii_case(requirement)
+        || md
+            .tags()
+            .iter()
+            .filter_map(|tag| tag.strip_prefix(PROVIDES_PREFIX))
+            .any(|provided| provided.eq_ignore_ascii_case(requirement))
+        || (md.kind() == ModKind::Loader
+            && LOADER_REQUIREMENTS
+                .iter()
+                .any(|r| r.eq_ignore_ascii_case(requirement)))
+}
+
+/// The requirements of the enabled mods of `mod_list` which no enabled mod provides.
+pub fn unmet_requirements(mod_list: &[Manifest]) -> Vec<Unmet> {
+    let enabled = mod_list
+        .iter()
+        .filter(|md| md.is_enabled())
+        .collect::<Vec<_>>();
+
+    enabled
+        .iter()
+        .flat_map(|md| requirements(md).map(move |requirement| (md, requirement)))
+        .filter(|(_, requirement)| !is_prefix_component(requirement))
+        .filter(|(_, requirement)| !enabled.iter().any(|other| provides(other, requirement)))
+        .map(|(md, requirement)| Unmet {
+            mod_name: md.name().to_owned(),
+            requirement: requirement.to_owned(),
+        })
+        .collect()
+}
+
+/// The prefix components the enabled mods of `mod_list` need which are not `installed`.
+pub fn unmet_components(mod_list: &[Manifest], installed: &[String]) -> Vec<Unmet> {
+    mod_list
+        .iter()
+        .filter(|md| md.is_enabled())
+        .flat_map(|md| requirements(md).map(move |requirement| (md, requirement)))
+        .filter(|(_, requirement)| is_prefix_component(requirement))
+        .filter(|(_, requirement)| {
+            let requirement = requirement.to_lowercase();
+            !installed.iter().any(|verb| {
+                *verb == requirement
+                    || (PREFIX_COMPONENTS.contains(&requirement.as_str())
+                        && verb.starts_with(&requirement))
+            })
+        })
+        .map(|(md, requirement)| Unmet {
+            mod_name: md.name().to_owned(),
+            requirement: requirement.to_owned(),
+        })
+        .collect()
+}
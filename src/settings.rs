@@ -1,24 +1,64 @@
 use anyhow::{Context, Result};
 use clap::ValueEnum;
-use comfy_table::{presets::NOTHING, ContentArrangement, Table};
+use comfy_table::{presets::NOTHING, Cell, Color, ContentArrangement, Table};
 use flexi_logger::Duplicate;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     env,
     fmt::Display,
     fs::File,
     io::{BufReader, Read, Write},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        OnceLock,
+    },
 };
-use steamlocate::SteamDir;
 use xdg::BaseDirectories;
 
 use camino::{Utf8Path, Utf8PathBuf};
 use log::LevelFilter;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
-use crate::{commands::game::RunCmd, dmodman::DModManConfig, errors::SettingErrors, game::Game};
+use crate::{
+    commands::game::RunCmd, deployment::DeploymentStrategy, dmodman::DModManConfig,
+    errors::SettingErrors, game::Game, i18n, ini::IniMergeStrategy, mods::BACKUP_EXTENTION, steam,
+    ui::ModListColumn,
+};
 
 const CONFIG_EXTENTION: &str = "ron";
 const EDITOR_ENV: &str = "EDITOR";
+const NO_COLOR_ENV: &str = "NO_COLOR";
+const USER_STATE_DIR: &str = "state";
+const INSTALLER_SCRIPTS_DIR: &str = "installers";
+const OVERLAY_DIR: &str = "overlay";
+// Relative to the prefix of the game in the compat directory.
+const PROTON_USER_DIR: &str = "pfx/drive_c/users/steamuser";
+const WINE_USERS_DIR: &str = "drive_c/users";
+const WINE_PUBLIC_USER: &str = "Public";
+const WINE_STEAM_USER: &str = "steamuser";
+
+// Display preferences, applied once at start-up; used by `create_table` and `default_page_size`.
+static COLOUR: AtomicBool = AtomicBool::new(true);
+static SYMBOLS_ONLY: AtomicBool = AtomicBool::new(false);
+static PAGE_SIZE: AtomicUsize = AtomicUsize::new(0);
+// Mirrors `Settings::read_only` for code which writes to disk without access to the settings.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+// Set when the cache is shared between users; holds this user's mod state.
+static SHARED_STATE_DIR: OnceLock<Utf8PathBuf> = OnceLock::new();
+// Set by the 'state-dir' setting; holds the mod state instead of the cache.
+static STATE_DIR: OnceLock<Utf8PathBuf> = OnceLock::new();
+// Set when the IO concurrency is limited; runs the parallel work which hits the disk.
+static IO_POOL: OnceLock<ThreadPool> = OnceLock::new();
+// Cleared when mods are mounted over the game directory instead of linked into it.
+static LINK_MODS: AtomicBool = AtomicBool::new(true);
+// Set when destinations are folded onto the casing already in the game directory.
+static FOLD_CASE: AtomicBool = AtomicBool::new(false);
+// Set by the 'backup-extension' setting; names the backups of files in the way of links.
+static BACKUP_EXTENSION: OnceLock<String> = OnceLock::new();
+// Holds the user's installer scripts; see `installers::script`.
+static INSTALLER_SCRIPTS: OnceLock<Utf8PathBuf> = OnceLock::new();
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, ValueEnum)]
 pub enum RunCmdKind {
@@ -38,6 +78,111 @@ impl From<RunCmdKind> for RunCmd {
     }
 }
 
+/// Colour used in the mod-list for mods with a certain tag, written as '<tag>=<colour>'.
+/// The colour is a name like 'blue' or 'dark-cyan', or a hex value like '#ff8800'.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TagColour {
+    tag: String,
+    colour: String,
+}
+impl TagColour {
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+    pub fn colour(&self) -> Color {
+        parse_colour(&self.colour).unwrap_or(Color::Reset)
+    }
+}
+impl FromStr for TagColour {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tag, colour) = s
+            .split_once('=')
+            .ok_or_else(|| format!("'{s}' should be written as '<tag>=<colour>'"))?;
+        if parse_colour(colour).is_none() {
+            return Err(format!("unknown colour '{colour}'"));
+        }
+        Ok(Self {
+            tag: tag.trim().to_lowercase(),
+            colour: colour.trim().to_lowercase(),
+        })
+    }
+}
+impl Display for TagColour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.tag, self.colour)
+    }
+}
+
+/// Editor or viewer used for files with a certain extension, written as '<extension>=<command>'.
+/// The file is appended to the command, or replaces '{}' when the command contains it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct EditorMapping {
+    extension: String,
+    command: String,
+}
+impl EditorMapping {
+    pub fn extension(&self) -> &str {
+        &self.extension
+    }
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+}
+impl FromStr for EditorMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (extension, command) = s
+            .split_once('=')
+            .ok_or_else(|| format!("'{s}' should be written as '<extension>=<command>'"))?;
+        let extension = extension.trim().trim_start_matches('.').to_lowercase();
+        let command = command.trim().to_owned();
+        if extension.is_empty() || command.is_empty() {
+            return Err(format!(
+                "'{s}' should be written as '<extension>=<command>'"
+            ));
+        }
+        Ok(Self { extension, command })
+    }
+}
+impl Display for EditorMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.extension, self.command)
+    }
+}
+
+fn parse_colour(colour: &str) -> Option<Color> {
+    let colour = colour.trim().to_lowercase().replace(['-', '_', ' '], "");
+    if let Some(hex) = colour.strip_prefix('#') {
+        let rgb = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)?;
+        let [_, r, g, b] = rgb.to_be_bytes();
+        return Some(Color::Rgb { r, g, b });
+    }
+    Some(match colour.as_str() {
+        "black" => Color::Black,
+        "darkgrey" | "darkgray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "darkred" => Color::DarkRed,
+        "green" => Color::Green,
+        "darkgreen" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "darkyellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "darkblue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "darkmagenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "darkcyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    })
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub enum LootType {
     Windows(Utf8PathBuf),
@@ -92,6 +237,8 @@ pub struct Settings {
     game: Game,
     #[serde(skip_serializing, default)]
     verbosity: LogLevel,
+    #[serde(skip_serializing, default)]
+    read_only: bool,
     cache_dir: Utf8PathBuf,
     config_path: Utf8PathBuf,
     log_path: Utf8PathBuf,
@@ -103,6 +250,10 @@ pub struct Settings {
     compat_dir: Option<Utf8PathBuf>,
     #[serde(default)]
     steam_dir: Option<Utf8PathBuf>,
+    #[serde(default)]
+    wine: Option<Utf8PathBuf>,
+    #[serde(default)]
+    wine_prefix: Option<Utf8PathBuf>,
     loot: LootType,
     loot_data_dir: Utf8PathBuf,
     #[serde(default)]
@@ -111,6 +262,48 @@ pub struct Settings {
     default_run: Option<RunCmdKind>,
     #[serde(default)]
     editor: Option<String>,
+    #[serde(default)]
+    list_columns: Option<Vec<ModListColumn>>,
+    #[serde(default)]
+    colour: Option<bool>,
+    #[serde(default)]
+    page_size: Option<usize>,
+    #[serde(default)]
+    shared_cache: Option<bool>,
+    #[serde(default)]
+    new_mod_priority: Option<isize>,
+    #[serde(default)]
+    tag_colours: Option<Vec<TagColour>>,
+    #[serde(default)]
+    editors: Option<Vec<EditorMapping>>,
+    #[serde(default)]
+    ini_merge: Option<IniMergeStrategy>,
+    #[serde(default)]
+    run_wrappers: Option<Vec<String>>,
+    #[serde(default)]
+    threads: Option<usize>,
+    #[serde(default)]
+    io_limit: Option<usize>,
+    #[serde(default)]
+    notifications: Option<bool>,
+    #[serde(default)]
+    deployment: Option<DeploymentStrategy>,
+    #[serde(default)]
+    case_insensitive: Option<bool>,
+    #[serde(default)]
+    auto_update_masterlist: Option<bool>,
+    #[serde(default)]
+    symbols_only: Option<bool>,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    aliases: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    state_dir: Option<Utf8PathBuf>,
+    #[serde(default)]
+    cache_images: Option<bool>,
+    #[serde(default)]
+    backup_extension: Option<String>,
 }
 impl Settings {
     fn create(game: Game, verbosity: LogLevel) -> Result<Self> {
@@ -140,43 +333,17 @@ impl Settings {
         let loot = LootType::FlatPack;
         let proton_dir = None;
         let xedit_dir = None;
-        let game_dir = SteamDir::locate()
-            .and_then(|mut sd| {
-                sd.app(&game.steam_id())
-                    .map(|sa| Utf8PathBuf::try_from(sa.path.clone()).unwrap_or_default())
-            })
+        // The Steam installation which has the game, be it native, the flatpak or with the
+        // game on an SD card; otherwise the first one found.
+        let steam_game = steam::find_game(game.steam_id());
+        let game_dir = steam_game
+            .as_ref()
+            .map(|sg| sg.game_dir().to_owned())
             .unwrap_or_default();
-        let compat_dir = game_dir
-            .parent()
-            .map(|p| {
-                p.parent().map(|p| {
-                    Utf8PathBuf::try_from(
-                        p.with_file_name("compatdata")
-                            .with_file_name(game.steam_id().to_string()),
-                    )
-                    .unwrap_or_default()
-                })
-            })
-            .flatten();
-
-        let steam_dir = SteamDir::locate()
-            .map(|steam_dir| steam_dir.path)
-            .or_else(|| {
-                dirs::home_dir().map(|mut d| {
-                    d.push(".steam/steam");
-                    d
-                })
-            });
-
-        let steam_dir = steam_dir
-            .and_then(|steam_dir| {
-                if steam_dir.exists() {
-                    Some(steam_dir)
-                } else {
-                    None
-                }
-            })
-            .map(|sd| Utf8PathBuf::try_from(sd).unwrap_or_default());
+        let compat_dir = steam_game.as_ref().map(|sg| sg.compat_dir().to_owned());
+        let steam_dir = steam_game
+            .map(|sg| sg.steam_dir().to_owned())
+            .or_else(|| steam::steam_dirs().into_iter().next());
 
         let default_run = None;
 
@@ -189,6 +356,7 @@ impl Settings {
         Ok(Self {
             game,
             verbosity,
+            read_only: false,
             config_path,
             log_path,
             download_dir,
@@ -198,10 +366,33 @@ impl Settings {
             proton_dir,
             compat_dir,
             steam_dir,
+            wine: None,
+            wine_prefix: None,
             loot,
             loot_data_dir,
             xedit_dir,
             default_run,
+            list_columns: None,
+            colour: None,
+            page_size: None,
+            shared_cache: None,
+            new_mod_priority: None,
+            tag_colours: None,
+            editors: None,
+            ini_merge: None,
+            run_wrappers: None,
+            threads: None,
+            io_limit: None,
+            notifications: None,
+            deployment: None,
+            case_insensitive: None,
+            auto_update_masterlist: None,
+            symbols_only: None,
+            locale: None,
+            aliases: None,
+            state_dir: None,
+            cache_images: None,
+            backup_extension: None,
         })
     }
     pub fn valid_config(&self) -> bool {
@@ -220,7 +411,6 @@ impl Settings {
     pub const fn cmd_name(&self) -> &str {
         self.game.mod_manager_name()
     }
-    #[allow(unused)]
     pub fn config_file(&self) -> &Utf8Path {
         &self.config_path
     }
@@ -245,12 +435,53 @@ impl Settings {
     pub fn steam_dir(&self) -> Option<&Utf8Path> {
         self.steam_dir.as_deref()
     }
+    /// The wine binary to run the game with, instead of Steam's proton; for games which are
+    /// not installed through Steam.
+    pub fn wine(&self) -> Option<&Utf8Path> {
+        self.wine.as_deref()
+    }
+    /// The prefix `wine` runs the game in.
+    pub fn wine_prefix(&self) -> Option<&Utf8Path> {
+        self.wine_prefix.as_deref()
+    }
+    /// The home directory of the windows user the game runs as, in the prefix of proton, or
+    /// the one of `wine`; the game keeps its configuration and plugin list below it.
+    pub fn windows_user_dir(&self) -> Option<Utf8PathBuf> {
+        if self.wine.is_some() {
+            let users_dir = self.wine_prefix.as_ref()?.join(WINE_USERS_DIR);
+            return wine_user_dir(&users_dir, env::var("USER").ok().as_deref());
+        }
+        self.compat_dir.as_ref().map(|compat_dir| {
+            compat_dir
+                .join(self.game.steam_id().to_string())
+                .join(PROTON_USER_DIR)
+        })
+    }
     pub const fn loot(&self) -> &LootType {
         &self.loot
     }
     pub fn loot_data_dir(&self) -> &Utf8Path {
         self.loot_data_dir.as_path()
     }
+    /// LOOT's userlist of the game, which holds the user's own sorting rules.
+    pub fn loot_userlist(&self) -> Utf8PathBuf {
+        self.loot_data_dir
+            .join("games")
+            .join(self.game.game_name())
+            .join("userlist.yaml")
+    }
+    /// LOOT's masterlist of the game, next to the userlist.
+    pub fn loot_masterlist(&self) -> Utf8PathBuf {
+        self.loot_userlist().with_file_name("masterlist.yaml")
+    }
+    /// The prelude shared by the masterlists of all games.
+    pub fn loot_prelude(&self) -> Utf8PathBuf {
+        self.loot_data_dir.join("prelude").join("prelude.yaml")
+    }
+    /// Download LOOT's masterlist before the plugins are sorted.
+    pub fn auto_update_masterlist(&self) -> bool {
+        self.auto_update_masterlist.unwrap_or(false)
+    }
     pub fn xedit_dir(&self) -> Option<&Utf8Path> {
         self.xedit_dir.as_deref()
     }
@@ -260,6 +491,184 @@ impl Settings {
     pub fn editor(&self) -> String {
         self.editor.clone().unwrap_or_else(|| "xdg-open".to_owned())
     }
+    /// The editor for `file`, based on its extension; defaults to `editor`.
+    pub fn editor_for(&self, file: &Utf8Path) -> String {
+        let extension = file.extension().unwrap_or_default().to_lowercase();
+        self.editors
+            .iter()
+            .flatten()
+            .find(|e| e.extension() == extension)
+            .map_or_else(|| self.editor(), |e| e.command().to_owned())
+    }
+    pub fn list_columns(&self) -> &[ModListColumn] {
+        self.list_columns
+            .as_deref()
+            .unwrap_or(ModListColumn::DEFAULT)
+    }
+    pub fn colour(&self) -> bool {
+        self.colour.unwrap_or(true)
+    }
+    /// Show the state of mods with the glyphs of their tags instead of colours.
+    pub fn symbols_only(&self) -> bool {
+        self.symbols_only.unwrap_or(false)
+    }
+    /// Directory holding the priority, state, tags and notes of the mods, and a copy of the
+    /// load order, instead of the cache; meant to be kept in git, see `commands::state`.
+    pub fn state_dir(&self) -> Option<&Utf8Path> {
+        self.state_dir.as_deref()
+    }
+    /// Download the images of mods on Nexus while checking for updates, for 'mods show --image'.
+    pub fn cache_images(&self) -> bool {
+        self.cache_images.unwrap_or(false)
+    }
+    pub fn ini_merge(&self) -> IniMergeStrategy {
+        self.ini_merge.unwrap_or_default()
+    }
+    /// The commands of the user's aliases, by name; see `commands::alias`.
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        static NONE: BTreeMap<String, String> = BTreeMap::new();
+        self.aliases.as_ref().unwrap_or(&NONE)
+    }
+    /// Commands, like 'gamemoderun' or 'gamescope -W 2560 -H 1440 --', which the game and
+    /// tools are run through; the first is the outermost.
+    pub fn run_wrappers(&self) -> &[String] {
+        self.run_wrappers.as_deref().unwrap_or_default()
+    }
+    pub fn tag_colours(&self) -> &[TagColour] {
+        self.tag_colours.as_deref().unwrap_or_default()
+    }
+    /// Priority given to newly installed mods, unless told otherwise.
+    pub fn new_mod_priority(&self) -> isize {
+        self.new_mod_priority.unwrap_or_default()
+    }
+    /// Show a desktop notification when long operations, like 'extract-all', finish.
+    pub fn notifications(&self) -> bool {
+        self.notifications.unwrap_or(false)
+    }
+    pub fn deployment(&self) -> DeploymentStrategy {
+        self.deployment.unwrap_or_default()
+    }
+    /// Deploy files into the directories already in the game directory, whatever their
+    /// casing, like a case-insensitive file system would.
+    pub fn case_insensitive(&self) -> bool {
+        self.case_insensitive.unwrap_or(false)
+    }
+    /// Extension given to the files moved aside for a link, like `Starfield.ini.starmod_bkp`.
+    pub fn backup_extension(&self) -> &str {
+        self.backup_extension.as_deref().unwrap_or(BACKUP_EXTENTION)
+    }
+    /// Holds the links to the files of the enabled mods, and the files written to the game
+    /// directory, for the overlay deployment.
+    ///
+    /// # Errors
+    ///
+    /// When the directory cannot be created, or its path is not UTF-8.
+    pub fn overlay_dir(&self) -> Result<Utf8PathBuf> {
+        let xdg_base = BaseDirectories::with_prefix(self.cmd_name())?;
+        Ok(Utf8PathBuf::try_from(
+            xdg_base.create_state_directory(OVERLAY_DIR)?,
+        )?)
+    }
+    pub fn shared_cache(&self) -> bool {
+        self.shared_cache.unwrap_or(false)
+    }
+    /// Directory holding the priority, state and tags of mods in a shared cache;
+    /// it lives next to the configuration, so every user has their own.
+    pub fn user_state_dir(&self) -> Utf8PathBuf {
+        self.config_path.with_file_name(USER_STATE_DIR)
+    }
+    /// Directory of the scripts which install the mods starmod cannot, see
+    /// `installers::script`; it lives next to the configuration.
+    pub fn installer_scripts_dir(&self) -> Utf8PathBuf {
+        self.config_path.with_file_name(INSTALLER_SCRIPTS_DIR)
+    }
+    /// Make the installer scripts known to the mod detection, see `installer_scripts_dir`.
+    pub fn apply_installer_settings(&self) {
+        let _ = INSTALLER_SCRIPTS.set(self.installer_scripts_dir());
+    }
+    /// Make a shared cache known to the manifests, see `shared_state_dir`.
+    ///
+    /// # Errors
+    ///
+    /// When the shared state directory cannot be created.
+    pub fn apply_cache_settings(&self) -> Result<()> {
+        if self.shared_cache() {
+            let state_dir = self.user_state_dir();
+            if !self.read_only {
+                std::fs::create_dir_all(&state_dir)?;
+            }
+            let _ = SHARED_STATE_DIR.set(state_dir);
+        }
+        if let Some(state_dir) = self.state_dir() {
+            if !self.read_only {
+                std::fs::create_dir_all(state_dir)?;
+            }
+            let _ = STATE_DIR.set(state_dir.to_owned());
+        }
+        Ok(())
+    }
+    /// Show messages in the language of the 'locale' setting, or of the environment; see
+    /// `i18n::select_locale`.
+    pub fn apply_locale_settings(&self) {
+        i18n::select_locale(self.cmd_name(), self.locale.as_deref());
+    }
+    /// Make the 'deployment', 'case-insensitive' and 'backup-extension' settings known to the
+    /// mod-list, see `links_mods`, `folds_case` and `backup_extension`.
+    pub fn apply_deployment_settings(&self) {
+        LINK_MODS.store(
+            self.deployment() == DeploymentStrategy::Symlink,
+            Ordering::Relaxed,
+        );
+        FOLD_CASE.store(self.case_insensitive(), Ordering::Relaxed);
+        let _ = BACKUP_EXTENSION.set(self.backup_extension().to_owned());
+    }
+    /// Size the thread pools by the 'threads' and 'io-limit' settings, see `with_io_limit`.
+    ///
+    /// # Errors
+    ///
+    /// When the thread pools were sized already.
+    pub fn apply_concurrency_settings(&self) -> Result<()> {
+        if let Some(threads) = self.threads {
+            ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global()?;
+        }
+        if let Some(io_limit) = self.io_limit {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(io_limit)
+                .thread_name(|idx| format!("io-{idx}"))
+                .build()?;
+            let _ = IO_POOL.set(pool);
+        }
+        Ok(())
+    }
+    /// Block every change to manifests, the cache and the game directory.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        READ_ONLY.store(read_only, Ordering::Relaxed);
+    }
+    pub const fn read_only(&self) -> bool {
+        self.read_only
+    }
+    /// Make the display preferences known to `create_table` and `default_page_size`.
+    /// Colours are disabled by `no_colour`, a non-empty `NO_COLOR`, the 'colour' or the
+    /// 'symbols-only' setting.
+    pub fn apply_display_settings(&self, no_colour: bool) {
+        let no_colour_env = env::var_os(NO_COLOR_ENV).is_some_and(|v| !v.is_empty());
+        let colour = self.colour() && !self.symbols_only() && !no_colour && !no_colour_env;
+
+        COLOUR.store(colour, Ordering::Relaxed);
+        SYMBOLS_ONLY.store(self.symbols_only(), Ordering::Relaxed);
+        if !colour {
+            inquire::set_global_render_config(inquire::ui::RenderConfig::empty());
+        }
+        PAGE_SIZE.store(self.page_size.unwrap_or_default(), Ordering::Relaxed);
+    }
+    /// The settings of `game`, from its configuration file when there is one.
+    ///
+    /// # Errors
+    ///
+    /// When the directories of starmod are not known, or the configuration file is broken.
     pub fn read_config(game: Game, verbosity: LogLevel) -> Result<Self> {
         let settings = Self::create(game, verbosity)?;
         if let Ok(config) = File::open(&settings.config_path) {
@@ -272,6 +681,12 @@ impl Settings {
         }
     }
     //TODO option to fetch download dir from dmodman's config
+    /// Update the settings with the given values, and write them to the configuration file; `None`
+    /// keeps a setting as it is.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when the configuration file cannot be written.
     #[allow(clippy::too_many_arguments)]
     pub fn create_config(
         &self,
@@ -285,17 +700,54 @@ impl Settings {
         xedit_dir: Option<Utf8PathBuf>,
         loot_type: Option<LootType>,
         loot_data_dir: Option<Utf8PathBuf>,
+        list_columns: Option<Vec<ModListColumn>>,
+        colour: Option<bool>,
+        page_size: Option<usize>,
+        shared_cache: Option<bool>,
+        new_mod_priority: Option<isize>,
+        tag_colours: Option<Vec<TagColour>>,
+        editors: Option<Vec<EditorMapping>>,
+        ini_merge: Option<IniMergeStrategy>,
+        run_wrappers: Option<Vec<String>>,
+        threads: Option<usize>,
+        io_limit: Option<usize>,
+        notifications: Option<bool>,
+        deployment: Option<DeploymentStrategy>,
+        case_insensitive: Option<bool>,
+        wine: Option<Utf8PathBuf>,
+        wine_prefix: Option<Utf8PathBuf>,
+        auto_update_masterlist: Option<bool>,
+        symbols_only: Option<bool>,
+        locale: Option<String>,
+        state_dir: Option<Utf8PathBuf>,
+        cache_images: Option<bool>,
+        backup_extension: Option<String>,
     ) -> Result<Self> {
+        ensure_writable("update the configuration")?;
         let mut settings = self.clone();
 
         let cache_dir = cache_dir.unwrap_or(settings.cache_dir);
         let download_dir = download_dir.unwrap_or(settings.download_dir);
 
+        // An empty path goes back to running the game with Steam's proton.
+        settings.wine = wine.map_or_else(
+            || self.wine.clone(),
+            |w| Some(w).filter(|w| !w.as_str().is_empty()),
+        );
+        settings.wine_prefix = wine_prefix.or_else(|| self.wine_prefix.clone());
+        if settings.wine.is_some() && settings.wine_prefix.is_none() {
+            return Err(SettingErrors::NoWinePrefixFound(self.cmd_name().to_owned()).into());
+        }
+
         // We take steams listing as true if we can use it, since the game can easily be changed between config updates.
-        // If we can't find it via steam, we use the configured value
-        let game_dir = SteamDir::locate()
-            .and_then(|mut sd| sd.app(&self.game.steam_id()).map(|sa| sa.path.clone()))
-            .map(|p| Utf8PathBuf::try_from(p).unwrap_or_default())
+        // If we can't find it via steam, we use the configured value; without Steam, only that.
+        let steam_game = settings
+            .wine
+            .is_none()
+            .then(|| steam::find_game(self.game.steam_id()))
+            .flatten();
+        let game_dir = steam_game
+            .map(|sg| sg.game_dir().to_owned())
             .unwrap_or(game_dir.unwrap_or(settings.game_dir));
 
         let game_dir = if game_dir.exists() {
@@ -331,15 +783,85 @@ impl Settings {
         settings.xedit_dir = xedit_dir.or_else(|| self.xedit_dir.clone());
         settings.loot_data_dir = loot_data_dir.unwrap_or_else(|| self.loot_data_dir.clone());
         settings.loot = loot_type.unwrap_or_else(|| self.loot.clone());
+        settings.list_columns = list_columns.or_else(|| self.list_columns.clone());
+        settings.colour = colour.or(self.colour);
+        settings.page_size = page_size.or(self.page_size);
+        settings.shared_cache = shared_cache.or(self.shared_cache);
+        settings.new_mod_priority = new_mod_priority.or(self.new_mod_priority);
+        settings.tag_colours = tag_colours.or_else(|| self.tag_colours.clone());
+        settings.editors = editors.or_else(|| self.editors.clone());
+        settings.ini_merge = ini_merge.or(self.ini_merge);
+        // An empty wrapper clears the list.
+        settings.run_wrappers = run_wrappers
+            .map(|w| w.into_iter().filter(|w| !w.trim().is_empty()).collect())
+            .or_else(|| self.run_wrappers.clone());
+        // Zero goes back to the default.
+        settings.threads = threads.or(self.threads).filter(|t| *t > 0);
+        settings.io_limit = io_limit.or(self.io_limit).filter(|l| *l > 0);
+        settings.notifications = notifications.or(self.notifications);
+        settings.deployment = deployment.or(self.deployment);
+        settings.case_insensitive = case_insensitive.or(self.case_insensitive);
+        settings.auto_update_masterlist = auto_update_masterlist.or(self.auto_update_masterlist);
+        settings.symbols_only = symbols_only.or(self.symbols_only);
+        // An empty locale goes back to the one of the environment.
+        settings.locale = locale.map_or_else(
+            || self.locale.clone(),
+            |l| Some(l).filter(|l| !l.is_empty()),
+        );
+        // An empty path keeps the state in the cache again.
+        settings.state_dir = state_dir.map_or_else(
+            || self.state_dir.clone(),
+            |d| Some(d).filter(|d| !d.as_str().is_empty()),
+        );
+        settings.cache_images = cache_images.or(self.cache_images);
+        // An empty extension goes back to the default.
+        settings.backup_extension = match backup_extension {
+            Some(e) if e.contains('/') => {
+                return Err(SettingErrors::InvalidBackupExtension(e).into())
+            }
+            Some(e) => Some(e.trim_start_matches('.').to_owned()).filter(|e| !e.is_empty()),
+            None => self.backup_extension.clone(),
+        };
 
+        settings.write_config()?;
+        Ok(settings)
+    }
+    /// Add alias `name` for `command`, or remove it when `command` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when the configuration file cannot be written.
+    pub fn set_alias(&self, name: &str, command: Option<String>) -> Result<Self> {
+        ensure_writable("update the aliases")?;
+        let mut settings = self.clone();
+        let aliases = settings.aliases.get_or_insert_with(BTreeMap::new);
+        match command {
+            Some(command) => {
+                aliases.insert(name.to_owned(), command);
+            }
+            None => {
+                aliases
+                    .remove(name)
+                    .ok_or_else(|| SettingErrors::AliasNotFound(name.to_owned()))?;
+            }
+        }
+        settings.write_config()?;
+        Ok(settings)
+    }
+    fn write_config(&self) -> Result<()> {
         let mut file = File::create(&self.config_path)?;
 
-        let serialized = ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default())?;
+        let serialized = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
         file.write_all(serialized.as_bytes())?;
-
-        Ok(settings)
+        Ok(())
     }
+    /// Remove the configuration file and the cache directory.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when they cannot be removed.
     pub fn purge_config(&self) -> Result<()> {
+        ensure_writable("remove the configuration")?;
         self.purge_cache()?;
 
         println!("Removing file: {}", self.config_path);
@@ -350,7 +872,13 @@ impl Settings {
         }
         Ok(())
     }
+    /// Remove the cache directory, with every mod in it.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when it cannot be removed.
     pub fn purge_cache(&self) -> Result<()> {
+        ensure_writable("remove the cache directory")?;
         println!(
             "Removing cache directory and it's contents: {}",
             self.cache_dir
@@ -386,6 +914,10 @@ impl Display for Settings {
                 format!("{}", self.download_dir),
             ])
             .add_row(vec!["Game Dir".to_owned(), format!("{}", self.game_dir)])
+            .add_row(vec![
+                "Installer Scripts Dir".to_owned(),
+                self.installer_scripts_dir().to_string(),
+            ])
             .add_row(vec![
                 "Steam Proton Dir".to_owned(),
                 format!(
@@ -395,6 +927,20 @@ impl Display for Settings {
                         .map_or_else(|| "<Unknown>".to_owned(), ToString::to_string)
                 ),
             ])
+            .add_row(vec![
+                "Wine".to_owned(),
+                self.wine.as_ref().map_or_else(
+                    || "<Steam's Proton>".to_owned(),
+                    |wine| {
+                        format!(
+                            "{wine} in {}",
+                            self.wine_prefix
+                                .as_ref()
+                                .map_or_else(|| "<Unknown>".to_owned(), ToString::to_string)
+                        )
+                    },
+                ),
+            ])
             .add_row(vec![
                 "Xedit Dir".to_owned(),
                 format!(
@@ -421,24 +967,275 @@ impl Display for Settings {
                         .clone()
                         .unwrap_or_else(|| "<Unknown>".to_owned())
                 ),
+            ])
+            .add_row(vec![
+                "List Columns".to_owned(),
+                self.list_columns()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ])
+            .add_row(vec!["Colour".to_owned(), self.colour().to_string()])
+            .add_row(vec![
+                "Locale".to_owned(),
+                self.locale
+                    .clone()
+                    .unwrap_or_else(|| format!("<Environment: {}>", i18n::locale())),
+            ])
+            .add_row(vec![
+                "State Dir".to_owned(),
+                self.state_dir()
+                    .map_or_else(|| "<Cache Dir>".to_owned(), ToString::to_string),
+            ])
+            .add_row(vec![
+                "Symbols Only".to_owned(),
+                self.symbols_only().to_string(),
+            ])
+            .add_row(vec![
+                "Page Size".to_owned(),
+                self.page_size
+                    .map_or_else(|| "<Terminal Height>".to_owned(), |p| p.to_string()),
+            ])
+            .add_row(vec![
+                "Shared Cache".to_owned(),
+                self.shared_cache().to_string(),
+            ])
+            .add_row(vec![
+                "New Mod Priority".to_owned(),
+                self.new_mod_priority().to_string(),
+            ])
+            .add_row(vec![
+                "Editors".to_owned(),
+                self.editors
+                    .iter()
+                    .flatten()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ])
+            .add_row(vec!["Ini Merge".to_owned(), self.ini_merge().to_string()])
+            .add_row(vec![
+                "Run Wrappers".to_owned(),
+                self.run_wrappers().join(", "),
+            ])
+            .add_row(vec![
+                "Threads".to_owned(),
+                or_default(self.threads, "<CPUs>"),
+            ])
+            .add_row(vec![
+                "IO Limit".to_owned(),
+                or_default(self.io_limit, "<Threads>"),
+            ])
+            .add_row(vec![
+                "Notifications".to_owned(),
+                self.notifications().to_string(),
+            ])
+            .add_row(vec!["Deployment".to_owned(), self.deployment().to_string()])
+            .add_row(vec![
+                "Case Insensitive".to_owned(),
+                self.case_insensitive().to_string(),
+            ])
+            .add_row(vec![
+                "Auto Update Masterlist".to_owned(),
+                self.auto_update_masterlist().to_string(),
+            ])
+            .add_row(vec![
+                "Cache Images".to_owned(),
+                self.cache_images().to_string(),
+            ])
+            .add_row(vec![
+                "Backup Extension".to_owned(),
+                self.backup_extension().to_owned(),
+            ])
+            .add_row(vec![
+                "Tag Colours".to_owned(),
+                self.tag_colours()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ])
+            .add_row(vec![
+                "Aliases".to_owned(),
+                self.aliases()
+                    .iter()
+                    .map(|(name, command)| format!("{name}={command}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
             ]);
 
         write!(f, "{table}")
     }
 }
 
-pub fn create_table(headers: Vec<&'static str>) -> Table {
+/// `value`, or `default` when it is not set.
+fn or_default(value: Option<usize>, default: &str) -> String {
+    value.map_or_else(|| default.to_owned(), |v| v.to_string())
+}
+
+pub fn create_table<T: Into<Cell>>(headers: Vec<T>) -> Table {
     let mut table = Table::new();
     table
         .load_preset(NOTHING)
         .set_content_arrangement(ContentArrangement::Dynamic)
         // .set_content_arrangement(ContentArrangement::Disabled)
         .set_header(headers);
+    if !COLOUR.load(Ordering::Relaxed) {
+        table.force_no_tty();
+    }
     table
 }
 
+/// Whether rows show the glyphs of their tags, since colours are not used; see
+/// `Settings::symbols_only`.
+pub fn symbols_only() -> bool {
+    SYMBOLS_ONLY.load(Ordering::Relaxed)
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Fails when running in read-only mode; `action` describes what was attempted.
+///
+/// # Errors
+///
+/// In read-only mode.
+pub fn ensure_writable(action: &str) -> Result<()> {
+    if is_read_only() {
+        Err(SettingErrors::ReadOnly(action.to_owned()).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Run `op`, whose parallel iterators hit the disk, on no more threads than the 'io-limit'
+/// setting allows; a hard disk is unusable when it is hammered by every CPU at once.
+pub fn with_io_limit<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    match IO_POOL.get() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Whether enabling mods links their files into the game directory; with the overlay
+/// deployment they are only mounted over it while the game runs.
+pub fn links_mods() -> bool {
+    LINK_MODS.load(Ordering::Relaxed)
+}
+
+/// Whether destinations are deployed with the casing already in the game directory.
+pub fn folds_case() -> bool {
+    FOLD_CASE.load(Ordering::Relaxed)
+}
+
+/// Extension of the backups of files in the way of links; see `deployment::backup`.
+pub fn backup_extension() -> &'static str {
+    BACKUP_EXTENSION
+        .get()
+        .map_or(BACKUP_EXTENTION, String::as_str)
+}
+
+/// Directory of the user's installer scripts, once the settings are applied.
+pub fn installer_scripts_dir() -> Option<&'static Utf8Path> {
+    INSTALLER_SCRIPTS.get().map(Utf8PathBuf::as_path)
+}
+
+/// This user's state directory when the cache is shared with other users.
+pub fn shared_state_dir() -> Option<&'static Utf8Path> {
+    SHARED_STATE_DIR.get().map(Utf8PathBuf::as_path)
+}
+
+/// The home directory of the windows user in the users directory of a wine prefix.
+///
+/// Wine names the user after the one running it, so `user` is preferred, then the user of
+/// proton; otherwise the first other user by name, so the choice does not change between runs.
+pub fn wine_user_dir(users_dir: &Utf8Path, user: Option<&str>) -> Option<Utf8PathBuf> {
+    if let Some(dir) = user
+        .into_iter()
+        .chain([WINE_STEAM_USER])
+        .map(|name| users_dir.join(name))
+        .find(|dir| dir.is_dir())
+    {
+        return Some(dir);
+    }
+    let mut users = users_dir
+        .read_dir_utf8()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()) && e.file_name() != WINE_PUBLIC_USER)
+        .map(camino::Utf8DirEntry::into_path)
+        .collect::<Vec<_>>();
+    users.sort_unstable();
+    users
+        .into_iter()
+        .next()
+        .or_else(|| user.map(|name| users_dir.join(name)))
+}
+
+/// The directory the mod state is kept in, when that is not the cache: the 'state-dir'
+/// setting, or else this user's state directory of a shared cache.
+pub fn mod_state_dir() -> Option<&'static Utf8Path> {
+    STATE_DIR
+        .get()
+        .map(Utf8PathBuf::as_path)
+        .or_else(shared_state_dir)
+}
+
+/// Give the group write access to `path` when the cache is shared, so other users can update it.
+///
+/// The umask is honoured for everything else; directories get the set-group-id bit,
+/// so their contents belong to the same group.
+///
+/// # Errors
+///
+/// When the permissions cannot be changed.
+pub fn share_with_group(path: &Utf8Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    const GROUP_RW: u32 = 0o060;
+    const GROUP_X_SETGID: u32 = 0o2010;
+
+    if shared_state_dir().is_none() {
+        return Ok(());
+    }
+
+    let metadata = path.symlink_metadata()?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+    let mut mode = metadata.permissions().mode() | GROUP_RW;
+    if metadata.is_dir() {
+        mode |= GROUP_X_SETGID;
+    }
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+/// `share_with_group` for `path` and everything below it.
+///
+/// # Errors
+///
+/// When the permissions of a file cannot be changed.
+pub fn share_with_group_recursive(path: &Utf8Path) -> Result<()> {
+    if shared_state_dir().is_none() {
+        return Ok(());
+    }
+    for entry in walkdir::WalkDir::new(path).follow_links(false) {
+        let entry = entry?;
+        share_with_group(Utf8Path::from_path(entry.path()).unwrap_or(path))?;
+    }
+    Ok(())
+}
+
 pub fn default_page_size() -> usize {
     const MAX: usize = 50;
+    let page_size = PAGE_SIZE.load(Ordering::Relaxed);
+    if page_size > 0 {
+        return page_size;
+    }
     let h = term_size::dimensions_stdout().map(|d| d.1).unwrap_or(MAX);
     if h > MAX {
         MAX
@@ -0,0 +1,222 @@
+//! The hashes of the game's own files, to find out later which of them were changed behind
+//! starmod's back.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    fs::{metadata, read, rename, File},
+    io::Write,
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{
+    deployment::backup::{original_of, BackupRegistry},
+    hash::{hash_file, ContentHash},
+    settings::{ensure_writable, share_with_group, with_io_limit},
+    utils::AddExtension,
+};
+
+// Deliberately not a manifest extension, so `gather_mods` skips it.
+const SNAPSHOT_FILE: &str = "game.snapshot";
+const SNAPSHOT_TEMP_EXTENSION: &str = "tmp";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct SnapshotEntry {
+    len: u64,
+    modified: SystemTime,
+    hash: ContentHash,
+}
+
+/// How a file of the game differs from its snapshot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileChange {
+    Modified,
+    Missing,
+    Added,
+}
+impl Display for FileChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Modified => write!(f, "Modified"),
+            Self::Missing => write!(f, "Missing"),
+            Self::Added => write!(f, "Added"),
+        }
+    }
+}
+
+/// The size, modification time and hash of every file of the game, by their path relative
+/// to the game directory.
+///
+/// Files starmod linked into the game directory are not part of the game; for the original
+/// files they replaced, the backups starmod made are used.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GameSnapshot {
+    taken: SystemTime,
+    files: BTreeMap<Utf8PathBuf, SnapshotEntry>,
+}
+impl GameSnapshot {
+    /// Hash every file of the game in `game_dir`.
+    ///
+    /// # Errors
+    ///
+    /// When the game directory or one of its files cannot be read.
+    pub fn take(cache_dir: &Utf8Path, game_dir: &Utf8Path) -> Result<Self> {
+        let files = game_files(cache_dir, game_dir)?;
+        let entries = with_io_limit(|| {
+            files
+                .par_iter()
+                .map(|(relative, path)| {
+                    let meta = metadata(path)?;
+                    Ok((
+                        relative.clone(),
+                        SnapshotEntry {
+                            len: meta.len(),
+                            modified: meta.modified()?,
+                            hash: hash_file(path)?,
+                        },
+                    ))
+                })
+                .collect::<Result<BTreeMap<_, _>>>()
+        })?;
+
+        Ok(Self {
+            taken: SystemTime::now(),
+            files: entries,
+        })
+    }
+    /// The snapshot in `cache_dir`, if one was taken.
+    ///
+    /// # Errors
+    ///
+    /// When the snapshot cannot be read.
+    pub fn read(cache_dir: &Utf8Path) -> Result<Option<Self>> {
+        let path = cache_dir.join(SNAPSHOT_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(rmp_serde::from_slice(&read(path)?)?))
+    }
+    /// Write the snapshot into `cache_dir`.
+    ///
+    /// # Errors
+    ///
+    /// In read-only mode, or when the file cannot be written.
+    pub fn write(&self, cache_dir: &Utf8Path) -> Result<()> {
+        ensure_writable("write a snapshot of the game files")?;
+
+        let path = cache_dir.join(SNAPSHOT_FILE);
+        let temp_path = path.add_extension(SNAPSHOT_TEMP_EXTENSION);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&rmp_serde::to_vec_named(&self)?)?;
+        rename(&temp_path, &path)?;
+        share_with_group(&path)
+    }
+    pub fn exists(cache_dir: &Utf8Path) -> bool {
+        cache_dir.join(SNAPSHOT_FILE).exists()
+    }
+
+    pub const fn taken(&self) -> SystemTime {
+        self.taken
+    }
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The files of the game in `game_dir` which differ from the snapshot, sorted by path.
+    ///
+    /// Files whose size and modification time did not change are taken to be unchanged,
+    /// unless `full` is set; then every file is hashed again.
+    ///
+    /// # Errors
+    ///
+    /// When the game directory or one of its files cannot be read.
+    pub fn verify(
+        &self,
+        cache_dir: &Utf8Path,
+        game_dir: &Utf8Path,
+        full: bool,
+    ) -> Result<Vec<(Utf8PathBuf, FileChange)>> {
+        let files = game_files(cache_dir, game_dir)?;
+        let present = files
+            .iter()
+            .map(|(relative, _)| relative.as_path())
+            .collect::<HashSet<_>>();
+
+        let mut changes = with_io_limit(|| {
+            files
+                .par_iter()
+                .filter_map(|(relative, path)| {
+                    let Some(entry) = self.files.get(relative) else {
+                        return Some(Ok((relative.clone(), FileChange::Added)));
+                    };
+                    is_modified(entry, path, full)
+                        .map(|modified| modified.then(|| (relative.clone(), FileChange::Modified)))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+        changes.extend(
+            self.files
+                .keys()
+                .filter(|relative| !present.contains(relative.as_path()))
+                .map(|relative| (relative.clone(), FileChange::Missing)),
+        );
+
+        changes.sort();
+        Ok(changes)
+    }
+}
+
+fn is_modified(entry: &SnapshotEntry, path: &Utf8Path, full: bool) -> Result<bool> {
+    let meta = metadata(path)?;
+    if meta.len() != entry.len {
+        return Ok(true);
+    }
+    if !full && meta.modified()? == entry.modified {
+        return Ok(false);
+    }
+    Ok(hash_file(path)? != entry.hash)
+}
+
+/// The files of the game, as (path relative to `game_dir`, path to read it from); links into
+/// `cache_dir` are skipped, the newest backups of the files they replaced are read instead.
+fn game_files(
+    cache_dir: &Utf8Path,
+    game_dir: &Utf8Path,
+) -> Result<Vec<(Utf8PathBuf, Utf8PathBuf)>> {
+    let registry = BackupRegistry::read(cache_dir);
+    let mut files = Vec::new();
+    for entry in WalkDir::new(game_dir)
+        .min_depth(1)
+        .follow_links(false)
+        .same_file_system(true)
+    {
+        let entry = entry?;
+        let path = Utf8PathBuf::try_from(entry.path().to_path_buf())?;
+        let is_deployed = entry.path_is_symlink()
+            && path
+                .read_link_utf8()
+                .is_ok_and(|t| t.starts_with(cache_dir));
+        if is_deployed || !path.is_file() {
+            continue;
+        }
+
+        if let Some(original) = original_of(&path) {
+            if registry.backups_of(&original).last() == Some(&path) {
+                files.push((original.strip_prefix(game_dir)?.to_owned(), path));
+            }
+        } else if registry.backups_of(&path).is_empty() {
+            files.push((path.strip_prefix(game_dir)?.to_owned(), path));
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,143 @@
+//! Finding Steam and the games installed by it; besides the native install, this knows about
+//! the flatpak of Steam and libraries on SD cards, like those of the Steam Deck.
+
+use std::fs::read_to_string;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use steamlocate::SteamDir;
+
+use crate::vdf;
+
+/// The application id of the flatpak of Steam.
+pub const FLATPAK_ID: &str = "com.valvesoftware.Steam";
+
+// Relative to the home directory; the native ones first.
+const STEAM_DIRS: [&str; 5] = [
+    ".steam/steam",
+    ".local/share/Steam",
+    ".var/app/com.valvesoftware.Steam/.steam/steam",
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ".var/app/com.valvesoftware.Steam/data/Steam",
+];
+// SD cards are mounted here, either directly or below the name of the user.
+const REMOVABLE_MEDIA_DIR: &str = "/run/media";
+const STEAMAPPS_DIR: &str = "steamapps";
+const LIBRARY_FOLDERS: &str = "libraryfolders.vdf";
+const COMMON_DIR: &str = "common";
+const COMPAT_DIR: &str = "compatdata";
+const LIBRARY_PATH_KEY: &str = "path";
+const INSTALL_DIR_KEY: &str = "installdir";
+
+/// A game installed by Steam.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SteamGame {
+    steam_dir: Utf8PathBuf,
+    game_dir: Utf8PathBuf,
+    compat_data: Utf8PathBuf,
+}
+impl SteamGame {
+    /// The Steam installation which knows about the game.
+    pub fn steam_dir(&self) -> &Utf8Path {
+        &self.steam_dir
+    }
+    pub fn game_dir(&self) -> &Utf8Path {
+        &self.game_dir
+    }
+    /// The directory with the proton prefixes of the library the game is installed in.
+    pub fn compat_dir(&self) -> &Utf8Path {
+        &self.compat_data
+    }
+}
+
+/// Every Steam installation on this system, the one `steamlocate` finds first.
+pub fn steam_dirs() -> Vec<Utf8PathBuf> {
+    let located = SteamDir::locate().and_then(|sd| Utf8PathBuf::try_from(sd.path).ok());
+    let home = dirs::home_dir().and_then(|d| Utf8PathBuf::try_from(d).ok());
+    let candidates = located.into_iter().chain(
+        home.into_iter()
+            .flat_map(|home| STEAM_DIRS.map(|dir| home.join(dir))),
+    );
+
+    // The same installation is often reachable through a link, like '~/.steam/steam'.
+    let mut found: Vec<(Utf8PathBuf, Utf8PathBuf)> = Vec::new();
+    for dir in candidates.filter(|dir| dir.join(STEAMAPPS_DIR).is_dir()) {
+        let real = dir.canonicalize_utf8().unwrap_or_else(|_| dir.clone());
+        if !found.iter().any(|(_, r)| *r == real) {
+            found.push((dir, real));
+        }
+    }
+    found.into_iter().map(|(dir, _)| dir).collect()
+}
+
+/// Whether `steam_dir` belongs to the flatpak of Steam; programs which run with it, like
+/// proton, then have to run inside its sandbox.
+pub fn is_flatpak(steam_dir: &Utf8Path) -> bool {
+    steam_dir.components().any(|c| c.as_str() == FLATPAK_ID)
+}
+
+/// The libraries of `steam_dir`, with those on SD cards Steam does not list (yet).
+pub fn library_dirs(steam_dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let listed = read_to_string(steam_dir.join(STEAMAPPS_DIR).join(LIBRARY_FOLDERS))
+        .map(|contents| vdf::values(&contents, LIBRARY_PATH_KEY))
+        .unwrap_or_default()
+        .into_iter()
+        .map(Utf8PathBuf::from);
+
+    let mut libraries = vec![steam_dir.to_owned()];
+    for library in listed.chain(removable_libraries()) {
+        if library.join(STEAMAPPS_DIR).is_dir() && !libraries.contains(&library) {
+            libraries.push(library);
+        }
+    }
+    libraries
+}
+
+/// Libraries on removable media; `/run/media/mmcblk0p1` on older, and
+/// `/run/media/<user>/<label>` on newer versions of the Steam Deck.
+fn removable_libraries() -> Vec<Utf8PathBuf> {
+    let subdirs = |dir: &Utf8Path| {
+        dir.read_dir_utf8()
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(camino::Utf8DirEntry::into_path)
+                    .filter(|p| p.is_dir())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut libraries = Vec::new();
+    for dir in subdirs(Utf8Path::new(REMOVABLE_MEDIA_DIR)) {
+        if dir.join(STEAMAPPS_DIR).is_dir() {
+            libraries.push(dir);
+        } else {
+            libraries.extend(
+                subdirs(&dir)
+                    .into_iter()
+                    .filter(|d| d.join(STEAMAPPS_DIR).is_dir()),
+            );
+        }
+    }
+    libraries.sort();
+    libraries
+}
+
+/// Where the game with `steam_id` is installed, in any library of any Steam installation.
+pub fn find_game(steam_id: u32) -> Option<SteamGame> {
+    steam_dirs().into_iter().find_map(|steam_dir| {
+        library_dirs(&steam_dir).into_iter().find_map(|library| {
+            let steamapps = library.join(STEAMAPPS_DIR);
+            let manifest =
+                read_to_string(steamapps.join(format!("appmanifest_{steam_id}.acf"))).ok()?;
+            let install_dir = vdf::values(&manifest, INSTALL_DIR_KEY).into_iter().next()?;
+            let game_dir = steamapps.join(COMMON_DIR).join(install_dir);
+
+            game_dir.is_dir().then(|| SteamGame {
+                steam_dir: steam_dir.clone(),
+                game_dir,
+                compat_data: steamapps.join(COMPAT_DIR),
+            })
+        })
+    })
+}
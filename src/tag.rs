@@ -2,6 +2,8 @@ use std::fmt::Display;
 
 use comfy_table::Color;
 
+use crate::i18n::tr;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Tag {
     Enabled,
@@ -10,6 +12,40 @@ pub enum Tag {
     CompleteLoser,
     Conflict,
     Disabled,
+    Pinned,
+}
+impl Tag {
+    pub const ALL: [Self; 7] = [
+        Self::Enabled,
+        Self::Winner,
+        Self::Loser,
+        Self::CompleteLoser,
+        Self::Conflict,
+        Self::Disabled,
+        Self::Pinned,
+    ];
+
+    /// What the tag means, as shown by the legenda.
+    pub fn meaning(self) -> String {
+        tr(match self {
+            Self::Enabled => "tag-enabled",
+            Self::Winner => "tag-winner",
+            Self::Loser => "tag-loser",
+            Self::CompleteLoser => "tag-complete-loser",
+            Self::Conflict => "tag-conflict",
+            Self::Disabled => "tag-disabled",
+            Self::Pinned => "tag-pinned",
+        })
+    }
+    /// The glyph and name of every tag on a single line, like 'e Enabled, w Winner', to show
+    /// below prompts.
+    pub fn short_legenda() -> String {
+        Self::ALL
+            .iter()
+            .map(|tag| format!("{} {tag}", char::from(*tag)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 impl Display for Tag {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,6 +59,7 @@ impl Display for Tag {
                 Self::CompleteLoser => "All Files Overwritten",
                 Self::Conflict => "Conflict",
                 Self::Disabled => "Disabled",
+                Self::Pinned => "Pinned",
             }
         )
     }
@@ -36,6 +73,7 @@ impl From<Tag> for char {
             Tag::CompleteLoser => 'L',
             Tag::Conflict => 'c',
             Tag::Disabled => 'D',
+            Tag::Pinned => 'P',
         }
     }
 }
@@ -48,6 +86,7 @@ impl From<Tag> for Color {
             Tag::CompleteLoser => Self::Red,
             Tag::Conflict => Self::Magenta,
             Tag::Disabled => Self::DarkGrey,
+            Tag::Pinned => Self::DarkBlue,
         }
     }
 }
@@ -1,14 +1,45 @@
 mod list;
 
 use inquire::Select;
-pub use list::{ArchiveListBuilder, FileListBuilder, ListBuilder, ModListBuilder};
+pub use list::{
+    ArchiveListBuilder, FileListBuilder, ListBuilder, ModListBuilder, ModListColumn, ModListGroup,
+    ModListSort,
+};
+
+mod confirm;
+pub use confirm::{ask_password, assume_yes, confirm};
+
+mod fuzzy;
+pub use fuzzy::fuzzy_find;
+
+mod image;
+pub use image::{base64, show_image};
 
 mod inquiry;
 pub use inquiry::{InquireBuilder, SelectToIdx};
 
+mod notify;
+pub use notify::{enable_notifications, notify};
+
+mod pager;
+pub use pager::{disable_pager, page};
+
+mod tree;
+pub use tree::FileTreeBuilder;
+
+use std::{fmt::Display, sync::OnceLock};
+
 use anyhow::Result;
+use camino::Utf8Path;
 
-use crate::{mods::FindInModList, settings::default_page_size};
+use crate::{
+    commands::downloads::find_archive,
+    filter::{matches_all, parse_filter_input},
+    i18n::tr,
+    manifest::Manifest,
+    mods::FindInModList,
+    tag::Tag,
+};
 
 pub struct FindSelectBuilder<'a, B: ListBuilder> {
     msg: Option<&'a str>,
@@ -19,7 +50,7 @@ impl<'a, B: ListBuilder> FindSelectBuilder<'a, B> {
     pub fn new(list_builder: B) -> Self {
         Self {
             msg: None,
-            list_builder,
+            list_builder: list_builder.single_line(),
             input: None,
         }
     }
@@ -33,16 +64,68 @@ impl<'a, B: ListBuilder> FindSelectBuilder<'a, B> {
         self
     }
 }
+/// A row of the mod-list in a prompt, together with the mod it shows.
+#[derive(Clone)]
+pub struct ModOption<'a> {
+    row: String,
+    manifest: &'a Manifest,
+}
+impl Display for ModOption<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.row)
+    }
+}
+impl PartialEq for ModOption<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.row == other.row && std::ptr::eq(self.manifest, other.manifest)
+    }
+}
+
+/// Besides the usual matching on the text of a row, mods can be filtered on
+/// filter expressions typed into the prompt, like 'tag:ui' or 'state:disabled'.
+fn score_mod_option(
+    input: &str,
+    option: &ModOption<'_>,
+    string_value: &str,
+    idx: usize,
+) -> Option<i64> {
+    let (filters, text) = parse_filter_input(input);
+    if !matches_all(&filters, option.manifest) {
+        None
+    } else if text.is_empty() {
+        Some(0)
+    } else {
+        Select::<ModOption<'_>>::DEFAULT_SCORER(&text, option, string_value, idx)
+    }
+}
+
+/// The filter help, with the glyphs in front of the mods; colours alone do not tell everyone
+/// what state a mod is in.
+fn mod_select_help() -> &'static str {
+    static HELP: OnceLock<String> = OnceLock::new();
+    HELP.get_or_init(|| format!("{}\n{}", tr("mod-filter-help"), Tag::short_legenda()))
+}
+
 impl<'a> FindSelectBuilder<'a, ModListBuilder<'a>> {
-    pub fn build(self) -> Result<InquireBuilder<SelectToIdx<'a, String>>> {
+    pub fn build(self) -> Result<InquireBuilder<SelectToIdx<'a, ModOption<'a>>>> {
+        // Filter expressions only select in the prompt, never a mod directly.
         let idx = self
             .input
-            .map(|input| self.list_builder.list().find_mod(input))
-            .flatten();
+            .filter(|input| parse_filter_input(input).0.is_empty())
+            .and_then(|input| self.list_builder.list().find_mod(input));
 
-        let list = self.list_builder.build()?;
+        let manifests = self.list_builder.list();
+        let list = self
+            .list_builder
+            .build()?
+            .into_iter()
+            .zip(manifests)
+            .map(|(row, manifest)| ModOption { row, manifest })
+            .collect();
 
-        let select = SelectToIdx::new(self.msg.unwrap_or_default(), list);
+        let select = SelectToIdx::new(self.msg.unwrap_or_default(), list)
+            .with_scorer(&score_mod_option)
+            .with_help_message(mod_select_help());
         let select = if let Some(input) = self.input {
             select.with_starting_filter_input(input)
         } else {
@@ -53,45 +136,42 @@ impl<'a> FindSelectBuilder<'a, ModListBuilder<'a>> {
     }
 }
 impl<'a> FindSelectBuilder<'a, FileListBuilder<'a>> {
-    pub fn build(self) -> Result<InquireBuilder<Select<'a, String>>> {
-        // let idx = self
-        //     .input
-        //     .map(|input| self.list_builder.list().find_mod(input))
-        //     .flatten();
+    pub fn build(self) -> Result<InquireBuilder<SelectToIdx<'a, String>>> {
+        let files = self.list_builder.list()?;
+        let idx = self.input.and_then(|input| {
+            files.iter().position(|f| {
+                f.source().as_str() == input
+                    || f.destination() == input
+                    || Utf8Path::new(f.destination()).file_name() == Some(input)
+            })
+        });
 
         let list = self.list_builder.build()?;
 
-        let select =
-            Select::new(self.msg.unwrap_or_default(), list).with_page_size(default_page_size());
+        let select = SelectToIdx::new(self.msg.unwrap_or_default(), list);
         let select = if let Some(input) = self.input {
             select.with_starting_filter_input(input)
         } else {
             select
         };
 
-        // Ok(InquireBuilder::new_with_test(idx, select))
-        Ok(InquireBuilder::new(select))
+        Ok(InquireBuilder::new_with_test(idx, select))
     }
 }
 impl<'a> FindSelectBuilder<'a, ArchiveListBuilder<'a>> {
-    pub fn build(self) -> Result<InquireBuilder<Select<'a, String>>> {
-        //TODO: allow for input in archive_list_builder select
-        // let idx = self
-        //     .input
-        //     .map(|input| self.list_builder.list()?.find_mod(input))
-        //     .flatten();
+    pub fn build(self) -> Result<InquireBuilder<SelectToIdx<'a, String>>> {
+        let archives = self.list_builder.list()?;
+        let idx = self.input.and_then(|input| find_archive(&archives, input));
 
         let list = self.list_builder.build()?;
 
-        let select =
-            Select::new(self.msg.unwrap_or_default(), list).with_page_size(default_page_size());
+        let select = SelectToIdx::new(self.msg.unwrap_or_default(), list);
         let select = if let Some(input) = self.input {
             select.with_starting_filter_input(input)
         } else {
             select
         };
 
-        // Ok(InquireBuilder::new_with_test(idx, select))
-        Ok(InquireBuilder::new(select))
+        Ok(InquireBuilder::new_with_test(idx, select))
     }
 }
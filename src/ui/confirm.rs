@@ -0,0 +1,59 @@
+use std::{
+    io::IsTerminal,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::Result;
+use inquire::{Confirm, Password, PasswordDisplayMode};
+
+use crate::{
+    errors::SettingErrors,
+    i18n::{tr, tr_args},
+};
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Answer every confirmation prompt with 'yes'; used for '--yes'.
+pub fn assume_yes() {
+    ASSUME_YES.store(true, Ordering::Relaxed);
+}
+
+/// Show the items `affected` by `action` and ask the user whether to continue.
+/// Without a terminal to ask on, the action is refused unless '--yes' was given.
+pub fn confirm(action: &str, affected: &[String]) -> Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(SettingErrors::NotConfirmed(action.to_owned()).into());
+    }
+
+    if !affected.is_empty() {
+        log::info!("{}", tr_args("confirm-affected", &[("action", &action)]));
+        for item in affected {
+            log::info!("  {item}");
+        }
+    }
+
+    let confirmed = Confirm::new(&tr_args("confirm-question", &[("action", &action)]))
+        .with_default(false)
+        .prompt()?;
+    if !confirmed {
+        log::info!("{}", tr("confirm-aborted"));
+    }
+    Ok(confirmed)
+}
+
+/// Show `msg` and ask for a password; `None` without a terminal to ask on, or when left empty.
+pub fn ask_password(msg: &str) -> Result<Option<String>> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    log::warn!("{msg}");
+    let password = Password::new(&tr("password-prompt"))
+        .without_confirmation()
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .prompt()?;
+    Ok(Some(password).filter(|p| !p.is_empty()))
+}
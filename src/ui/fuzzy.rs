@@ -0,0 +1,64 @@
+use std::io::IsTerminal;
+
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use inquire::Select;
+
+use crate::settings::default_page_size;
+
+// Matches scoring this or lower are too far-fetched to offer.
+const MIN_SCORE: i64 = 50;
+// At most this many matches are offered to choose from.
+const MAX_MATCHES: usize = 6;
+
+/// The indices of the `candidates` which match `pattern` reasonably well, best match first.
+fn fuzzy_matches<S: AsRef<str>>(candidates: &[S], pattern: &str) -> Vec<usize> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+
+    let mut scored = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, c)| {
+            matcher
+                .fuzzy_match(c.as_ref(), pattern)
+                .filter(|&score| score > MIN_SCORE)
+                .map(|score| (score, idx))
+        })
+        .collect::<Vec<_>>();
+    scored.sort_unstable_by(|(score_a, idx_a), (score_b, idx_b)| {
+        score_b.cmp(score_a).then(idx_a.cmp(idx_b))
+    });
+    scored.truncate(MAX_MATCHES);
+
+    scored.into_iter().map(|(_, idx)| idx).collect()
+}
+
+/// Find `pattern` among the `candidates`. When more than one matches, the user is asked
+/// to choose; without a terminal to ask on, nothing is found.
+pub fn fuzzy_find<S: AsRef<str>>(candidates: &[S], pattern: &str) -> Option<usize> {
+    let matches = fuzzy_matches(candidates, pattern);
+    match matches.as_slice() {
+        [] => None,
+        [idx] => Some(*idx),
+        _ => {
+            let options = matches
+                .iter()
+                .map(|&idx| candidates[idx].as_ref().to_owned())
+                .collect::<Vec<_>>();
+
+            if !std::io::stdin().is_terminal() {
+                log::warn!(
+                    "'{pattern}' matches multiple entries: {}; please be more specific.",
+                    options.join(", ")
+                );
+                return None;
+            }
+
+            let message = format!("Multiple matches for '{pattern}'; please choose one:");
+            Select::new(&message, options)
+                .with_page_size(default_page_size())
+                .raw_prompt()
+                .ok()
+                .map(|choice| matches[choice.index])
+        }
+    }
+}
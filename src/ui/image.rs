@@ -0,0 +1,96 @@
+use std::{
+    env,
+    fs::read,
+    io::{stdout, IsTerminal, Write},
+};
+
+use anyhow::Result;
+use camino::Utf8Path;
+
+use crate::utils::open_url;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+// Terminals which show images sent with the inline image protocol of iTerm2.
+const INLINE_IMAGE_TERMINALS: [&str; 2] = ["iTerm.app", "WezTerm"];
+
+/// How the terminal can show an image, if it can.
+enum ImageProtocol {
+    Kitty,
+    Iterm,
+}
+impl ImageProtocol {
+    fn detect() -> Option<Self> {
+        if !stdout().is_terminal() {
+            None
+        } else if env::var_os("KITTY_WINDOW_ID").is_some() {
+            Some(Self::Kitty)
+        } else if env::var("TERM_PROGRAM")
+            .is_ok_and(|t| INLINE_IMAGE_TERMINALS.contains(&t.as_str()))
+            || env::var("LC_TERMINAL").is_ok_and(|t| t == "iTerm2")
+        {
+            Some(Self::Iterm)
+        } else {
+            None
+        }
+    }
+}
+
+/// Show the image at `path` in the terminal, when it supports a protocol for it;
+/// otherwise open it with the image viewer of the user.
+pub fn show_image(path: &Utf8Path) -> Result<()> {
+    let is_png = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("png"));
+
+    match ImageProtocol::detect() {
+        // Kitty only decodes PNG itself; it reads the file, so only its path is sent.
+        Some(ImageProtocol::Kitty) if is_png => {
+            let path = path.canonicalize_utf8()?;
+            let mut out = stdout().lock();
+            writeln!(
+                out,
+                "\x1b_Ga=T,f=100,t=f;{}\x1b\\",
+                base64(path.as_str().as_bytes())
+            )?;
+            out.flush()?;
+            Ok(())
+        }
+        Some(ImageProtocol::Iterm) => {
+            let contents = read(path)?;
+            let mut out = stdout().lock();
+            writeln!(
+                out,
+                "\x1b]1337;File=inline=1;preserveAspectRatio=1;size={}:{}\x07",
+                contents.len(),
+                base64(&contents)
+            )?;
+            out.flush()?;
+            Ok(())
+        }
+        _ => open_url(path.as_str()),
+    }
+}
+
+/// `bytes` in base64, with padding, as the image protocols want.
+pub fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or_default(),
+            chunk.get(2).copied().unwrap_or_default(),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(
+                    BASE64_ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -197,6 +197,10 @@ impl<'a, T: Display + Clone> SelectToIdx<'a, T> {
         self.select = self.select.with_help_message(message);
         self
     }
+    pub fn with_scorer(mut self, scorer: inquire::type_aliases::Scorer<'a, T>) -> Self {
+        self.select = self.select.with_scorer(scorer);
+        self
+    }
 }
 impl<'a, T: Display + Clone + PartialEq> SelectToIdx<'a, T> {
     pub fn prompt(self) -> InquireResult<<Self as InquireExt>::Output> {
@@ -218,64 +222,3 @@ impl<'a, T: Display + Clone + PartialEq> InquireExt for SelectToIdx<'a, T> {
         self.prompt()
     }
 }
-
-pub struct MultiSelectToIdx<'a, T> {
-    list: Vec<T>,
-    select: inquire::MultiSelect<'a, T>,
-}
-impl<'a, T: Display + Clone> MultiSelectToIdx<'a, T> {
-    pub fn new(message: &'a str, list: Vec<T>) -> Self {
-        let select =
-            inquire::MultiSelect::new(message, list.to_vec()).with_page_size(default_page_size());
-        Self { list, select }
-    }
-    // pub fn new_with(select: inquire::Select<'a, T>, list: &'a [T]) -> Self {
-    //     Self { list, select }
-    // }
-    // pub fn with_starting_filter_input(mut self, starting_filter_input: &'a str) -> Self {
-    //     self.select = self
-    //         .select
-    //         .with_starting_filter_input(starting_filter_input);
-    //     self
-    // }
-    pub fn with_vim_mode(mut self, vim_mode: bool) -> Self {
-        self.select = self.select.with_vim_mode(vim_mode);
-        self
-    }
-    pub fn with_page_size(mut self, page_size: usize) -> Self {
-        self.select = self.select.with_page_size(page_size);
-        self
-    }
-    pub fn with_help_message(mut self, message: &'a str) -> Self {
-        self.select = self.select.with_help_message(message);
-        self
-    }
-}
-impl<'a, T: Display + Clone + PartialEq> MultiSelectToIdx<'a, T> {
-    pub fn prompt(self) -> InquireResult<<Self as InquireExt>::Output> {
-        let choice = self.select.prompt()?;
-
-        let mut idx_list = Vec::with_capacity(choice.len());
-
-        for c in choice {
-            let idx = self
-                .list
-                .iter()
-                .enumerate()
-                .find_map(|(idx, t)| (c == *t).then_some(idx))
-                .ok_or_else(|| {
-                    inquire::InquireError::Custom(Box::new(ModErrors::ModNotFound(String::new())))
-                })?;
-            idx_list.push(idx);
-        }
-
-        Ok(idx_list)
-    }
-}
-impl<'a, T: Display + Clone + PartialEq> InquireExt for MultiSelectToIdx<'a, T> {
-    type Output = Vec<usize>;
-
-    fn prompt(self) -> InquireResult<Self::Output> {
-        self.prompt()
-    }
-}
@@ -1,60 +1,200 @@
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap, fmt::Display, str::FromStr};
 
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
-use comfy_table::{Cell, Color};
+use clap::ValueEnum;
+use comfy_table::{Attribute, Cell, Color, ContentArrangement};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     commands::downloads::downloaded_files,
     conflict::{conflict_list_by_file, conflict_list_by_mod},
     decompress::SupportedArchives,
     dmodman::DmodMan,
-    manifest::Manifest,
+    i18n::tr,
+    manifest::{install_file::InstallFile, Manifest},
     mods::GatherModList,
-    settings::create_table,
+    nexus::{updates::UpdateCache, EndorseStatus},
+    settings::{create_table, symbols_only, TagColour},
     tag::Tag,
-    utils::AddExtension,
+    utils::{human_size, AddExtension},
+    version::Version,
 };
 
 pub trait ListBuilder {
     fn build(self) -> Result<Vec<String>>;
+    /// Keep every item on a single line, so the rows map one-to-one onto the items.
+    fn single_line(self) -> Self;
+}
+
+/// Optional columns of the mod-list; the name is always shown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+pub enum ModListColumn {
+    Index,
+    /// The glyph of the mod's state, see 'legenda'.
+    Glyph,
+    Priority,
+    Status,
+    Version,
+    NexusId,
+    ModType,
+    Size,
+    Tags,
+    Notes,
+    /// Whether the mod is endorsed on Nexus, as found by 'check-updates'.
+    Endorsed,
+}
+impl ModListColumn {
+    pub const DEFAULT: &'static [Self] = &[
+        Self::Index,
+        Self::Priority,
+        Self::Status,
+        Self::Version,
+        Self::NexusId,
+        Self::ModType,
+        Self::Tags,
+        Self::Notes,
+    ];
+}
+impl Display for ModListColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_possible_value()
+            .map_or(Ok(()), |v| f.write_str(v.get_name()))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ModListSortKey {
+    Index,
+    Name,
+    Priority,
+    Status,
+    Version,
+    NexusId,
+    ModType,
+    Size,
+}
+
+/// Show the mod-list in groups; mods with several tags are shown in each of their groups.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ModListGroup {
+    Tag,
+    ModType,
+    Status,
+}
+impl ModListGroup {
+    fn groups_of(self, md: &Manifest) -> Vec<String> {
+        match self {
+            Self::Tag if md.tags().is_empty() => vec![tr("untagged")],
+            Self::Tag => md.tags().to_vec(),
+            Self::ModType => vec![md.kind().to_string()],
+            Self::Status => vec![md.mod_state().to_string()],
+        }
+    }
+}
+
+/// Sort order of the mod-list, parsed from '<column>[:asc|:desc]'.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ModListSort {
+    key: ModListSortKey,
+    descending: bool,
+}
+impl FromStr for ModListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, order) = s.split_once(':').unwrap_or((s, "asc"));
+        let key = ModListSortKey::from_str(key, true)?;
+        let descending = match order.to_lowercase().as_str() {
+            "asc" => false,
+            "desc" => true,
+            order => return Err(format!("invalid sort order '{order}', use 'asc' or 'desc'")),
+        };
+        Ok(Self { key, descending })
+    }
+}
+impl ModListSort {
+    fn compare(self, a: (usize, &Manifest), b: (usize, &Manifest), sizes: &[u64]) -> Ordering {
+        let (ia, ma) = a;
+        let (ib, mb) = b;
+        let o = match self.key {
+            ModListSortKey::Index => ia.cmp(&ib),
+            ModListSortKey::Name => ma.name().to_lowercase().cmp(&mb.name().to_lowercase()),
+            ModListSortKey::Priority => ma.priority().cmp(&mb.priority()),
+            ModListSortKey::Status => ma.mod_state().to_string().cmp(&mb.mod_state().to_string()),
+            ModListSortKey::Version => {
+                Version::from(ma.version()).cmp(&Version::from(mb.version()))
+            }
+            ModListSortKey::NexusId => ma.nexus_id().cmp(&mb.nexus_id()),
+            ModListSortKey::ModType => ma.kind().to_string().cmp(&mb.kind().to_string()),
+            ModListSortKey::Size => sizes[ia].cmp(&sizes[ib]),
+        }
+        // Keep the mod-list order for equal keys.
+        .then_with(|| ia.cmp(&ib));
+
+        if self.descending {
+            o.reverse()
+        } else {
+            o
+        }
+    }
 }
 
 pub struct ModListBuilder<'a> {
     list: &'a [Manifest],
-    download_dir: Option<Utf8PathBuf>,
+    cache_dir: Option<Utf8PathBuf>,
     with_index: bool,
+    with_glyph: bool,
     with_priority: bool,
     with_status: bool,
     with_version: bool,
     with_nexus_id: bool,
     with_mod_type: bool,
+    with_size: bool,
     with_tags: bool,
     with_notes: bool,
+    with_endorsed: bool,
     with_colour: bool,
     with_headers: bool,
+    sort: Option<ModListSort>,
+    group_by: Option<ModListGroup>,
+    tag_colours: &'a [TagColour],
+    single_line: bool,
 }
 impl<'a> ModListBuilder<'a> {
     pub fn new(list: &'a [Manifest]) -> Self {
         Self {
             list,
             with_index: false,
+            with_glyph: false,
             with_priority: false,
             with_status: false,
             with_version: false,
             with_nexus_id: false,
             with_mod_type: false,
+            with_size: false,
             with_tags: false,
             with_notes: false,
+            with_endorsed: false,
             with_colour: false,
             with_headers: false,
-            download_dir: None,
+            cache_dir: None,
+            sort: None,
+            group_by: None,
+            tag_colours: &[],
+            single_line: false,
         }
     }
     pub fn with_index(mut self) -> Self {
         self.with_index = true;
         self
     }
+    /// Show the glyph of the tag of each mod, so its state does not depend on colours alone;
+    /// always shown in the 'symbols-only' mode.
+    pub const fn with_glyph(mut self) -> Self {
+        self.with_glyph = true;
+        self
+    }
     pub fn with_priority(mut self) -> Self {
         self.with_priority = true;
         self
@@ -75,13 +215,24 @@ impl<'a> ModListBuilder<'a> {
         self.with_mod_type = true;
         self
     }
+    pub fn with_size(mut self) -> Self {
+        self.with_size = true;
+        self
+    }
     pub fn with_tags(mut self) -> Self {
         self.with_tags = true;
         self
     }
-    pub fn with_notes(mut self, download_dir: &Utf8Path) -> Self {
+    /// Show which mods have an update, as found by `check-updates`; see `UpdateCache`.
+    pub fn with_notes(mut self, cache_dir: &Utf8Path) -> Self {
         self.with_notes = true;
-        self.download_dir = Some(download_dir.to_owned());
+        self.cache_dir = Some(cache_dir.to_owned());
+        self
+    }
+    /// Show whether mods are endorsed, as found by `check-updates`; see `UpdateCache`.
+    pub fn with_endorsed(mut self, cache_dir: &Utf8Path) -> Self {
+        self.with_endorsed = true;
+        self.cache_dir = Some(cache_dir.to_owned());
         self
     }
     pub fn with_colour(mut self) -> Self {
@@ -92,41 +243,87 @@ impl<'a> ModListBuilder<'a> {
         self.with_headers = true;
         self
     }
-    pub fn list(&self) -> &[Manifest] {
+    pub fn with_columns(self, columns: &[ModListColumn], cache_dir: &Utf8Path) -> Self {
+        columns.iter().fold(self, |builder, column| match column {
+            ModListColumn::Index => builder.with_index(),
+            ModListColumn::Glyph => builder.with_glyph(),
+            ModListColumn::Priority => builder.with_priority(),
+            ModListColumn::Status => builder.with_status(),
+            ModListColumn::Version => builder.with_version(),
+            ModListColumn::NexusId => builder.with_nexus_id(),
+            ModListColumn::ModType => builder.with_mod_type(),
+            ModListColumn::Size => builder.with_size(),
+            ModListColumn::Tags => builder.with_tags(),
+            ModListColumn::Notes => builder.with_notes(cache_dir),
+            ModListColumn::Endorsed => builder.with_endorsed(cache_dir),
+        })
+    }
+    pub fn with_sort(mut self, sort: Option<ModListSort>) -> Self {
+        self.sort = sort;
+        self
+    }
+    pub fn with_group_by(mut self, group_by: Option<ModListGroup>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+    /// Colour mods by their first tag with a colour, unless they are coloured by a conflict.
+    pub fn with_tag_colours(mut self, tag_colours: &'a [TagColour]) -> Self {
+        self.tag_colours = tag_colours;
+        self
+    }
+    pub fn list(&self) -> &'a [Manifest] {
         self.list
     }
+    fn tag_colour(&self, md: &Manifest) -> Option<Color> {
+        md.tags().iter().find_map(|tag| {
+            self.tag_colours
+                .iter()
+                .find(|tc| tc.tag() == tag)
+                .map(TagColour::colour)
+        })
+    }
     pub fn build(self) -> Result<Vec<String>> {
         log::trace!("Building Mod List");
 
         let conflict_list = conflict_list_by_mod(self.list)?;
         let file_conflist_list = conflict_list_by_file(self.list)?;
+        let with_glyph = self.with_glyph || symbols_only();
 
         let headers = if self.with_headers {
             let mut headers = Vec::new();
             if self.with_index {
-                headers.push("Index");
+                headers.push(tr("column-index"));
+            }
+            if with_glyph {
+                headers.push(tr("column-tag"));
             }
-            headers.push("Name");
+            headers.push(tr("column-name"));
             if self.with_priority {
-                headers.push("Priority");
+                headers.push(tr("column-priority"));
             }
             if self.with_status {
-                headers.push("Status");
+                headers.push(tr("column-status"));
             }
             if self.with_version {
-                headers.push("Version");
+                headers.push(tr("column-version"));
             }
             if self.with_nexus_id {
-                headers.push("Nexus Id");
+                headers.push(tr("column-nexus-id"));
             }
             if self.with_mod_type {
-                headers.push("Mod Type");
+                headers.push(tr("column-mod-type"));
+            }
+            if self.with_size {
+                headers.push(tr("column-size"));
             }
             if self.with_tags {
-                headers.push("Tags");
+                headers.push(tr("column-tags"));
             }
             if self.with_notes {
-                headers.push("Notes");
+                headers.push(tr("column-notes"));
+            }
+            if self.with_endorsed {
+                headers.push(tr("column-endorsed"));
             }
             headers
         } else {
@@ -134,101 +331,180 @@ impl<'a> ModListBuilder<'a> {
         };
 
         let mut table = create_table(headers);
+        if self.single_line {
+            table.set_content_arrangement(ContentArrangement::Disabled);
+        }
 
-        let dmodman_list = if self.with_notes {
-            DmodMan::gather_list(&self.download_dir.unwrap())?
+        let updates = self
+            .cache_dir
+            .as_deref()
+            .map(UpdateCache::read)
+            .unwrap_or_default();
+
+        let sizes = if self.with_size || self.sort.is_some_and(|s| s.key == ModListSortKey::Size) {
+            self.list
+                .iter()
+                .map(Manifest::size)
+                .collect::<Result<Vec<_>>>()?
         } else {
             vec![]
         };
 
-        for (idx, m) in self.list.iter().enumerate() {
-            let mut row = Vec::new();
-
-            let is_loser = conflict_list
-                .get(&m.name().to_string())
-                .is_some_and(|c| !c.losing_to().is_empty());
-            let is_winner = conflict_list
-                .get(&m.name().to_string())
-                .is_some_and(|c| !c.winning_over().is_empty());
-
-            // Detect if we all files of this manifest are overwritten by other mods
-            let tag = Tag::from((is_loser, is_winner));
-            let tag = if is_loser {
-                let mut file_not_lost = false;
-
-                for f in m.dest_files()? {
-                    if let Some(contenders) = file_conflist_list.get(&f) {
-                        if let Some(c) = contenders.last() {
-                            if c == m.name() {
-                                file_not_lost = true;
-                            }
-                        }
+        let mut order = self.list.iter().enumerate().collect::<Vec<_>>();
+        if let Some(sort) = self.sort {
+            order.sort_by(|a, b| sort.compare(*a, *b, &sizes));
+        }
+
+        // Without grouping, all mods are in a single group without a header.
+        let groups = if let Some(group_by) = self.group_by {
+            let mut groups = Vec::<(Option<String>, Vec<(usize, &Manifest)>)>::new();
+            for (idx, m) in &order {
+                for group in group_by.groups_of(m) {
+                    if let Some((_, mods)) =
+                        groups.iter_mut().find(|(g, _)| g.as_ref() == Some(&group))
+                    {
+                        mods.push((*idx, m));
                     } else {
-                        file_not_lost = true;
+                        groups.push((Some(group), vec![(*idx, m)]));
                     }
                 }
+            }
+            groups.sort_by(|(a, _), (b, _)| a.cmp(b));
+            groups
+        } else {
+            vec![(None, order)]
+        };
 
-                if file_not_lost {
-                    tag
+        for (group, order) in groups {
+            if let Some(group) = group {
+                let attribute = if self.with_colour {
+                    Attribute::Bold
                 } else {
-                    Tag::CompleteLoser
-                }
-            } else {
-                tag
-            };
-            let tag = if m.is_enabled() { tag } else { Tag::Disabled };
+                    Attribute::NormalIntensity
+                };
+                let mut row =
+                    vec![Cell::new(""); usize::from(self.with_index) + usize::from(with_glyph)];
+                row.push(Cell::new(group).add_attribute(attribute));
+                table.add_row(row);
+            }
+            for (idx, m) in order {
+                let mut row = Vec::new();
+
+                let is_loser = conflict_list
+                    .get(&m.name().to_string())
+                    .is_some_and(|c| !c.losing_to().is_empty());
+                let is_winner = conflict_list
+                    .get(&m.name().to_string())
+                    .is_some_and(|c| !c.winning_over().is_empty());
+
+                // Detect if we all files of this manifest are overwritten by other mods
+                let tag = Tag::from((is_loser, is_winner));
+                let tag = if is_loser {
+                    let mut file_not_lost = false;
+
+                    for f in m.dest_files()? {
+                        if let Some(contenders) = file_conflist_list.get(&f) {
+                            if let Some(c) = contenders.last() {
+                                if c == m.name() {
+                                    file_not_lost = true;
+                                }
+                            }
+                        } else {
+                            file_not_lost = true;
+                        }
+                    }
 
-            let (color, idx_color) = if self.with_colour {
-                let color = Color::from(tag);
-                if color == Color::White {
-                    (color, Color::Reset)
+                    if file_not_lost {
+                        tag
+                    } else {
+                        Tag::CompleteLoser
+                    }
                 } else {
-                    (color, color)
-                }
-            } else {
-                (Color::Reset, Color::Reset)
-            };
+                    tag
+                };
+                let tag = if m.is_enabled() {
+                    tag
+                } else if m.mod_state().is_pinned() {
+                    Tag::Pinned
+                } else {
+                    Tag::Disabled
+                };
 
-            if self.with_index {
-                row.push(Cell::new(idx.to_string()).fg(idx_color));
-            }
-            row.push(Cell::new(m.name().to_string()).fg(color));
-            if self.with_priority {
-                row.push(Cell::new(m.priority().to_string()).fg(color));
-            }
-            if self.with_status {
-                row.push(Cell::new(m.mod_state().to_string()).fg(color));
-            }
-            if self.with_version {
-                row.push(Cell::new(m.version().unwrap_or("<Unknown>").to_string()).fg(color));
-            }
-            if self.with_nexus_id {
-                row.push(
-                    Cell::new(
-                        m.nexus_id()
-                            .map_or("<Unknown>".to_owned(), |nid| nid.to_string()),
-                    )
-                    .fg(color),
-                );
-            }
-            if self.with_mod_type {
-                row.push(Cell::new(m.kind().to_string()).fg(color));
-            }
-            if self.with_tags {
-                row.push(Cell::new(format!("{}", m.tags().join(","))));
-            }
-            if self.with_notes {
-                let notes = {
-                    if dmodman_list.iter().any(|dmod| m.is_an_update(dmod)) {
-                        "Update Available"
+                let tag_colour = if self.with_colour {
+                    self.tag_colour(m)
+                } else {
+                    None
+                };
+                let (color, idx_color) = if self.with_colour {
+                    let color = Color::from(tag);
+                    if color == Color::White {
+                        (tag_colour.unwrap_or(color), Color::Reset)
                     } else {
-                        ""
+                        (color, color)
                     }
+                } else {
+                    (Color::Reset, Color::Reset)
                 };
-                row.push(Cell::new(notes));
-            }
 
-            table.add_row(row);
+                if self.with_index {
+                    row.push(Cell::new(idx.to_string()).fg(idx_color));
+                }
+                if with_glyph {
+                    row.push(Cell::new(char::from(tag)).fg(color));
+                }
+                row.push(Cell::new(m.name().to_string()).fg(color));
+                if self.with_priority {
+                    row.push(Cell::new(m.priority().to_string()).fg(color));
+                }
+                if self.with_status {
+                    row.push(Cell::new(m.mod_state().to_string()).fg(color));
+                }
+                if self.with_version {
+                    row.push(
+                        Cell::new(m.version().map_or_else(|| tr("unknown"), ToOwned::to_owned))
+                            .fg(color),
+                    );
+                }
+                if self.with_nexus_id {
+                    row.push(
+                        Cell::new(
+                            m.nexus_id()
+                                .map_or_else(|| tr("unknown"), |nid| nid.to_string()),
+                        )
+                        .fg(color),
+                    );
+                }
+                if self.with_mod_type {
+                    row.push(Cell::new(m.kind().to_string()).fg(color));
+                }
+                if self.with_size {
+                    row.push(Cell::new(human_size(sizes[idx])).fg(color));
+                }
+                if self.with_tags {
+                    row.push(
+                        Cell::new(format!("{}", m.tags().join(",")))
+                            .fg(tag_colour.unwrap_or(Color::Reset)),
+                    );
+                }
+                if self.with_notes {
+                    let notes = if updates.update_of(m).is_some() {
+                        tr("update-available")
+                    } else {
+                        String::new()
+                    };
+                    row.push(Cell::new(notes));
+                }
+                if self.with_endorsed {
+                    let endorsed = match m.nexus_id().map(|id| updates.endorsement(id)) {
+                        Some(EndorseStatus::Endorsed) => tr("endorsed"),
+                        Some(EndorseStatus::Abstained) => tr("abstained"),
+                        Some(EndorseStatus::Undecided) | None => String::new(),
+                    };
+                    row.push(Cell::new(endorsed).fg(color));
+                }
+
+                table.add_row(row);
+            }
         }
 
         let skip = if self.with_headers { 0 } else { 1 };
@@ -241,6 +517,10 @@ impl<'a> ListBuilder for ModListBuilder<'a> {
     fn build(self) -> Result<Vec<String>> {
         self.build()
     }
+    fn single_line(mut self) -> Self {
+        self.single_line = true;
+        self
+    }
 }
 
 pub struct FileListBuilder<'a> {
@@ -250,6 +530,7 @@ pub struct FileListBuilder<'a> {
     with_origin: bool,
     with_headers: bool,
     with_colour: bool,
+    single_line: bool,
 }
 impl<'a> FileListBuilder<'a> {
     pub fn new(manifest: &'a Manifest) -> Self {
@@ -260,6 +541,7 @@ impl<'a> FileListBuilder<'a> {
             with_origin: false,
             with_headers: false,
             with_colour: false,
+            single_line: false,
         }
     }
     pub fn disabled_files(mut self) -> Self {
@@ -278,6 +560,14 @@ impl<'a> FileListBuilder<'a> {
         self.with_headers = true;
         self
     }
+    /// The files shown, in the order of the rows.
+    pub fn list(&self) -> Result<Vec<InstallFile>> {
+        if self.disabled_files {
+            Ok(self.manifest.disabled_files())
+        } else {
+            self.manifest.files()
+        }
+    }
     pub fn build(self) -> Result<Vec<String>> {
         let headers = if self.with_headers {
             let mut headers = Vec::new();
@@ -294,12 +584,11 @@ impl<'a> FileListBuilder<'a> {
         };
 
         let mut table = create_table(headers);
+        if self.single_line {
+            table.set_content_arrangement(ContentArrangement::Disabled);
+        }
 
-        let files = if self.disabled_files {
-            self.manifest.disabled_files()
-        } else {
-            self.manifest.files()?
-        };
+        let files = self.list()?;
 
         for (idx, isf) in files.iter().enumerate() {
             let color = Color::White;
@@ -325,6 +614,10 @@ impl<'a> ListBuilder for FileListBuilder<'a> {
     fn build(self) -> Result<Vec<String>> {
         self.build()
     }
+    fn single_line(mut self) -> Self {
+        self.single_line = true;
+        self
+    }
 }
 
 pub struct ArchiveListBuilder<'a> {
@@ -334,6 +627,7 @@ pub struct ArchiveListBuilder<'a> {
     with_status: bool,
     with_headers: bool,
     with_colour: bool,
+    single_line: bool,
 }
 impl<'a> ArchiveListBuilder<'a> {
     pub fn new(download_dir: &'a Utf8Path, cache_dir: &'a Utf8Path) -> Self {
@@ -344,6 +638,7 @@ impl<'a> ArchiveListBuilder<'a> {
             with_status: false,
             with_headers: false,
             with_colour: false,
+            single_line: false,
         }
     }
     pub fn with_index(mut self) -> Self {
@@ -388,6 +683,9 @@ impl<'a> ArchiveListBuilder<'a> {
         };
 
         let mut table = create_table(headers);
+        if self.single_line {
+            table.set_content_arrangement(ContentArrangement::Disabled);
+        }
 
         for (idx, (_, f)) in sf.iter().enumerate() {
             let dmodman = DmodMan::try_from(self.download_dir.join(&f).add_extension("json")).ok();
@@ -453,4 +751,8 @@ impl<'a> ListBuilder for ArchiveListBuilder<'a> {
     fn build(self) -> Result<Vec<String>> {
         self.build()
     }
+    fn single_line(mut self) -> Self {
+        self.single_line = true;
+        self
+    }
 }
@@ -0,0 +1,27 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use notify_rust::Notification;
+
+static NOTIFICATIONS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Show desktop notifications when long operations finish; used for the 'notifications' setting.
+pub fn enable_notifications() {
+    NOTIFICATIONS_ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Show a desktop notification, when they are enabled; a missing notification daemon is
+/// no reason to fail the operation that finished.
+pub fn notify(summary: &str, body: &str) {
+    if !NOTIFICATIONS_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    if let Err(e) = Notification::new()
+        .appname("starmod")
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        log::debug!("Unable to show a notification: {e}");
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    env,
+    io::{ErrorKind, IsTerminal, Write},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::Result;
+
+const PAGER_ENV: &str = "PAGER";
+const LESS_ENV: &str = "LESS";
+const DEFAULT_PAGER: &str = "less -R";
+// Quit when the output fits, keep the colours and do not clear the screen.
+const DEFAULT_LESS_FLAGS: &str = "FRX";
+
+static PAGER_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Never page output; used for '--no-pager'.
+pub fn disable_pager() {
+    PAGER_ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Show `content` to the user; when it does not fit on the terminal,
+/// run it through '$PAGER' (or 'less -R') instead of dumping it to stdout.
+pub fn page(content: &str) {
+    if should_page(content) {
+        match run_pager(content) {
+            Ok(()) => return,
+            Err(e) => log::debug!("Unable to run pager: {e}"),
+        }
+    }
+    log::info!("{content}");
+}
+
+fn should_page(content: &str) -> bool {
+    PAGER_ENABLED.load(Ordering::Relaxed)
+        && std::io::stdout().is_terminal()
+        && term_size::dimensions_stdout()
+            .is_some_and(|(_, height)| content.lines().count() >= height)
+}
+
+fn run_pager(content: &str) -> Result<()> {
+    let pager = env::var(PAGER_ENV)
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_owned());
+
+    let mut args = pager.split_whitespace();
+    let mut cmd = Command::new(args.next().unwrap_or(DEFAULT_PAGER));
+    cmd.args(args).stdin(Stdio::piped());
+    if env::var_os(LESS_ENV).is_none() {
+        cmd.env(LESS_ENV, DEFAULT_LESS_FLAGS);
+    }
+
+    let mut child = cmd.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything.
+        if let Err(e) = writeln!(stdin, "{content}") {
+            if e.kind() != ErrorKind::BrokenPipe {
+                return Err(e.into());
+            }
+        }
+    }
+    child.wait()?;
+
+    Ok(())
+}
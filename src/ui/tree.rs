@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use comfy_table::{Cell, Color};
+
+use crate::settings::create_table;
+
+#[derive(Debug, Default)]
+struct Node {
+    children: BTreeMap<String, Self>,
+    // Attribution and colour of a file; directories have none.
+    file: Option<(String, Color)>,
+}
+impl Node {
+    fn file_count(&self) -> usize {
+        usize::from(self.file.is_some())
+            + self.children.values().map(Self::file_count).sum::<usize>()
+    }
+    fn labels<'a>(&'a self, labels: &mut BTreeSet<&'a str>, colors: &mut Vec<Color>) {
+        if let Some((label, color)) = &self.file {
+            labels.insert(label);
+            if !colors.contains(color) {
+                colors.push(*color);
+            }
+        }
+        for child in self.children.values() {
+            child.labels(labels, colors);
+        }
+    }
+}
+
+/// Renders a list of destination paths as a directory tree, with every node
+/// attributed to the mod(s) providing it.
+///
+/// Directories deeper than `depth` are collapsed into a single line,
+/// showing the number of files and every mod they contain.
+#[derive(Debug, Default)]
+pub struct FileTreeBuilder {
+    root: Node,
+    depth: Option<usize>,
+}
+impl FileTreeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_depth(mut self, depth: Option<usize>) -> Self {
+        self.depth = depth;
+        self
+    }
+    pub fn insert(&mut self, path: &str, label: impl Into<String>, color: Color) {
+        let node = path
+            .split('/')
+            .filter(|p| !p.is_empty())
+            .fold(&mut self.root, |node, part| {
+                node.children.entry(part.to_owned()).or_default()
+            });
+        node.file = Some((label.into(), color));
+    }
+    pub fn build(self) -> Vec<String> {
+        let mut table = create_table(vec!["File", "Mod"]);
+        Self::add_rows(&mut table, &self.root, "", 0, self.depth);
+
+        table.add_row_if(|idx, _row| idx.eq(&0), vec![Cell::new("No files found.")]);
+
+        table.lines().collect()
+    }
+
+    fn add_rows(
+        table: &mut comfy_table::Table,
+        node: &Node,
+        prefix: &str,
+        level: usize,
+        depth: Option<usize>,
+    ) {
+        let len = node.children.len();
+        for (idx, (name, child)) in node.children.iter().enumerate() {
+            let last = idx + 1 == len;
+            let branch = if last { "└── " } else { "├── " };
+            let indent = if last { "    " } else { "│   " };
+
+            if let Some((label, color)) = &child.file {
+                table.add_row(vec![
+                    Cell::new(format!("{prefix}{branch}{name}")).fg(*color),
+                    Cell::new(label).fg(*color),
+                ]);
+            }
+            if child.children.is_empty() {
+                continue;
+            }
+
+            if depth.is_some_and(|d| level >= d) {
+                let mut labels = BTreeSet::new();
+                let mut colors = Vec::new();
+                child.labels(&mut labels, &mut colors);
+
+                let color = match colors.as_slice() {
+                    [color] => *color,
+                    _ => Color::White,
+                };
+                table.add_row(vec![
+                    Cell::new(format!(
+                        "{prefix}{branch}{name}/ ({} files)",
+                        child.file_count()
+                    ))
+                    .fg(color),
+                    Cell::new(labels.into_iter().collect::<Vec<_>>().join(", ")).fg(color),
+                ]);
+            } else {
+                table.add_row(vec![Cell::new(format!("{prefix}{branch}{name}/"))]);
+                Self::add_rows(table, child, &format!("{prefix}{indent}"), level + 1, depth);
+            }
+        }
+    }
+}
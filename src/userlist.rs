@@ -0,0 +1,153 @@
+use std::fs::{create_dir_all, read_to_string, write};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use serde_yaml::{Mapping, Value};
+
+const PLUGINS_KEY: &str = "plugins";
+const GROUPS_KEY: &str = "groups";
+const NAME_KEY: &str = "name";
+const AFTER_KEY: &str = "after";
+const GROUP_KEY: &str = "group";
+
+/// A sorting rule of a single plugin.
+pub struct Rule<'a> {
+    pub plugin: &'a str,
+    pub after: Vec<&'a str>,
+    pub group: Option<&'a str>,
+}
+
+/// LOOT's 'userlist.yaml', which holds the sorting rules added by the user.
+///
+/// Only the 'after' and 'group' rules are handled; everything else in the file is kept as is.
+#[derive(Clone, Debug, Default)]
+pub struct UserList {
+    root: Mapping,
+}
+impl UserList {
+    /// Read the userlist at `path`; a missing file is an empty userlist.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be read, or is not a userlist.
+    pub fn read(path: &Utf8Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let root = match serde_yaml::from_str(&read_to_string(path)?)? {
+            Value::Mapping(root) => root,
+            _ => Mapping::new(),
+        };
+        Ok(Self { root })
+    }
+    /// Write the userlist to `path`, creating its directory.
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be written.
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        write(path, serde_yaml::to_string(&self.root)?)?;
+        Ok(())
+    }
+    pub fn rules(&self) -> Vec<Rule<'_>> {
+        entries(&self.root, PLUGINS_KEY)
+            .filter_map(|entry| {
+                Some(Rule {
+                    plugin: entry.get(NAME_KEY)?.as_str()?,
+                    after: entry
+                        .get(AFTER_KEY)
+                        .and_then(Value::as_sequence)
+                        .map(|a| a.iter().filter_map(after_name).collect())
+                        .unwrap_or_default(),
+                    group: entry.get(GROUP_KEY).and_then(Value::as_str),
+                })
+            })
+            .collect()
+    }
+    /// Make `plugin` load after all plugins in `after`, and put it in `group`, if given.
+    pub fn add_rule(&mut self, plugin: &str, after: &[String], group: Option<&str>) {
+        let entry = entry_mut(&mut self.root, PLUGINS_KEY, plugin);
+
+        if !after.is_empty() {
+            let list = entry
+                .entry(AFTER_KEY.into())
+                .or_insert_with(|| Value::Sequence(Vec::new()));
+            if let Value::Sequence(list) = list {
+                for other in after {
+                    if !list
+                        .iter()
+                        .filter_map(after_name)
+                        .any(|a| a.eq_ignore_ascii_case(other))
+                    {
+                        list.push(other.as_str().into());
+                    }
+                }
+            }
+        }
+
+        if let Some(group) = group {
+            entry.insert(GROUP_KEY.into(), group.into());
+            // LOOT refuses groups it does not know; those of the masterlist may be redefined.
+            entry_mut(&mut self.root, GROUPS_KEY, group);
+        }
+    }
+    /// Remove all rules of `plugin`; returns false if it had none.
+    pub fn remove_rules(&mut self, plugin: &str) -> bool {
+        let Some(Value::Sequence(plugins)) = self.root.get_mut(PLUGINS_KEY) else {
+            return false;
+        };
+        let len = plugins.len();
+        plugins.retain(|p| !has_name(p, plugin));
+        len != plugins.len()
+    }
+}
+
+fn entries<'a>(root: &'a Mapping, key: &str) -> impl Iterator<Item = &'a Value> {
+    root.get(key)
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+}
+
+/// The entry named `name` in list `key`, which is created when needed.
+fn entry_mut<'a>(root: &'a mut Mapping, key: &str, name: &str) -> &'a mut Mapping {
+    let list = root
+        .entry(key.into())
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    if !list.is_sequence() {
+        *list = Value::Sequence(Vec::new());
+    }
+    let list = list
+        .as_sequence_mut()
+        .expect("replaced by a sequence above");
+
+    let idx = list
+        .iter()
+        .position(|e| has_name(e, name))
+        .unwrap_or_else(|| {
+            let mut entry = Mapping::new();
+            entry.insert(NAME_KEY.into(), name.into());
+            list.push(Value::Mapping(entry));
+            list.len() - 1
+        });
+    list[idx]
+        .as_mapping_mut()
+        .expect("only mappings have a name")
+}
+
+fn has_name(entry: &Value, name: &str) -> bool {
+    entry
+        .get(NAME_KEY)
+        .and_then(Value::as_str)
+        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// Entries of 'after' are either a plain name, or a mapping with a name and a condition.
+fn after_name(after: &Value) -> Option<&str> {
+    after
+        .as_str()
+        .or_else(|| after.get(NAME_KEY).and_then(Value::as_str))
+}
@@ -1,7 +1,18 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    process::{Command, Stdio},
+    thread,
+};
+
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
 use walkdir::WalkDir;
 
+use crate::{errors::GameErrors, settings::Settings};
+
+const EDITOR_FILE_PLACEHOLDER: &str = "{}";
+const OPEN_PROGRAM: &str = "xdg-open";
+
 pub trait AddExtension {
     fn add_extension(&self, extension: impl AsRef<str>) -> Utf8PathBuf;
 }
@@ -21,6 +32,150 @@ impl AddExtension for Utf8PathBuf {
     }
 }
 
+/// Run `command`, logging its output while it runs; fails when it exits unsuccessfully.
+/// With `detach`, it is only started, without its output.
+///
+/// # Errors
+///
+/// When `command` cannot be started, or exits unsuccessfully.
+pub fn run_process(mut command: Command, detach: bool) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+
+    if detach {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        log::info!("Started '{program}' in the background.");
+        return Ok(());
+    }
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    thread::scope(|s| {
+        if let Some(stdout) = stdout {
+            s.spawn(|| log_lines(stdout, log::Level::Info));
+        }
+        // Wine and proton are very chatty on stderr.
+        if let Some(stderr) = stderr {
+            s.spawn(|| log_lines(stderr, log::Level::Debug));
+        }
+    });
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(GameErrors::ProcessFailed(program, status.to_string()).into())
+    }
+}
+
+fn log_lines(output: impl Read, level: log::Level) {
+    for line in BufReader::new(output).lines().map_while(Result::ok) {
+        log::log!(level, "{line}");
+    }
+}
+
+/// Open `url` in the browser of the user.
+///
+/// # Errors
+///
+/// When the browser cannot be started.
+pub fn open_url(url: &str) -> Result<()> {
+    log::info!("Opening {url}");
+    let mut command = Command::new(OPEN_PROGRAM);
+    command.arg(url);
+    run_process(command, false)
+}
+
+/// Quote `s` for a POSIX shell.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Open `files` in the editor configured for their extension; files which share an
+/// editor are opened together, unless the editor takes a single file through '{}'.
+///
+/// # Errors
+///
+/// When an editor cannot be started.
+pub fn edit_files(settings: &Settings, files: &[Utf8PathBuf]) -> Result<()> {
+    let mut by_editor = Vec::<(String, Vec<&Utf8PathBuf>)>::new();
+    for f in files {
+        let editor = settings.editor_for(f);
+        if let Some((_, files)) = by_editor.iter_mut().find(|(e, _)| *e == editor) {
+            files.push(f);
+        } else {
+            by_editor.push((editor, vec![f]));
+        }
+    }
+
+    for (editor, files) in by_editor {
+        let mut parts = editor.split_whitespace();
+        let Some(program) = parts.next() else {
+            continue;
+        };
+        let args = parts.collect::<Vec<_>>();
+
+        if args.contains(&EDITOR_FILE_PLACEHOLDER) {
+            for f in files {
+                let mut editor_cmd = Command::new(program);
+                editor_cmd.args(args.iter().map(|a| {
+                    if *a == EDITOR_FILE_PLACEHOLDER {
+                        f.as_str()
+                    } else {
+                        a
+                    }
+                }));
+                run_editor(editor_cmd)?;
+            }
+        } else {
+            let mut editor_cmd = Command::new(program);
+            editor_cmd.args(args).args(files);
+            run_editor(editor_cmd)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_editor(mut editor_cmd: Command) -> Result<()> {
+    log::info!("Running '{editor_cmd:?}'");
+
+    let status = editor_cmd.spawn()?.wait()?;
+    if !status.success() {
+        log::info!("Editor failed with exit status: {status}");
+    }
+    Ok(())
+}
+
+/// Lower-case the names of everything below `path`.
+///
+/// # Errors
+///
+/// When a directory cannot be read, or a file cannot be renamed.
 pub fn rename_recursive(path: &Utf8Path) -> Result<()> {
     let walker = WalkDir::new(path)
         .min_depth(1)
@@ -41,6 +196,15 @@ pub fn rename_recursive(path: &Utf8Path) -> Result<()> {
     Ok(())
 }
 
+/// Lower-case the name of `path`.
+///
+/// # Errors
+///
+/// When it cannot be renamed.
+///
+/// # Panics
+///
+/// When `path` has no name, like '/' and '..'.
 pub fn lower_case(path: &Utf8Path) -> Result<()> {
     let name = path.file_name().unwrap();
     let name = name.to_lowercase();
@@ -0,0 +1,147 @@
+//! Minimal editing of Valve's text key-values files, like Steam's `localconfig.vdf`.
+//! Everything which is not changed is kept byte for byte.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Str(&'a str),
+    Open,
+    Close,
+}
+
+/// Tokens of `contents`, with the byte offsets of their start and end.
+fn tokenize(contents: &str) -> Vec<(Token<'_>, usize, usize)> {
+    let bytes = contents.as_bytes();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'{' => tokens.push((Token::Open, idx, idx + 1)),
+            b'}' => tokens.push((Token::Close, idx, idx + 1)),
+            b'/' if bytes.get(idx + 1) == Some(&b'/') => {
+                while idx < bytes.len() && bytes[idx] != b'\n' {
+                    idx += 1;
+                }
+            }
+            b'"' => {
+                let start = idx;
+                idx += 1;
+                while idx < bytes.len() && bytes[idx] != b'"' {
+                    if bytes[idx] == b'\\' {
+                        idx += 1;
+                    }
+                    idx += 1;
+                }
+                let end = (idx + 1).min(bytes.len());
+                tokens.push((
+                    Token::Str(&contents[start + 1..idx.min(bytes.len())]),
+                    start,
+                    end,
+                ));
+            }
+            _ => (),
+        }
+        idx += 1;
+    }
+    tokens
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Set the `LaunchOptions` of `app_id` in a Steam `localconfig.vdf`.
+/// Returns `None` when the file has no settings for the app.
+pub fn set_launch_options(contents: &str, app_id: u32, options: &str) -> Option<String> {
+    const APPS_KEY: &str = "apps";
+    const LAUNCH_OPTIONS_KEY: &str = "LaunchOptions";
+
+    let tokens = tokenize(contents);
+    let app_id = app_id.to_string();
+
+    // The key of every open block, from the root.
+    let mut path = Vec::new();
+    let mut app_block = None;
+    for (idx, (token, _, _)) in tokens.iter().enumerate() {
+        match token {
+            Token::Open => {
+                let key = match idx.checked_sub(1).map(|i| tokens[i].0) {
+                    Some(Token::Str(key)) => key,
+                    _ => "",
+                };
+                path.push(key);
+                if path.len() >= 2
+                    && path[path.len() - 2].eq_ignore_ascii_case(APPS_KEY)
+                    && path[path.len() - 1] == app_id
+                {
+                    app_block = Some(idx);
+                    break;
+                }
+            }
+            Token::Close => {
+                path.pop();
+            }
+            Token::Str(_) => (),
+        }
+    }
+    let open = app_block?;
+
+    // Look for the key directly in the block of the app.
+    let mut depth = 0;
+    let mut idx = open + 1;
+    while idx < tokens.len() {
+        match tokens[idx].0 {
+            Token::Open => depth += 1,
+            Token::Close if depth == 0 => break,
+            Token::Close => depth -= 1,
+            Token::Str(key) if depth == 0 => {
+                if let Some((Token::Str(_), start, end)) = tokens.get(idx + 1) {
+                    if key.eq_ignore_ascii_case(LAUNCH_OPTIONS_KEY) {
+                        return Some(format!(
+                            "{}{}{}",
+                            &contents[..*start],
+                            quote(options),
+                            &contents[*end..]
+                        ));
+                    }
+                    // Skip the value.
+                    idx += 1;
+                }
+            }
+            Token::Str(_) => (),
+        }
+        idx += 1;
+    }
+
+    // Not set yet; add it as the first key of the app, indented like the app itself.
+    let (_, _, after_open) = tokens[open];
+    let line_start = contents[..tokens[open].1].rfind('\n').map_or(0, |i| i + 1);
+    let indent = &contents[line_start..tokens[open].1];
+    Some(format!(
+        "{}\n{indent}\t{}\t\t{}{}",
+        &contents[..after_open],
+        quote(LAUNCH_OPTIONS_KEY),
+        quote(options),
+        &contents[after_open..]
+    ))
+}
+
+/// Every value of `key`, anywhere in `contents`; like the `path` of each library in
+/// `libraryfolders.vdf`.
+pub fn values(contents: &str, key: &str) -> Vec<String> {
+    let tokens = tokenize(contents);
+    let mut values = Vec::new();
+    let mut idx = 0;
+    while idx < tokens.len() {
+        if let (Token::Str(k), Some((Token::Str(value), _, _))) =
+            (tokens[idx].0, tokens.get(idx + 1))
+        {
+            if k.eq_ignore_ascii_case(key) {
+                values.push(value.replace("\\\\", "\\").replace("\\\"", "\""));
+            }
+            // Skip the value.
+            idx += 1;
+        }
+        idx += 1;
+    }
+    values
+}
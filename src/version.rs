@@ -0,0 +1,130 @@
+use std::{cmp::Ordering, fmt::Display};
+
+// Words which, when used as a version segment, mark a pre-release.
+// Such a version is considered older than the same version without them.
+const PRE_RELEASE_MARKERS: &[&str] = &[
+    "alpha", "beta", "rc", "pre", "preview", "dev", "test", "wip",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    // A numeric segment with an optional alphabetic suffix, like '10' or '2a'.
+    Number(u64, String),
+    // A purely textual segment, like 'beta'.
+    Text(String),
+}
+impl Segment {
+    fn parse(segment: &str) -> Self {
+        let digits = segment
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii_digit())
+            .map_or(segment.len(), |(idx, _)| idx);
+
+        if digits == 0 {
+            Self::Text(segment.to_owned())
+        } else {
+            let (number, suffix) = segment.split_at(digits);
+            // Digits only, so this can only fail on overflow.
+            Self::Number(number.parse().unwrap_or(u64::MAX), suffix.to_owned())
+        }
+    }
+    fn is_pre_release(&self) -> bool {
+        match self {
+            Self::Text(t) => PRE_RELEASE_MARKERS.iter().any(|m| t.starts_with(m)),
+            Self::Number(..) => false,
+        }
+    }
+}
+impl Ord for Segment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(na, sa), Self::Number(nb, sb)) => na.cmp(nb).then_with(|| sa.cmp(sb)),
+            (Self::Text(ta), Self::Text(tb)) => ta.cmp(tb),
+            (Self::Number(..), Self::Text(_)) => Ordering::Greater,
+            (Self::Text(_), Self::Number(..)) => Ordering::Less,
+        }
+    }
+}
+impl PartialOrd for Segment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A loosely parsed version, as found on Nexus and in archive names.
+///
+/// Versions are split into segments on '.', '-', '_', '+' and whitespace,
+/// and compared segment by segment; numeric segments are compared by value,
+/// so "1.10" is newer than "1.9". Missing trailing segments count as zero,
+/// except for pre-release markers ("1.0-beta" is older than "1.0"); other
+/// trailing words are ignored ("1.0 final" is the same as "1.0").
+#[derive(Clone, Debug, Default)]
+pub struct Version {
+    original: String,
+    segments: Vec<Segment>,
+}
+impl From<&str> for Version {
+    fn from(version: &str) -> Self {
+        let lower = version.trim().to_lowercase();
+        let stripped = lower
+            .strip_prefix("version")
+            .or_else(|| lower.strip_prefix('v'))
+            .unwrap_or(&lower);
+
+        let segments = stripped
+            .split(|c: char| matches!(c, '.' | '-' | '_' | '+') || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(Segment::parse)
+            .collect();
+
+        Self {
+            original: version.to_owned(),
+            segments,
+        }
+    }
+}
+impl From<Option<&str>> for Version {
+    fn from(version: Option<&str>) -> Self {
+        version.map(Self::from).unwrap_or_default()
+    }
+}
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.original)
+    }
+}
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.segments.len().max(other.segments.len());
+        let zero = Segment::Number(0, String::new());
+
+        for idx in 0..len {
+            let o = match (self.segments.get(idx), other.segments.get(idx)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(a), None) if a.is_pre_release() => Ordering::Less,
+                (None, Some(b)) if b.is_pre_release() => Ordering::Greater,
+                // Other trailing words, like '1.0 final', neither make a version older nor newer.
+                (Some(Segment::Text(_)) | None, None) | (None, Some(Segment::Text(_))) => {
+                    Ordering::Equal
+                }
+                (Some(a), None) => a.cmp(&zero),
+                (None, Some(b)) => zero.cmp(b),
+            };
+            if o != Ordering::Equal {
+                return o;
+            }
+        }
+        Ordering::Equal
+    }
+}
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Version {}
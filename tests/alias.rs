@@ -0,0 +1,46 @@
+//! Replacing the aliases of the user by their commands.
+
+use std::{collections::BTreeMap, ffi::OsString};
+
+use starmod_core::commands::alias::expand;
+
+fn args(line: &str) -> Vec<OsString> {
+    line.split_whitespace().map(OsString::from).collect()
+}
+
+fn aliases() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("sp".to_owned(), "mods set-priority".to_owned()),
+        ("up".to_owned(), "downloads upgrade-all".to_owned()),
+    ])
+}
+
+#[test]
+fn the_first_command_is_replaced() {
+    let value_options = ["-v".to_owned(), "--verbose".to_owned()];
+    assert_eq!(
+        expand(args("starmod sp armor 3"), &aliases(), &value_options),
+        args("starmod mods set-priority armor 3")
+    );
+    assert_eq!(
+        expand(
+            args("starmod --no-pager -v debug up -y"),
+            &aliases(),
+            &value_options
+        ),
+        args("starmod --no-pager -v debug downloads upgrade-all -y")
+    );
+}
+
+#[test]
+fn other_words_are_left_alone() {
+    let value_options = ["-v".to_owned()];
+    for line in [
+        "starmod mods sp",
+        "starmod -v sp mods",
+        "starmod",
+        "starmod list",
+    ] {
+        assert_eq!(expand(args(line), &aliases(), &value_options), args(line));
+    }
+}
@@ -0,0 +1,22 @@
+//! Encoding images for the terminal image protocols.
+
+use starmod_core::ui::base64;
+
+#[test]
+fn known_vectors_are_encoded() {
+    // From RFC 4648, section 10.
+    let cases = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(base64(input.as_bytes()), expected, "{input}");
+    }
+    assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+    assert_eq!(base64(&[0x00, 0x10, 0x83]), "ABCD");
+}
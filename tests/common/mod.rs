@@ -0,0 +1,239 @@
+//! Temporary download, cache and game directories for the integration tests, filled with
+//! archives and manifests like starmod finds them; either written by the test itself or
+//! taken from 'tests/fixtures'.
+
+// Every test binary includes this module, but none of them uses all of it.
+#![allow(dead_code)]
+
+use std::fs::{copy, create_dir_all, read_dir, read_link, write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+use starmod_core::{
+    commands::downloads::find_and_extract_archive,
+    decompress::SupportedArchives,
+    dmodman::DmodMan,
+    manifest::{Manifest, MANIFEST_EXTENSION},
+    mods::GatherModList,
+};
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+const NEXUS_GAME_NAME: &str = "starfield";
+
+/// A download, cache and game directory, removed again when dropped.
+pub struct TestTree {
+    // Only kept to remove the directories at the end of the test.
+    _root: TempDir,
+    root: Utf8PathBuf,
+    download_dir: Utf8PathBuf,
+    cache_dir: Utf8PathBuf,
+    game_dir: Utf8PathBuf,
+}
+impl TestTree {
+    pub fn new() -> Self {
+        let root_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(root_dir.path().to_path_buf()).unwrap();
+
+        let download_dir = root.join("downloads");
+        let cache_dir = root.join("cache");
+        let game_dir = root.join("game");
+        for dir in [&download_dir, &cache_dir, &game_dir] {
+            create_dir_all(dir).unwrap();
+        }
+
+        Self {
+            _root: root_dir,
+            root,
+            download_dir,
+            cache_dir,
+            game_dir,
+        }
+    }
+    pub fn download_dir(&self) -> &Utf8Path {
+        &self.download_dir
+    }
+    pub fn cache_dir(&self) -> &Utf8Path {
+        &self.cache_dir
+    }
+    pub fn game_dir(&self) -> &Utf8Path {
+        &self.game_dir
+    }
+
+    /// Write zip archive `file_name` to the download directory, with `files` as
+    /// (path in the archive, contents).
+    pub fn add_archive(&self, file_name: &str, files: &[(&str, &str)]) -> Utf8PathBuf {
+        let staging = self.root.join("staging").join(file_name);
+        let entries = files
+            .iter()
+            .map(|(path, contents)| {
+                let source = staging.join(path);
+                create_dir_all(source.parent().unwrap()).unwrap();
+                write(&source, contents).unwrap();
+                ((*path).to_owned(), source.into_std_path_buf())
+            })
+            .collect::<Vec<_>>();
+
+        let archive = self.download_dir.join(file_name);
+        SupportedArchives::Zip
+            .compress(&entries, archive.as_std_path())
+            .unwrap();
+        Utf8PathBuf::from(file_name)
+    }
+
+    /// Like `add_archive`, with the files of 'tests/fixtures/archives/<fixture>'.
+    pub fn add_fixture_archive(&self, fixture: &str, file_name: &str) -> Utf8PathBuf {
+        let fixture_dir = Utf8PathBuf::from(FIXTURES_DIR)
+            .join("archives")
+            .join(fixture);
+        let entries = fixture_files(&fixture_dir)
+            .into_iter()
+            .map(|path| {
+                let source = fixture_dir.join(&path);
+                (path.to_string(), source.into_std_path_buf())
+            })
+            .collect::<Vec<_>>();
+
+        let archive = self.download_dir.join(file_name);
+        SupportedArchives::Zip
+            .compress(&entries, archive.as_std_path())
+            .unwrap();
+        Utf8PathBuf::from(file_name)
+    }
+
+    /// Write an archive named the way Nexus names its files, together with the metadata
+    /// dmodman writes for it; the version uses dots, like '1.0'.
+    pub fn add_nexus_archive(
+        &self,
+        name: &str,
+        mod_id: u32,
+        version: &str,
+        files: &[(&str, &str)],
+    ) -> Utf8PathBuf {
+        // Also used as the file id, which has to be unique.
+        let timestamp = 1_700_000_000 + read_dir(&self.download_dir).unwrap().count() as u64;
+        let file_name = format!(
+            "{name}-{mod_id}-{}-{timestamp}.zip",
+            version.replace('.', "-")
+        );
+        let archive = self.add_archive(&file_name, files);
+
+        DmodMan::new(NEXUS_GAME_NAME, &file_name, mod_id, timestamp, timestamp)
+            .write(&self.download_dir.join(format!("{file_name}.json")))
+            .unwrap();
+        archive
+    }
+
+    /// Extract and install archive `file_name` from the download directory.
+    pub fn install(&self, file_name: &Utf8Path) -> Manifest {
+        find_and_extract_archive(&self.download_dir, &self.cache_dir, file_name.as_str())
+            .unwrap()
+            .expect("the archive was extracted before")
+    }
+
+    /// Put manifest 'tests/fixtures/manifests/<manifest>.ron' in the cache, together with
+    /// the files of 'tests/fixtures/archives/<fixture>' as its extracted archive.
+    pub fn add_fixture_manifest(&self, manifest: &str, fixture: &str) -> Manifest {
+        let manifests_dir = Utf8PathBuf::from(FIXTURES_DIR).join("manifests");
+        let fixture_dir = Utf8PathBuf::from(FIXTURES_DIR)
+            .join("archives")
+            .join(fixture);
+
+        let staged = self.root.join("staging").join(manifest);
+        create_dir_all(&staged).unwrap();
+        let staged = staged.join(format!("{manifest}.{MANIFEST_EXTENSION}"));
+        copy(
+            manifests_dir.join(format!("{manifest}.{MANIFEST_EXTENSION}")),
+            &staged,
+        )
+        .unwrap();
+        let manifest_dir = Manifest::try_from(staged.as_path())
+            .unwrap()
+            .manifest_dir()
+            .to_owned();
+
+        for path in fixture_files(&fixture_dir) {
+            let destination = self.cache_dir.join(&manifest_dir).join(&path);
+            create_dir_all(destination.parent().unwrap()).unwrap();
+            copy(fixture_dir.join(&path), destination).unwrap();
+        }
+        let manifest_file = self
+            .cache_dir
+            .join(format!("{manifest_dir}.{MANIFEST_EXTENSION}"));
+        copy(
+            manifests_dir.join(format!("{manifest}.{MANIFEST_EXTENSION}")),
+            &manifest_file,
+        )
+        .unwrap();
+
+        Manifest::from_file(&self.cache_dir, &manifest_dir).unwrap()
+    }
+
+    /// Put only manifest 'tests/fixtures/manifests/<manifest>.ron' in the cache, without the
+    /// files of its mod, and return its path there.
+    pub fn add_manifest_file(&self, manifest: &str) -> Utf8PathBuf {
+        let file_name = format!("{manifest}.{MANIFEST_EXTENSION}");
+        let path = self.cache_dir.join(&file_name);
+        copy(
+            Utf8PathBuf::from(FIXTURES_DIR)
+                .join("manifests")
+                .join(file_name),
+            &path,
+        )
+        .unwrap();
+        path
+    }
+
+    /// All mods in the cache, in the order starmod deploys them.
+    pub fn mods(&self) -> Vec<Manifest> {
+        Vec::gather_mods(&self.cache_dir).unwrap()
+    }
+
+    /// Put a file in the game directory which does not belong to any mod.
+    pub fn add_game_file(&self, destination: &str, contents: &str) {
+        let path = self.game_dir.join(destination);
+        create_dir_all(path.parent().unwrap()).unwrap();
+        write(path, contents).unwrap();
+    }
+
+    /// The directory in the cache of the mod whose file is linked at `destination`;
+    /// `None` when nothing is linked there.
+    pub fn deployed_from(&self, destination: &str) -> Option<String> {
+        let target = read_link(self.game_dir.join(destination)).ok()?;
+        let target = Utf8PathBuf::try_from(target).unwrap();
+        let relative = target.strip_prefix(&self.cache_dir).ok()?;
+        relative.components().next().map(|c| c.as_str().to_owned())
+    }
+
+    /// Everything linked into the game directory, relative to it and sorted.
+    pub fn deployed(&self) -> Vec<String> {
+        let mut deployed = WalkDir::new(&self.game_dir)
+            .min_depth(1)
+            .follow_links(false)
+            .into_iter()
+            .map(Result::unwrap)
+            .filter(|entry| entry.path_is_symlink())
+            .map(|entry| {
+                let path = Utf8PathBuf::try_from(entry.path().to_path_buf()).unwrap();
+                path.strip_prefix(&self.game_dir).unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+        deployed.sort();
+        deployed
+    }
+}
+
+/// The files below `dir`, relative to it.
+fn fixture_files(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    WalkDir::new(dir)
+        .min_depth(1)
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let path = Utf8PathBuf::try_from(entry.path().to_path_buf()).unwrap();
+            path.strip_prefix(dir).unwrap().to_owned()
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+//! Which mod wins when several mods provide the same file.
+
+mod common;
+
+use common::TestTree;
+use starmod_core::{
+    conflict::{conflict_list_by_file, conflict_list_by_mod},
+    mods::{FindInModList, ModList},
+};
+
+const SHARED_TEXTURE: &str = "Data/Textures/armor/vest.dds";
+
+/// Two enabled mods which both provide `SHARED_TEXTURE`; 'vest' has priority 1, 'vest_hd' 2.
+fn conflicting_mods() -> TestTree {
+    let tree = TestTree::new();
+    let files = [
+        ("vest", "textures/armor/vest.dds", "vest.esm", 1),
+        ("vest_hd", "textures/armor/vest.dds", "vest_hd.esm", 2),
+    ];
+    for (name, texture, plugin, priority) in files {
+        let archive = tree.add_archive(&format!("{name}.zip"), &[(texture, name), (plugin, name)]);
+        tree.install(&archive).set_priority(priority).unwrap();
+    }
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    tree
+}
+
+#[test]
+fn the_mod_with_the_highest_priority_wins() {
+    let tree = conflicting_mods();
+
+    assert_eq!(
+        tree.deployed_from(SHARED_TEXTURE).as_deref(),
+        Some("vest_hd")
+    );
+    assert_eq!(tree.deployed_from("Data/vest.esm").as_deref(), Some("vest"));
+    assert_eq!(
+        tree.deployed_from("Data/vest_hd.esm").as_deref(),
+        Some("vest_hd")
+    );
+}
+
+#[test]
+fn conflicts_list_the_winner_last() {
+    let tree = conflicting_mods();
+    let mod_list = tree.mods();
+
+    let by_file = conflict_list_by_file(&mod_list).unwrap();
+    assert_eq!(by_file.len(), 1);
+    assert_eq!(by_file[SHARED_TEXTURE], vec!["vest", "vest_hd"]);
+
+    let by_mod = conflict_list_by_mod(&mod_list).unwrap();
+    assert!(by_mod["vest"].losing_to().contains("vest_hd"));
+    assert!(by_mod["vest"].winning_over().is_empty());
+    assert!(by_mod["vest_hd"].winning_over().contains("vest"));
+    assert!(by_mod["vest_hd"].losing_to().is_empty());
+}
+
+#[test]
+fn changing_the_priorities_changes_the_winner() {
+    let tree = conflicting_mods();
+
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("vest").unwrap();
+    mod_list[idx].set_priority(3).unwrap();
+
+    let mut mod_list = tree.mods();
+    mod_list
+        .re_enable(tree.cache_dir(), tree.game_dir())
+        .unwrap();
+
+    assert_eq!(tree.deployed_from(SHARED_TEXTURE).as_deref(), Some("vest"));
+}
+
+#[test]
+fn disabling_the_winner_deploys_the_file_of_the_loser() {
+    let tree = conflicting_mods();
+
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("vest_hd").unwrap();
+    mod_list
+        .disable_mod(tree.cache_dir(), tree.game_dir(), idx)
+        .unwrap();
+
+    assert_eq!(tree.deployed_from(SHARED_TEXTURE).as_deref(), Some("vest"));
+    assert_eq!(tree.deployed(), vec![SHARED_TEXTURE, "Data/vest.esm"]);
+}
+
+#[test]
+fn mods_of_equal_priority_win_in_the_order_they_were_installed() {
+    let tree = TestTree::new();
+    for name in ["zz_vest", "aa_vest"] {
+        let archive =
+            tree.add_archive(&format!("{name}.zip"), &[("textures/armor/vest.dds", name)]);
+        tree.install(&archive);
+    }
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(
+        tree.deployed_from(SHARED_TEXTURE).as_deref(),
+        Some("aa_vest")
+    );
+
+    // Renaming does not change the order; a new sequence number does.
+    let idx = mod_list.find_mod_by_name("aa_vest").unwrap();
+    let last = mod_list[idx].sequence();
+    mod_list[idx].set_name("a renamed vest".to_owned()).unwrap();
+    let idx = mod_list.find_mod_by_name("zz_vest").unwrap();
+    mod_list[idx].set_sequence(last + 1).unwrap();
+
+    let mut mod_list = tree.mods();
+    mod_list
+        .re_enable(tree.cache_dir(), tree.game_dir())
+        .unwrap();
+    assert_eq!(
+        tree.deployed_from(SHARED_TEXTURE).as_deref(),
+        Some("zz_vest")
+    );
+}
+
+#[test]
+fn enabling_the_loser_keeps_the_winner_deployed() {
+    let tree = conflicting_mods();
+
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("vest").unwrap();
+    mod_list
+        .disable_mod(tree.cache_dir(), tree.game_dir(), idx)
+        .unwrap();
+    mod_list
+        .enable_mod(tree.cache_dir(), tree.game_dir(), idx)
+        .unwrap();
+
+    assert_eq!(
+        tree.deployed_from(SHARED_TEXTURE).as_deref(),
+        Some("vest_hd")
+    );
+    assert_eq!(tree.deployed_from("Data/vest.esm").as_deref(), Some("vest"));
+}
@@ -0,0 +1,46 @@
+//! Custom mods, whose files are whatever is in their directory in the cache.
+
+mod common;
+
+use std::fs::{create_dir_all, write};
+
+use camino::Utf8Path;
+use common::TestTree;
+use starmod_core::{
+    manifest::install_file::InstallFile,
+    mods::{ModKind, ModList},
+};
+
+fn destinations(files: &[InstallFile]) -> Vec<&str> {
+    let mut destinations = files.iter().map(|f| f.destination()).collect::<Vec<_>>();
+    destinations.sort_unstable();
+    destinations
+}
+
+#[test]
+fn the_files_in_the_mod_directory_can_be_disabled() {
+    let tree = TestTree::new();
+    let mod_dir = tree.cache_dir().join("my_tweaks");
+    create_dir_all(mod_dir.join("textures")).unwrap();
+    write(mod_dir.join("tweaks.ini"), "[Display]").unwrap();
+    write(mod_dir.join("textures/sky.dds"), "texture").unwrap();
+
+    let mut md = ModKind::Custom
+        .create_mod(tree.cache_dir(), Utf8Path::new("my_tweaks"))
+        .unwrap();
+    assert_eq!(
+        destinations(&md.files().unwrap()),
+        ["Data/Textures/sky.dds", "Data/tweaks.ini"]
+    );
+
+    assert!(md.disable_file("tweaks.ini").unwrap());
+    assert_eq!(
+        destinations(&md.files().unwrap()),
+        ["Data/Textures/sky.dds"]
+    );
+    assert_eq!(destinations(&md.disabled_files()), ["Data/tweaks.ini"]);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(tree.deployed(), vec!["Data/Textures/sky.dds"]);
+}
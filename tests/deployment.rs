@@ -0,0 +1,319 @@
+//! Linking mods into the game directory and taking them out again.
+
+mod common;
+
+use std::fs::{create_dir_all, read_to_string, remove_file};
+
+use camino::Utf8Path;
+use common::TestTree;
+use starmod_core::{
+    deployment::{
+        case::{case_duplicates, CaseFolder},
+        DeployPlan,
+    },
+    manifest::{install_file::InstallFile, Manifest},
+    mods::{FindInModList, ModKind, ModList},
+};
+
+const VEST_TEXTURE: &str = "Data/Textures/armor/vest.dds";
+const VEST_PLUGIN: &str = "Data/armorvest.esm";
+
+#[test]
+fn enabling_links_the_files_into_the_game_dir() {
+    let tree = TestTree::new();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    let md = tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert_eq!(tree.deployed(), vec![VEST_TEXTURE, VEST_PLUGIN]);
+    assert_eq!(
+        tree.deployed_from(VEST_TEXTURE).as_deref(),
+        Some(md.manifest_dir().as_str())
+    );
+    assert_eq!(
+        read_to_string(tree.game_dir().join(VEST_PLUGIN)).unwrap(),
+        "armor vest plugin\n"
+    );
+    assert!(tree.mods().iter().all(|md| md.is_enabled()));
+}
+
+#[test]
+fn disabling_removes_the_links_and_the_directories_they_were_in() {
+    let tree = TestTree::new();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    mod_list.disable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert!(tree.deployed().is_empty());
+    assert!(!tree.game_dir().join("Data").exists());
+    assert!(tree.mods().iter().all(|md| md.is_disabled()));
+}
+
+#[test]
+fn foreign_files_are_kept_aside_while_a_mod_is_enabled() {
+    let tree = TestTree::new();
+    tree.add_game_file(VEST_PLUGIN, "original plugin\n");
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert!(tree.deployed_from(VEST_PLUGIN).is_some());
+
+    mod_list.disable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(
+        read_to_string(tree.game_dir().join(VEST_PLUGIN)).unwrap(),
+        "original plugin\n"
+    );
+    assert!(tree.deployed().is_empty());
+}
+
+#[test]
+fn re_enable_only_deploys_the_enabled_mods() {
+    let tree = TestTree::new();
+    for (name, file) in [("boots", "boots.esm"), ("helmet", "helmet.esm")] {
+        let archive = tree.add_archive(&format!("{name}.zip"), &[(file, name)]);
+        tree.install(&archive);
+    }
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    let idx = mod_list.find_mod_by_name("helmet").unwrap();
+    mod_list
+        .disable_mod(tree.cache_dir(), tree.game_dir(), idx)
+        .unwrap();
+    assert_eq!(tree.deployed(), vec!["Data/boots.esm"]);
+
+    let mut mod_list = tree.mods();
+    mod_list
+        .re_enable(tree.cache_dir(), tree.game_dir())
+        .unwrap();
+    assert_eq!(tree.deployed(), vec!["Data/boots.esm"]);
+
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("helmet").unwrap();
+    mod_list
+        .enable_mod(tree.cache_dir(), tree.game_dir(), idx)
+        .unwrap();
+    assert_eq!(tree.deployed(), vec!["Data/boots.esm", "Data/helmet.esm"]);
+}
+
+#[test]
+fn pinned_mods_are_never_deployed() {
+    let tree = TestTree::new();
+    // An old manifest which was enabled with a negative priority; that is migrated to 'pinned'.
+    let md = tree.add_fixture_manifest("v0_data", "armor_vest");
+    assert!(md.mod_state().is_pinned());
+    assert_eq!(md.priority(), 0);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    mod_list
+        .enable_mod(tree.cache_dir(), tree.game_dir(), 0)
+        .unwrap();
+
+    assert!(tree.deployed().is_empty());
+    assert!(tree.mods()[0].mod_state().is_pinned());
+
+    let mut mod_list = tree.mods();
+    assert!(mod_list[0].unpin().unwrap());
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(
+        tree.deployed(),
+        vec!["Data/Textures/armor/vest.dds", "Data/armorvest.esm"]
+    );
+}
+
+#[test]
+fn negative_priorities_only_order_mods() {
+    let tree = TestTree::new();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive).set_priority(-5).unwrap();
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert_eq!(tree.deployed().len(), 2);
+    assert!(tree.mods()[0].is_enabled());
+}
+
+#[test]
+fn deploying_twice_changes_nothing_the_second_time() {
+    let tree = TestTree::new();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive).set_enabled().unwrap();
+
+    let plan = DeployPlan::new(&tree.mods(), tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(plan.create().len(), 2);
+    plan.apply(tree.game_dir()).unwrap();
+    assert_eq!(tree.deployed(), vec![VEST_TEXTURE, VEST_PLUGIN]);
+
+    let plan = DeployPlan::new(&tree.mods(), tree.cache_dir(), tree.game_dir()).unwrap();
+    assert!(plan.is_empty());
+    assert_eq!(plan.unchanged(), 2);
+}
+
+#[test]
+fn deploying_only_changes_the_links_which_differ() {
+    let tree = TestTree::new();
+    tree.add_game_file(VEST_PLUGIN, "original plugin\n");
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+    let archive = tree.add_archive("boots.zip", &[("boots.esm", "boots")]);
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    // State changes only; nothing is deployed until the plan is applied.
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("armor_vest").unwrap();
+    mod_list[idx].set_disabled().unwrap();
+    assert_eq!(tree.deployed().len(), 3);
+
+    let plan = DeployPlan::new(&tree.mods(), tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(plan.remove().len(), 2);
+    assert!(plan.create().is_empty() && plan.replace().is_empty());
+    assert_eq!(plan.unchanged(), 1);
+    plan.apply(tree.game_dir()).unwrap();
+
+    assert_eq!(tree.deployed(), vec!["Data/boots.esm"]);
+    assert_eq!(
+        read_to_string(tree.game_dir().join(VEST_PLUGIN)).unwrap(),
+        "original plugin\n"
+    );
+    assert!(!tree.game_dir().join("Data/Textures").exists());
+}
+
+#[test]
+fn only_directories_made_for_links_are_removed() {
+    let tree = TestTree::new();
+    // Made by the user, and empty; not ours to remove.
+    create_dir_all(tree.game_dir().join("Data/Textures")).unwrap();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    let mut mod_list = tree.mods();
+    mod_list.disable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert!(tree.deployed().is_empty());
+    assert!(!tree.game_dir().join("Data/Textures/armor").exists());
+    assert!(tree.game_dir().join("Data/Textures").is_dir());
+}
+
+#[test]
+fn earlier_backups_are_never_overwritten() {
+    let tree = TestTree::new();
+    tree.add_game_file(VEST_PLUGIN, "original plugin\n");
+    // Left behind by an earlier run, whose original could not be put back.
+    tree.add_game_file(&format!("{VEST_PLUGIN}.starmod_bkp"), "earlier backup\n");
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(
+        read_to_string(tree.game_dir().join(format!("{VEST_PLUGIN}.starmod_bkp.1"))).unwrap(),
+        "original plugin\n"
+    );
+
+    let mut mod_list = tree.mods();
+    mod_list.disable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert_eq!(
+        read_to_string(tree.game_dir().join(VEST_PLUGIN)).unwrap(),
+        "original plugin\n"
+    );
+    assert_eq!(
+        read_to_string(tree.game_dir().join(format!("{VEST_PLUGIN}.starmod_bkp"))).unwrap(),
+        "earlier backup\n"
+    );
+}
+
+#[test]
+fn folding_uses_the_casing_already_in_the_game_dir() {
+    let tree = TestTree::new();
+    tree.add_game_file("Data/Textures/original.dds", "original");
+
+    let mut folder = CaseFolder::new(tree.game_dir());
+    assert_eq!(
+        folder.fold("data/textures/armor/vest.dds"),
+        "Data/Textures/armor/vest.dds"
+    );
+    // New directories keep the casing they were first seen with.
+    assert_eq!(
+        folder.fold("DATA/TEXTURES/Armor/Vest_n.dds"),
+        "Data/Textures/armor/Vest_n.dds"
+    );
+    assert_eq!(folder.fold("SFSE_Loader.exe"), "SFSE_Loader.exe");
+}
+
+#[test]
+fn paths_which_only_differ_in_casing_are_found() {
+    let tree = TestTree::new();
+    tree.add_game_file("Data/Textures/vest.dds", "vest");
+    tree.add_game_file("Data/textures/boots.dds", "boots");
+    tree.add_game_file("Data/Meshes/vest.nif", "vest");
+
+    assert_eq!(
+        case_duplicates(&tree.game_dir().join("Data")),
+        vec![vec!["Textures", "textures"]]
+    );
+}
+
+#[test]
+fn files_outside_of_the_game_dir_are_never_deployed() {
+    assert!(InstallFile::new("escape.esm".into(), "../../escape.esm").is_none());
+
+    // Manifests of older releases kept such destinations.
+    let tree = TestTree::new();
+    let files = vec![
+        InstallFile::new_raw("ok.esm".into(), "Data/ok.esm".to_owned()),
+        InstallFile::new_raw("escape.esm".into(), "Data/../../escape.esm".to_owned()),
+    ];
+    let mut md = Manifest::new(
+        tree.cache_dir(),
+        Utf8Path::new("old"),
+        "old".to_owned(),
+        "old".to_owned(),
+        None,
+        None,
+        files,
+        Vec::new(),
+        ModKind::Data,
+    );
+    md.set_enabled().unwrap();
+
+    let plan = DeployPlan::new(&[md], tree.cache_dir(), tree.game_dir()).unwrap();
+    let created = plan
+        .create()
+        .iter()
+        .map(|l| l.destination().strip_prefix(tree.game_dir()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(created, [Utf8Path::new("Data/ok.esm")]);
+}
+
+#[test]
+fn directories_of_links_made_before_the_record_are_removed() {
+    let tree = TestTree::new();
+    tree.add_game_file("Data/Starfield.esm", "game plugin\n");
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    // Like after upgrading from a release which did not record the directories.
+    remove_file(tree.cache_dir().join("directories.registry")).unwrap();
+    let mut mod_list = tree.mods();
+    mod_list.disable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert!(tree.deployed().is_empty());
+    assert!(!tree.game_dir().join("Data/Textures").exists());
+    assert!(tree.game_dir().join("Data/Starfield.esm").is_file());
+}
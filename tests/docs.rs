@@ -0,0 +1,37 @@
+//! The readme and changelog files which come with a mod.
+
+mod common;
+
+use common::TestTree;
+
+#[test]
+fn readmes_and_changelogs_are_recorded() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive(
+        "armor.zip",
+        &[
+            ("Data/armor.esp", "plugin"),
+            ("Readme.txt", "how to use"),
+            ("docs/CHANGELOG.md", "what changed"),
+            ("Data/textures/readme_image.dds", "not a document"),
+        ],
+    );
+    let md = tree.install(&archive);
+
+    let docs = md
+        .docs()
+        .iter()
+        .map(|doc| doc.file_name().unwrap().to_lowercase())
+        .collect::<Vec<_>>();
+    assert_eq!(docs, ["changelog.md", "readme.txt"]);
+    assert!(md.docs().iter().all(|doc| doc.is_file()));
+}
+
+#[test]
+fn a_mod_without_documents_has_none() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive("plain.zip", &[("Data/plain.esp", "plugin")]);
+    let md = tree.install(&archive);
+
+    assert!(md.docs().is_empty());
+}
@@ -0,0 +1,90 @@
+//! Archives with entries which point outside of the directory they are extracted to,
+//! crafted with the same writers starmod uses to export mods.
+
+use std::{fs::write, path::Path};
+
+use starmod_core::decompress::SupportedArchives;
+use tempfile::TempDir;
+
+/// Write an archive of `archive_type` to `dir` with the given entry names, all with the same contents.
+fn craft_archive(
+    dir: &Path,
+    archive_type: SupportedArchives,
+    entries: &[&str],
+) -> std::path::PathBuf {
+    let source = dir.join("source.txt");
+    write(&source, "contents").unwrap();
+
+    let files = entries
+        .iter()
+        .map(|name| ((*name).to_owned(), source.clone()))
+        .collect::<Vec<_>>();
+    let archive = dir.join(format!("crafted.{archive_type}"));
+    archive_type.compress(&files, &archive).unwrap();
+    archive
+}
+
+/// Extracting an archive with the entry `entry`, given where it would end up, must fail.
+fn assert_refused(archive_type: SupportedArchives, entry: impl Fn(&Path) -> String) {
+    let dir = TempDir::new().unwrap();
+    let escaped = dir.path().join("escaped.txt");
+    let entry = entry(&escaped);
+    let archive = craft_archive(dir.path(), archive_type, &["readme.txt", &entry]);
+    let destination = dir.path().join("extracted");
+
+    let result = archive_type.decompress(&archive, &destination, None);
+
+    assert!(
+        result.is_err(),
+        "'{entry}' in a {archive_type} archive was extracted"
+    );
+    assert!(
+        !escaped.exists(),
+        "'{entry}' was written outside of the destination"
+    );
+}
+
+#[test]
+fn zip_entry_with_parent_dir_is_refused() {
+    assert_refused(SupportedArchives::Zip, |_| "../escaped.txt".to_owned());
+    assert_refused(SupportedArchives::Zip, |_| {
+        "data/../../escaped.txt".to_owned()
+    });
+}
+
+#[test]
+fn zip_entry_with_absolute_path_is_refused() {
+    assert_refused(SupportedArchives::Zip, |escaped| {
+        escaped.display().to_string()
+    });
+}
+
+#[test]
+fn sevenz_entry_with_parent_dir_is_refused() {
+    assert_refused(SupportedArchives::SevenZip, |_| "../escaped.txt".to_owned());
+    assert_refused(SupportedArchives::SevenZip, |_| {
+        "data/../../escaped.txt".to_owned()
+    });
+}
+
+#[test]
+fn sevenz_entry_with_absolute_path_is_refused() {
+    assert_refused(SupportedArchives::SevenZip, |escaped| {
+        escaped.display().to_string()
+    });
+}
+
+#[test]
+fn parent_dirs_within_the_archive_are_extracted() {
+    for archive_type in [SupportedArchives::Zip, SupportedArchives::SevenZip] {
+        let dir = TempDir::new().unwrap();
+        let archive = craft_archive(dir.path(), archive_type, &["textures/../readme.txt"]);
+        let destination = dir.path().join("extracted");
+
+        archive_type
+            .decompress(&archive, &destination, None)
+            .unwrap();
+
+        assert!(destination.join("readme.txt").exists());
+    }
+}
@@ -0,0 +1,24 @@
+//! The help of the commands, in the catalog of messages; apart from the tests of `i18n`,
+//! which select another catalog.
+
+use clap::{Command, CommandFactory};
+use starmod_core::{commands::Subcommands, i18n::tr};
+
+// The catalog has to follow the doc comments of the commands.
+#[test]
+fn the_help_of_every_command_is_in_the_catalog() {
+    fn check(cmd: &Command, id: &str) {
+        if let Some(about) = cmd.get_about() {
+            assert_eq!(tr(id), about.to_string());
+        }
+        for arg in cmd.get_arguments() {
+            if let Some(help) = arg.get_help() {
+                assert_eq!(tr(&format!("{id}--{}", arg.get_id())), help.to_string());
+            }
+        }
+        for sub in cmd.get_subcommands() {
+            check(sub, &format!("{id}-{}", sub.get_name()));
+        }
+    }
+    check(&Subcommands::command(), "help");
+}
@@ -0,0 +1,62 @@
+//! Messages in the locale of the user.
+
+use std::fs::{create_dir_all, write};
+
+use clap::CommandFactory;
+use starmod_core::{
+    commands::Subcommands,
+    errors::ModErrors,
+    i18n::{locale, localize_command, select_locale, tr, tr_args},
+};
+use tempfile::TempDir;
+
+// The catalog can only be selected once, so this is a single test.
+#[test]
+fn messages_of_the_locale_fall_back_to_english() {
+    assert_eq!(locale(), "en");
+    assert_eq!(tr("column-name"), "Name");
+    assert_eq!(
+        tr_args(
+            "confirm-question",
+            &[("action", &"disable all enabled mods")]
+        ),
+        "Do you want to disable all enabled mods?"
+    );
+    assert_eq!(tr("no-such-message"), "no-such-message");
+
+    let data_dir = TempDir::new().unwrap();
+    let locales_dir = data_dir.path().join("starmod/locales");
+    create_dir_all(&locales_dir).unwrap();
+    write(
+        locales_dir.join("de.ftl"),
+        "# Deutsch\ncolumn-name = Name des Mods\nconfirm-question = Wirklich { $action }?\n\
+         help-list = Verschiedene Listen\n",
+    )
+    .unwrap();
+    std::env::set_var("XDG_DATA_HOME", data_dir.path());
+
+    select_locale("starmod", Some("de_DE.UTF-8"));
+    assert_eq!(locale(), "de");
+    assert_eq!(tr("column-name"), "Name des Mods");
+    assert_eq!(
+        tr_args("confirm-question", &[("action", &"alles entfernen")]),
+        "Wirklich alles entfernen?"
+    );
+    assert_eq!(tr("column-priority"), "Priority");
+    let cmd = localize_command(Subcommands::command());
+    let list = cmd.find_subcommand("list").unwrap();
+    assert_eq!(list.get_about().unwrap().to_string(), "Verschiedene Listen");
+    let mods = cmd.find_subcommand("mods").unwrap();
+    assert_eq!(
+        mods.get_about().unwrap().to_string(),
+        "Commands related to mods; defaults to showing the mod-list"
+    );
+}
+
+#[test]
+fn errors_are_in_the_catalog() {
+    assert_eq!(
+        ModErrors::TagNotFound("armor".to_owned(), "heavy".to_owned()).to_string(),
+        "Could not find tag 'heavy' in mod armor. Did you perhaps mispel it?"
+    );
+}
@@ -0,0 +1,54 @@
+//! Merging the ini files shipped by several mods into a patch mod.
+
+mod common;
+
+use std::fs::read_to_string;
+
+use common::TestTree;
+use starmod_core::{
+    commands::mods::merge_ini_files,
+    ini::IniMergeStrategy,
+    mods::{FindInModList, ModList},
+};
+
+#[test]
+fn ini_files_of_several_mods_are_merged_key_by_key() {
+    let tree = TestTree::new();
+    let low = tree.add_archive(
+        "low.zip",
+        &[("Data/foo.ini", "[General]\nshared=low\nlow=1\n")],
+    );
+    let high = tree.add_archive(
+        "high.zip",
+        &[("data/FOO.ini", "[General]\nshared=high\nhigh=1\n")],
+    );
+    tree.install(&low).set_priority(1).unwrap();
+    tree.install(&high).set_priority(2).unwrap();
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    merge_ini_files(
+        tree.cache_dir(),
+        tree.game_dir(),
+        "starmod",
+        "ini-merge",
+        IniMergeStrategy::HighestPriority,
+    )
+    .unwrap();
+
+    let mod_list = tree.mods();
+    let patch = &mod_list[mod_list.find_mod_by_name("ini-merge").unwrap()];
+    assert!(patch.is_enabled());
+    assert!(patch.priority() > 2);
+
+    let merged = read_to_string(tree.cache_dir().join("ini-merge/foo.ini")).unwrap();
+    assert!(merged.starts_with("; Merged by starmod (highest-priority) from: low, high"));
+    let values = merged.lines().skip(1).collect::<Vec<_>>();
+    assert!(values.contains(&"shared=high"), "{merged}");
+    assert!(values.contains(&"low=1"), "{merged}");
+    assert!(values.contains(&"high=1"), "{merged}");
+    assert_eq!(
+        tree.deployed_from("Data/foo.ini").as_deref(),
+        Some("ini-merge")
+    );
+}
@@ -0,0 +1,154 @@
+//! Mods installed by the user's installer scripts.
+
+mod common;
+
+use std::{
+    fs::{create_dir_all, set_permissions, write, Permissions},
+    os::unix::fs::{symlink, PermissionsExt},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use common::TestTree;
+use starmod_core::{
+    game::Game,
+    installers::script::{handles, installer_scripts, run_installer_script},
+    manifest::Manifest,
+    mods::ModKind,
+    settings::{LogLevel, Settings},
+};
+use tempfile::TempDir;
+
+// Installs the packages which describe themselves; 'package.txt' holds the mapping.
+const SCRIPT: &str = r#"#!/bin/sh
+case "$1" in
+    detect) [ -f "$2/package.txt" ] ;;
+    install) cat "$2/package.txt" > "$3" ;;
+    *) exit 1 ;;
+esac
+"#;
+
+fn write_script(scripts_dir: &Utf8Path) -> Utf8PathBuf {
+    create_dir_all(scripts_dir).unwrap();
+    let script = scripts_dir.join("packaged.sh");
+    write(&script, SCRIPT).unwrap();
+    set_permissions(&script, Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+/// Install a package with `mapping` as its 'package.txt' with the script, after `prepare` ran
+/// on its extracted archive.
+fn install_package(mapping: &str, prepare: impl FnOnce(&Utf8Path)) -> anyhow::Result<Manifest> {
+    let tree = TestTree::new();
+    let script = write_script(&tree.cache_dir().parent().unwrap().join("installers"));
+    let archive = tree.add_archive(
+        "packaged.zip",
+        &[("package.txt", mapping), ("core/armor.esm", "plugin")],
+    );
+    let md = tree.install(&archive);
+    prepare(&tree.cache_dir().join(md.manifest_dir()));
+    run_installer_script(
+        &script,
+        ModKind::Scripted,
+        tree.cache_dir(),
+        md.manifest_dir(),
+    )
+}
+
+#[test]
+fn a_script_installs_the_files_it_maps() {
+    let tree = TestTree::new();
+    let scripts_dir = tree.cache_dir().parent().unwrap().join("installers");
+    let script = write_script(&scripts_dir);
+    // Not executable, so not a script.
+    write(scripts_dir.join("notes.txt"), "").unwrap();
+    assert_eq!(
+        installer_scripts(&scripts_dir),
+        std::slice::from_ref(&script)
+    );
+
+    let archive = tree.add_archive(
+        "packaged.zip",
+        &[
+            ("package.txt", r#"{"core/./armor.esm": "data/Armor.esm"}"#),
+            ("core/armor.esm", "plugin"),
+            ("optional/extra.ini", "settings"),
+        ],
+    );
+    let md = tree.install(&archive);
+    let archive_dir = tree.cache_dir().join(md.manifest_dir());
+    assert!(handles(&script, &archive_dir));
+    assert!(!handles(&script, tree.game_dir()));
+
+    let md = run_installer_script(
+        &script,
+        ModKind::Scripted,
+        tree.cache_dir(),
+        md.manifest_dir(),
+    )
+    .unwrap();
+    assert_eq!(md.dest_files().unwrap(), ["Data/armor.esm"]);
+    let mut disabled = md
+        .disabled_files()
+        .iter()
+        .map(|f| f.source().to_string())
+        .collect::<Vec<_>>();
+    disabled.sort();
+    assert_eq!(disabled, ["optional/extra.ini", "package.txt"]);
+    assert!(!archive_dir.with_extension("mapping.json").exists());
+}
+
+#[test]
+fn a_script_cannot_install_files_outside_of_the_mod() {
+    assert!(install_package(r#"{"../armor.esm": "Data/armor.esm"}"#, |_| ()).is_err());
+    assert!(install_package(r#"{"/etc/passwd": "Data/armor.esm"}"#, |_| ()).is_err());
+
+    let outside = TempDir::new().unwrap();
+    let secret = outside.path().join("secret.esm");
+    write(&secret, "not of the mod").unwrap();
+    let linked = install_package(r#"{"linked.esm": "Data/armor.esm"}"#, |archive_dir| {
+        symlink(&secret, archive_dir.join("linked.esm")).unwrap();
+    });
+    assert!(linked.is_err());
+}
+
+#[test]
+fn a_script_cannot_install_files_outside_of_the_game_dir() {
+    assert!(install_package(r#"{"core/armor.esm": "../armor.esm"}"#, |_| ()).is_err());
+    assert!(install_package(r#"{"core/armor.esm": "Data/../../armor.esm"}"#, |_| ()).is_err());
+    assert!(install_package(r#"{"core/armor.esm": "Data/../armor.esm"}"#, |_| ()).is_ok());
+}
+
+// The scripts directory can only be set once, so this is a single test.
+#[test]
+fn scripts_install_only_the_archives_starmod_does_not_recognise() {
+    let config_dir = TempDir::new().unwrap();
+    std::env::set_var("XDG_CONFIG_HOME", config_dir.path());
+    std::env::set_var("XDG_CACHE_HOME", config_dir.path());
+    let settings = Settings::read_config(Game::Starfield, LogLevel::Warn).unwrap();
+    settings.apply_installer_settings();
+    write_script(&settings.installer_scripts_dir());
+
+    let tree = TestTree::new();
+    let archive = tree.add_archive(
+        "packaged.zip",
+        &[
+            ("package.txt", r#"{"core/armor.pkg": "Data/armor.esm"}"#),
+            ("core/armor.pkg", "plugin"),
+        ],
+    );
+    let md = tree.install(&archive);
+    assert_eq!(md.kind(), ModKind::Scripted);
+    assert_eq!(md.dest_files().unwrap(), ["Data/armor.esm"]);
+    let detected = ModKind::detect_mod_type(tree.cache_dir(), md.manifest_dir()).unwrap();
+    assert_eq!(detected.kind(), ModKind::Scripted);
+
+    // A plugin is something starmod installs itself, whatever the scripts say.
+    let archive = tree.add_archive(
+        "plugin.zip",
+        &[
+            ("package.txt", r#"{"armor.esm": "Data/armor.esm"}"#),
+            ("armor.esm", "plugin"),
+        ],
+    );
+    assert_eq!(tree.install(&archive).kind(), ModKind::Data);
+}
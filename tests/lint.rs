@@ -0,0 +1,65 @@
+//! The rules 'mods lint' checks the files of a mod against.
+
+mod common;
+
+use common::TestTree;
+use starmod_core::lint::{lint, LintRule, Severity};
+
+#[test]
+fn packaging_mistakes_are_found() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive(
+        "armor.zip",
+        &[
+            ("Data/armor.esm", "plugin"),
+            ("Data/armor - Main.ba2", "archive"),
+            ("Data/armour - Textures.ba2", "archive"),
+            ("Data/tools/install.bat", "program"),
+        ],
+    );
+    let md = tree.install(&archive);
+
+    let findings = lint(&md).unwrap();
+    let rules = findings
+        .iter()
+        .map(|f| (f.rule, f.destination.as_deref().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rules,
+        [
+            (LintRule::Executable, "Data/tools/install.bat"),
+            (LintRule::ArchiveWithoutPlugin, "Data/armour - textures.ba2"),
+        ]
+    );
+    assert!(findings.iter().all(|f| f.severity == Severity::Warning));
+
+    let json = serde_json::to_value(&findings[0]).unwrap();
+    assert_eq!(json["mod"], md.name());
+    assert_eq!(json["rule"], "executable");
+    assert_eq!(json["severity"], "warning");
+}
+
+#[test]
+fn a_well_packaged_mod_has_no_findings() {
+    let tree = TestTree::new();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    let md = tree.install(&archive);
+
+    assert!(lint(&md).unwrap().is_empty());
+}
+
+#[test]
+fn files_with_the_same_destination_are_an_error() {
+    // Installers never map two files to one destination, but a manifest edited by hand can.
+    let tree = TestTree::new();
+    let md = tree.add_fixture_manifest("v0_duplicate", "armor_vest");
+
+    let findings = lint(&md).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].rule, LintRule::DuplicateDestination);
+    assert_eq!(findings[0].severity, Severity::Error);
+    assert_eq!(
+        findings[0].destination.as_deref(),
+        Some("Data/ArmorVest.esm")
+    );
+}
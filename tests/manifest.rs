@@ -0,0 +1,81 @@
+//! Reading the manifests in the cache directory, including those of older and newer releases.
+
+mod common;
+
+use std::fs::read_to_string;
+
+use camino::Utf8Path;
+use common::TestTree;
+use starmod_core::{
+    errors::ModErrors,
+    manifest::{Manifest, MANIFEST_FORMAT_VERSION},
+    mods::{ModKind, ModList},
+};
+
+#[test]
+fn unversioned_manifests_are_upgraded() {
+    let tree = TestTree::new();
+    let path = tree.add_manifest_file("v0_custom");
+
+    let md = Manifest::try_from(path.as_path()).unwrap();
+    assert_eq!(md.kind(), ModKind::Custom);
+    assert_eq!(md.name(), "my_tweaks");
+
+    // The state moved out of the manifest, into a file of its own.
+    assert!(tree.cache_dir().join("my_tweaks.state").is_file());
+    let upgraded = read_to_string(tree.cache_dir().join("my_tweaks.ron")).unwrap();
+    assert!(upgraded.contains(&format!("format_version: {MANIFEST_FORMAT_VERSION}")));
+}
+
+#[test]
+fn loaders_keep_their_files_and_state() {
+    let tree = TestTree::new();
+    let path = tree.add_manifest_file("v1_loader");
+
+    let md = Manifest::try_from(path.as_path()).unwrap();
+    assert_eq!(md.kind(), ModKind::Loader);
+    assert!(md.is_enabled());
+    let mut files = md
+        .files()
+        .unwrap()
+        .iter()
+        .map(|f| f.destination().to_owned())
+        .collect::<Vec<_>>();
+    files.sort_unstable();
+    assert_eq!(files, ["sfse_1_7_29.dll", "sfse_loader.exe"]);
+
+    let md =
+        Manifest::from_file(tree.cache_dir(), Utf8Path::new("sfse-106-0-1-1-1700000000")).unwrap();
+    assert!(md.is_enabled());
+    assert_eq!(md.files().unwrap().len(), 2);
+}
+
+#[test]
+fn manifests_of_newer_releases_are_refused() {
+    let tree = TestTree::new();
+    let path = tree.add_manifest_file("v99_future");
+
+    let err = Manifest::try_from(path.as_path()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ModErrors>(),
+        Some(ModErrors::ManifestTooNew(name, 99)) if name == "from_the_future"
+    ));
+}
+
+#[test]
+fn the_index_follows_changes_to_the_state() {
+    let tree = TestTree::new();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+
+    // The first read writes the index, the next ones are served from it.
+    let mut mod_list = tree.mods();
+    assert_eq!(mod_list[0].priority(), 0);
+    mod_list[0].set_priority(7).unwrap();
+    assert_eq!(tree.mods()[0].priority(), 7);
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    assert!(tree.mods()[0].is_enabled());
+    assert_eq!(tree.mods()[0].priority(), 7);
+}
@@ -0,0 +1,42 @@
+//! Re-archiving a mod from the cache, and installing it again.
+
+mod common;
+
+use camino::Utf8Path;
+use common::TestTree;
+use starmod_core::{commands::mods::pack_mod, mods::ModList};
+
+#[test]
+fn a_packed_manifest_brings_the_state_along() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive("boots.zip", &[("boots.esm", "boots")]);
+    let mut md = tree.install(&archive);
+    md.set_priority(7).unwrap();
+    md.add_tag("armor").unwrap();
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    let output = tree.download_dir().join("boots_packed.zip");
+    pack_mod(tree.cache_dir(), &mod_list[0], &output, true).unwrap();
+    let packed = tree.install(Utf8Path::new("boots_packed.zip"));
+
+    assert_eq!(packed.priority(), 7);
+    assert_eq!(packed.tags(), ["armor"]);
+    // It is not deployed yet.
+    assert!(!packed.is_enabled());
+    assert_eq!(packed.dest_files().unwrap(), vec!["Data/boots.esm"]);
+}
+
+#[test]
+fn a_packed_mod_without_manifest_is_a_new_mod() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive("boots.zip", &[("boots.esm", "boots")]);
+    tree.install(&archive).set_priority(7).unwrap();
+
+    let output = tree.download_dir().join("boots_packed.zip");
+    pack_mod(tree.cache_dir(), &tree.mods()[0], &output, false).unwrap();
+    let packed = tree.install(Utf8Path::new("boots_packed.zip"));
+
+    assert_eq!(packed.priority(), 0);
+    assert_eq!(packed.dest_files().unwrap(), vec!["Data/boots.esm"]);
+}
@@ -0,0 +1,47 @@
+//! The plugins in the game's 'Data' directory, and which mods provide them.
+
+mod common;
+
+use common::TestTree;
+use starmod_core::{
+    commands::plugins::unmanaged_plugins,
+    game::Game,
+    mods::{FindInModList, ModList},
+};
+
+/// 'boots' and 'helmet' installed, each with a plugin, and only 'boots' enabled.
+fn boots_without_helmet() -> TestTree {
+    let tree = TestTree::new();
+    for (name, file) in [("boots", "boots.esm"), ("helmet", "helmet.esm")] {
+        let archive = tree.add_archive(&format!("{name}.zip"), &[(file, name)]);
+        tree.install(&archive);
+    }
+
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+    let idx = mod_list.find_mod_by_name("helmet").unwrap();
+    mod_list
+        .disable_mod(tree.cache_dir(), tree.game_dir(), idx)
+        .unwrap();
+    tree
+}
+
+#[test]
+fn plugins_of_enabled_mods_and_the_game_are_managed() {
+    let tree = boots_without_helmet();
+    tree.add_game_file("Data/Starfield.esm", "game");
+
+    let unmanaged = unmanaged_plugins(&tree.mods(), Game::Starfield, tree.game_dir()).unwrap();
+    assert!(unmanaged.is_empty(), "{unmanaged:?}");
+}
+
+#[test]
+fn plugins_of_disabled_mods_are_unmanaged() {
+    let tree = boots_without_helmet();
+    // Left behind by hand, like a copy of the plugin of the disabled mod.
+    tree.add_game_file("Data/helmet.esm", "helmet");
+    tree.add_game_file("Data/other.esp", "other");
+
+    let unmanaged = unmanaged_plugins(&tree.mods(), Game::Starfield, tree.game_dir()).unwrap();
+    assert_eq!(unmanaged, vec!["helmet.esm", "other.esp"]);
+}
@@ -0,0 +1,67 @@
+//! The requirements mods declare with their 'needs:' tags.
+
+mod common;
+
+use common::TestTree;
+use starmod_core::{
+    mods::GatherModList,
+    requirements::{unmet_components, unmet_requirements, Unmet},
+};
+
+fn tree_with_mods() -> TestTree {
+    let tree = TestTree::new();
+    let armor = tree.add_archive("armor.zip", &[("Data/armor.esp", "plugin")]);
+    let enabler = tree.add_archive("enabler.zip", &[("Data/SFSE/Plugins/enabler.dll", "dll")]);
+
+    let mut armor = tree.install(&armor);
+    armor.add_tag("needs:plugins-enabler").unwrap();
+    armor.set_enabled().unwrap();
+    let mut enabler = tree.install(&enabler);
+    enabler.add_tag("provides:plugins-enabler").unwrap();
+    tree
+}
+
+#[test]
+fn a_disabled_provider_does_not_count() {
+    let tree = tree_with_mods();
+
+    assert_eq!(
+        unmet_requirements(&Vec::gather_mods(tree.cache_dir()).unwrap()),
+        [Unmet {
+            mod_name: "armor".to_owned(),
+            requirement: "plugins-enabler".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn an_enabled_provider_meets_the_requirement() {
+    let tree = tree_with_mods();
+    let mut mod_list = Vec::gather_mods(tree.cache_dir()).unwrap();
+    for md in &mut mod_list {
+        md.set_enabled().unwrap();
+    }
+
+    assert!(unmet_requirements(&mod_list).is_empty());
+}
+
+#[test]
+fn prefix_components_are_looked_up_in_the_prefix() {
+    let tree = TestTree::new();
+    let tool = tree.add_archive("tool.zip", &[("Data/tool.esp", "plugin")]);
+    let mut tool = tree.install(&tool);
+    tool.add_tag("needs:vcrun").unwrap();
+    tool.add_tag("needs:dotnet48").unwrap();
+    tool.set_enabled().unwrap();
+    let mod_list = Vec::gather_mods(tree.cache_dir()).unwrap();
+
+    // Not a mod, so not a requirement another mod can meet.
+    assert!(unmet_requirements(&mod_list).is_empty());
+    assert_eq!(
+        unmet_components(&mod_list, &["vcrun2022".to_owned()]),
+        [Unmet {
+            mod_name: "tool".to_owned(),
+            requirement: "dotnet48".to_owned(),
+        }]
+    );
+}
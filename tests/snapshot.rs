@@ -0,0 +1,69 @@
+//! Finding the game files which were changed behind starmod's back.
+
+mod common;
+
+use std::fs::{remove_file, write};
+
+use common::TestTree;
+use starmod_core::{
+    mods::ModList,
+    snapshot::{FileChange, GameSnapshot},
+};
+
+const GAME_PLUGIN: &str = "Data/Starfield.esm";
+const GAME_EXE: &str = "Starfield.exe";
+
+fn vanilla_game() -> TestTree {
+    let tree = TestTree::new();
+    tree.add_game_file(GAME_PLUGIN, "game plugin");
+    tree.add_game_file(GAME_EXE, "game exe");
+    tree.add_game_file("Data/armorvest.esm", "original plugin\n");
+
+    let snapshot = GameSnapshot::take(tree.cache_dir(), tree.game_dir()).unwrap();
+    snapshot.write(tree.cache_dir()).unwrap();
+    tree
+}
+
+fn changes(tree: &TestTree, full: bool) -> Vec<(String, FileChange)> {
+    GameSnapshot::read(tree.cache_dir())
+        .unwrap()
+        .unwrap()
+        .verify(tree.cache_dir(), tree.game_dir(), full)
+        .unwrap()
+        .into_iter()
+        .map(|(path, change)| (path.into_string(), change))
+        .collect()
+}
+
+#[test]
+fn deployed_mods_are_not_changes_to_the_game() {
+    let tree = vanilla_game();
+    let archive = tree.add_fixture_archive("armor_vest", "armor_vest.zip");
+    tree.install(&archive);
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert_eq!(
+        GameSnapshot::read(tree.cache_dir()).unwrap().unwrap().len(),
+        3
+    );
+    assert!(changes(&tree, true).is_empty());
+}
+
+#[test]
+fn changed_removed_and_added_files_are_reported() {
+    let tree = vanilla_game();
+    // Same size, so only the hash tells.
+    write(tree.game_dir().join(GAME_PLUGIN), "GAME PLUGIN").unwrap();
+    remove_file(tree.game_dir().join(GAME_EXE)).unwrap();
+    tree.add_game_file("Data/unknown.esp", "tool output");
+
+    assert_eq!(
+        changes(&tree, true),
+        vec![
+            (GAME_PLUGIN.to_owned(), FileChange::Modified),
+            ("Data/unknown.esp".to_owned(), FileChange::Added),
+            (GAME_EXE.to_owned(), FileChange::Missing),
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+//! Finding the libraries of a Steam installation.
+
+use std::fs::{create_dir_all, write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use starmod_core::{steam, vdf};
+use tempfile::TempDir;
+
+const LIBRARY_FOLDERS: &str = r#""libraryfolders"
+{
+	"0"
+	{
+		"path"		"STEAM"
+		"apps"
+		{
+			"1716740"		"123"
+		}
+	}
+	"1"
+	{
+		"path"		"LIBRARY"
+	}
+	"2"
+	{
+		"path"		"GONE"
+	}
+}
+"#;
+
+fn root(temp: &TempDir) -> Utf8PathBuf {
+    Utf8PathBuf::try_from(temp.path().to_path_buf()).unwrap()
+}
+
+fn add_library(dir: &Utf8Path) {
+    create_dir_all(dir.join("steamapps")).unwrap();
+}
+
+#[test]
+fn listed_libraries_which_exist_are_found() {
+    let temp = TempDir::new().unwrap();
+    let steam_dir = root(&temp).join("Steam");
+    let library = root(&temp).join("games");
+    add_library(&steam_dir);
+    add_library(&library);
+    write(
+        steam_dir.join("steamapps/libraryfolders.vdf"),
+        LIBRARY_FOLDERS
+            .replace("STEAM", steam_dir.as_str())
+            .replace("LIBRARY", library.as_str())
+            .replace("GONE", root(&temp).join("sdcard").as_str()),
+    )
+    .unwrap();
+
+    let libraries = steam::library_dirs(&steam_dir);
+    assert_eq!(libraries[..2], [steam_dir, library]);
+    assert!(!libraries.iter().any(|l| l.ends_with("sdcard")));
+}
+
+#[test]
+fn values_are_read_from_every_block() {
+    assert_eq!(
+        vdf::values(LIBRARY_FOLDERS, "path"),
+        ["STEAM", "LIBRARY", "GONE"]
+    );
+    assert!(vdf::values(LIBRARY_FOLDERS, "apps").is_empty());
+}
+
+#[test]
+fn the_flatpak_is_recognised_by_its_path() {
+    assert!(steam::is_flatpak(Utf8Path::new(
+        "/home/deck/.var/app/com.valvesoftware.Steam/.local/share/Steam"
+    )));
+    assert!(!steam::is_flatpak(Utf8Path::new(
+        "/home/deck/.local/share/Steam"
+    )));
+}
@@ -0,0 +1,123 @@
+//! Replacing a mod with a newer archive of it, as downloaded from Nexus.
+
+mod common;
+
+use std::fs::{read_to_string, write};
+
+use common::TestTree;
+use starmod_core::{
+    commands::downloads::{available_upgrades, upgrade_mod},
+    dmodman::DmodMan,
+    mods::ModList,
+};
+
+const MOD_ID: u32 = 1234;
+
+/// 'armor_vest' 1.0, installed with priority 5 and enabled, and its 1.1 archive downloaded.
+fn outdated_mod() -> TestTree {
+    let tree = TestTree::new();
+    let archive = tree.add_nexus_archive(
+        "armor_vest",
+        MOD_ID,
+        "1.0",
+        &[("textures/armor/vest.dds", "1.0"), ("armorvest.esm", "1.0")],
+    );
+    tree.install(&archive).set_priority(5).unwrap();
+    let mut mod_list = tree.mods();
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    tree.add_nexus_archive(
+        "armor_vest",
+        MOD_ID,
+        "1.1",
+        &[
+            ("textures/armor/vest.dds", "1.1"),
+            ("textures/armor/vest_n.dds", "1.1"),
+            ("armorvest.esm", "1.1"),
+        ],
+    );
+    tree
+}
+
+#[test]
+fn the_newest_archive_is_an_upgrade() {
+    let tree = outdated_mod();
+    tree.add_nexus_archive("armor_vest", MOD_ID, "1.0.1", &[("armorvest.esm", "1.0.1")]);
+    tree.add_nexus_archive("other_mod", MOD_ID + 1, "2.0", &[("other.esm", "2.0")]);
+
+    let mod_list = tree.mods();
+    let dmodman_list = DmodMan::gather_list(tree.download_dir()).unwrap();
+    let upgrades = available_upgrades(&mod_list, &dmodman_list);
+
+    assert_eq!(upgrades.len(), 1);
+    let (idx, dmod) = upgrades[0];
+    assert_eq!(mod_list[idx].version(), Some("1.0"));
+    assert_eq!(dmod.version().as_deref(), Some("1.1"));
+}
+
+#[test]
+fn an_upgraded_mod_keeps_its_priority_and_state() {
+    let tree = outdated_mod();
+    let mod_list = tree.mods();
+    let dmodman_list = DmodMan::gather_list(tree.download_dir()).unwrap();
+    let (idx, dmod) = available_upgrades(&mod_list, &dmodman_list)[0];
+    let sequence = mod_list[idx].sequence();
+
+    upgrade_mod(tree.download_dir(), tree.cache_dir(), &mod_list[idx], dmod).unwrap();
+
+    let mod_list = tree.mods();
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].version(), Some("1.1"));
+    assert_eq!(mod_list[0].priority(), 5);
+    assert_eq!(mod_list[0].sequence(), sequence);
+    assert!(mod_list[0].is_enabled());
+    assert!(available_upgrades(&mod_list, &dmodman_list).is_empty());
+}
+
+#[test]
+fn redeploying_an_upgraded_mod_links_its_new_files() {
+    let tree = outdated_mod();
+    let mod_list = tree.mods();
+    let dmodman_list = DmodMan::gather_list(tree.download_dir()).unwrap();
+    let (idx, dmod) = available_upgrades(&mod_list, &dmodman_list)[0];
+    upgrade_mod(tree.download_dir(), tree.cache_dir(), &mod_list[idx], dmod).unwrap();
+
+    let mut mod_list = tree.mods();
+    mod_list
+        .re_enable(tree.cache_dir(), tree.game_dir())
+        .unwrap();
+
+    let new_dir = mod_list[0].manifest_dir().as_str();
+    for destination in [
+        "Data/Textures/armor/vest.dds",
+        "Data/Textures/armor/vest_n.dds",
+        "Data/armorvest.esm",
+    ] {
+        assert_eq!(tree.deployed_from(destination).as_deref(), Some(new_dir));
+    }
+    assert_eq!(
+        read_to_string(tree.game_dir().join("Data/armorvest.esm")).unwrap(),
+        "1.1"
+    );
+}
+
+#[test]
+fn a_broken_archive_leaves_the_mod_as_it_was() {
+    let tree = outdated_mod();
+    let mod_list = tree.mods();
+    let dmodman_list = DmodMan::gather_list(tree.download_dir()).unwrap();
+    let (idx, dmod) = available_upgrades(&mod_list, &dmodman_list)[0];
+    write(
+        tree.download_dir().join(dmod.file_name()),
+        "not a zip archive",
+    )
+    .unwrap();
+
+    assert!(upgrade_mod(tree.download_dir(), tree.cache_dir(), &mod_list[idx], dmod).is_err());
+
+    let mod_list = tree.mods();
+    assert_eq!(mod_list.len(), 1);
+    assert_eq!(mod_list[0].version(), Some("1.0"));
+    assert_eq!(mod_list[0].priority(), 5);
+    assert!(mod_list[0].is_enabled());
+}
@@ -0,0 +1,42 @@
+//! Comparing the versions found on Nexus and in archive names.
+
+use std::cmp::Ordering;
+
+use starmod_core::version::Version;
+
+#[test]
+fn common_nexus_versions_are_ordered() {
+    let cases = [
+        ("1.10", "1.9", Ordering::Greater),
+        ("v1.2", "1.2", Ordering::Equal),
+        ("Version 2.0", "1.9", Ordering::Greater),
+        ("1.0-beta", "1.0", Ordering::Less),
+        ("1.0 RC2", "1.0", Ordering::Less),
+        ("1.0-beta", "1.0-rc", Ordering::Less),
+        ("1.0", "1.0.0", Ordering::Equal),
+        ("1.0.1", "1.0", Ordering::Greater),
+        ("2a", "2", Ordering::Greater),
+        ("2b", "2a", Ordering::Greater),
+        ("1.0 final", "1.0", Ordering::Equal),
+        ("1.0 final", "1.0.1", Ordering::Less),
+        ("1_2_3", "1.2.3", Ordering::Equal),
+    ];
+    for (a, b, expected) in cases {
+        assert_eq!(
+            Version::from(a).cmp(&Version::from(b)),
+            expected,
+            "{a} against {b}"
+        );
+        assert_eq!(
+            Version::from(b).cmp(&Version::from(a)),
+            expected.reverse(),
+            "{b} against {a}"
+        );
+    }
+}
+
+#[test]
+fn a_version_is_shown_as_written() {
+    assert_eq!(Version::from("V1.0-Beta").to_string(), "V1.0-Beta");
+    assert_eq!(Version::from(None).to_string(), "");
+}
@@ -0,0 +1,101 @@
+//! Destinations which windows, and so the game under Proton, cannot open.
+
+mod common;
+
+use camino::Utf8Path;
+use common::TestTree;
+use starmod_core::{
+    manifest::install_file::{windows_path_issues, windows_safe_destination, WindowsPathIssue},
+    mods::{FindInModList, ModList},
+};
+
+#[test]
+fn names_windows_does_not_allow_are_found() {
+    let game_dir = Utf8Path::new("/games/Starfield");
+
+    assert!(windows_path_issues(game_dir, "Data/armor.esp").is_empty());
+    assert_eq!(
+        windows_path_issues(game_dir, "Data/what?.esp"),
+        [WindowsPathIssue::InvalidCharacters]
+    );
+    assert_eq!(
+        windows_path_issues(game_dir, "Data/con.txt"),
+        [WindowsPathIssue::ReservedName]
+    );
+    assert_eq!(
+        windows_path_issues(game_dir, &format!("Data/{}.dds", "a".repeat(240))),
+        [WindowsPathIssue::PathTooLong(268)]
+    );
+    assert_eq!(
+        windows_safe_destination("Data/notes: v2./aux.txt"),
+        "Data/notes_ v2/aux_.txt"
+    );
+
+    // Windows counts UTF-16 units; every emoji is two of them.
+    let name = format!("{}.esp", "\u{1f600}".repeat(126));
+    assert_eq!(
+        windows_path_issues(game_dir, &format!("Data/{name}")),
+        [
+            WindowsPathIssue::NameTooLong,
+            WindowsPathIssue::PathTooLong(280)
+        ]
+    );
+    let safe = windows_safe_destination(&format!("Data/{name}"));
+    assert!(safe.ends_with(".esp"));
+    assert_eq!(
+        windows_path_issues(game_dir, &safe),
+        [WindowsPathIssue::PathTooLong(278)]
+    );
+}
+
+#[test]
+fn fixing_renames_the_files_with_remap_rules() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive(
+        "questions.zip",
+        &[("Data/what?.esp", "plugin"), ("Data/fine.esp", "plugin")],
+    );
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("questions").unwrap();
+    assert_eq!(
+        mod_list[idx]
+            .windows_path_issues(tree.game_dir())
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(mod_list[idx].rename_for_windows().unwrap(), 1);
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert_eq!(tree.deployed(), vec!["Data/fine.esp", "Data/what_.esp"]);
+    assert!(tree.mods()[idx]
+        .windows_path_issues(tree.game_dir())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn renamed_files_never_end_up_on_another_file() {
+    let tree = TestTree::new();
+    let archive = tree.add_archive(
+        "wildcards.zip",
+        &[
+            ("Data/a?b.esp", "question"),
+            ("Data/a*b.esp", "star"),
+            ("Data/a_b.esp", "underscore"),
+        ],
+    );
+    tree.install(&archive);
+
+    let mut mod_list = tree.mods();
+    let idx = mod_list.find_mod_by_name("wildcards").unwrap();
+    assert_eq!(mod_list[idx].rename_for_windows().unwrap(), 2);
+    mod_list.enable(tree.cache_dir(), tree.game_dir()).unwrap();
+
+    assert_eq!(
+        tree.deployed(),
+        vec!["Data/a_b.esp", "Data/a_b_1.esp", "Data/a_b_2.esp"]
+    );
+}
@@ -0,0 +1,41 @@
+//! Finding the windows user the game runs as in a wine prefix.
+
+use std::fs::create_dir_all;
+
+use camino::Utf8PathBuf;
+use starmod_core::settings::wine_user_dir;
+use tempfile::TempDir;
+
+#[test]
+fn the_user_running_wine_is_preferred() {
+    let root = TempDir::new().unwrap();
+    let users_dir = Utf8PathBuf::try_from(root.path().to_path_buf()).unwrap();
+
+    assert_eq!(
+        wine_user_dir(&users_dir, Some("alex")),
+        Some(users_dir.join("alex"))
+    );
+    assert_eq!(wine_user_dir(&users_dir, None), None);
+
+    for user in ["Public", "zoe", "bob"] {
+        create_dir_all(users_dir.join(user)).unwrap();
+    }
+    assert_eq!(
+        wine_user_dir(&users_dir, Some("alex")),
+        Some(users_dir.join("bob"))
+    );
+    assert_eq!(
+        wine_user_dir(&users_dir, Some("zoe")),
+        Some(users_dir.join("zoe"))
+    );
+
+    create_dir_all(users_dir.join("steamuser")).unwrap();
+    assert_eq!(
+        wine_user_dir(&users_dir, Some("alex")),
+        Some(users_dir.join("steamuser"))
+    );
+    assert_eq!(
+        wine_user_dir(&users_dir, Some("zoe")),
+        Some(users_dir.join("zoe"))
+    );
+}